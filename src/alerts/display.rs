@@ -1,12 +1,26 @@
 use colored::*;
 
 use crate::alerts::{MarketContext, OrderBookSummary, TopHoldersSummary};
+use crate::execution::arbitrage::ArbitragePair;
+use crate::execution::combinatorial::{self, OverroundSignal};
 use crate::platforms::{kalshi, polymarket};
 use crate::types::{self, WhaleReturnScenario};
 use crate::whale_profile::WhaleProfile;
 
 use super::anomaly;
 use super::sound;
+use crate::metrics::metrics;
+
+/// `"heavy"`/`"repeat"`/`"normal"` label for `Metrics::whale_alerts_by_actor`.
+/// Heavy wins over repeat when both are set — it's the stronger signal, and
+/// the labeled-counter convention here is one value per call, not a set.
+fn actor_label(wallet_activity: Option<&types::WalletActivity>) -> &'static str {
+    match wallet_activity {
+        Some(a) if a.is_heavy_actor => "heavy",
+        Some(a) if a.is_repeat_actor => "repeat",
+        _ => "normal",
+    }
+}
 
 pub fn print_market_context(ctx: &MarketContext) {
     println!();
@@ -49,6 +63,40 @@ pub fn print_market_context(ctx: &MarketContext) {
     if !ctx.tags.is_empty() {
         println!("Tags:          {}", ctx.tags.join(", ").dimmed());
     }
+
+    if let Some(outcomes) = &ctx.outcomes {
+        println!();
+        println!("{}", "[OUTCOMES]".bright_blue().bold());
+        for outcome in outcomes {
+            println!(
+                "  {:<30} {:.1}%  (vol ${:.0})",
+                outcome.label,
+                outcome.price * 100.0,
+                outcome.volume
+            );
+        }
+
+        if let Some(opp) = combinatorial::detect_overround(
+            outcomes,
+            combinatorial::DEFAULT_OVERROUND_THRESHOLD,
+        ) {
+            let label = match opp.signal {
+                OverroundSignal::DutchBook => "DUTCH BOOK",
+                OverroundSignal::Overround => "OVERROUND",
+            };
+            let line = format!(
+                "  {}: outcomes sum to {:.1}% ({:.1}c edge per $1 staked across the set)",
+                label,
+                opp.total_probability * 100.0,
+                opp.edge_cents
+            );
+            let line = match opp.signal {
+                OverroundSignal::DutchBook => line.bright_green().bold(),
+                OverroundSignal::Overround => line.bright_red().bold(),
+            };
+            println!("{}", line);
+        }
+    }
 }
 
 pub fn print_whale_alert(
@@ -59,6 +107,13 @@ pub fn print_whale_alert(
 ) {
     let is_sell = trade.side.to_uppercase() == "SELL";
 
+    metrics().whale_alerts_by_actor.inc(format!(
+        "{}_{}_{}",
+        platform.to_lowercase(),
+        actor_label(wallet_activity),
+        if is_sell { "sell" } else { "buy" },
+    ));
+
     // Enhanced alert sound for repeat actors or sells
     if let Some(activity) = wallet_activity {
         if activity.is_repeat_actor || activity.is_heavy_actor {
@@ -195,6 +250,12 @@ pub fn print_kalshi_alert(
     // We cannot detect exits from the public Kalshi trade API
     let is_sell = false;
 
+    metrics().whale_alerts_by_actor.inc(format!(
+        "kalshi_{}_{}",
+        actor_label(wallet_activity),
+        if is_sell { "sell" } else { "buy" },
+    ));
+
     if is_sell {
         sound::play_triple_beep();
     } else if let Some(activity) = wallet_activity {
@@ -406,6 +467,30 @@ pub fn print_returning_whale(scenario: &WhaleReturnScenario, platform: &str) {
                 );
             }
         }
+        WhaleReturnScenario::ProfitTaking {
+            realized_pnl,
+            avg_cost_basis,
+            remaining_shares,
+            total_12h_volume,
+            total_12h_txns,
+        } => {
+            sound::play_triple_beep();
+            println!();
+            println!(
+                "{}",
+                format!("[PROFIT TAKING] Selling an accumulated position - {}", platform)
+                    .bright_yellow()
+                    .bold()
+            );
+            println!(
+                "Realized P&L: ${:.2} - {} shares remaining @ ${:.4} avg cost",
+                realized_pnl, remaining_shares, avg_cost_basis
+            );
+            println!(
+                "12h total: {} txns, ${:.0} volume",
+                total_12h_txns, total_12h_volume
+            );
+        }
     }
 }
 
@@ -441,6 +526,25 @@ pub fn print_order_book(ob: &OrderBookSummary) {
     println!("Imbalance:  {:.0}% bid / {:.0}% ask ({})", imbalance * 100.0, (1.0 - imbalance) * 100.0, imbalance_label);
 }
 
+/// Prints a `HybridRouter`-detected cross-venue mispricing: both platforms'
+/// quotes, which side is cheaper to buy where, and the riskless edge per
+/// `ArbitrageOpportunity::break_even_notional`-and-up trade.
+pub fn print_arbitrage_alert(pair: &ArbitragePair) {
+    println!();
+    println!("{}", "[CROSS-VENUE ARBITRAGE]".bright_green().bold());
+    println!("Kalshi:     {} (yes ${:.2} / no ${:.2})", pair.kalshi_ticker, pair.kalshi_yes_price, pair.kalshi_no_price);
+    println!("Polymarket: {} (yes ${:.2} / no ${:.2})", pair.polymarket_market, pair.polymarket_yes_price, pair.polymarket_no_price);
+    if pair.buy_yes_on_polymarket {
+        println!("Buy:        YES on Polymarket + NO on Kalshi");
+    } else {
+        println!("Buy:        YES on Kalshi + NO on Polymarket");
+    }
+    println!(
+        "Edge:       ${:.2} after fees  |  Break-even size: ${:.2}",
+        pair.opportunity.edge, pair.opportunity.break_even_notional
+    );
+}
+
 pub fn print_top_holders(th: &TopHoldersSummary) {
     if th.top_holders.is_empty() {
         return;
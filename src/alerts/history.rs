@@ -1,55 +1,21 @@
-use std::fs::OpenOptions;
-use std::io::Write;
+use std::sync::Arc;
 use colored::*;
-use rusqlite::Connection;
 
+use super::sinks::{AlertSink, JsonlSink, SqliteSink};
 use super::AlertData;
-use crate::db;
+use crate::db::AlertStore;
+use crate::metrics::metrics;
 
 /// Log an alert to the SQLite database and JSONL file (sync; watch uses log_alert_blocking)
 #[allow(dead_code)]
-pub fn log_alert(alert: &AlertData, conn: &Connection) -> Option<i64> {
-    let alert_json = super::build_alert_payload(alert, false);
-
-    let wallet_activity_json = alert_json.get("wallet_activity").map(|v| v.to_string());
-    let market_context_json = alert_json.get("market_context").map(|v| v.to_string());
-
-    // JSONL Logging
-    if let Some(config_dir) = dirs::config_dir() {
-        let jsonl_path = config_dir.join("wwatcher").join("alert_history.jsonl");
-        if let Ok(mut file) = OpenOptions::new()
-            .create(true)
-            .append(true)
-            .open(&jsonl_path)
-        {
-            if let Ok(line) = serde_json::to_string(&alert_json) {
-                let _ = writeln!(file, "{}", line);
-            }
-        }
-    }
-
-    // Database Logging
-    db::insert_alert(
-        conn,
-        alert.platform,
-        alert.alert_type(),
-        &alert.side.to_uppercase(),
-        alert.value,
-        alert.price,
-        alert.size,
-        alert.market_title,
-        alert.market_id,
-        alert.outcome,
-        alert.wallet_id,
-        alert.timestamp,
-        market_context_json.as_deref(),
-        wallet_activity_json.as_deref(),
-    )
+pub fn log_alert(alert: &AlertData, store: Arc<dyn AlertStore>) -> Option<i64> {
+    let params = build_log_params(alert);
+    log_alert_blocking(params, &[Arc::new(JsonlSink), Arc::new(SqliteSink::new(store))])
 }
 
 /// Build LogAlertParams from AlertData for use with log_alert_blocking
 pub fn build_log_params(alert: &AlertData) -> LogAlertParams {
-    let alert_json = super::build_alert_payload(alert, false);
+    let alert_json = super::build_alert_payload(alert, super::webhook::TextFormat::Plain);
     let jsonl_line = serde_json::to_string(&alert_json).unwrap_or_default();
     LogAlertParams {
         platform: alert.platform.to_string(),
@@ -60,6 +26,7 @@ pub fn build_log_params(alert: &AlertData) -> LogAlertParams {
         size: alert.size,
         market_title: alert.market_title.map(|s| s.to_string()),
         market_id: alert.market_id.map(|s| s.to_string()),
+        trade_id: alert.trade_id.map(|s| s.to_string()),
         outcome: alert.outcome.map(|s| s.to_string()),
         wallet_id: alert.wallet_id.map(|s| s.to_string()),
         timestamp: alert.timestamp.to_string(),
@@ -69,7 +36,10 @@ pub fn build_log_params(alert: &AlertData) -> LogAlertParams {
     }
 }
 
-/// Owned params for log_alert_blocking (used with spawn_blocking)
+/// Owned params for log_alert_blocking (used with spawn_blocking). Cloned
+/// once per configured `AlertSink` so a queueing sink like `PostgresSink`
+/// can hold its own copy past the call to `write`.
+#[derive(Clone)]
 pub struct LogAlertParams {
     pub platform: String,
     pub alert_type: String,
@@ -79,6 +49,9 @@ pub struct LogAlertParams {
     pub size: f64,
     pub market_title: Option<String>,
     pub market_id: Option<String>,
+    /// The platform's own trade id, used for `AlertStore::insert_alert`'s
+    /// `(platform, trade_id)` dedup. `None` for synthetic/test alerts.
+    pub trade_id: Option<String>,
     pub outcome: Option<String>,
     pub wallet_id: Option<String>,
     pub timestamp: String,
@@ -87,44 +60,33 @@ pub struct LogAlertParams {
     pub jsonl_line: String,
 }
 
-/// Log an alert using owned params (for spawn_blocking). Returns alert row id.
-pub fn log_alert_blocking(params: LogAlertParams, conn: &Connection) -> Option<i64> {
-    if let Some(config_dir) = dirs::config_dir() {
-        let jsonl_path = config_dir.join("wwatcher").join("alert_history.jsonl");
-        if let Ok(mut file) = OpenOptions::new()
-            .create(true)
-            .append(true)
-            .open(&jsonl_path)
-        {
-            let _ = writeln!(file, "{}", params.jsonl_line);
+/// Log an alert through every configured `AlertSink` (for spawn_blocking).
+/// Returns the row id the `SqliteSink` assigned, if one is present — the
+/// other sinks (JSONL, Postgres) don't assign ids and return `None`.
+pub fn log_alert_blocking(params: LogAlertParams, sinks: &[Arc<dyn AlertSink>]) -> Option<i64> {
+    let mut row_id = None;
+    for sink in sinks {
+        if let Some(id) = sink.write(&params) {
+            row_id = Some(id);
         }
     }
-
-    db::insert_alert(
-        conn,
-        &params.platform,
-        &params.alert_type,
-        &params.action,
-        params.value,
-        params.price,
-        params.size,
-        params.market_title.as_deref(),
-        params.market_id.as_deref(),
-        params.outcome.as_deref(),
-        params.wallet_id.as_deref(),
-        &params.timestamp,
-        params.market_context_json.as_deref(),
-        params.wallet_activity_json.as_deref(),
-    )
+    metrics().alerts_logged.inc();
+    metrics().alerts_by_platform_side.inc(format!(
+        "{}_{}",
+        params.platform.to_lowercase(),
+        params.action.to_lowercase(),
+    ));
+    metrics().alerted_notional_usd.add(params.value.max(0.0).round() as u64);
+    row_id
 }
 
 pub fn show_alert_history(
     limit: usize,
     platform_filter: &str,
     as_json: bool,
-    conn: &Connection,
+    store: &dyn AlertStore,
 ) -> Result<(), Box<dyn std::error::Error>> {
-    let alerts = db::query_alerts(conn, limit, platform_filter)?;
+    let alerts = store.query_alerts(limit, platform_filter)?;
 
     if alerts.is_empty() {
         println!("No alerts found matching filters.");
@@ -192,7 +154,7 @@ pub fn show_alert_history(
             println!();
         }
 
-        let total = db::alert_count(conn);
+        let total = store.alert_count();
         println!(
             "Total alerts in database: {}",
             total.to_string().bright_white()
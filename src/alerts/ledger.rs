@@ -0,0 +1,78 @@
+use super::webhook::ExecutionAlert;
+use std::fs::OpenOptions;
+use std::io::Write;
+
+/// Renders one `ExecutionAlert` as a Ledger-CLI double-entry transaction
+/// block: a dated header, a contracts-held posting, a fee posting, and a
+/// cash posting carrying a balance assertion against `balance_after_cents`
+/// — `commands::watch` re-fetches this from Kalshi after the fill, so the
+/// assertion catches a missed/misordered entry instead of just checking our
+/// own arithmetic against itself.
+pub fn format_execution_entry(alert: &ExecutionAlert) -> String {
+    let date = chrono::Utc::now().format("%Y-%m-%d");
+    let side = alert.side.to_uppercase();
+    let price = alert.price_cents as f64 / 100.0;
+    let fee = (alert.fee_cents * alert.count as i64) as f64 / 100.0;
+    let total_cost = alert.total_cost_cents as f64 / 100.0;
+    let balance = alert.balance_after_cents as f64 / 100.0;
+
+    format!(
+        "{date} * \"Trade Executed: {side} {ticker}\"\n    Assets:Kalshi:{ticker}    {count} {ticker} @ ${price:.2}\n    Expenses:Fees    ${fee:.2}\n    Assets:Kalshi:Cash    -${total_cost:.2}  = ${balance:.2}\n\n",
+        date = date,
+        side = side,
+        ticker = alert.kalshi_ticker,
+        count = alert.count,
+        price = price,
+        fee = fee,
+        total_cost = total_cost,
+        balance = balance,
+    )
+}
+
+/// Appends `alert`'s Ledger-CLI entry to `path`, creating the file if it
+/// doesn't exist yet. Called once per execution (see `commands::watch`) so
+/// the journal is a complete, restart-proof accounting trail even though
+/// `ExecutionAlert`s themselves are never otherwise persisted.
+pub fn append_execution(path: &str, alert: &ExecutionAlert) -> std::io::Result<()> {
+    let entry = format_execution_entry(alert);
+    let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+    file.write_all(entry.as_bytes())?;
+    file.flush()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn make_alert() -> ExecutionAlert {
+        ExecutionAlert {
+            kalshi_ticker: "KXNBAGAME-A".to_string(),
+            side: "yes".to_string(),
+            count: 10,
+            price_cents: 55,
+            fee_cents: 2,
+            total_cost_cents: 570,
+            ev_cents: 3.2,
+            kelly_pct: 1.5,
+            whale_win_rate: 0.9,
+            balance_after_cents: 99430,
+            poly_title: "Will X win?".to_string(),
+            order_id: "order-123".to_string(),
+        }
+    }
+
+    #[test]
+    fn entry_has_dated_header_and_balancing_postings() {
+        let entry = format_execution_entry(&make_alert());
+        assert!(entry.contains("* \"Trade Executed: YES KXNBAGAME-A\""));
+        assert!(entry.contains("Assets:Kalshi:KXNBAGAME-A    10 KXNBAGAME-A @ $0.55"));
+        assert!(entry.contains("Expenses:Fees    $0.20"));
+        assert!(entry.contains("Assets:Kalshi:Cash    -$5.70  = $994.30"));
+    }
+
+    #[test]
+    fn entry_ends_with_blank_line_separator() {
+        let entry = format_execution_entry(&make_alert());
+        assert!(entry.ends_with("\n\n"));
+    }
+}
@@ -1,12 +1,45 @@
 pub mod anomaly;
 pub mod display;
 pub mod history;
+pub mod ledger;
+pub mod sinks;
 pub mod sound;
 pub mod webhook;
 
+use crate::execution::arbitrage::ArbitragePair;
+use crate::execution::combinatorial::{Leg, LegSide};
 use crate::types;
 use crate::whale_profile::WhaleProfile;
 
+/// A platform's maker/taker fee, as a fraction of notional (e.g. `0.02` =
+/// 2%). Populated per-platform in `platforms::kalshi`/`platforms::polymarket`
+/// `fetch_market_context`, since the two venues' schedules differ.
+#[derive(Debug, Clone, Copy)]
+pub struct Fees {
+    pub maker: f64,
+    pub taker: f64,
+}
+
+/// A platform's order-granularity constraints: the smallest price increment
+/// and the smallest order size it accepts. `build_alert_payload`'s
+/// `net_edge` rounds the alert's price/size down to these before computing
+/// expected value, so the figure reflects an order that's actually
+/// placeable rather than the raw (possibly sub-tick) trade print.
+#[derive(Debug, Clone, Copy)]
+pub struct Precision {
+    pub tick_size: f64,
+    pub lot_size: f64,
+}
+
+/// One outcome of an N-way (non-binary) market: its label, best-ask implied
+/// probability, and trading volume.
+#[derive(Debug, Clone)]
+pub struct OutcomeQuote {
+    pub label: String,
+    pub price: f64,
+    pub volume: f64,
+}
+
 /// Market context data fetched per whale alert for edge detection
 #[derive(Debug, Clone)]
 pub struct MarketContext {
@@ -18,6 +51,13 @@ pub struct MarketContext {
     pub price_change_24h: f64,
     pub liquidity: f64,
     pub tags: Vec<String>,
+    pub fees: Fees,
+    pub precision: Precision,
+    /// Every outcome of the underlying event, for an N-way market whose
+    /// fetcher can enumerate them (e.g. a multi-candidate Kalshi event).
+    /// `None` for an ordinary binary YES/NO market, where `yes_price`/
+    /// `no_price` already say everything there is to say.
+    pub outcomes: Option<Vec<OutcomeQuote>>,
 }
 
 /// Order book depth summary
@@ -31,6 +71,68 @@ pub struct OrderBookSummary {
     pub ask_levels: u32,
 }
 
+/// Estimated fill for a given notional, from `price_impact`.
+#[derive(Debug, Clone, Copy)]
+pub struct PriceImpact {
+    /// Average execution price across the whole notional.
+    pub vwap: f64,
+    /// `(vwap - mid) / mid` in basis points, signed: positive for a buy that
+    /// walks the ask up, negative for a sell that walks the bid down.
+    pub slippage_bps: f64,
+    /// Marginal price left standing at the front of the book after the fill.
+    pub final_price: f64,
+}
+
+/// Estimate the VWAP and slippage a `notional`-sized order would get against
+/// `ob`, by fitting the 10%-depth side of the book to a constant-product
+/// curve `x*y=k` and integrating along it. `best_bid`/`best_ask` give the
+/// curve's starting marginal price; the matching depth figure
+/// (`ask_depth_10pct` for a buy, `bid_depth_10pct` for a sell) stands in for
+/// the quote-side reserve, with the token-side reserve derived so the
+/// marginal price `reserve_quote / reserve_base` equals that quote. `side`
+/// is `"sell"` (case-insensitive) for a sell walking the bid down; anything
+/// else is treated as a buy walking the ask up.
+pub fn price_impact(ob: &OrderBookSummary, notional: f64, side: &str) -> PriceImpact {
+    let mid = (ob.best_bid + ob.best_ask) / 2.0;
+    let is_sell = side.eq_ignore_ascii_case("sell");
+    let (price, depth) = if is_sell {
+        (ob.best_bid, ob.bid_depth_10pct)
+    } else {
+        (ob.best_ask, ob.ask_depth_10pct)
+    };
+
+    if price <= 0.0 || depth <= 0.0 || mid <= 0.0 {
+        return PriceImpact { vwap: price, slippage_bps: 0.0, final_price: price };
+    }
+
+    let reserve_quote = depth;
+    let reserve_base = reserve_quote / price;
+    let k = reserve_quote * reserve_base;
+
+    let (vwap, final_price) = if is_sell {
+        // Selling notional's worth of tokens into the pool: token reserve grows,
+        // quote reserve (and so the marginal price) shrinks.
+        let tokens_in = notional / price;
+        let new_reserve_base = reserve_base + tokens_in;
+        let new_reserve_quote = k / new_reserve_base;
+        let cash_out = reserve_quote - new_reserve_quote;
+        (cash_out / tokens_in, new_reserve_quote / new_reserve_base)
+    } else {
+        // Buying notional's worth of tokens from the pool: quote reserve grows,
+        // token reserve shrinks, so the marginal price rises.
+        let new_reserve_quote = reserve_quote + notional;
+        let new_reserve_base = k / new_reserve_quote;
+        let tokens_out = reserve_base - new_reserve_base;
+        (notional / tokens_out, new_reserve_quote / new_reserve_base)
+    };
+
+    PriceImpact {
+        vwap,
+        slippage_bps: (vwap - mid) / mid * 10_000.0,
+        final_price,
+    }
+}
+
 /// Top holders summary for a Polymarket market
 #[derive(Debug, Clone)]
 pub struct TopHoldersSummary {
@@ -45,10 +147,34 @@ pub struct TopHolder {
     pub value: f64,
 }
 
+/// A parlay title decomposed into legs (via
+/// `execution::combinatorial::decompose`), paired with each leg's quoted
+/// price and the resulting joint probability and edge against `cost`.
+/// Computing `leg_prices` requires a per-leg price feed that today's
+/// single-trade whale prints don't carry, so this is populated by whichever
+/// upstream source does have it, the same way `arbitrage` is attached only
+/// where `HybridRouter` has matched a pair.
+#[derive(Debug, Clone)]
+pub struct CombinatorialSummary {
+    pub legs: Vec<Leg>,
+    pub leg_prices: Vec<f64>,
+    pub joint_probability: f64,
+    pub cost: f64,
+    pub payout: f64,
+    pub edge: f64,
+}
+
 /// Shared alert data structure used by webhook, logging, and display
 pub struct AlertData<'a> {
     pub platform: &'a str,
     pub market_title: Option<&'a str>,
+    pub market_id: Option<&'a str>,
+    /// The platform's own trade id (Kalshi's `trade_id`, Polymarket's
+    /// `transactionHash`), threaded through to `history::LogAlertParams` so
+    /// `AlertStore::insert_alert` can dedup on `(platform, trade_id)` —
+    /// `commands::backfill` relies on this to stay idempotent over an
+    /// overlapping re-run.
+    pub trade_id: Option<&'a str>,
     pub outcome: Option<&'a str>,
     pub side: &'a str,
     pub value: f64,
@@ -61,6 +187,23 @@ pub struct AlertData<'a> {
     pub whale_profile: Option<&'a WhaleProfile>,
     pub order_book: Option<&'a OrderBookSummary>,
     pub top_holders: Option<&'a TopHoldersSummary>,
+    /// Set by `commands::watch`'s `execution::arbitrage::HybridRouter` when
+    /// this trade's fresh market context lines up with a cached context on
+    /// the other platform for a combined cost that clears the fee floor.
+    /// Carries both platforms' prices and the implied edge instead of a
+    /// dedicated `AlertData` struct, the same way `market_context`/
+    /// `order_book` attach their own structs rather than flattening fields
+    /// directly onto `AlertData`.
+    pub arbitrage: Option<&'a ArbitragePair>,
+    /// Set when `market_title` is a comma-separated parlay and an upstream
+    /// per-leg price feed let us price the "all legs hit" ticket.
+    pub combinatorial: Option<&'a CombinatorialSummary>,
+    /// Set by `commands::watch`'s `execution::rollover` when this alert
+    /// records a closed-and-reopened weekly position rather than a fresh
+    /// whale trade. `price`/`size` carry the roll's entry price/count the
+    /// same way a normal entry alert does — this flag only changes
+    /// `alert_type()`'s label.
+    pub is_rollover: bool,
 }
 
 impl<'a> AlertData<'a> {
@@ -69,25 +212,24 @@ impl<'a> AlertData<'a> {
     }
 
     pub fn alert_type(&self) -> &'static str {
-        if self.is_sell() { "WHALE_EXIT" } else { "WHALE_ENTRY" }
+        if self.is_rollover {
+            "ROLLOVER"
+        } else if self.arbitrage.is_some() {
+            "ARBITRAGE"
+        } else if self.is_sell() {
+            "WHALE_EXIT"
+        } else {
+            "WHALE_ENTRY"
+        }
     }
 }
 
 /// Build a serde_json::Value payload from AlertData. Used by both webhook and history logging.
-pub fn build_alert_payload(alert: &AlertData, escape_text: bool) -> serde_json::Value {
+pub fn build_alert_payload(alert: &AlertData, format: webhook::TextFormat) -> serde_json::Value {
     use serde_json::json;
 
-    let market_title = if escape_text {
-        alert.market_title.map(webhook::escape_special_chars)
-    } else {
-        alert.market_title.map(|s| s.to_string())
-    };
-
-    let outcome = if escape_text {
-        alert.outcome.map(webhook::escape_special_chars)
-    } else {
-        alert.outcome.map(|s| s.to_string())
-    };
+    let market_title = alert.market_title.map(|s| webhook::escape_for(format, s));
+    let outcome = alert.outcome.map(|s| webhook::escape_for(format, s));
 
     let mut payload = json!({
         "platform": alert.platform,
@@ -127,7 +269,10 @@ pub fn build_alert_payload(alert: &AlertData, escape_text: bool) -> serde_json::
             "price_change_24h": ctx.price_change_24h,
             "liquidity": ctx.liquidity,
             "tags": ctx.tags,
+            "fees": { "maker": ctx.fees.maker, "taker": ctx.fees.taker },
+            "precision": { "tick_size": ctx.precision.tick_size, "lot_size": ctx.precision.lot_size },
         });
+        payload["net_edge"] = json!(net_edge(alert, ctx));
     }
 
     if let Some(wp) = alert.whale_profile {
@@ -150,6 +295,14 @@ pub fn build_alert_payload(alert: &AlertData, escape_text: bool) -> serde_json::
             "bid_levels": ob.bid_levels,
             "ask_levels": ob.ask_levels,
         });
+
+        let side = if alert.is_sell() { "sell" } else { "buy" };
+        let impact = price_impact(ob, alert.value, side);
+        payload["estimated_fill"] = json!({
+            "vwap": impact.vwap,
+            "slippage_bps": impact.slippage_bps,
+            "final_price": impact.final_price,
+        });
     }
 
     if let Some(th) = alert.top_holders {
@@ -166,5 +319,61 @@ pub fn build_alert_payload(alert: &AlertData, escape_text: bool) -> serde_json::
         });
     }
 
+    if let Some(arb) = alert.arbitrage {
+        payload["arbitrage"] = json!({
+            "kalshi_ticker": arb.kalshi_ticker,
+            "polymarket_market": arb.polymarket_market,
+            "kalshi_yes_price": arb.kalshi_yes_price,
+            "kalshi_no_price": arb.kalshi_no_price,
+            "polymarket_yes_price": arb.polymarket_yes_price,
+            "polymarket_no_price": arb.polymarket_no_price,
+            "buy_yes_on_polymarket": arb.buy_yes_on_polymarket,
+            "edge": arb.opportunity.edge,
+            "break_even_notional": arb.opportunity.break_even_notional,
+        });
+    }
+
+    if let Some(combo) = alert.combinatorial {
+        let legs: Vec<serde_json::Value> = combo
+            .legs
+            .iter()
+            .zip(combo.leg_prices.iter())
+            .map(|(leg, price)| {
+                json!({
+                    "side": match leg.side { LegSide::Yes => "yes", LegSide::No => "no" },
+                    "description": leg.description,
+                    "price": price,
+                })
+            })
+            .collect();
+        payload["combinatorial"] = json!({
+            "legs": legs,
+            "joint_probability": combo.joint_probability,
+            "cost": combo.cost,
+            "payout": combo.payout,
+            "edge": combo.edge,
+        });
+    }
+
     payload
 }
+
+/// Dollar expected value of taking `alert`'s side at `alert.price`/
+/// `alert.size`, after rounding the entry down to `ctx.precision.tick_size`
+/// and the size down to `ctx.precision.lot_size`, then netting out
+/// `ctx.fees.taker` applied to the rounded notional. Uses `ctx.no_price`/
+/// `ctx.yes_price` as the fair probability for whichever side the alert
+/// took, so a `SELL` alert is priced against the book's NO side rather than
+/// YES. This is the after-fee figure `max_entry_price_cents` only
+/// approximates — n8n workflows can filter on it directly instead of
+/// inferring edge from raw `value`.
+fn net_edge(alert: &AlertData, ctx: &MarketContext) -> f64 {
+    let fair_price = if alert.is_sell() { ctx.no_price } else { ctx.yes_price };
+    let tick = if ctx.precision.tick_size > 0.0 { ctx.precision.tick_size } else { 0.01 };
+    let lot = if ctx.precision.lot_size > 0.0 { ctx.precision.lot_size } else { 1.0 };
+    let entry_price = (alert.price / tick).floor() * tick;
+    let size = (alert.size / lot).floor() * lot;
+    let notional = entry_price * size;
+    let fee = notional * ctx.fees.taker;
+    (fair_price - entry_price) * size - fee
+}
@@ -0,0 +1,285 @@
+//! Pluggable alert persistence sinks. `log_alert_blocking` used to hardcode
+//! a JSONL append and a direct `db::insert_alert` call; this splits those
+//! into an `AlertSink` trait so a shared Postgres analytics database can
+//! sit alongside the local SQLite store without changing the call sites
+//! that log alerts.
+use std::sync::Arc;
+use std::time::Duration;
+
+use tokio::sync::mpsc;
+use tokio_postgres::NoTls;
+
+use super::history::LogAlertParams;
+use crate::db::AlertStore;
+
+/// Durably records a logged alert, returning the row id if the sink's
+/// backing store assigns one (only `SqliteSink` does; other sinks return
+/// `None`). `write` is synchronous to keep the watcher's `spawn_blocking`
+/// call site unchanged — a sink that needs to batch or go over the network
+/// queues internally instead of blocking here (see `PostgresSink`).
+pub trait AlertSink: Send + Sync {
+    fn write(&self, params: &LogAlertParams) -> Option<i64>;
+}
+
+/// Appends the alert as a line of JSON to `~/.config/wwatcher/alert_history.jsonl`.
+pub struct JsonlSink;
+
+impl AlertSink for JsonlSink {
+    fn write(&self, params: &LogAlertParams) -> Option<i64> {
+        use std::io::Write;
+
+        let config_dir = dirs::config_dir()?;
+        let jsonl_path = config_dir.join("wwatcher").join("alert_history.jsonl");
+        if let Ok(mut file) = std::fs::OpenOptions::new().create(true).append(true).open(&jsonl_path) {
+            let _ = writeln!(file, "{}", params.jsonl_line);
+        }
+        None
+    }
+}
+
+/// Inserts into the local store via the existing `AlertStore` trait
+/// (SQLite in production, in-memory in tests) and returns its row id —
+/// the only sink that does, since it's the store `mark_alert_executed`
+/// later writes back to.
+pub struct SqliteSink {
+    store: Arc<dyn AlertStore>,
+}
+
+impl SqliteSink {
+    pub fn new(store: Arc<dyn AlertStore>) -> Self {
+        Self { store }
+    }
+}
+
+impl AlertSink for SqliteSink {
+    fn write(&self, params: &LogAlertParams) -> Option<i64> {
+        self.store.insert_alert(
+            &params.platform,
+            &params.alert_type,
+            &params.action,
+            params.value,
+            params.price,
+            params.size,
+            params.market_title.as_deref(),
+            params.market_id.as_deref(),
+            params.outcome.as_deref(),
+            params.wallet_id.as_deref(),
+            &params.timestamp,
+            params.market_context_json.as_deref(),
+            params.wallet_activity_json.as_deref(),
+            params.trade_id.as_deref(),
+        )
+    }
+}
+
+/// Rows buffered before a flush is forced, even if `flush_interval` hasn't elapsed.
+const DEFAULT_MAX_BATCH: usize = 200;
+const DEFAULT_FLUSH_INTERVAL: Duration = Duration::from_secs(5);
+/// Alerts queued for Postgres before `write` starts dropping them rather
+/// than blocking the caller (the WS read loop, via `spawn_blocking`).
+const DEFAULT_CHANNEL_CAPACITY: usize = 2000;
+
+/// Modeled on mango-feeds-connector's `fill_event_postgres_target`: a
+/// bounded channel feeds a dedicated task that buffers `LogAlertParams` and
+/// flushes them as batched multi-row `INSERT`s on a timer or once the batch
+/// fills up, whichever comes first. The task reconnects on connection loss
+/// so a dropped database never stalls the caller, which only ever does a
+/// non-blocking channel send.
+pub struct PostgresSink {
+    tx: mpsc::Sender<LogAlertParams>,
+}
+
+impl PostgresSink {
+    /// Connects to `connection_string` (a libpq-style DSN, e.g.
+    /// `host=localhost user=wwatcher dbname=analytics`) and spawns the
+    /// background flush task, using the default batch size and flush
+    /// interval. Plain (non-TLS) connection — see `connect_from_env` for a
+    /// connection built from `WWATCHER_PG_*` environment variables with
+    /// optional TLS.
+    pub fn connect(connection_string: String) -> Self {
+        Self::connect_with_batching(connection_string, DEFAULT_MAX_BATCH, DEFAULT_FLUSH_INTERVAL)
+    }
+
+    /// Same as `connect`, with an explicit max batch size and flush interval.
+    pub fn connect_with_batching(connection_string: String, max_batch: usize, flush_interval: Duration) -> Self {
+        Self::connect_with_batching_tls(connection_string, false, max_batch, flush_interval)
+    }
+
+    /// Same as `connect_with_batching`, additionally taking `ssl` — when
+    /// `true`, the background task negotiates TLS via `native-tls` instead
+    /// of connecting in the clear.
+    pub fn connect_with_batching_tls(connection_string: String, ssl: bool, max_batch: usize, flush_interval: Duration) -> Self {
+        let (tx, rx) = mpsc::channel(DEFAULT_CHANNEL_CAPACITY);
+        tokio::spawn(run_postgres_sink(connection_string, ssl, rx, max_batch, flush_interval));
+        Self { tx }
+    }
+
+    /// Builds a connection from discrete `WWATCHER_PG_*` environment
+    /// variables rather than a single DSN string, for deployments that wire
+    /// Postgres credentials through the environment instead of (or in
+    /// addition to) `config.postgres_alert_url`:
+    ///   - `WWATCHER_PG_HOST` (required — `None` is returned if unset, so
+    ///     this backend stays opt-in)
+    ///   - `WWATCHER_PG_PORT` (default `5432`)
+    ///   - `WWATCHER_PG_USER` (default `postgres`)
+    ///   - `WWATCHER_PG_PASSWORD` (optional)
+    ///   - `WWATCHER_PG_DBNAME` (default `postgres`)
+    ///   - `WWATCHER_PG_SSL` (`"true"`/`"1"` to enable TLS; default disabled)
+    pub fn connect_from_env(max_batch: usize, flush_interval: Duration) -> Option<Self> {
+        let (connection_string, ssl) = postgres_env_connection_string()?;
+        Some(Self::connect_with_batching_tls(connection_string, ssl, max_batch, flush_interval))
+    }
+}
+
+/// Reads `WWATCHER_PG_*` environment variables into a libpq-style DSN and an
+/// `ssl` flag. Returns `None` when `WWATCHER_PG_HOST` is unset, which keeps
+/// this env-based backend selection opt-in alongside the existing
+/// `config.postgres_alert_url` DSN path.
+fn postgres_env_connection_string() -> Option<(String, bool)> {
+    let host = std::env::var("WWATCHER_PG_HOST").ok()?;
+    let port = std::env::var("WWATCHER_PG_PORT").unwrap_or_else(|_| "5432".to_string());
+    let user = std::env::var("WWATCHER_PG_USER").unwrap_or_else(|_| "postgres".to_string());
+    let dbname = std::env::var("WWATCHER_PG_DBNAME").unwrap_or_else(|_| "postgres".to_string());
+    let ssl = matches!(
+        std::env::var("WWATCHER_PG_SSL").as_deref(),
+        Ok("true") | Ok("1")
+    );
+
+    let mut dsn = format!("host={} port={} user={} dbname={}", host, port, user, dbname);
+    if let Ok(password) = std::env::var("WWATCHER_PG_PASSWORD") {
+        dsn.push_str(&format!(" password={}", password));
+    }
+
+    Some((dsn, ssl))
+}
+
+impl AlertSink for PostgresSink {
+    fn write(&self, params: &LogAlertParams) -> Option<i64> {
+        if self.tx.try_send(params.clone()).is_err() {
+            eprintln!("[alerts] Postgres sink backlog full or closed, dropping alert");
+        }
+        None
+    }
+}
+
+/// Connects with or without TLS depending on `ssl`. Both branches return a
+/// boxed error so the caller doesn't need to match on `tokio_postgres::Error`
+/// vs. `native_tls::Error` separately.
+async fn connect_postgres(connection_string: &str, ssl: bool) -> Result<tokio_postgres::Client, Box<dyn std::error::Error + Send + Sync>> {
+    if ssl {
+        let connector = native_tls::TlsConnector::new()?;
+        let connector = postgres_native_tls::MakeTlsConnector::new(connector);
+        let (client, connection) = tokio_postgres::connect(connection_string, connector).await?;
+        tokio::spawn(async move {
+            if let Err(e) = connection.await {
+                eprintln!("[alerts] Postgres connection closed: {}", e);
+            }
+        });
+        Ok(client)
+    } else {
+        let (client, connection) = tokio_postgres::connect(connection_string, NoTls).await?;
+        tokio::spawn(async move {
+            if let Err(e) = connection.await {
+                eprintln!("[alerts] Postgres connection closed: {}", e);
+            }
+        });
+        Ok(client)
+    }
+}
+
+async fn run_postgres_sink(
+    connection_string: String,
+    ssl: bool,
+    mut rx: mpsc::Receiver<LogAlertParams>,
+    max_batch: usize,
+    flush_interval: Duration,
+) {
+    let mut buffer: Vec<LogAlertParams> = Vec::with_capacity(max_batch);
+
+    'reconnect: loop {
+        let client = match connect_postgres(&connection_string, ssl).await {
+            Ok(client) => client,
+            Err(e) => {
+                eprintln!("[alerts] Postgres sink connect failed: {}, retrying in {:?}", e, flush_interval);
+                tokio::time::sleep(flush_interval).await;
+                continue;
+            }
+        };
+
+        let mut ticker = tokio::time::interval(flush_interval);
+
+        loop {
+            tokio::select! {
+                received = rx.recv() => {
+                    match received {
+                        Some(params) => {
+                            buffer.push(params);
+                            if buffer.len() >= max_batch && flush_batch(&client, &mut buffer).await.is_err() {
+                                continue 'reconnect;
+                            }
+                        }
+                        None => {
+                            let _ = flush_batch(&client, &mut buffer).await;
+                            return;
+                        }
+                    }
+                }
+                _ = ticker.tick() => {
+                    if !buffer.is_empty() && flush_batch(&client, &mut buffer).await.is_err() {
+                        continue 'reconnect;
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Flush `buffer` as a single batched multi-row `INSERT`, clearing it on
+/// success. Leaves `buffer` untouched on failure so the caller can retry
+/// the same rows once it has reconnected.
+async fn flush_batch(client: &tokio_postgres::Client, buffer: &mut Vec<LogAlertParams>) -> Result<(), tokio_postgres::Error> {
+    if buffer.is_empty() {
+        return Ok(());
+    }
+
+    const COLUMNS: usize = 13;
+    let mut query = String::from(
+        "INSERT INTO wwatcher_alerts \
+         (platform, alert_type, action, value, price, size, market_title, market_id, \
+          outcome, wallet_id, occurred_at, market_context, wallet_activity) VALUES ",
+    );
+    let mut values: Vec<&(dyn tokio_postgres::types::ToSql + Sync)> = Vec::with_capacity(buffer.len() * COLUMNS);
+
+    for (row, params) in buffer.iter().enumerate() {
+        if row > 0 {
+            query.push(',');
+        }
+        let base = row * COLUMNS;
+        query.push('(');
+        for col in 0..COLUMNS {
+            if col > 0 {
+                query.push(',');
+            }
+            query.push_str(&format!("${}", base + col + 1));
+        }
+        query.push(')');
+
+        values.push(&params.platform);
+        values.push(&params.alert_type);
+        values.push(&params.action);
+        values.push(&params.value);
+        values.push(&params.price);
+        values.push(&params.size);
+        values.push(&params.market_title);
+        values.push(&params.market_id);
+        values.push(&params.outcome);
+        values.push(&params.wallet_id);
+        values.push(&params.timestamp);
+        values.push(&params.market_context_json);
+        values.push(&params.wallet_activity_json);
+    }
+
+    client.execute(query.as_str(), &values).await?;
+    buffer.clear();
+    Ok(())
+}
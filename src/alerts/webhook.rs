@@ -1,19 +1,166 @@
 use colored::*;
 use serde_json::json;
 
-/// Sanitize text for messaging platforms that use Markdown/HTML parsing
-pub fn escape_special_chars(s: &str) -> String {
-    s.chars()
-        .map(|c| match c {
-            'a'..='z' | 'A'..='Z' | '0'..='9' | ' ' | ',' | ':' | '?' | '.' => c,
-            '(' | '[' | '{' => '(',
-            ')' | ']' | '}' => ')',
-            _ => ' ',
-        })
-        .collect::<String>()
-        .split_whitespace()
-        .collect::<Vec<&str>>()
-        .join(" ")
+use crate::metrics::metrics;
+
+/// Destination-specific text markup a message body is rendered for.
+/// Escaping is platform-specific because each platform reserves a different
+/// character set for its own markup — there's no single escape that's safe
+/// (or non-destructive) everywhere.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TextFormat {
+    /// Discord's subset of Markdown: `* _ ~ \`` and a leading `>` (blockquote).
+    Discord,
+    /// Telegram's MarkdownV2, which reserves a much larger character set and
+    /// requires every reserved character to be backslash-escaped, not just
+    /// the ones actually used as markup in a given message.
+    TelegramMarkdownV2,
+    /// No markup — pass text through unchanged.
+    Plain,
+}
+
+impl TextFormat {
+    /// Discord webhook URLs are self-identifying (`discord.com/api/webhooks/...`);
+    /// anything else is assumed plain until a Telegram sender exists to pick
+    /// `TelegramMarkdownV2` explicitly.
+    pub fn from_webhook_url(webhook_url: &str) -> Self {
+        if webhook_url.contains("discord.com/api/webhooks") {
+            TextFormat::Discord
+        } else {
+            TextFormat::Plain
+        }
+    }
+
+    /// Parse `config.text_format` ("discord" / "telegram_markdown_v2" /
+    /// "plain", case-insensitive). Unrecognized values return `None` so the
+    /// caller falls back to `from_webhook_url` instead of silently picking
+    /// the wrong format.
+    pub fn from_config_str(s: &str) -> Option<Self> {
+        match s.to_lowercase().as_str() {
+            "discord" => Some(TextFormat::Discord),
+            "telegram_markdown_v2" | "telegram" => Some(TextFormat::TelegramMarkdownV2),
+            "plain" => Some(TextFormat::Plain),
+            _ => None,
+        }
+    }
+
+    /// Resolve the format to render a message in: an explicit
+    /// `config.text_format` override takes priority, since that's the only
+    /// way to ever reach `TelegramMarkdownV2` (Telegram bot webhook URLs
+    /// aren't self-identifying the way Discord's are); otherwise fall back
+    /// to sniffing `webhook_url`.
+    pub fn resolve(webhook_url: &str, config_override: Option<&str>) -> Self {
+        config_override
+            .and_then(Self::from_config_str)
+            .unwrap_or_else(|| Self::from_webhook_url(webhook_url))
+    }
+}
+
+/// Escape `s` for safe rendering under `format`, preserving every character
+/// that isn't actually reserved by that destination's markup — unlike the
+/// old whitelist-based stripper, Unicode, digits, and punctuation like `$`/`%`
+/// survive untouched.
+pub fn escape_for(format: TextFormat, s: &str) -> String {
+    match format {
+        TextFormat::Discord => escape_discord(s),
+        TextFormat::TelegramMarkdownV2 => escape_telegram_markdown_v2(s),
+        TextFormat::Plain => s.to_string(),
+    }
+}
+
+/// Escapes Discord's Markdown reserved characters (`* _ ~ \`` anywhere, `>`
+/// only where it would start a blockquote) by backslash-prefixing them.
+fn escape_discord(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    let mut at_line_start = true;
+    for c in s.chars() {
+        if matches!(c, '*' | '_' | '~' | '`' | '\\') || (c == '>' && at_line_start) {
+            out.push('\\');
+        }
+        out.push(c);
+        at_line_start = c == '\n';
+    }
+    out
+}
+
+/// Escapes every character in Telegram's MarkdownV2 reserved set, per
+/// https://core.telegram.org/bots/api#markdownv2-style: `_ * [ ] ( ) ~ \` > # + - = | { } . !`.
+fn escape_telegram_markdown_v2(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for c in s.chars() {
+        if matches!(
+            c,
+            '_' | '*' | '[' | ']' | '(' | ')' | '~' | '`' | '>' | '#' | '+' | '-' | '=' | '|' | '{' | '}' | '.' | '!' | '\\'
+        ) {
+            out.push('\\');
+        }
+        out.push(c);
+    }
+    out
+}
+
+/// Send a whale/arbitrage alert (the payload from `build_alert_payload`) to
+/// a generic or Discord webhook. Unlike `send_execution_alert`/
+/// `send_exit_alert`, there's no copy-trade-specific data to format into a
+/// richer embed, so both branches post the same JSON — Discord just needs
+/// it wrapped in a code block to render legibly in a message.
+///
+/// `text_format_override` is `config.text_format`, if set — see
+/// `TextFormat::resolve`.
+pub async fn send_webhook_alert(
+    webhook_url: &str,
+    alert: &super::AlertData<'_>,
+    text_format_override: Option<&str>,
+) {
+    let format = TextFormat::resolve(webhook_url, text_format_override);
+    let is_discord = format == TextFormat::Discord;
+    let body = super::build_alert_payload(alert, format);
+
+    let payload = if is_discord {
+        json!({ "content": format!("```json\n{}\n```", serde_json::to_string_pretty(&body).unwrap_or_default()) })
+    } else {
+        body
+    };
+
+    let client = match reqwest::Client::builder()
+        .timeout(std::time::Duration::from_secs(5))
+        .build()
+    {
+        Ok(c) => c,
+        Err(e) => {
+            eprintln!(
+                "{} Failed to create webhook client: {}",
+                "[WEBHOOK ERROR]".red(),
+                e
+            );
+            metrics().webhook_failures.inc();
+            return;
+        }
+    };
+
+    let start = std::time::Instant::now();
+    match client.post(webhook_url).json(&payload).send().await {
+        Ok(response) => {
+            if !response.status().is_success() {
+                let status = response.status();
+                let body = response.text().await.unwrap_or_default();
+                eprintln!(
+                    "{} Webhook failed ({}): {}",
+                    "[WEBHOOK ERROR]".red(),
+                    status,
+                    body
+                );
+                metrics().webhook_failures.inc();
+            }
+        }
+        Err(e) => {
+            eprintln!("{} Failed to send webhook: {}", "[WEBHOOK ERROR]".red(), e);
+            metrics().webhook_failures.inc();
+        }
+    }
+    metrics()
+        .webhook_latency_ms
+        .observe(start.elapsed().as_millis() as f64);
 }
 
 /// Info about an executed Kalshi trade, used to build rich Discord embeds.
@@ -33,13 +180,24 @@ pub struct ExecutionAlert {
 }
 
 /// Send a rich Discord embed for an executed Kalshi trade.
-pub async fn send_execution_alert(webhook_url: &str, alert: &ExecutionAlert) {
-    let is_discord = webhook_url.contains("discord.com/api/webhooks");
+///
+/// `text_format_override` is `config.text_format`, if set — see
+/// `TextFormat::resolve`.
+pub async fn send_execution_alert(
+    webhook_url: &str,
+    alert: &ExecutionAlert,
+    text_format_override: Option<&str>,
+) {
+    let format = TextFormat::resolve(webhook_url, text_format_override);
 
-    let payload = if is_discord {
-        build_discord_embed(alert)
-    } else {
-        build_generic_payload(alert)
+    metrics().executed_trades_by_side.inc(alert.side.to_lowercase());
+    metrics().execution_ev_cents.observe(alert.ev_cents);
+    metrics().execution_kelly_pct.observe(alert.kelly_pct);
+    metrics().execution_balance_after_cents.set(alert.balance_after_cents.max(0) as u64);
+
+    let payload = match format {
+        TextFormat::Discord => build_discord_embed(alert),
+        TextFormat::TelegramMarkdownV2 | TextFormat::Plain => build_generic_payload(alert),
     };
 
     let client = match reqwest::Client::builder()
@@ -53,6 +211,7 @@ pub async fn send_execution_alert(webhook_url: &str, alert: &ExecutionAlert) {
                 "[WEBHOOK ERROR]".red(),
                 e
             );
+            metrics().webhook_failures.inc();
             return;
         }
     };
@@ -68,10 +227,12 @@ pub async fn send_execution_alert(webhook_url: &str, alert: &ExecutionAlert) {
                     status,
                     body
                 );
+                metrics().webhook_failures.inc();
             }
         }
         Err(e) => {
             eprintln!("{} Failed to send webhook: {}", "[WEBHOOK ERROR]".red(), e);
+            metrics().webhook_failures.inc();
         }
     }
 }
@@ -83,7 +244,7 @@ fn build_discord_embed(a: &ExecutionAlert) -> serde_json::Value {
     json!({
         "embeds": [{
             "title": format!("Trade Executed: {} {}", side_upper, a.kalshi_ticker),
-            "description": format!("Matched from Polymarket: *{}*", escape_special_chars(&a.poly_title)),
+            "description": format!("Matched from Polymarket: *{}*", escape_for(TextFormat::Discord, &a.poly_title)),
             "color": color,
             "fields": [
                 { "name": "Side",     "value": side_upper,                                          "inline": true },
@@ -120,3 +281,158 @@ fn build_generic_payload(a: &ExecutionAlert) -> serde_json::Value {
         "timestamp": chrono::Utc::now().to_rfc3339(),
     })
 }
+
+/// Info about a closed Kalshi position, used to build rich Discord embeds
+/// for take-profit/stop-loss/settlement exits.
+pub struct ExitAlert {
+    pub kalshi_ticker: String,
+    pub side: String,
+    pub count: i32,
+    pub entry_price_cents: i64,
+    pub exit_price_cents: i64,
+    pub reason: String,
+    pub realized_pnl_cents: i64,
+    pub order_id: String,
+}
+
+/// Send a rich Discord embed for a closed Kalshi position.
+pub async fn send_exit_alert(webhook_url: &str, alert: &ExitAlert) {
+    let is_discord = webhook_url.contains("discord.com/api/webhooks");
+
+    let payload = if is_discord {
+        build_discord_exit_embed(alert)
+    } else {
+        build_generic_exit_payload(alert)
+    };
+
+    let client = match reqwest::Client::builder()
+        .timeout(std::time::Duration::from_secs(5))
+        .build()
+    {
+        Ok(c) => c,
+        Err(e) => {
+            eprintln!(
+                "{} Failed to create webhook client: {}",
+                "[WEBHOOK ERROR]".red(),
+                e
+            );
+            return;
+        }
+    };
+
+    match client.post(webhook_url).json(&payload).send().await {
+        Ok(response) => {
+            if !response.status().is_success() {
+                let status = response.status();
+                let body = response.text().await.unwrap_or_default();
+                eprintln!(
+                    "{} Webhook failed ({}): {}",
+                    "[WEBHOOK ERROR]".red(),
+                    status,
+                    body
+                );
+            }
+        }
+        Err(e) => {
+            eprintln!("{} Failed to send webhook: {}", "[WEBHOOK ERROR]".red(), e);
+        }
+    }
+}
+
+fn build_discord_exit_embed(a: &ExitAlert) -> serde_json::Value {
+    let side_upper = a.side.to_uppercase();
+    let color = if a.realized_pnl_cents >= 0 { 0x00cc66 } else { 0xff4444 };
+
+    json!({
+        "embeds": [{
+            "title": format!("Position Closed: {} {}", side_upper, a.kalshi_ticker),
+            "description": format!("Exit reason: *{}*", a.reason),
+            "color": color,
+            "fields": [
+                { "name": "Side",        "value": side_upper,                                              "inline": true },
+                { "name": "Qty",         "value": format!("{}", a.count),                                  "inline": true },
+                { "name": "Entry",       "value": format!("{}c", a.entry_price_cents),                     "inline": true },
+                { "name": "Exit",        "value": format!("{}c", a.exit_price_cents),                      "inline": true },
+                { "name": "Realized PnL","value": format!("${:.2}", a.realized_pnl_cents as f64 / 100.0),  "inline": true },
+            ],
+            "footer": { "text": format!("Order: {}", a.order_id) },
+            "timestamp": chrono::Utc::now().to_rfc3339(),
+        }]
+    })
+}
+
+fn build_generic_exit_payload(a: &ExitAlert) -> serde_json::Value {
+    json!({
+        "event": "position_closed",
+        "kalshi_ticker": a.kalshi_ticker,
+        "side": a.side,
+        "count": a.count,
+        "entry_price_cents": a.entry_price_cents,
+        "exit_price_cents": a.exit_price_cents,
+        "reason": a.reason,
+        "realized_pnl_cents": a.realized_pnl_cents,
+        "order_id": a.order_id,
+        "timestamp": chrono::Utc::now().to_rfc3339(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn discord_escapes_markup_but_preserves_everything_else() {
+        let escaped = escape_for(TextFormat::Discord, "50% chance: $5 *bold* move — naïve");
+        assert_eq!(escaped, "50% chance: $5 \\*bold\\* move — naïve");
+    }
+
+    #[test]
+    fn discord_only_escapes_blockquote_at_line_start() {
+        assert_eq!(escape_for(TextFormat::Discord, ">quote"), "\\>quote");
+        assert_eq!(escape_for(TextFormat::Discord, "5 > 3"), "5 > 3");
+    }
+
+    #[test]
+    fn telegram_escapes_full_reserved_set() {
+        let escaped = escape_for(TextFormat::TelegramMarkdownV2, "Win 50%! Price-target.");
+        assert_eq!(escaped, "Win 50%\\! Price\\-target\\.");
+    }
+
+    #[test]
+    fn plain_passes_text_through_unchanged() {
+        let text = "50% chance: $5 *bold* — naïve";
+        assert_eq!(escape_for(TextFormat::Plain, text), text);
+    }
+
+    #[test]
+    fn from_webhook_url_detects_discord() {
+        assert_eq!(
+            TextFormat::from_webhook_url("https://discord.com/api/webhooks/123/abc"),
+            TextFormat::Discord
+        );
+        assert_eq!(
+            TextFormat::from_webhook_url("https://example.com/hook"),
+            TextFormat::Plain
+        );
+    }
+
+    #[test]
+    fn resolve_prefers_config_override_over_url_heuristic() {
+        assert_eq!(
+            TextFormat::resolve("https://discord.com/api/webhooks/123/abc", Some("telegram_markdown_v2")),
+            TextFormat::TelegramMarkdownV2
+        );
+    }
+
+    #[test]
+    fn resolve_falls_back_to_url_heuristic_when_override_is_unset_or_unknown() {
+        assert_eq!(
+            TextFormat::resolve("https://discord.com/api/webhooks/123/abc", None),
+            TextFormat::Discord
+        );
+        assert_eq!(
+            TextFormat::resolve("https://discord.com/api/webhooks/123/abc", Some("nonsense")),
+            TextFormat::Discord
+        );
+    }
+}
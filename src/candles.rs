@@ -0,0 +1,683 @@
+//! OHLCV candle aggregation over logged whale trades (both Kalshi and
+//! Polymarket), the way a fill-event indexer buckets fills into candles at
+//! multiple resolutions. Candles live in the `candles` table (see
+//! `db::migrate_v7_candles_platform`) keyed by `(platform, market, resolution,
+//! start_ts)`.
+//!
+//! `record_trade` upserts one bucket directly — used by `backfill` to replay
+//! a time range from scratch so re-running it after a gap never double-counts
+//! volume. The hot path instead goes through `CandleCache`, which keeps each
+//! `(platform, market, resolution)`'s currently-open candle in memory and
+//! only hits SQLite when a bucket completes, so a busy market isn't upserting
+//! on every single trade.
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use rusqlite::{params, Connection};
+
+use crate::kalshi::Trade;
+
+/// A candle resolution. `seconds()` is the bucket width used to compute
+/// `start_ts = timestamp - (timestamp % seconds())`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Resolution {
+    OneMinute,
+    FiveMinutes,
+    FifteenMinutes,
+    OneHour,
+    FourHours,
+    OneDay,
+}
+
+/// Every resolution a trade is rolled into on ingest.
+pub const ALL_RESOLUTIONS: [Resolution; 6] = [
+    Resolution::OneMinute,
+    Resolution::FiveMinutes,
+    Resolution::FifteenMinutes,
+    Resolution::OneHour,
+    Resolution::FourHours,
+    Resolution::OneDay,
+];
+
+impl Resolution {
+    pub fn seconds(self) -> i64 {
+        match self {
+            Resolution::OneMinute => 60,
+            Resolution::FiveMinutes => 300,
+            Resolution::FifteenMinutes => 900,
+            Resolution::OneHour => 3600,
+            Resolution::FourHours => 14400,
+            Resolution::OneDay => 86400,
+        }
+    }
+
+    /// Stable text form stored in the `candles.resolution` column.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Resolution::OneMinute => "1m",
+            Resolution::FiveMinutes => "5m",
+            Resolution::FifteenMinutes => "15m",
+            Resolution::OneHour => "1h",
+            Resolution::FourHours => "4h",
+            Resolution::OneDay => "1d",
+        }
+    }
+
+    pub fn from_str(s: &str) -> Option<Self> {
+        match s {
+            "1m" => Some(Resolution::OneMinute),
+            "5m" => Some(Resolution::FiveMinutes),
+            "15m" => Some(Resolution::FifteenMinutes),
+            "1h" => Some(Resolution::OneHour),
+            "4h" => Some(Resolution::FourHours),
+            "1d" => Some(Resolution::OneDay),
+            _ => None,
+        }
+    }
+}
+
+/// One OHLCV bucket for `market` on `platform` at `resolution`, starting at
+/// `start_ts` (unix seconds). `last_ts` is the timestamp of the latest trade
+/// folded in so far, used to decide whether a new trade should move `close`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Candle {
+    pub platform: String,
+    pub market: String,
+    pub resolution: Resolution,
+    pub start_ts: i64,
+    pub open: f64,
+    pub high: f64,
+    pub low: f64,
+    pub close: f64,
+    pub volume: f64,
+    pub trade_count: i64,
+    pub last_ts: i64,
+}
+
+fn bucket_start(timestamp: i64, resolution: Resolution) -> i64 {
+    timestamp - timestamp.rem_euclid(resolution.seconds())
+}
+
+/// Fold one trade directly into its `(platform, market, resolution,
+/// bucket_start)` candle via an UPSERT: the first trade in a bucket seeds
+/// open/high/low/close, later trades widen high/low, accumulate volume
+/// (`price * size`) and trade_count, and only move `close` if this trade is
+/// the newest one seen for the bucket so far — trades can arrive out of
+/// order on backfill/replay.
+pub fn record_trade(
+    conn: &Connection,
+    platform: &str,
+    market: &str,
+    resolution: Resolution,
+    timestamp: i64,
+    price: f64,
+    size: f64,
+) -> rusqlite::Result<()> {
+    let start_ts = bucket_start(timestamp, resolution);
+    let volume = price * size;
+
+    conn.execute(
+        "INSERT INTO candles (platform, market, resolution, start_ts, open, high, low, close, volume, trade_count, last_ts)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?5, ?5, ?5, ?6, 1, ?7)
+         ON CONFLICT(platform, market, resolution, start_ts) DO UPDATE SET
+             high = MAX(candles.high, excluded.high),
+             low = MIN(candles.low, excluded.low),
+             close = CASE WHEN excluded.last_ts >= candles.last_ts THEN excluded.close ELSE candles.close END,
+             last_ts = MAX(candles.last_ts, excluded.last_ts),
+             volume = candles.volume + excluded.volume,
+             trade_count = candles.trade_count + 1",
+        params![platform, market, resolution.as_str(), start_ts, price, volume, timestamp],
+    )?;
+
+    Ok(())
+}
+
+/// Roll a batch of `fetch_recent_trades` results into candles at every
+/// resolution in `ALL_RESOLUTIONS`. Trades with an unparseable
+/// `created_time` are skipped, matching the feed's own silent-skip
+/// handling of malformed entries.
+pub fn ingest_trades(conn: &Connection, trades: &[Trade]) -> rusqlite::Result<()> {
+    for trade in trades {
+        let Some(timestamp) = trade_timestamp(trade) else { continue };
+        for resolution in ALL_RESOLUTIONS {
+            record_trade(conn, "Kalshi", &trade.ticker, resolution, timestamp, trade.price, trade.count as f64)?;
+        }
+    }
+    Ok(())
+}
+
+fn trade_timestamp(trade: &Trade) -> Option<i64> {
+    chrono::DateTime::parse_from_rfc3339(&trade.created_time)
+        .ok()
+        .map(|dt| dt.timestamp())
+}
+
+/// Keeps each `(platform, market, resolution)`'s currently-open candle in
+/// memory so the hot trade-processing path (`watch_whales`) doesn't hit
+/// SQLite on every single trade. Only flushed to `candles` when a bucket
+/// actually completes (a later trade lands in the next bucket) or when
+/// `flush_stale` is called on the existing prune cycle, so low-volume
+/// markets whose bucket never "completes" still end up persisted.
+pub struct CandleCache {
+    open: Mutex<HashMap<(String, String, Resolution), Candle>>,
+    /// Resolutions `record_at_all_resolutions` folds each trade into.
+    /// Defaults to `ALL_RESOLUTIONS`; `set_active_resolutions` narrows this
+    /// to `Config::candle_intervals` so a deployment that only cares about
+    /// `1h`/`1d` bars isn't paying to maintain `1m` buckets it never reads.
+    active: Mutex<Vec<Resolution>>,
+}
+
+impl Default for CandleCache {
+    fn default() -> Self {
+        Self {
+            open: Mutex::new(HashMap::new()),
+            active: Mutex::new(ALL_RESOLUTIONS.to_vec()),
+        }
+    }
+}
+
+impl CandleCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Narrow (or widen) which resolutions `record_at_all_resolutions`
+    /// maintains going forward. An empty `resolutions` is treated as "use
+    /// the default set" rather than "maintain nothing" — a blank
+    /// `candle_intervals` in config shouldn't silently stop candle
+    /// generation.
+    pub fn set_active_resolutions(&self, resolutions: Vec<Resolution>) {
+        let mut active = self.active.lock().unwrap();
+        *active = if resolutions.is_empty() {
+            ALL_RESOLUTIONS.to_vec()
+        } else {
+            resolutions
+        };
+    }
+
+    /// Fold one trade into its in-memory open candle. If a prior open candle
+    /// for this `(platform, market, resolution)` belongs to an earlier
+    /// bucket, it's flushed to `candles` via `flush_one` first and
+    /// replaced with a fresh one. A trade older than the open candle's
+    /// bucket is upserted straight into `candles` via `record_trade`,
+    /// leaving the open candle untouched.
+    pub fn record(
+        &self,
+        conn: &Connection,
+        platform: &str,
+        market: &str,
+        resolution: Resolution,
+        timestamp: i64,
+        price: f64,
+        size: f64,
+    ) -> rusqlite::Result<()> {
+        let start_ts = bucket_start(timestamp, resolution);
+        let volume = price * size;
+        let key = (platform.to_string(), market.to_string(), resolution);
+
+        let mut open = self.open.lock().unwrap();
+        match open.get_mut(&key) {
+            Some(candle) if candle.start_ts == start_ts => {
+                candle.high = candle.high.max(price);
+                candle.low = candle.low.min(price);
+                if timestamp >= candle.last_ts {
+                    candle.close = price;
+                    candle.last_ts = timestamp;
+                }
+                candle.volume += volume;
+                candle.trade_count += 1;
+                Ok(())
+            }
+            Some(candle) if start_ts < candle.start_ts => {
+                // An out-of-order trade for an earlier bucket — merge it
+                // into `candles` directly rather than displacing the open one.
+                record_trade(conn, platform, market, resolution, timestamp, price, size)
+            }
+            Some(candle) => {
+                // A later bucket arrived — flush the completed candle and
+                // start a fresh one.
+                flush_one(conn, candle)?;
+                *candle = Candle {
+                    platform: platform.to_string(),
+                    market: market.to_string(),
+                    resolution,
+                    start_ts,
+                    open: price,
+                    high: price,
+                    low: price,
+                    close: price,
+                    volume,
+                    trade_count: 1,
+                    last_ts: timestamp,
+                };
+                Ok(())
+            }
+            None => {
+                open.insert(
+                    key,
+                    Candle {
+                        platform: platform.to_string(),
+                        market: market.to_string(),
+                        resolution,
+                        start_ts,
+                        open: price,
+                        high: price,
+                        low: price,
+                        close: price,
+                        volume,
+                        trade_count: 1,
+                        last_ts: timestamp,
+                    },
+                );
+                Ok(())
+            }
+        }
+    }
+
+    /// Fold one Kalshi/Polymarket trade into every currently active
+    /// resolution at once (see `set_active_resolutions`), matching
+    /// `ingest_trades`' per-trade fan-out but through the hot cache instead
+    /// of direct UPSERTs.
+    pub fn record_at_all_resolutions(
+        &self,
+        conn: &Connection,
+        platform: &str,
+        market: &str,
+        timestamp: i64,
+        price: f64,
+        size: f64,
+    ) -> rusqlite::Result<()> {
+        let resolutions = self.active.lock().unwrap().clone();
+        for resolution in resolutions {
+            self.record(conn, platform, market, resolution, timestamp, price, size)?;
+        }
+        Ok(())
+    }
+
+    /// Flush every still-open candle to `candles`, keeping it in memory
+    /// (it's still the current bucket, just persisted so it shows up in
+    /// `get_candles` before the bucket completes). Meant to run on the
+    /// existing 60-tick prune cycle alongside the other periodic
+    /// maintenance, so a quiet market's open candle isn't lost if the
+    /// process restarts before its bucket ever completes.
+    pub fn flush_stale(&self, conn: &Connection) -> rusqlite::Result<()> {
+        let open = self.open.lock().unwrap();
+        for candle in open.values() {
+            flush_one(conn, candle)?;
+        }
+        Ok(())
+    }
+}
+
+fn flush_one(conn: &Connection, candle: &Candle) -> rusqlite::Result<()> {
+    conn.execute(
+        "INSERT INTO candles (platform, market, resolution, start_ts, open, high, low, close, volume, trade_count, last_ts)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)
+         ON CONFLICT(platform, market, resolution, start_ts) DO UPDATE SET
+             open = excluded.open,
+             high = excluded.high,
+             low = excluded.low,
+             close = excluded.close,
+             volume = excluded.volume,
+             trade_count = excluded.trade_count,
+             last_ts = excluded.last_ts",
+        params![
+            candle.platform,
+            candle.market,
+            candle.resolution.as_str(),
+            candle.start_ts,
+            candle.open,
+            candle.high,
+            candle.low,
+            candle.close,
+            candle.volume,
+            candle.trade_count,
+            candle.last_ts,
+        ],
+    )?;
+    Ok(())
+}
+
+/// Regenerate `market`'s `resolution` candles on `platform` over `[from_ts,
+/// to_ts]` from scratch: existing candles in that bucket range are cleared
+/// first, then replayed from `wallet_memory` rows (the closest thing to a
+/// persisted trade log for a market) in timestamp order. Safe to re-run over
+/// the same range — the clear-then-rebuild means it never double-counts
+/// volume the way calling `record_trade` twice on the same row would.
+pub fn backfill(
+    conn: &Connection,
+    platform: &str,
+    market: &str,
+    resolution: Resolution,
+    from_ts: i64,
+    to_ts: i64,
+) -> rusqlite::Result<usize> {
+    conn.execute(
+        "DELETE FROM candles WHERE platform = ?1 AND market = ?2 AND resolution = ?3 AND start_ts >= ?4 AND start_ts <= ?5",
+        params![platform, market, resolution.as_str(), bucket_start(from_ts, resolution), to_ts],
+    )?;
+
+    let mut stmt = conn.prepare(
+        "SELECT seen_at, price, value FROM wallet_memory
+         WHERE market_id = ?1 AND seen_at >= ?2 AND seen_at <= ?3
+         ORDER BY seen_at ASC",
+    )?;
+    let rows = stmt.query_map(params![market, from_ts, to_ts], |row| {
+        Ok((row.get::<_, i64>(0)?, row.get::<_, f64>(1)?, row.get::<_, f64>(2)?))
+    })?;
+
+    let mut replayed = 0usize;
+    for row in rows {
+        let (seen_at, price, value) = row?;
+        if price <= 0.0 {
+            continue;
+        }
+        record_trade(conn, platform, market, resolution, seen_at, price, value / price)?;
+        replayed += 1;
+    }
+
+    Ok(replayed)
+}
+
+/// Like `backfill`, but sources from `backfill_trades` (see
+/// `db::migrate_v11_raw_trades`) instead of `wallet_memory`, across every
+/// market a window touches and every resolution in `ALL_RESOLUTIONS` rather
+/// than one at a time — the bulk counterpart `commands::backfill`'s two-phase
+/// rebuild calls once a window's raw trades are persisted. Also
+/// clear-then-rebuild, so restarting after an interrupted rebuild over the
+/// same window lands on the same candles rather than double-counting.
+pub fn rebuild_from_raw_trades(conn: &Connection, platform: &str, from_ts: i64, to_ts: i64) -> rusqlite::Result<()> {
+    let markets: Vec<String> = {
+        let mut stmt = conn.prepare(
+            "SELECT DISTINCT market FROM backfill_trades WHERE platform = ?1 AND ts_unix >= ?2 AND ts_unix <= ?3",
+        )?;
+        let rows = stmt.query_map(params![platform, from_ts, to_ts], |row| row.get::<_, String>(0))?;
+        rows.collect::<rusqlite::Result<Vec<_>>>()?
+    };
+
+    for market in &markets {
+        for resolution in ALL_RESOLUTIONS {
+            conn.execute(
+                "DELETE FROM candles WHERE platform = ?1 AND market = ?2 AND resolution = ?3 AND start_ts >= ?4 AND start_ts <= ?5",
+                params![platform, market, resolution.as_str(), bucket_start(from_ts, resolution), to_ts],
+            )?;
+
+            let mut stmt = conn.prepare(
+                "SELECT ts_unix, price, size FROM backfill_trades
+                 WHERE platform = ?1 AND market = ?2 AND ts_unix >= ?3 AND ts_unix <= ?4
+                 ORDER BY ts_unix ASC",
+            )?;
+            let rows = stmt.query_map(params![platform, market, from_ts, to_ts], |row| {
+                Ok((row.get::<_, i64>(0)?, row.get::<_, f64>(1)?, row.get::<_, f64>(2)?))
+            })?;
+
+            for row in rows {
+                let (ts_unix, price, size) = row?;
+                record_trade(conn, platform, market, resolution, ts_unix, price, size)?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Most recent `limit` candles for `market` on `platform` at `resolution`,
+/// newest first.
+pub fn get_candles(
+    conn: &Connection,
+    platform: &str,
+    market: &str,
+    resolution: Resolution,
+    limit: u32,
+) -> rusqlite::Result<Vec<Candle>> {
+    let mut stmt = conn.prepare(
+        "SELECT platform, market, resolution, start_ts, open, high, low, close, volume, trade_count, last_ts
+         FROM candles
+         WHERE platform = ?1 AND market = ?2 AND resolution = ?3
+         ORDER BY start_ts DESC
+         LIMIT ?4",
+    )?;
+
+    let rows = stmt.query_map(params![platform, market, resolution.as_str(), limit], |row| {
+        let resolution_str: String = row.get(2)?;
+        Ok(Candle {
+            platform: row.get(0)?,
+            market: row.get(1)?,
+            resolution: Resolution::from_str(&resolution_str).unwrap_or(resolution),
+            start_ts: row.get(3)?,
+            open: row.get(4)?,
+            high: row.get(5)?,
+            low: row.get(6)?,
+            close: row.get(7)?,
+            volume: row.get(8)?,
+            trade_count: row.get(9)?,
+            last_ts: row.get(10)?,
+        })
+    })?;
+
+    rows.collect()
+}
+
+/// Every candle for `market` on `platform` at `resolution` whose bucket
+/// starts in `[from, to]` (unix seconds), oldest first — the time-ranged
+/// counterpart to `get_candles`' most-recent-`limit` query, for a CLI query
+/// command or a chart that wants a specific window rather than just "the
+/// last N bars".
+pub fn build_candles(
+    conn: &Connection,
+    platform: &str,
+    market: &str,
+    resolution: Resolution,
+    from: i64,
+    to: i64,
+) -> rusqlite::Result<Vec<Candle>> {
+    let mut stmt = conn.prepare(
+        "SELECT platform, market, resolution, start_ts, open, high, low, close, volume, trade_count, last_ts
+         FROM candles
+         WHERE platform = ?1 AND market = ?2 AND resolution = ?3 AND start_ts BETWEEN ?4 AND ?5
+         ORDER BY start_ts ASC",
+    )?;
+
+    let rows = stmt.query_map(params![platform, market, resolution.as_str(), from, to], |row| {
+        let resolution_str: String = row.get(2)?;
+        Ok(Candle {
+            platform: row.get(0)?,
+            market: row.get(1)?,
+            resolution: Resolution::from_str(&resolution_str).unwrap_or(resolution),
+            start_ts: row.get(3)?,
+            open: row.get(4)?,
+            high: row.get(5)?,
+            low: row.get(6)?,
+            close: row.get(7)?,
+            volume: row.get(8)?,
+            trade_count: row.get(9)?,
+            last_ts: row.get(10)?,
+        })
+    })?;
+
+    rows.collect()
+}
+
+/// Fill gaps in a chronologically-ordered (oldest-first) candle series with
+/// flat, zero-volume candles carried forward at the prior bucket's close —
+/// charting whale-flow intensity needs an evenly-spaced series, and a quiet
+/// bucket shouldn't just vanish from the x-axis the way `get_candles`' raw
+/// query leaves it. `series` must already be a single `(platform, market,
+/// resolution)`'s candles in ascending `start_ts` order; an empty series is
+/// returned unchanged since there's no close to carry forward from.
+pub fn fill_gaps(series: &[Candle]) -> Vec<Candle> {
+    let Some(first) = series.first() else { return Vec::new() };
+    let step = first.resolution.seconds();
+
+    let mut filled = Vec::with_capacity(series.len());
+    let mut prev_close = first.open;
+    let mut next_ts = first.start_ts;
+
+    for candle in series {
+        while next_ts < candle.start_ts {
+            filled.push(Candle {
+                platform: candle.platform.clone(),
+                market: candle.market.clone(),
+                resolution: candle.resolution,
+                start_ts: next_ts,
+                open: prev_close,
+                high: prev_close,
+                low: prev_close,
+                close: prev_close,
+                volume: 0.0,
+                trade_count: 0,
+                last_ts: next_ts,
+            });
+            next_ts += step;
+        }
+        filled.push(candle.clone());
+        prev_close = candle.close;
+        next_ts = candle.start_ts + step;
+    }
+
+    filled
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn setup() -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        crate::db::run_migrations(&conn).unwrap();
+        conn
+    }
+
+    #[test]
+    fn first_trade_in_bucket_seeds_ohlc() {
+        let conn = setup();
+        record_trade(&conn, "Kalshi", "KXTEST", Resolution::OneMinute, 1_000, 0.55, 100.0).unwrap();
+
+        let candles = get_candles(&conn, "Kalshi", "KXTEST", Resolution::OneMinute, 10).unwrap();
+        assert_eq!(candles.len(), 1);
+        let candle = &candles[0];
+        assert_eq!(candle.start_ts, 960);
+        assert_eq!((candle.open, candle.high, candle.low, candle.close), (0.55, 0.55, 0.55, 0.55));
+        assert!((candle.volume - 55.0).abs() < 1e-9, "volume = price * size");
+        assert_eq!(candle.trade_count, 1);
+    }
+
+    #[test]
+    fn subsequent_trades_widen_high_low_and_track_latest_close() {
+        let conn = setup();
+        record_trade(&conn, "Kalshi", "KXTEST", Resolution::OneMinute, 960, 0.50, 10.0).unwrap();
+        record_trade(&conn, "Kalshi", "KXTEST", Resolution::OneMinute, 970, 0.60, 10.0).unwrap();
+        record_trade(&conn, "Kalshi", "KXTEST", Resolution::OneMinute, 965, 0.40, 10.0).unwrap();
+
+        let candles = get_candles(&conn, "Kalshi", "KXTEST", Resolution::OneMinute, 10).unwrap();
+        assert_eq!(candles.len(), 1);
+        let candle = &candles[0];
+        assert_eq!(candle.open, 0.50);
+        assert_eq!(candle.high, 0.60);
+        assert_eq!(candle.low, 0.40);
+        assert_eq!(candle.close, 0.60, "close should track the latest timestamp, not insertion order");
+        assert_eq!(candle.trade_count, 3);
+    }
+
+    #[test]
+    fn trades_in_different_buckets_produce_separate_candles() {
+        let conn = setup();
+        record_trade(&conn, "Kalshi", "KXTEST", Resolution::OneMinute, 0, 0.50, 10.0).unwrap();
+        record_trade(&conn, "Kalshi", "KXTEST", Resolution::OneMinute, 90, 0.60, 10.0).unwrap();
+
+        let candles = get_candles(&conn, "Kalshi", "KXTEST", Resolution::OneMinute, 10).unwrap();
+        assert_eq!(candles.len(), 2);
+        assert_eq!(candles[0].start_ts, 60, "newest-first ordering");
+        assert_eq!(candles[1].start_ts, 0);
+    }
+
+    #[test]
+    fn platforms_with_the_same_market_id_dont_collide() {
+        let conn = setup();
+        record_trade(&conn, "Kalshi", "SAME-ID", Resolution::OneMinute, 0, 0.50, 10.0).unwrap();
+        record_trade(&conn, "Polymarket", "SAME-ID", Resolution::OneMinute, 0, 0.90, 10.0).unwrap();
+
+        let kalshi = get_candles(&conn, "Kalshi", "SAME-ID", Resolution::OneMinute, 10).unwrap();
+        let poly = get_candles(&conn, "Polymarket", "SAME-ID", Resolution::OneMinute, 10).unwrap();
+        assert_eq!(kalshi[0].close, 0.50);
+        assert_eq!(poly[0].close, 0.90);
+    }
+
+    #[test]
+    fn backfill_is_idempotent_over_the_same_range() {
+        let conn = setup();
+        conn.execute(
+            "INSERT INTO wallet_memory (wallet_hash, wallet_id, market_title, market_id, outcome, action, value, price, platform, seen_at)
+             VALUES ('h1', 'w1', 'Test', 'KXTEST', 'Yes', 'BUY', 50.0, 0.5, 'Kalshi', 100)",
+            [],
+        ).unwrap();
+
+        backfill(&conn, "Kalshi", "KXTEST", Resolution::OneMinute, 0, 200).unwrap();
+        let first = get_candles(&conn, "Kalshi", "KXTEST", Resolution::OneMinute, 10).unwrap();
+        backfill(&conn, "Kalshi", "KXTEST", Resolution::OneMinute, 0, 200).unwrap();
+        let second = get_candles(&conn, "Kalshi", "KXTEST", Resolution::OneMinute, 10).unwrap();
+
+        assert_eq!(first, second);
+        assert_eq!(second[0].volume, 50.0);
+        assert_eq!(second[0].trade_count, 1);
+    }
+
+    #[test]
+    fn cache_batches_same_bucket_trades_without_db_writes() {
+        let conn = setup();
+        let cache = CandleCache::new();
+        cache.record(&conn, "Kalshi", "KXTEST", Resolution::OneMinute, 960, 0.50, 10.0).unwrap();
+        cache.record(&conn, "Kalshi", "KXTEST", Resolution::OneMinute, 970, 0.60, 10.0).unwrap();
+
+        // Still in memory — nothing flushed to the DB yet.
+        assert!(get_candles(&conn, "Kalshi", "KXTEST", Resolution::OneMinute, 10).unwrap().is_empty());
+    }
+
+    #[test]
+    fn cache_flushes_completed_bucket_when_next_bucket_arrives() {
+        let conn = setup();
+        let cache = CandleCache::new();
+        cache.record(&conn, "Kalshi", "KXTEST", Resolution::OneMinute, 0, 0.50, 10.0).unwrap();
+        cache.record(&conn, "Kalshi", "KXTEST", Resolution::OneMinute, 90, 0.60, 10.0).unwrap();
+
+        let candles = get_candles(&conn, "Kalshi", "KXTEST", Resolution::OneMinute, 10).unwrap();
+        assert_eq!(candles.len(), 1, "only the completed bucket is flushed");
+        assert_eq!(candles[0].close, 0.50);
+        assert_eq!(candles[0].trade_count, 1);
+    }
+
+    #[test]
+    fn fill_gaps_carries_forward_the_prior_close_for_quiet_buckets() {
+        let conn = setup();
+        record_trade(&conn, "Kalshi", "KXTEST", Resolution::OneMinute, 0, 0.50, 10.0).unwrap();
+        record_trade(&conn, "Kalshi", "KXTEST", Resolution::OneMinute, 180, 0.70, 10.0).unwrap();
+
+        let mut series = get_candles(&conn, "Kalshi", "KXTEST", Resolution::OneMinute, 10).unwrap();
+        series.reverse(); // oldest-first, as fill_gaps expects
+
+        let filled = fill_gaps(&series);
+        assert_eq!(filled.len(), 4, "minutes 0, 60, 120, 180");
+        assert_eq!(filled[0].close, 0.50);
+        assert_eq!((filled[1].open, filled[1].close, filled[1].volume), (0.50, 0.50, 0.0));
+        assert_eq!((filled[2].open, filled[2].close, filled[2].volume), (0.50, 0.50, 0.0));
+        assert_eq!(filled[3].close, 0.70);
+    }
+
+    #[test]
+    fn fill_gaps_on_empty_series_returns_empty() {
+        assert!(fill_gaps(&[]).is_empty());
+    }
+
+    #[test]
+    fn flush_stale_persists_the_still_open_candle() {
+        let conn = setup();
+        let cache = CandleCache::new();
+        cache.record(&conn, "Kalshi", "KXTEST", Resolution::OneMinute, 0, 0.50, 10.0).unwrap();
+
+        cache.flush_stale(&conn).unwrap();
+
+        let candles = get_candles(&conn, "Kalshi", "KXTEST", Resolution::OneMinute, 10).unwrap();
+        assert_eq!(candles.len(), 1);
+        assert_eq!(candles[0].close, 0.50);
+    }
+}
@@ -2,10 +2,162 @@
 /// and series ticker mapping for Kalshi.
 
 use std::collections::HashMap;
+use std::path::Path;
+
+/// Convert a compiled `&'static str` keyword list into owned `String`s, so it can
+/// be merged with keywords loaded from the user's registry file.
+fn kw(keywords: Vec<&str>) -> Vec<String> {
+    keywords.into_iter().map(String::from).collect()
+}
+
+/// Split a title into lowercase word-boundary tokens, so keyword matching can't
+/// false-positive on substrings (e.g. "ADA" inside "Canada", "match" inside
+/// "matchup").
+fn tokenize(text: &str) -> Vec<String> {
+    text.to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|s| !s.is_empty())
+        .map(String::from)
+        .collect()
+}
+
+/// Levenshtein edit distance, used for typo-tolerant token matching.
+fn levenshtein(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    let mut dp = vec![vec![0usize; b.len() + 1]; a.len() + 1];
+    for (i, row) in dp.iter_mut().enumerate() {
+        row[0] = i;
+    }
+    for j in 0..=b.len() {
+        dp[0][j] = j;
+    }
+    for i in 1..=a.len() {
+        for j in 1..=b.len() {
+            let cost = if a[i - 1] == b[j - 1] { 0 } else { 1 };
+            dp[i][j] = (dp[i - 1][j] + 1).min(dp[i][j - 1] + 1).min(dp[i - 1][j - 1] + cost);
+        }
+    }
+    dp[a.len()][b.len()]
+}
+
+/// Two tokens match if they're equal, or — for tokens of 5+ chars — within a
+/// Levenshtein distance of 1, so "Djokovis"/"Bitcon" still resolve.
+fn tokens_match(title_token: &str, keyword_token: &str) -> bool {
+    if title_token == keyword_token {
+        return true;
+    }
+    title_token.len() >= 5 && keyword_token.len() >= 5 && levenshtein(title_token, keyword_token) <= 1
+}
+
+/// Does `keyword_tokens` appear as a contiguous span within `title_tokens`?
+/// Multi-word keywords must match every word in order; this is what keeps
+/// "Open Championship" from matching a title that only has "Open".
+fn keyword_spans(title_tokens: &[String], keyword_tokens: &[String]) -> bool {
+    if keyword_tokens.is_empty() || keyword_tokens.len() > title_tokens.len() {
+        return false;
+    }
+    title_tokens
+        .windows(keyword_tokens.len())
+        .any(|window| window.iter().zip(keyword_tokens).all(|(t, k)| tokens_match(t, k)))
+}
+
+/// Score a keyword list against the tokenized title: each matched keyword adds
+/// one point per word it spans (so multi-word matches outweigh single-word
+/// ones), and the longest matched keyword (by character length) is tracked for
+/// tie-breaking between equally-scored categories.
+fn score_keywords(title_tokens: &[String], kw_list: &[String]) -> Option<(u32, usize)> {
+    let mut score = 0u32;
+    let mut longest_match = 0usize;
+    for keyword in kw_list {
+        let keyword_tokens = tokenize(keyword);
+        if keyword_spans(title_tokens, &keyword_tokens) {
+            score += keyword_tokens.len() as u32;
+            longest_match = longest_match.max(keyword.len());
+        }
+    }
+    if score > 0 {
+        Some((score, longest_match))
+    } else {
+        None
+    }
+}
+
+/// Split a "category:subcategory" registry key into the pair `categorize`/
+/// `matches_selection` return.
+fn split_key(key: &str) -> (String, String) {
+    let parts: Vec<&str> = key.splitn(2, ':').collect();
+    (parts[0].to_string(), parts.get(1).unwrap_or(&"all").to_string())
+}
+
+/// The orthogonal label sets `CategoryRegistry::classify` attaches to a market
+/// title, on top of the primary topical `(category, subcategory)` pair:
+/// a regional tag and any entities (teams, tickers) recognized in the title.
+#[derive(Debug, Clone, Default)]
+pub struct MarketLabels {
+    /// Primary topical label, same as `categorize`/`matches_selection`.
+    pub category: Option<(String, String)>,
+    /// Coarse region the matched category belongs to ("us", "intl"), when known.
+    pub region: Option<String>,
+    /// Entity tags extracted from matched keywords, e.g. "team:Lakers", "ticker:SOL".
+    pub entities: Vec<String>,
+}
+
+/// Coarse region for a `category:subcategory` key, where the topic implies one.
+/// Categories with no strong regional association (tech, weather, health, ...)
+/// are left unlabeled rather than guessed.
+fn region_for(key: &str) -> Option<&'static str> {
+    match key {
+        "sports:nba" | "sports:nfl" | "sports:mlb" | "sports:nhl"
+        | "sports:college_football" | "sports:college_basketball" => Some("us"),
+        "sports:soccer" | "sports:tennis" | "sports:golf" | "sports:mma" => Some("intl"),
+        "politics:us_elections" | "politics:congress" | "politics:policy" => Some("us"),
+        "politics:international" => Some("intl"),
+        "economics:fed" | "economics:inflation" | "economics:jobs" | "economics:gdp"
+        | "economics:recession" => Some("us"),
+        _ if key.starts_with("world:") => Some("intl"),
+        _ => None,
+    }
+}
+
+/// The entity tag prefix for a top-level category, e.g. sports markets carry
+/// "team:" entities and crypto/finance markets carry "ticker:" entities.
+fn entity_kind_for(category: &str) -> Option<&'static str> {
+    match category {
+        "sports" => Some("team"),
+        "crypto" | "finance" => Some("ticker"),
+        _ => None,
+    }
+}
 
 pub struct CategoryRegistry {
     /// category:subcategory -> list of keywords for matching market titles
-    keywords: HashMap<String, Vec<&'static str>>,
+    keywords: HashMap<String, Vec<String>>,
+    /// category:subcategory -> keywords that double as entity tags (team names,
+    /// tickers), surfaced by `classify` alongside the primary category match.
+    entity_keywords: HashMap<String, Vec<String>>,
+    /// User-provided additions/overrides to `native_to_internal`, keyed lowercase.
+    native_overrides: HashMap<String, String>,
+    /// Surface names ("pres", "soccer-epl") that transparently redirect to a
+    /// canonical `category` or `category:subcategory` key, keyed lowercase.
+    aliases: HashMap<String, String>,
+    /// Retired/renamed canonical keys, mapped to their replacement, so old
+    /// `cfg.categories` entries still resolve instead of silently matching nothing.
+    deprecated: HashMap<String, String>,
+}
+
+/// On-disk shape of a user-editable category registry, merged on top of the
+/// compiled defaults by `CategoryRegistry::from_file`.
+#[derive(serde::Deserialize, Default)]
+struct RegistryFile {
+    #[serde(default)]
+    keywords: HashMap<String, Vec<String>>,
+    #[serde(default, rename = "native_to_internal")]
+    native_to_internal: HashMap<String, String>,
+    #[serde(default)]
+    aliases: HashMap<String, String>,
+    #[serde(default)]
+    deprecated: HashMap<String, String>,
 }
 
 impl CategoryRegistry {
@@ -13,69 +165,164 @@ impl CategoryRegistry {
         let mut keywords = HashMap::new();
 
         // Sports
-        keywords.insert("sports:nba".into(), vec!["NBA", "basketball", "Lakers", "Celtics", "Warriors", "Bucks", "Thunder", "76ers", "Nuggets", "Knicks", "Heat", "Nets", "Suns", "Mavericks", "Clippers", "Cavaliers", "Timberwolves", "Pacers", "Pelicans", "Kings", "Hawks", "Bulls", "Pistons", "Rockets", "Spurs", "Grizzlies", "Raptors", "Trail Blazers", "Jazz", "Wizards", "Hornets", "Magic"]);
-        keywords.insert("sports:nfl".into(), vec!["NFL", "football", "Super Bowl", "Chiefs", "Eagles", "49ers", "Bills", "Cowboys", "Dolphins", "Ravens", "Lions", "Bengals", "Chargers", "Jets", "Packers", "Seahawks", "Rams", "Steelers", "Browns", "Vikings", "Jaguars", "Broncos", "Saints", "Buccaneers", "Cardinals", "Colts", "Falcons", "Panthers", "Bears", "Commanders", "Titans", "Raiders", "Texans", "Giants", "Patriots"]);
-        keywords.insert("sports:mlb".into(), vec!["MLB", "baseball", "Yankees", "Dodgers", "Mets", "Braves", "Astros", "Phillies", "Red Sox", "Cubs", "Padres", "Rangers", "Mariners", "Twins", "Orioles", "Guardians", "Rays", "Brewers", "Cardinals", "Blue Jays", "Giants", "Reds", "Pirates", "Diamondbacks", "Royals", "Tigers", "White Sox", "Rockies", "Angels", "Athletics", "Nationals", "Marlins"]);
-        keywords.insert("sports:nhl".into(), vec!["NHL", "hockey", "Bruins", "Panthers", "Oilers", "Rangers", "Hurricanes", "Stars", "Avalanche", "Golden Knights", "Maple Leafs", "Lightning", "Devils", "Islanders", "Penguins", "Canucks", "Jets", "Kings", "Wild", "Senators", "Capitals", "Flames", "Predators", "Kraken", "Blue Jackets", "Flyers", "Red Wings", "Sabres", "Ducks", "Coyotes", "Sharks", "Blackhawks"]);
-        keywords.insert("sports:soccer".into(), vec!["soccer", "football", "FIFA", "World Cup", "Premier League", "Champions League", "La Liga", "Bundesliga", "Serie A", "MLS", "Arsenal", "Manchester", "Liverpool", "Chelsea", "Barcelona", "Real Madrid", "Bayern", "PSG", "Juventus", "Inter Milan"]);
-        keywords.insert("sports:golf".into(), vec!["golf", "PGA", "Masters", "Open Championship", "US Open golf", "Ryder Cup", "birdie", "eagle"]);
-        keywords.insert("sports:mma".into(), vec!["UFC", "MMA", "fight", "bout", "knockout", "submission", "Octagon", "Dana White"]);
-        keywords.insert("sports:tennis".into(), vec!["tennis", "ATP", "WTA", "Grand Slam", "Wimbledon", "Roland Garros", "US Open tennis", "Australian Open", "match", "Mannarino", "Shelton", "Djokovic", "Sinner", "Alcaraz", "Swiatek"]);
-        keywords.insert("sports:college_football".into(), vec!["college football", "NCAA football", "CFB", "College Football Playoff", "Heisman", "NCAAF", "Bowl Game"]);
-        keywords.insert("sports:college_basketball".into(), vec!["college basketball", "NCAA basketball", "March Madness", "NCAAB", "Final Four"]);
+        keywords.insert("sports:nba".into(), kw(vec!["NBA", "basketball", "Lakers", "Celtics", "Warriors", "Bucks", "Thunder", "76ers", "Nuggets", "Knicks", "Heat", "Nets", "Suns", "Mavericks", "Clippers", "Cavaliers", "Timberwolves", "Pacers", "Pelicans", "Kings", "Hawks", "Bulls", "Pistons", "Rockets", "Spurs", "Grizzlies", "Raptors", "Trail Blazers", "Jazz", "Wizards", "Hornets", "Magic"]));
+        keywords.insert("sports:nfl".into(), kw(vec!["NFL", "football", "Super Bowl", "Chiefs", "Eagles", "49ers", "Bills", "Cowboys", "Dolphins", "Ravens", "Lions", "Bengals", "Chargers", "Jets", "Packers", "Seahawks", "Rams", "Steelers", "Browns", "Vikings", "Jaguars", "Broncos", "Saints", "Buccaneers", "Cardinals", "Colts", "Falcons", "Panthers", "Bears", "Commanders", "Titans", "Raiders", "Texans", "Giants", "Patriots"]));
+        keywords.insert("sports:mlb".into(), kw(vec!["MLB", "baseball", "Yankees", "Dodgers", "Mets", "Braves", "Astros", "Phillies", "Red Sox", "Cubs", "Padres", "Rangers", "Mariners", "Twins", "Orioles", "Guardians", "Rays", "Brewers", "Cardinals", "Blue Jays", "Giants", "Reds", "Pirates", "Diamondbacks", "Royals", "Tigers", "White Sox", "Rockies", "Angels", "Athletics", "Nationals", "Marlins"]));
+        keywords.insert("sports:nhl".into(), kw(vec!["NHL", "hockey", "Bruins", "Panthers", "Oilers", "Rangers", "Hurricanes", "Stars", "Avalanche", "Golden Knights", "Maple Leafs", "Lightning", "Devils", "Islanders", "Penguins", "Canucks", "Jets", "Kings", "Wild", "Senators", "Capitals", "Flames", "Predators", "Kraken", "Blue Jackets", "Flyers", "Red Wings", "Sabres", "Ducks", "Coyotes", "Sharks", "Blackhawks"]));
+        keywords.insert("sports:soccer".into(), kw(vec!["soccer", "football", "FIFA", "World Cup", "Premier League", "Champions League", "La Liga", "Bundesliga", "Serie A", "MLS", "Arsenal", "Manchester", "Liverpool", "Chelsea", "Barcelona", "Real Madrid", "Bayern", "PSG", "Juventus", "Inter Milan"]));
+        keywords.insert("sports:golf".into(), kw(vec!["golf", "PGA", "Masters", "Open Championship", "US Open golf", "Ryder Cup", "birdie", "eagle"]));
+        keywords.insert("sports:mma".into(), kw(vec!["UFC", "MMA", "fight", "bout", "knockout", "submission", "Octagon", "Dana White"]));
+        keywords.insert("sports:tennis".into(), kw(vec!["tennis", "ATP", "WTA", "Grand Slam", "Wimbledon", "Roland Garros", "US Open tennis", "Australian Open", "match", "Mannarino", "Shelton", "Djokovic", "Sinner", "Alcaraz", "Swiatek"]));
+        keywords.insert("sports:college_football".into(), kw(vec!["college football", "NCAA football", "CFB", "College Football Playoff", "Heisman", "NCAAF", "Bowl Game"]));
+        keywords.insert("sports:college_basketball".into(), kw(vec!["college basketball", "NCAA basketball", "March Madness", "NCAAB", "Final Four"]));
 
         // Politics
-        keywords.insert("politics:us_elections".into(), vec!["President", "presidential", "election", "electoral", "White House", "nominee", "primary", "caucus", "swing state", "ballot", "vote", "campaign", "running mate", "vice president"]);
-        keywords.insert("politics:congress".into(), vec!["Congress", "Senate", "House", "bill", "legislation", "filibuster", "committee", "Speaker", "impeach", "confirmation"]);
-        keywords.insert("politics:policy".into(), vec!["policy", "regulation", "executive order", "tariff", "sanctions", "mandate", "government shutdown", "debt ceiling"]);
-        keywords.insert("politics:international".into(), vec!["NATO", "EU", "United Nations", "G7", "G20", "Brexit", "trade deal", "summit", "diplomatic"]);
+        keywords.insert("politics:us_elections".into(), kw(vec!["President", "presidential", "election", "electoral", "White House", "nominee", "primary", "caucus", "swing state", "ballot", "vote", "campaign", "running mate", "vice president"]));
+        keywords.insert("politics:congress".into(), kw(vec!["Congress", "Senate", "House", "bill", "legislation", "filibuster", "committee", "Speaker", "impeach", "confirmation"]));
+        keywords.insert("politics:policy".into(), kw(vec!["policy", "regulation", "executive order", "tariff", "sanctions", "mandate", "government shutdown", "debt ceiling"]));
+        keywords.insert("politics:international".into(), kw(vec!["NATO", "EU", "United Nations", "G7", "G20", "Brexit", "trade deal", "summit", "diplomatic"]));
 
         // Economics
-        keywords.insert("economics:fed".into(), vec!["Fed", "interest rate", "FOMC", "Federal Reserve", "rate cut", "rate hike", "monetary policy", "Jerome Powell", "basis points", "taper"]);
-        keywords.insert("economics:inflation".into(), vec!["inflation", "CPI", "consumer price", "deflation", "price index", "PCE"]);
-        keywords.insert("economics:jobs".into(), vec!["jobs", "unemployment", "nonfarm payroll", "jobless claims", "labor market", "hiring", "layoffs", "employment"]);
-        keywords.insert("economics:gdp".into(), vec!["GDP", "gross domestic product", "economic growth", "recession", "contraction", "expansion"]);
-        keywords.insert("economics:recession".into(), vec!["recession", "downturn", "depression", "economic decline", "yield curve"]);
+        keywords.insert("economics:fed".into(), kw(vec!["Fed", "interest rate", "FOMC", "Federal Reserve", "rate cut", "rate hike", "monetary policy", "Jerome Powell", "basis points", "taper"]));
+        keywords.insert("economics:inflation".into(), kw(vec!["inflation", "CPI", "consumer price", "deflation", "price index", "PCE"]));
+        keywords.insert("economics:jobs".into(), kw(vec!["jobs", "unemployment", "nonfarm payroll", "jobless claims", "labor market", "hiring", "layoffs", "employment"]));
+        keywords.insert("economics:gdp".into(), kw(vec!["GDP", "gross domestic product", "economic growth", "recession", "contraction", "expansion"]));
+        keywords.insert("economics:recession".into(), kw(vec!["recession", "downturn", "depression", "economic decline", "yield curve"]));
 
         // Crypto
-        keywords.insert("crypto:bitcoin".into(), vec!["Bitcoin", "BTC", "bitcoin price", "satoshi", "halving", "mining BTC"]);
-        keywords.insert("crypto:ethereum".into(), vec!["Ethereum", "ETH", "ether", "Vitalik", "EIP", "staking ETH"]);
-        keywords.insert("crypto:altcoins".into(), vec!["Solana", "SOL", "XRP", "Ripple", "Cardano", "ADA", "Dogecoin", "DOGE", "Polkadot", "DOT", "Avalanche", "AVAX", "Chainlink", "LINK", "Polygon", "MATIC", "Litecoin", "LTC"]);
-        keywords.insert("crypto:regulation".into(), vec!["SEC crypto", "crypto regulation", "crypto ban", "stablecoin", "CBDC", "crypto ETF", "Bitcoin ETF"]);
+        keywords.insert("crypto:bitcoin".into(), kw(vec!["Bitcoin", "BTC", "bitcoin price", "satoshi", "halving", "mining BTC"]));
+        keywords.insert("crypto:ethereum".into(), kw(vec!["Ethereum", "ETH", "ether", "Vitalik", "EIP", "staking ETH"]));
+        keywords.insert("crypto:altcoins".into(), kw(vec!["Solana", "SOL", "XRP", "Ripple", "Cardano", "ADA", "Dogecoin", "DOGE", "Polkadot", "DOT", "Avalanche", "AVAX", "Chainlink", "LINK", "Polygon", "MATIC", "Litecoin", "LTC"]));
+        keywords.insert("crypto:regulation".into(), kw(vec!["SEC crypto", "crypto regulation", "crypto ban", "stablecoin", "CBDC", "crypto ETF", "Bitcoin ETF"]));
 
         // Finance
-        keywords.insert("finance:sp500".into(), vec!["S&P 500", "SPX", "SPY", "S&P", "SP500"]);
-        keywords.insert("finance:nasdaq".into(), vec!["NASDAQ", "QQQ", "Nasdaq", "tech stocks"]);
-        keywords.insert("finance:commodities".into(), vec!["gold price", "oil price", "silver", "crude oil", "WTI", "Brent", "commodity"]);
-        keywords.insert("finance:forex".into(), vec!["EUR/USD", "USD/JPY", "GBP/USD", "forex", "currency pair", "dollar index", "DXY"]);
-        keywords.insert("finance:stocks".into(), vec!["TSLA", "Tesla", "AAPL", "Apple", "NVDA", "NVIDIA", "AMZN", "Amazon", "GOOGL", "Google", "META", "Microsoft", "MSFT"]);
+        keywords.insert("finance:sp500".into(), kw(vec!["S&P 500", "SPX", "SPY", "S&P", "SP500"]));
+        keywords.insert("finance:nasdaq".into(), kw(vec!["NASDAQ", "QQQ", "Nasdaq", "tech stocks"]));
+        keywords.insert("finance:commodities".into(), kw(vec!["gold price", "oil price", "silver", "crude oil", "WTI", "Brent", "commodity"]));
+        keywords.insert("finance:forex".into(), kw(vec!["EUR/USD", "USD/JPY", "GBP/USD", "forex", "currency pair", "dollar index", "DXY"]));
+        keywords.insert("finance:stocks".into(), kw(vec!["TSLA", "Tesla", "AAPL", "Apple", "NVDA", "NVIDIA", "AMZN", "Amazon", "GOOGL", "Google", "META", "Microsoft", "MSFT"]));
 
         // Weather
-        keywords.insert("weather:temperature".into(), vec!["temperature", "high temp", "low temp", "degrees", "heat", "cold", "record high", "record low", "Fahrenheit", "Celsius"]);
-        keywords.insert("weather:storms".into(), vec!["hurricane", "storm", "tornado", "cyclone", "typhoon", "tropical", "flooding", "blizzard"]);
-        keywords.insert("weather:disasters".into(), vec!["earthquake", "wildfire", "tsunami", "volcanic", "drought", "natural disaster"]);
+        keywords.insert("weather:temperature".into(), kw(vec!["temperature", "high temp", "low temp", "degrees", "heat", "cold", "record high", "record low", "Fahrenheit", "Celsius"]));
+        keywords.insert("weather:storms".into(), kw(vec!["hurricane", "storm", "tornado", "cyclone", "typhoon", "tropical", "flooding", "blizzard"]));
+        keywords.insert("weather:disasters".into(), kw(vec!["earthquake", "wildfire", "tsunami", "volcanic", "drought", "natural disaster"]));
 
         // Tech
-        keywords.insert("tech:ai".into(), vec!["AI", "artificial intelligence", "GPT", "Claude", "machine learning", "LLM", "OpenAI", "Anthropic", "deep learning", "neural"]);
-        keywords.insert("tech:launches".into(), vec!["iPhone", "launch", "release", "product announcement", "keynote", "WWDC", "I/O"]);
-        keywords.insert("tech:company".into(), vec!["IPO", "acquisition", "merger", "layoffs tech", "valuation", "funding round"]);
+        keywords.insert("tech:ai".into(), kw(vec!["AI", "artificial intelligence", "GPT", "Claude", "machine learning", "LLM", "OpenAI", "Anthropic", "deep learning", "neural"]));
+        keywords.insert("tech:launches".into(), kw(vec!["iPhone", "launch", "release", "product announcement", "keynote", "WWDC", "I/O"]));
+        keywords.insert("tech:company".into(), kw(vec!["IPO", "acquisition", "merger", "layoffs tech", "valuation", "funding round"]));
 
         // Culture
-        keywords.insert("culture:entertainment".into(), vec!["Oscar", "Academy Award", "Emmy", "Grammy", "Golden Globe", "BAFTA", "box office", "streaming", "Netflix", "Disney"]);
-        keywords.insert("culture:social".into(), vec!["Twitter", "TikTok", "Instagram", "viral", "trending", "influencer", "YouTube"]);
-        keywords.insert("culture:celebrity".into(), vec!["celebrity", "scandal", "divorce", "award show", "concert", "tour"]);
+        keywords.insert("culture:entertainment".into(), kw(vec!["Oscar", "Academy Award", "Emmy", "Grammy", "Golden Globe", "BAFTA", "box office", "streaming", "Netflix", "Disney"]));
+        keywords.insert("culture:social".into(), kw(vec!["Twitter", "TikTok", "Instagram", "viral", "trending", "influencer", "YouTube"]));
+        keywords.insert("culture:celebrity".into(), kw(vec!["celebrity", "scandal", "divorce", "award show", "concert", "tour"]));
 
         // World Events
-        keywords.insert("world:geopolitics".into(), vec!["geopolitics", "conflict", "war", "invasion", "ceasefire", "peace deal", "coup", "regime"]);
-        keywords.insert("world:conflicts".into(), vec!["Ukraine", "Russia", "Gaza", "Israel", "Taiwan", "China", "Iran", "North Korea", "military"]);
-        keywords.insert("world:treaties".into(), vec!["treaty", "agreement", "accord", "pact", "alliance", "trade agreement", "climate accord"]);
+        keywords.insert("world:geopolitics".into(), kw(vec!["geopolitics", "conflict", "war", "invasion", "ceasefire", "peace deal", "coup", "regime"]));
+        keywords.insert("world:conflicts".into(), kw(vec!["Ukraine", "Russia", "Gaza", "Israel", "Taiwan", "China", "Iran", "North Korea", "military"]));
+        keywords.insert("world:treaties".into(), kw(vec!["treaty", "agreement", "accord", "pact", "alliance", "trade agreement", "climate accord"]));
 
         // Health
-        keywords.insert("health:pandemics".into(), vec!["pandemic", "COVID", "virus", "outbreak", "epidemic", "WHO", "vaccine", "variant"]);
-        keywords.insert("health:fda".into(), vec!["FDA", "drug approval", "clinical trial", "pharmaceutical", "EUA", "therapy"]);
-        keywords.insert("health:public".into(), vec!["public health", "mortality", "life expectancy", "obesity", "mental health", "opioid"]);
+        keywords.insert("health:pandemics".into(), kw(vec!["pandemic", "COVID", "virus", "outbreak", "epidemic", "WHO", "vaccine", "variant"]));
+        keywords.insert("health:fda".into(), kw(vec!["FDA", "drug approval", "clinical trial", "pharmaceutical", "EUA", "therapy"]));
+        keywords.insert("health:public".into(), kw(vec!["public health", "mortality", "life expectancy", "obesity", "mental health", "opioid"]));
+
+        let mut entity_keywords: HashMap<String, Vec<String>> = HashMap::new();
+        entity_keywords.insert("sports:nba".into(), kw(vec!["Lakers", "Celtics", "Warriors", "Bucks", "Thunder", "76ers", "Nuggets", "Knicks", "Heat", "Nets", "Suns", "Mavericks", "Clippers", "Cavaliers", "Timberwolves", "Pacers", "Pelicans", "Kings", "Hawks", "Bulls", "Pistons", "Rockets", "Spurs", "Grizzlies", "Raptors", "Trail Blazers", "Jazz", "Wizards", "Hornets", "Magic"]));
+        entity_keywords.insert("sports:nfl".into(), kw(vec!["Chiefs", "Eagles", "49ers", "Bills", "Cowboys", "Dolphins", "Ravens", "Lions", "Bengals", "Chargers", "Jets", "Packers", "Seahawks", "Rams", "Steelers", "Browns", "Vikings", "Jaguars", "Broncos", "Saints", "Buccaneers", "Cardinals", "Colts", "Falcons", "Panthers", "Bears", "Commanders", "Titans", "Raiders", "Texans", "Giants", "Patriots"]));
+        entity_keywords.insert("sports:tennis".into(), kw(vec!["Mannarino", "Shelton", "Djokovic", "Sinner", "Alcaraz", "Swiatek"]));
+        entity_keywords.insert("crypto:bitcoin".into(), kw(vec!["Bitcoin", "BTC"]));
+        entity_keywords.insert("crypto:ethereum".into(), kw(vec!["Ethereum", "ETH"]));
+        entity_keywords.insert("crypto:altcoins".into(), kw(vec!["Solana", "SOL", "XRP", "Ripple", "Cardano", "ADA", "Dogecoin", "DOGE", "Polkadot", "DOT", "Avalanche", "AVAX", "Chainlink", "LINK", "Polygon", "MATIC", "Litecoin", "LTC"]));
+        entity_keywords.insert("finance:stocks".into(), kw(vec!["TSLA", "Tesla", "AAPL", "Apple", "NVDA", "NVIDIA", "AMZN", "Amazon", "GOOGL", "Google", "META", "Microsoft", "MSFT"]));
 
-        Self { keywords }
+        let mut aliases = HashMap::new();
+        aliases.insert("pres".into(), "politics:us_elections".to_string());
+        aliases.insert("elections-2024".into(), "politics:us_elections".to_string());
+        aliases.insert("crypto-currency".into(), "crypto".to_string());
+        aliases.insert("soccer-epl".into(), "sports:soccer".to_string());
+
+        // Renamed early on, kept here so old saved configs don't go dark.
+        let mut deprecated = HashMap::new();
+        deprecated.insert("sports:football".to_string(), "sports:nfl".to_string());
+        deprecated.insert("economics:rates".to_string(), "economics:fed".to_string());
+
+        Self {
+            keywords,
+            entity_keywords,
+            native_overrides: HashMap::new(),
+            aliases,
+            deprecated,
+        }
+    }
+
+    /// Load the registry from a JSON file, merging on top of the compiled defaults:
+    /// keywords for a `category:subcategory` key in the file are appended to the
+    /// built-in list for that key (creating it if new), and `native_to_internal`
+    /// entries override/extend the built-in Kalshi-category mapping. A missing or
+    /// unparseable file just falls back to `new()`, so the defaults always work.
+    pub fn from_file(path: &Path) -> Self {
+        let mut registry = Self::new();
+
+        let contents = match std::fs::read_to_string(path) {
+            Ok(contents) => contents,
+            Err(_) => return registry,
+        };
+
+        let file: RegistryFile = match serde_json::from_str(&contents) {
+            Ok(file) => file,
+            Err(e) => {
+                eprintln!("Warning: failed to parse category registry {}: {}", path.display(), e);
+                return registry;
+            }
+        };
+
+        for (key, extra_keywords) in file.keywords {
+            registry.keywords.entry(key).or_default().extend(extra_keywords);
+        }
+        for (native, internal) in file.native_to_internal {
+            registry.native_overrides.insert(native.to_lowercase(), internal);
+        }
+        for (alias, canonical) in file.aliases {
+            registry.aliases.insert(alias.to_lowercase(), canonical);
+        }
+        for (old_key, replacement) in file.deprecated {
+            registry.deprecated.insert(old_key, replacement);
+        }
+
+        registry
+    }
+
+    /// Resolve a surface name through the alias table, so "pres" or "soccer-epl"
+    /// behave like the canonical key they redirect to. Returns the input unchanged
+    /// if it isn't a known alias.
+    fn resolve_alias<'a>(&'a self, name: &'a str) -> &'a str {
+        self.aliases.get(&name.to_lowercase()).map(String::as_str).unwrap_or(name)
+    }
+
+    /// If `key` has been retired/renamed, return its replacement.
+    pub fn deprecated_replacement(&self, key: &str) -> Option<&str> {
+        self.deprecated.get(key).map(String::as_str)
+    }
+
+    /// Load from the default config-directory path (`~/.config/wwatcher/categories.json`
+    /// on Linux), falling back to the compiled defaults if it doesn't exist.
+    pub fn load() -> Self {
+        match dirs::config_dir() {
+            Some(dir) => Self::from_file(&dir.join("wwatcher").join("categories.json")),
+            None => Self::new(),
+        }
+    }
+
+    /// Map Kalshi's native category names to our internal category keys, checking
+    /// aliases and user-provided overrides before the compiled defaults.
+    fn native_to_internal_for(&self, native_category: &str) -> Option<String> {
+        let resolved = self.resolve_alias(native_category);
+        let lower = resolved.to_lowercase();
+        if let Some(internal) = self.native_overrides.get(&lower) {
+            return Some(internal.clone());
+        }
+        Self::native_to_internal(resolved).map(String::from)
     }
 
     /// Map Kalshi's native category names to our internal category keys
@@ -103,9 +350,9 @@ impl CategoryRegistry {
             return true;
         }
 
-        if let Some(internal) = Self::native_to_internal(native_category) {
+        if let Some(internal) = self.native_to_internal_for(native_category) {
             for sel in selected {
-                if sel == internal || sel.starts_with(&format!("{}:", internal)) {
+                if sel == &internal || sel.starts_with(&format!("{}:", internal)) {
                     return true;
                 }
             }
@@ -114,8 +361,12 @@ impl CategoryRegistry {
         false
     }
 
-    /// Check if a market title matches the user's selected categories
-    /// Returns (category, subcategory) if matched, None if not in user's selection
+    /// Check if a market title matches the user's selected categories, regions,
+    /// or entities. Selections of the form "region:us"/"region:intl" or
+    /// "team:Lakers"/"ticker:SOL" match on those dimensions of `classify`
+    /// instead of the topical category; everything else is matched the old way.
+    /// Returns the title's primary (category, subcategory), or None if nothing in
+    /// the user's selection matches.
     pub fn matches_selection(&self, market_title: &str, selected: &[String]) -> Option<(String, String)> {
         // "all" matches everything
         if selected.iter().any(|s| s == "all") {
@@ -123,51 +374,105 @@ impl CategoryRegistry {
             return self.categorize(market_title).or(Some(("uncategorized".into(), "other".into())));
         }
 
-        let title_lower = market_title.to_lowercase();
+        let labels = self.classify(market_title);
+        let uncategorized = || Some(("uncategorized".to_string(), "other".to_string()));
 
+        let mut category_selections: Vec<&str> = Vec::new();
         for selection in selected {
-            if selection.ends_with(":all") {
-                // e.g. "sports:all" — match any sports subcategory
-                let category = selection.trim_end_matches(":all");
-                let prefix = format!("{}:", category);
-                for (key, kw_list) in &self.keywords {
-                    if key.starts_with(&prefix) {
-                        for kw in kw_list {
-                            if title_lower.contains(&kw.to_lowercase()) {
-                                let parts: Vec<&str> = key.splitn(2, ':').collect();
-                                return Some((parts[0].to_string(), parts.get(1).unwrap_or(&"all").to_string()));
-                            }
-                        }
-                    }
+            if let Some(region) = selection.strip_prefix("region:") {
+                if labels.region.as_deref() == Some(region) {
+                    return labels.category.clone().or_else(uncategorized);
                 }
-            } else if let Some(kw_list) = self.keywords.get(selection) {
-                // Specific subcategory match
-                for kw in kw_list {
-                    if title_lower.contains(&kw.to_lowercase()) {
-                        let parts: Vec<&str> = selection.splitn(2, ':').collect();
-                        return Some((parts[0].to_string(), parts.get(1).unwrap_or(&"all").to_string()));
-                    }
+                continue;
+            }
+            if selection.starts_with("team:") || selection.starts_with("ticker:") {
+                if labels.entities.iter().any(|e| e.eq_ignore_ascii_case(selection)) {
+                    return labels.category.clone().or_else(uncategorized);
                 }
+                continue;
             }
+            category_selections.push(selection);
         }
 
-        None
+        let title_tokens = tokenize(market_title);
+        let mut candidates: Vec<&str> = Vec::new();
+        for selection in category_selections {
+            // Resolve aliases and deprecated keys transparently, so renamed/retired
+            // selections keep matching instead of silently going dark.
+            let resolved = self.resolve_alias(selection);
+            let resolved = self.deprecated_replacement(resolved).unwrap_or(resolved);
+
+            if let Some(category) = resolved.strip_suffix(":all") {
+                // e.g. "sports:all" — consider every sports subcategory
+                let prefix = format!("{}:", category);
+                candidates.extend(self.keywords.keys().filter(|k| k.starts_with(&prefix)).map(String::as_str));
+            } else {
+                candidates.push(resolved);
+            }
+        }
+
+        self.best_key(&title_tokens, candidates.into_iter()).map(split_key)
     }
 
-    /// Categorize a market title (best-effort, returns first match)
+    /// Categorize a market title by the highest-scoring (category, subcategory)
+    /// across the whole registry, breaking ties by the longest matched keyword.
     pub fn categorize(&self, market_title: &str) -> Option<(String, String)> {
-        let title_lower = market_title.to_lowercase();
+        let title_tokens = tokenize(market_title);
+        self.best_key(&title_tokens, self.keywords.keys().map(String::as_str)).map(split_key)
+    }
 
-        for (key, kw_list) in &self.keywords {
-            for kw in kw_list {
-                if title_lower.contains(&kw.to_lowercase()) {
-                    let parts: Vec<&str> = key.splitn(2, ':').collect();
-                    return Some((parts[0].to_string(), parts.get(1).unwrap_or(&"all").to_string()));
+    /// Classify a market title along every dimension: the primary topical
+    /// category, a coarse region (when the category implies one), and entity
+    /// tags (teams, tickers) recognized among the matched keywords.
+    pub fn classify(&self, market_title: &str) -> MarketLabels {
+        let title_tokens = tokenize(market_title);
+        let category = self.best_key(&title_tokens, self.keywords.keys().map(String::as_str)).map(split_key);
+
+        let region = category
+            .as_ref()
+            .and_then(|(cat, sub)| region_for(&format!("{}:{}", cat, sub)))
+            .map(String::from);
+
+        let entities = category
+            .as_ref()
+            .map(|(cat, sub)| self.matched_entities(&title_tokens, cat, &format!("{}:{}", cat, sub)))
+            .unwrap_or_default();
+
+        MarketLabels { category, region, entities }
+    }
+
+    /// Entity tags ("team:Lakers", "ticker:SOL") for the keywords in `key` that
+    /// actually matched the title, tagged with the entity kind for `top_category`.
+    fn matched_entities(&self, title_tokens: &[String], top_category: &str, key: &str) -> Vec<String> {
+        let Some(kind) = entity_kind_for(top_category) else { return Vec::new() };
+        let Some(entities) = self.entity_keywords.get(key) else { return Vec::new() };
+        entities
+            .iter()
+            .filter(|keyword| keyword_spans(title_tokens, &tokenize(keyword)))
+            .map(|keyword| format!("{}:{}", kind, keyword))
+            .collect()
+    }
+
+    /// Score every candidate `category:subcategory` key against the tokenized
+    /// title and return the highest scorer (ties broken by longest matched
+    /// keyword). Each matched keyword scores 1 point per word it spans, so
+    /// multi-word keyword matches outweigh single-word ones.
+    fn best_key<'c>(&self, title_tokens: &[String], candidates: impl Iterator<Item = &'c str>) -> Option<&'c str> {
+        let mut best: Option<(&'c str, u32, usize)> = None;
+        for key in candidates {
+            let Some(kw_list) = self.keywords.get(key) else { continue };
+            let Some((score, longest_match)) = score_keywords(title_tokens, kw_list) else { continue };
+            let is_better = match best {
+                None => true,
+                Some((_, best_score, best_longest)) => {
+                    score > best_score || (score == best_score && longest_match > best_longest)
                 }
+            };
+            if is_better {
+                best = Some((key, score, longest_match));
             }
         }
-
-        None
+        best.map(|(key, _, _)| key)
     }
 
     /// Get all top-level categories
@@ -0,0 +1,137 @@
+//! Read-only HTTP/JSON API over the same `AlertStore` the console and
+//! webhook sinks write through, so a dashboard or bot can poll recent
+//! alerts, per-market tickers, and candles instead of scraping stdout or
+//! tailing the JSONL sink. Every handler only reads — nothing here writes
+//! to the store or touches a platform's live API.
+//!
+//! Built on `axum`, which already pulls in `tokio`'s `hyper` stack the rest
+//! of the watcher depends on. Bind address is caller-supplied (see
+//! `config::Config::api_bind_addr`) rather than hardcoded, so running
+//! several watcher instances on one box doesn't collide on a fixed port.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use axum::extract::{Query, State};
+use axum::http::StatusCode;
+use axum::response::Json;
+use axum::routing::get;
+use axum::Router;
+use serde_json::{json, Value};
+
+use crate::categories::CategoryRegistry;
+use crate::db::AlertStore;
+
+#[derive(Clone)]
+struct ApiState {
+    store: Arc<dyn AlertStore>,
+    categories: Arc<CategoryRegistry>,
+}
+
+/// Serve `/tickers`, `/alerts`, and `/candles` on `bind_addr` until the
+/// process is killed. Returns an error if the port can't be bound; the
+/// server otherwise runs forever, so callers should `tokio::spawn` this
+/// alongside `watch_whales` rather than awaiting it inline.
+pub async fn serve_api(
+    bind_addr: &str,
+    store: Arc<dyn AlertStore>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let state = ApiState {
+        store,
+        categories: Arc::new(CategoryRegistry::load()),
+    };
+
+    let app = Router::new()
+        .route("/tickers", get(get_tickers))
+        .route("/alerts", get(get_alerts))
+        .route("/candles", get(get_candles))
+        .with_state(state);
+
+    println!("API server listening on http://{}", bind_addr);
+    let listener = tokio::net::TcpListener::bind(bind_addr).await?;
+    axum::serve(listener, app).await?;
+    Ok(())
+}
+
+async fn get_tickers(
+    State(state): State<ApiState>,
+    Query(params): Query<HashMap<String, String>>,
+) -> Result<Json<Value>, (StatusCode, String)> {
+    let platform_filter = params
+        .get("platform")
+        .map(|s| s.as_str())
+        .unwrap_or("all")
+        .to_string();
+
+    state
+        .store
+        .query_ticker_summary(&platform_filter)
+        .map(|tickers| Json(json!({ "tickers": tickers })))
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))
+}
+
+async fn get_alerts(
+    State(state): State<ApiState>,
+    Query(params): Query<HashMap<String, String>>,
+) -> Result<Json<Value>, (StatusCode, String)> {
+    let platform_filter = params
+        .get("platform")
+        .map(|s| s.as_str())
+        .unwrap_or("all")
+        .to_string();
+    let limit: usize = params
+        .get("limit")
+        .and_then(|s| s.parse().ok())
+        .unwrap_or(100);
+    let min_value: Option<f64> = params.get("min_value").and_then(|s| s.parse().ok());
+    let since_unix: Option<i64> = params.get("since").and_then(|s| s.parse().ok());
+    let until_unix: Option<i64> = params.get("until").and_then(|s| s.parse().ok());
+    let category_filter = params.get("category");
+
+    let alerts = state
+        .store
+        .query_alerts_filtered(limit, &platform_filter, min_value, since_unix, until_unix)
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    // `category`/`subcategory` are dead columns on `alerts` (never written
+    // by `insert_alert`), so filtering by category re-derives it from
+    // `market_title` the same way `commands::watch`'s live filter does,
+    // rather than trusting the stored (always-null) column.
+    let alerts = match category_filter {
+        Some(wanted) => alerts
+            .into_iter()
+            .filter(|a| {
+                a.get("market_title")
+                    .and_then(|v| v.as_str())
+                    .and_then(|title| state.categories.categorize(title))
+                    .map(|(cat, sub)| {
+                        wanted.eq_ignore_ascii_case(&cat) || wanted.eq_ignore_ascii_case(&format!("{}:{}", cat, sub))
+                    })
+                    .unwrap_or(false)
+            })
+            .collect(),
+        None => alerts,
+    };
+
+    Ok(Json(json!({ "alerts": alerts })))
+}
+
+async fn get_candles(
+    State(state): State<ApiState>,
+    Query(params): Query<HashMap<String, String>>,
+) -> Result<Json<Value>, (StatusCode, String)> {
+    let platform = params
+        .get("platform")
+        .ok_or((StatusCode::BAD_REQUEST, "missing ?platform=".to_string()))?;
+    let market = params
+        .get("market")
+        .ok_or((StatusCode::BAD_REQUEST, "missing ?market=".to_string()))?;
+    let resolution = params.get("resolution").map(|s| s.as_str()).unwrap_or("1h");
+    let limit: u32 = params.get("limit").and_then(|s| s.parse().ok()).unwrap_or(200);
+
+    state
+        .store
+        .query_candles(platform, market, resolution, limit)
+        .map(|candles| Json(json!({ "candles": candles })))
+        .map_err(|e| (StatusCode::BAD_REQUEST, e.to_string()))
+}
@@ -0,0 +1,515 @@
+//! Historical trade backfill: walks Kalshi and Polymarket trade history
+//! backward from now to `--since`, applying the same threshold/category/odds
+//! filters `commands::watch::watch_whales` uses, and feeding matches through
+//! the same alert sinks so the database is seeded before the live watcher
+//! takes over. Unlike the live loop this skips per-trade wallet tracking,
+//! whale profiles, and order-book snapshots — those are about live decision
+//! support, not historical seeding, and fetching them for a backlog of
+//! thousands of trades would make backfill impractically slow.
+//!
+//! Runs in two independent phases, per platform:
+//!
+//! 1. **Raw fetch** (`backfill_kalshi`/`backfill_polymarket`): page through
+//!    trade history, log alerts for whatever clears the usual filters, and
+//!    persist *every* trade in the window (not just the alerting ones) into
+//!    `backfill_trades` via `AlertStore::record_raw_trade` (see
+//!    `db::migrate_v11_raw_trades`). Kalshi's windows (bounded by `min_ts`/
+//!    `max_ts`) are independent of each other, so they're processed with a
+//!    bounded worker pool instead of one at a time. Polymarket's data-api has
+//!    no time-range filter — only forward offset paging that must stop at the
+//!    first trade older than `--since` — so its walk stays sequential.
+//! 2. **Candle rebuild** (`candles::rebuild_from_raw_trades`, via
+//!    `AlertStore::rebuild_candles`): recompute every OHLCV bucket the window
+//!    touches purely from `backfill_trades`, so candle generation never
+//!    depends on either platform's API being reachable. This phase
+//!    clear-then-rebuilds, so it's idempotent — resuming after an
+//!    interrupted rebuild just means running it again over the same range.
+//!
+//! Progress for phase 1 is checkpointed in the `metadata` table (via
+//! `AlertStore::get_metadata`/`set_metadata`). Polymarket writes its page
+//! offset after every page, so an interrupted Polymarket walk resumes from
+//! the last finished page. Kalshi writes its checkpoint only once its whole
+//! window set finishes, so an interrupted Kalshi run does not resume: the
+//! next run walks every window again (the trade-id dedup in
+//! `record_raw_trade`/`insert_alert` keeps that from double-counting). The
+//! checkpoint tracks how far back the walk has reached, not `--since`
+//! itself — rerunning with a later `--since` than a prior run picks up from
+//! the checkpoint, not the new cutoff.
+
+use std::collections::HashMap;
+use std::sync::{Arc, OnceLock};
+use std::time::Duration;
+
+use futures_util::future::join_all;
+use tokio::sync::Semaphore;
+
+use crate::alerts::history;
+use crate::alerts::sinks::{AlertSink, JsonlSink, PostgresSink, SqliteSink};
+use crate::alerts::AlertData;
+use crate::categories::CategoryRegistry;
+use crate::config::Config;
+use crate::db::AlertStore;
+use crate::platforms::kalshi;
+use crate::platforms::polymarket;
+
+/// Backward-in-time window Kalshi backfill walks per request, in seconds.
+/// Small enough that a handful of windows can run concurrently without any
+/// one of them covering an unreasonable slice of history.
+const KALSHI_WINDOW_SECS: i64 = 3600;
+
+/// Pause between Polymarket pages, so a large `--since` range doesn't hammer
+/// its API. Kalshi's windows don't need this — they're spread across
+/// `BACKFILL_CONCURRENCY` concurrent requests instead of fired back-to-back.
+const PAGE_DELAY: Duration = Duration::from_millis(250);
+
+/// Kalshi windows processed at once. Mirrors the pool sizing
+/// `store::PostgresTradeStore`/`tokio_postgres`-pool callers already use
+/// elsewhere in this codebase for "a handful of concurrent things, not a
+/// flood" — high enough to meaningfully parallelize a multi-day backfill,
+/// low enough not to trip Kalshi's rate limiter (`kalshi::shared_client`'s
+/// `limiter` still gates every individual request regardless).
+const BACKFILL_CONCURRENCY: usize = 4;
+
+const KALSHI_CHECKPOINT_KEY: &str = "backfill_checkpoint_kalshi_max_ts_ms";
+const POLYMARKET_CHECKPOINT_KEY: &str = "backfill_checkpoint_polymarket_offset";
+
+/// One pooled client for the whole backfill run, so walking thousands of
+/// Polymarket pages reuses connections instead of opening one per page —
+/// same rationale as `commands::watch`'s `shared_http_client`.
+fn shared_http_client() -> &'static reqwest::Client {
+    static CLIENT: OnceLock<reqwest::Client> = OnceLock::new();
+    CLIENT.get_or_init(|| {
+        reqwest::Client::builder()
+            .timeout(Duration::from_secs(10))
+            .pool_max_idle_per_host(4)
+            .build()
+            .expect("failed to build shared HTTP client")
+    })
+}
+
+/// Unix-seconds form of an RFC3339 timestamp, for folding a backfilled trade
+/// into its OHLCV candle bucket. Falls back to "now" so a malformed
+/// timestamp drops the trade into the current bucket rather than being
+/// skipped, matching `commands::watch`'s own fallback.
+fn unix_timestamp(rfc3339: &str) -> i64 {
+    chrono::DateTime::parse_from_rfc3339(rfc3339)
+        .map(|dt| dt.timestamp())
+        .unwrap_or_else(|_| chrono::Utc::now().timestamp())
+}
+
+/// Seed the database with trade history from `since` (an RFC3339 timestamp)
+/// up to `until` (an RFC3339 timestamp, defaulting to now). `platform` is one
+/// of "all", "kalshi", "polymarket"; `market` restricts the walk to a single
+/// ticker/condition id instead of the full firehose; `batch_size` caps trades
+/// fetched per page on both platforms.
+#[allow(clippy::too_many_arguments)]
+pub async fn backfill(
+    since: &str,
+    until: Option<&str>,
+    platform: &str,
+    market: Option<&str>,
+    batch_size: u32,
+    store: Arc<dyn AlertStore>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let since_ts = chrono::DateTime::parse_from_rfc3339(since)
+        .map(|dt| dt.timestamp())
+        .map_err(|e| format!("invalid --since (expected RFC3339, e.g. 2026-07-01T00:00:00Z): {}", e))?;
+    let until_ts = match until {
+        Some(until) => chrono::DateTime::parse_from_rfc3339(until)
+            .map(|dt| dt.timestamp())
+            .map_err(|e| format!("invalid --until (expected RFC3339, e.g. 2026-07-01T00:00:00Z): {}", e))?,
+        None => chrono::Utc::now().timestamp(),
+    };
+
+    let config = crate::config::load_config().ok();
+    let category_registry = CategoryRegistry::load();
+    let selected_categories: Vec<String> = config
+        .as_ref()
+        .map(|c| c.categories.clone())
+        .unwrap_or_else(|| vec!["all".into()]);
+    let threshold = config.as_ref().map(|c| c.threshold).unwrap_or(25_000);
+
+    // Same sink setup `watch_whales` uses: JSONL + SQLite always, Postgres
+    // only when configured.
+    let mut alert_sinks: Vec<Arc<dyn AlertSink>> =
+        vec![Arc::new(JsonlSink), Arc::new(SqliteSink::new(store.clone()))];
+    if let Some(ref cfg) = config {
+        if let Some(ref postgres_url) = cfg.postgres_alert_url {
+            alert_sinks.push(Arc::new(PostgresSink::connect_with_batching(
+                postgres_url.clone(),
+                cfg.postgres_max_batch,
+                Duration::from_secs(cfg.postgres_flush_interval_secs),
+            )));
+        }
+    }
+    let alert_sinks = Arc::new(alert_sinks);
+
+    let want_kalshi = platform == "all" || platform == "kalshi";
+    let want_polymarket = platform == "all" || platform == "polymarket";
+
+    if want_kalshi {
+        backfill_kalshi(
+            since_ts,
+            until_ts,
+            market,
+            batch_size,
+            config.as_ref(),
+            threshold,
+            &category_registry,
+            &selected_categories,
+            &store,
+            &alert_sinks,
+        )
+        .await?;
+
+        println!("Rebuilding Kalshi candles from stored trades...");
+        store.rebuild_candles("Kalshi", since_ts, until_ts);
+    }
+
+    if want_polymarket {
+        backfill_polymarket(
+            since_ts,
+            until_ts,
+            market,
+            batch_size,
+            config.as_ref(),
+            threshold,
+            &category_registry,
+            &selected_categories,
+            &store,
+            &alert_sinks,
+        )
+        .await?;
+
+        println!("Rebuilding Polymarket candles from stored trades...");
+        store.rebuild_candles("Polymarket", since_ts, until_ts);
+    }
+
+    Ok(())
+}
+
+/// One half-open `window_min_ts_ms..window_max_ts_ms` slice of the Kalshi
+/// walk, sized to `KALSHI_WINDOW_SECS`.
+fn kalshi_windows(since_ts_ms: i64, start_max_ts_ms: i64) -> Vec<(i64, i64)> {
+    let mut windows = Vec::new();
+    let mut window_max_ts_ms = start_max_ts_ms;
+    while window_max_ts_ms > since_ts_ms {
+        let window_min_ts_ms = (window_max_ts_ms - KALSHI_WINDOW_SECS * 1000).max(since_ts_ms);
+        windows.push((window_min_ts_ms, window_max_ts_ms));
+        window_max_ts_ms = window_min_ts_ms;
+    }
+    windows
+}
+
+#[allow(clippy::too_many_arguments)]
+async fn backfill_kalshi(
+    since_ts: i64,
+    until_ts: i64,
+    market: Option<&str>,
+    batch_size: u32,
+    config: Option<&Config>,
+    threshold: u64,
+    category_registry: &CategoryRegistry,
+    selected_categories: &[String],
+    store: &Arc<dyn AlertStore>,
+    alert_sinks: &Arc<Vec<Arc<dyn AlertSink>>>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let since_ts_ms = since_ts * 1000;
+    let start_max_ts_ms = store
+        .get_metadata(KALSHI_CHECKPOINT_KEY)
+        .and_then(|v| v.parse::<i64>().ok())
+        .unwrap_or(until_ts * 1000);
+    let windows = kalshi_windows(since_ts_ms, start_max_ts_ms);
+
+    println!(
+        "Backfilling Kalshi trades back to {} across {} window(s) ({} concurrent)...",
+        since_ts,
+        windows.len(),
+        BACKFILL_CONCURRENCY
+    );
+
+    let semaphore = Arc::new(Semaphore::new(BACKFILL_CONCURRENCY));
+    let tasks = windows.iter().map(|&(window_min_ts_ms, window_max_ts_ms)| {
+        let semaphore = semaphore.clone();
+        async move {
+            let _permit = semaphore.acquire().await.expect("semaphore not closed");
+            backfill_kalshi_window(
+                window_min_ts_ms,
+                window_max_ts_ms,
+                market,
+                batch_size,
+                config,
+                threshold,
+                category_registry,
+                selected_categories,
+                store,
+                alert_sinks,
+            )
+            .await
+        }
+    });
+
+    let results = join_all(tasks).await;
+    let stored: usize = results.into_iter().collect::<Result<Vec<_>, _>>()?.into_iter().sum();
+
+    // Checkpointed once the whole window set clears rather than per-window:
+    // with windows running concurrently there's no single "furthest point
+    // reached so far" to checkpoint mid-flight, and a re-run over an
+    // already-covered window is a no-op anyway (`record_raw_trade`/
+    // `insert_alert` both dedup on trade id).
+    store.set_metadata(KALSHI_CHECKPOINT_KEY, &since_ts_ms.to_string());
+
+    println!("Kalshi backfill complete: {} alerts stored", stored);
+    Ok(())
+}
+
+/// Fetch, filter, alert, and raw-persist one Kalshi window. Returns the
+/// number of trades that cleared the threshold/category/odds filters and
+/// were logged as alerts (raw persistence happens for every trade in the
+/// window regardless of whether it alerted).
+#[allow(clippy::too_many_arguments)]
+async fn backfill_kalshi_window(
+    window_min_ts_ms: i64,
+    window_max_ts_ms: i64,
+    market: Option<&str>,
+    batch_size: u32,
+    config: Option<&Config>,
+    threshold: u64,
+    category_registry: &CategoryRegistry,
+    selected_categories: &[String],
+    store: &Arc<dyn AlertStore>,
+    alert_sinks: &Arc<Vec<Arc<dyn AlertSink>>>,
+) -> Result<usize, Box<dyn std::error::Error>> {
+    let query = kalshi::TradeQuery {
+        ticker: market.map(str::to_string),
+        min_ts: Some(window_min_ts_ms),
+        max_ts: Some(window_max_ts_ms),
+        page_limit: batch_size,
+    };
+
+    let trades = kalshi::fetch_recent_trades_query(config, query).await?;
+
+    // Cache market titles per ticker for the life of this window, so a busy
+    // ticker's market info isn't refetched on every one of its trades.
+    let mut market_titles: HashMap<String, Option<String>> = HashMap::new();
+    let mut stored = 0usize;
+
+    for trade in &trades {
+        let taker_price = if trade.taker_side.eq_ignore_ascii_case("no") {
+            trade.no_price
+        } else {
+            trade.yes_price
+        };
+        let trade_value = (taker_price / 100.0) * f64::from(trade.count);
+        let timestamp = unix_timestamp(&trade.created_time);
+
+        store.record_raw_trade(
+            "Kalshi",
+            &trade.ticker,
+            Some(&trade.trade_id),
+            &trade.taker_side,
+            trade.yes_price / 100.0,
+            f64::from(trade.count),
+            timestamp,
+        );
+
+        if trade_value < threshold as f64 {
+            continue;
+        }
+
+        let market_title = if let Some(cached) = market_titles.get(&trade.ticker) {
+            cached.clone()
+        } else {
+            let title = kalshi::fetch_market_info_full(&trade.ticker).await.map(|info| info.title);
+            market_titles.insert(trade.ticker.clone(), title.clone());
+            title
+        };
+
+        if let Some(ref title) = market_title {
+            if category_registry.matches_selection(title, selected_categories).is_none() {
+                if category_registry.categorize(title).is_none() {
+                    store.record_uncategorized_title(title);
+                }
+                continue;
+            }
+        }
+
+        let market_ctx = kalshi::fetch_market_context(&trade.ticker).await;
+        if let Some(cfg) = config {
+            if let Some(ref ctx) = market_ctx {
+                if ctx.yes_price > cfg.max_odds || ctx.no_price > cfg.max_odds {
+                    continue;
+                }
+                if cfg.min_spread > 0.0 && ctx.spread < cfg.min_spread {
+                    continue;
+                }
+            }
+        }
+
+        let outcome = kalshi::parse_ticker_details(&trade.ticker, &trade.taker_side);
+        let action = trade.taker_side.to_uppercase();
+
+        let alert_data = AlertData {
+            platform: "Kalshi",
+            market_title: market_title.as_deref(),
+            market_id: Some(&trade.ticker),
+            trade_id: Some(&trade.trade_id),
+            outcome: Some(&outcome),
+            side: &action,
+            value: trade_value,
+            price: trade.yes_price / 100.0,
+            size: f64::from(trade.count),
+            timestamp: &trade.created_time,
+            wallet_id: None,
+            wallet_activity: None,
+            market_context: market_ctx.as_ref(),
+            whale_profile: None,
+            order_book: None,
+            top_holders: None,
+            arbitrage: None,
+            combinatorial: None,
+            is_rollover: false,
+        };
+
+        let params = history::build_log_params(&alert_data);
+        let sinks_clone = alert_sinks.clone();
+        tokio::task::spawn_blocking(move || history::log_alert_blocking(params, &sinks_clone))
+            .await
+            .ok();
+
+        stored += 1;
+    }
+
+    Ok(stored)
+}
+
+#[allow(clippy::too_many_arguments)]
+async fn backfill_polymarket(
+    since_ts: i64,
+    until_ts: i64,
+    market: Option<&str>,
+    batch_size: u32,
+    config: Option<&Config>,
+    threshold: u64,
+    category_registry: &CategoryRegistry,
+    selected_categories: &[String],
+    store: &Arc<dyn AlertStore>,
+    alert_sinks: &Arc<Vec<Arc<dyn AlertSink>>>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    // The data-api has no time-range filter, so this pages backward with
+    // `offset` and relies on the feed being newest-first: pages newer than
+    // `until_ts` are skipped without stopping, and the walk stops at the
+    // first trade older than `since_ts`. No independent windows to hand to a
+    // worker pool here — each page's stopping condition depends on the
+    // previous one, so this stays sequential.
+    let mut offset: u32 = store
+        .get_metadata(POLYMARKET_CHECKPOINT_KEY)
+        .and_then(|v| v.parse::<u32>().ok())
+        .unwrap_or(0);
+    let mut stored = 0usize;
+
+    println!("Backfilling Polymarket trades back to {}...", since_ts);
+
+    loop {
+        let page = polymarket::fetch_trades_page(shared_http_client(), offset, batch_size).await?;
+        if page.is_empty() {
+            break;
+        }
+
+        let mut reached_since = false;
+        for trade in &page {
+            if let Some(market) = market {
+                if trade.market != market {
+                    continue;
+                }
+            }
+
+            let trade_ts = unix_timestamp(&trade.timestamp);
+            if trade_ts < since_ts {
+                reached_since = true;
+                break;
+            }
+            if trade_ts > until_ts {
+                continue;
+            }
+
+            store.record_raw_trade(
+                "Polymarket",
+                &trade.market,
+                Some(&trade.id),
+                &trade.side,
+                trade.price,
+                trade.size,
+                trade_ts,
+            );
+
+            let trade_value = trade.size * trade.price;
+            if trade_value < threshold as f64 {
+                continue;
+            }
+
+            if let Some(ref title) = trade.market_title {
+                if category_registry.matches_selection(title, selected_categories).is_none() {
+                    if category_registry.categorize(title).is_none() {
+                        store.record_uncategorized_title(title);
+                    }
+                    continue;
+                }
+            }
+
+            let market_ctx = polymarket::fetch_market_context(&trade.market).await;
+            if let Some(cfg) = config {
+                if let Some(ref ctx) = market_ctx {
+                    if ctx.yes_price > cfg.max_odds || ctx.no_price > cfg.max_odds {
+                        continue;
+                    }
+                    if cfg.min_spread > 0.0 && ctx.spread < cfg.min_spread {
+                        continue;
+                    }
+                }
+            }
+
+            let alert_data = AlertData {
+                platform: "Polymarket",
+                market_title: trade.market_title.as_deref(),
+                market_id: Some(&trade.market),
+                trade_id: Some(&trade.id),
+                outcome: trade.outcome.as_deref(),
+                side: &trade.side,
+                value: trade_value,
+                price: trade.price,
+                size: trade.size,
+                timestamp: &trade.timestamp,
+                wallet_id: trade.wallet_id.as_deref(),
+                wallet_activity: None,
+                market_context: market_ctx.as_ref(),
+                whale_profile: None,
+                order_book: None,
+                top_holders: None,
+                arbitrage: None,
+                combinatorial: None,
+                is_rollover: false,
+            };
+
+            let params = history::build_log_params(&alert_data);
+            let sinks_clone = alert_sinks.clone();
+            tokio::task::spawn_blocking(move || history::log_alert_blocking(params, &sinks_clone))
+                .await
+                .ok();
+
+            stored += 1;
+        }
+
+        offset += page.len() as u32;
+        store.set_metadata(POLYMARKET_CHECKPOINT_KEY, &offset.to_string());
+
+        if reached_since {
+            break;
+        }
+
+        tokio::time::sleep(PAGE_DELAY).await;
+    }
+
+    println!("Polymarket backfill complete: {} alerts stored", stored);
+    Ok(())
+}
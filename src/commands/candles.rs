@@ -0,0 +1,53 @@
+//! CLI query command for the OHLCV candles `db::SqliteStore::record_candle_trade`
+//! materializes into the `candles` table (see `candles::CandleCache`). Prints
+//! a `(platform, market, resolution)`'s bars over a time window, oldest
+//! first — the same window `candles::build_candles` queries, just formatted
+//! for a terminal instead of an HTTP response (compare `commands::api`'s
+//! `/candles` endpoint, which serves the most-recent-`limit` form instead).
+
+use colored::*;
+
+use crate::db::AlertStore;
+
+#[allow(clippy::too_many_arguments)]
+pub fn show_candles(
+    store: &dyn AlertStore,
+    platform: &str,
+    market: &str,
+    resolution: &str,
+    from_unix: i64,
+    to_unix: i64,
+    as_json: bool,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let candles = store.query_candles_range(platform, market, resolution, from_unix, to_unix)?;
+
+    if as_json {
+        println!("{}", serde_json::to_string_pretty(&candles)?);
+        return Ok(());
+    }
+
+    if candles.is_empty() {
+        println!("No candles found for {} {} ({}) in that window.", platform, market, resolution);
+        return Ok(());
+    }
+
+    println!("{}", format!("CANDLES  {} {} ({})", platform, market, resolution).bright_cyan().bold());
+    println!(
+        "{:<12} {:>10} {:>10} {:>10} {:>10} {:>12} {:>6}",
+        "start_ts", "open", "high", "low", "close", "volume", "trades"
+    );
+    for c in &candles {
+        println!(
+            "{:<12} {:>10.4} {:>10.4} {:>10.4} {:>10.4} {:>12.2} {:>6}",
+            c["start_ts"].as_i64().unwrap_or(0),
+            c["open"].as_f64().unwrap_or(0.0),
+            c["high"].as_f64().unwrap_or(0.0),
+            c["low"].as_f64().unwrap_or(0.0),
+            c["close"].as_f64().unwrap_or(0.0),
+            c["volume"].as_f64().unwrap_or(0.0),
+            c["trade_count"].as_i64().unwrap_or(0),
+        );
+    }
+
+    Ok(())
+}
@@ -0,0 +1,56 @@
+//! Queries `store::PostgresTradeStore`'s shared `candles` table — the
+//! Postgres-backed counterpart to `commands::candles::show_candles`, which
+//! reads the local SQLite `candles` table instead. Use this one when
+//! `config::Config::trade_store_url` is set and several watcher instances
+//! are flowing trades into one database; use `candles` for a single local
+//! instance.
+use colored::*;
+
+use crate::store::PostgresTradeStore;
+
+#[allow(clippy::too_many_arguments)]
+pub async fn show_history(
+    trade_store: &PostgresTradeStore,
+    platform: &str,
+    market: &str,
+    resolution: &str,
+    from_unix: i64,
+    to_unix: i64,
+    as_json: bool,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let candles = trade_store.query_candles(platform, market, resolution, from_unix, to_unix).await;
+
+    if as_json {
+        println!("{}", serde_json::to_string_pretty(&candles.iter().map(|c| {
+            serde_json::json!({
+                "start_ts": c.start_ts,
+                "open": c.open,
+                "high": c.high,
+                "low": c.low,
+                "close": c.close,
+                "volume": c.volume,
+                "trade_count": c.trade_count,
+            })
+        }).collect::<Vec<_>>())?);
+        return Ok(());
+    }
+
+    if candles.is_empty() {
+        println!("No candles found for {} {} ({}) in that window.", platform, market, resolution);
+        return Ok(());
+    }
+
+    println!("{}", format!("HISTORY  {} {} ({})", platform, market, resolution).bright_cyan().bold());
+    println!(
+        "{:<12} {:>10} {:>10} {:>10} {:>10} {:>12} {:>6}",
+        "start_ts", "open", "high", "low", "close", "volume", "trades"
+    );
+    for c in &candles {
+        println!(
+            "{:<12} {:>10.4} {:>10.4} {:>10.4} {:>10.4} {:>12.2} {:>6}",
+            c.start_ts, c.open, c.high, c.low, c.close, c.volume, c.trade_count,
+        );
+    }
+
+    Ok(())
+}
@@ -2,14 +2,6 @@ use colored::*;
 
 use crate::execution::kalshi::KalshiExecutor;
 
-fn resolve_pem(input: &str) -> String {
-    if input.starts_with('/') || input.starts_with('.') || input.contains('/') {
-        std::fs::read_to_string(input).unwrap_or_else(|_| input.to_string())
-    } else {
-        input.to_string()
-    }
-}
-
 pub async fn show_positions() -> Result<(), Box<dyn std::error::Error>> {
     println!("{}", "KALSHI POSITIONS".bright_cyan().bold());
     println!();
@@ -25,18 +17,32 @@ pub async fn show_positions() -> Result<(), Box<dyn std::error::Error>> {
         }
     };
 
-    let (key_id, private_key_input) = match (&config.kalshi_api_key_id, &config.kalshi_private_key) {
-        (Some(k), Some(p)) => (k.clone(), p.clone()),
-        _ => {
+    let key_id = match &config.kalshi_api_key_id {
+        Some(k) => k.clone(),
+        None => {
+            println!(
+                "{}",
+                "Kalshi API not configured. Run 'wwatcher setup' to add credentials.".red()
+            );
+            return Ok(());
+        }
+    };
+
+    let private_key_pem = match crate::keystore::resolve_kalshi_private_key(&config) {
+        Ok(Some(pem)) => pem,
+        Ok(None) => {
             println!(
                 "{}",
                 "Kalshi API not configured. Run 'wwatcher setup' to add credentials.".red()
             );
             return Ok(());
         }
+        Err(e) => {
+            println!("{} Could not unlock Kalshi private key: {}", "[ERROR]".red(), e);
+            return Ok(());
+        }
     };
 
-    let private_key_pem = resolve_pem(&private_key_input);
     let executor = match KalshiExecutor::new(key_id, &private_key_pem, config.kalshi_is_demo) {
         Ok(ex) => ex,
         Err(e) => {
@@ -1,9 +1,9 @@
 use colored::*;
-use rusqlite::Connection;
 
-use crate::db;
+use crate::categories::CategoryRegistry;
+use crate::db::{self, AlertStore};
 
-pub async fn show_status(conn: &Connection) -> Result<(), Box<dyn std::error::Error>> {
+pub async fn show_status(store: &dyn AlertStore) -> Result<(), Box<dyn std::error::Error>> {
     println!("{}", "WHALE WATCHER STATUS".bright_cyan().bold());
     println!();
 
@@ -18,6 +18,18 @@ pub async fn show_status(conn: &Connection) -> Result<(), Box<dyn std::error::Er
                     "Not configured (using public data)".yellow()
                 }
             );
+            if cfg.kalshi_api_key_id.is_some() {
+                println!(
+                    "  Kalshi key: {}",
+                    if cfg.kalshi_private_key_encrypted.is_some() {
+                        "Encrypted".green()
+                    } else if cfg.kalshi_private_key.is_some() {
+                        "Plaintext — run 'wwatcher setup' to encrypt it".yellow()
+                    } else {
+                        "Not set".yellow()
+                    }
+                );
+            }
             println!(
                 "  Polymarket API: {}",
                 "Public access (no key needed)".green()
@@ -44,6 +56,18 @@ pub async fn show_status(conn: &Connection) -> Result<(), Box<dyn std::error::Er
                 cfg.categories.join(", ")
             };
             println!("  Categories:    {}", cat_display.green());
+
+            let registry = CategoryRegistry::load();
+            for category in &cfg.categories {
+                if let Some(replacement) = registry.deprecated_replacement(category) {
+                    println!(
+                        "  {} \"{}\" is deprecated, use \"{}\" instead",
+                        "Warning:".yellow().bold(),
+                        category,
+                        replacement
+                    );
+                }
+            }
             println!("  Threshold:     {}", format!("${}", cfg.threshold).green());
             println!("  Min whale WR: {}%", format!("{:.0}", cfg.min_whale_win_rate * 100.0).green());
             println!(
@@ -63,11 +87,20 @@ pub async fn show_status(conn: &Connection) -> Result<(), Box<dyn std::error::Er
 
     println!();
     println!("Database:");
-    let alert_count = db::alert_count(conn);
+    let alert_count = store.alert_count();
     println!("  Alerts stored: {}", alert_count.to_string().bright_white());
     if let Ok(path) = db::db_path() {
         println!("  Location: {}", path.display().to_string().dimmed());
     }
 
+    let uncategorized = store.top_uncategorized_titles(10);
+    if !uncategorized.is_empty() {
+        println!();
+        println!("Top uncategorized market phrases:");
+        for (title, hit_count, _last_seen) in &uncategorized {
+            println!("  {} {}", format!("({}x)", hit_count).dimmed(), title);
+        }
+    }
+
     Ok(())
 }
@@ -26,7 +26,7 @@ pub async fn test_sound() -> Result<(), Box<dyn std::error::Error>> {
     Ok(())
 }
 
-pub async fn test_webhook(conn: &rusqlite::Connection) -> Result<(), Box<dyn std::error::Error>> {
+pub async fn test_webhook(store: std::sync::Arc<dyn crate::db::AlertStore>) -> Result<(), Box<dyn std::error::Error>> {
     println!("{}", "TESTING WEBHOOK".bright_cyan().bold());
     println!();
 
@@ -79,6 +79,8 @@ pub async fn test_webhook(conn: &rusqlite::Connection) -> Result<(), Box<dyn std
     let buy_alert = AlertData {
         platform: "Polymarket",
         market_title: Some("yes Michigan St.,yes Saint Peter's,yes Harvard wins by over 5.5 Points,no Iona wins by over 5.5 Points,no Boise St. wins by over 9.5 Points"),
+        market_id: None,
+        trade_id: None,
         outcome: Some("Yes"),
         side: "BUY",
         value: 250000.0,
@@ -91,9 +93,12 @@ pub async fn test_webhook(conn: &rusqlite::Connection) -> Result<(), Box<dyn std
         whale_profile: Some(&test_whale),
         order_book: None,
         top_holders: None,
+        arbitrage: None,
+        combinatorial: None,
+        is_rollover: false,
     };
-    webhook::send_webhook_alert(&webhook_url, &buy_alert).await;
-    crate::alerts::history::log_alert(&buy_alert, conn);
+    webhook::send_webhook_alert(&webhook_url, &buy_alert, config.text_format.as_deref()).await;
+    crate::alerts::history::log_alert(&buy_alert, store.clone());
 
     println!("High-Tier Test BUY alert sent and logged!");
 
@@ -104,6 +109,8 @@ pub async fn test_webhook(conn: &rusqlite::Connection) -> Result<(), Box<dyn std
     let sell_alert = AlertData {
         platform: "Kalshi",
         market_title: Some("Bitcoin price on Jan 16, 2026?"),
+        market_id: None,
+        trade_id: None,
         outcome: Some("Bitcoin (BTC) price < $96999.99 at expiry"),
         side: "SELL",
         value: 35000.0,
@@ -116,9 +123,12 @@ pub async fn test_webhook(conn: &rusqlite::Connection) -> Result<(), Box<dyn std
         whale_profile: None,
         order_book: None,
         top_holders: None,
+        arbitrage: None,
+        combinatorial: None,
+        is_rollover: false,
     };
-    webhook::send_webhook_alert(&webhook_url, &sell_alert).await;
-    crate::alerts::history::log_alert(&sell_alert, conn);
+    webhook::send_webhook_alert(&webhook_url, &sell_alert, config.text_format.as_deref()).await;
+    crate::alerts::history::log_alert(&sell_alert, store);
 
     println!("Test SELL alert sent and logged!");
     println!();
@@ -1,27 +1,73 @@
-use std::sync::{Arc, Mutex, OnceLock};
+use std::sync::{Arc, OnceLock};
 use std::time::Duration;
 
 use colored::*;
-use rusqlite::Connection;
 use tokio::time;
 
 use crate::alerts::AlertData;
 use crate::alerts::display::{self, format_number, print_kalshi_alert, print_market_context, print_order_book, print_top_holders, print_whale_alert, print_whale_profile};
 use crate::alerts::history;
+use crate::alerts::sinks::{AlertSink, JsonlSink, PostgresSink, SqliteSink};
 use crate::categories::CategoryRegistry;
-use crate::db;
+use crate::db::AlertStore;
 use crate::platforms::kalshi;
 use crate::platforms::polymarket;
 use crate::types;
 use crate::whale_profile;
 use crate::execution::matcher::MarketMatcher;
 use crate::execution::kalshi::KalshiExecutor;
+use crate::execution::position::{self, ExitThresholds, Position, PositionState, PositionStore};
+use crate::execution::rollover;
+
+/// Unix-seconds form of an RFC3339 timestamp, for folding a logged alert into
+/// its OHLCV candle bucket. Falls back to "now" so a malformed timestamp
+/// drops the trade into the current bucket rather than being skipped.
+fn unix_timestamp(rfc3339: &str) -> i64 {
+    chrono::DateTime::parse_from_rfc3339(rfc3339)
+        .map(|dt| dt.timestamp())
+        .unwrap_or_else(|_| chrono::Utc::now().timestamp())
+}
 
-fn resolve_pem(input: &str) -> String {
-    if input.starts_with('/') || input.starts_with('.') || input.contains('/') {
-        std::fs::read_to_string(input).unwrap_or_else(|_| input.to_string())
-    } else {
-        input.to_string()
+/// Fire-and-forget a trade into `store::PostgresTradeStore` when one is
+/// configured, alongside the unconditional `store.record_candle_trade` call
+/// every trade already goes through for the local SQLite candle cache.
+/// Spawned rather than awaited so a slow/unreachable Postgres pool can't
+/// stall the trade-processing hot path the way it wouldn't for the local
+/// SQLite write either.
+fn record_trade_history(
+    trade_store: &Option<Arc<crate::store::PostgresTradeStore>>,
+    platform: &'static str,
+    market: String,
+    side: String,
+    price: f64,
+    size: f64,
+    occurred_at: i64,
+) {
+    if let Some(ts) = trade_store.clone() {
+        tokio::spawn(async move {
+            ts.record_trade(platform, &market, &side, price, size, occurred_at).await;
+        });
+    }
+}
+
+/// Whether `ob`'s resting depth clears `min_depth` on both sides, in dollar
+/// notional. `OrderBookSummary::bid_depth_10pct`/`ask_depth_10pct` already
+/// sum `price * quantity` (Kalshi: across every level `fetch_order_book`
+/// returned; Polymarket: across levels within 10% of the best price) —
+/// this just takes the thinner side, since a whale print backed by deep
+/// bids and an empty ask book still can't be unwound at size. `min_depth <=
+/// 0.0` (the config default) disables the check entirely, matching how
+/// `min_spread` disables itself at 0.0. No order book (a fetch timeout/
+/// error) passes through unfiltered — liquidity is unknown, not confirmed
+/// thin, and the existing odds/spread filters already run on the same
+/// context fetch that could fail the same way.
+fn passes_min_depth(order_book: &Option<crate::alerts::OrderBookSummary>, min_depth: f64) -> bool {
+    if min_depth <= 0.0 {
+        return true;
+    }
+    match order_book {
+        Some(ob) => ob.bid_depth_10pct.min(ob.ask_depth_10pct) >= min_depth,
+        None => true,
     }
 }
 
@@ -123,7 +169,1221 @@ async fn fetch_kalshi_market_snapshot(ticker: &str) -> Option<KalshiMarketSnapsh
     })
 }
 
-pub async fn watch_whales(threshold: u64, interval: u64, conn: Arc<Mutex<Connection>>) -> Result<(), Box<dyn std::error::Error>> {
+// ── Fill confirmation ───────────────────────────────────────────────────
+
+/// Confirm `order_id` reaches `count` fills (or gets canceled), preferring
+/// `fill_watcher`'s push-based `await_fill` when the fill socket is up —
+/// resolves as soon as Kalshi reports the fill instead of waiting out a
+/// fixed poll cadence. Falls back to the old 5-attempt/2s HTTP poll of
+/// `executor.get_order_status` when the socket is down, mirroring how
+/// `kalshi_ws_active` gates the trade feed's own HTTP fallback.
+async fn confirm_fill(
+    executor: &KalshiExecutor,
+    fill_watcher: &Option<Arc<crate::ws::kalshi_fills::FillWatcher>>,
+    order_id: &str,
+    count: i32,
+) -> bool {
+    if let Some(watcher) = fill_watcher {
+        if watcher.is_active() {
+            return match watcher.await_fill(order_id, count, Duration::from_secs(10)).await {
+                Some(update) => update.status == "executed" || update.fill_count >= count,
+                None => false,
+            };
+        }
+    }
+
+    for attempt in 1..=5 {
+        tokio::time::sleep(Duration::from_secs(2)).await;
+        if let Ok((status, fill_count)) = executor.get_order_status(order_id).await {
+            if status == "executed" || fill_count >= count {
+                return true;
+            }
+            if status == "canceled" {
+                return false;
+            }
+            if attempt < 5 {
+                println!("   Poll {}/5: status={} fill_count={} — waiting...", attempt, status, fill_count);
+            }
+        }
+    }
+    false
+}
+
+// ── Position lifecycle monitoring ──────────────────────────────────────
+
+/// Poll every position still `Open`/`Active`/`Settling` for its current
+/// Kalshi price and close out whichever ones cross a take-profit/stop-loss
+/// threshold (or Kalshi has stopped quoting it — treated as a settlement).
+/// Closing a position records realized P&L (replacing the old entry-cost
+/// `daily_loss_cents` accounting), frees its `dedup_key` back into Gate 3's
+/// open-position count, and sends a Discord exit alert alongside the
+/// existing entry `send_execution_alert`. A position moves to `Settling` the
+/// moment its exit order is placed, so a tick that catches it before the
+/// fill confirms (or resumes it after a restart) reconciles the existing
+/// order instead of placing a second one on top of it.
+#[allow(clippy::too_many_arguments)]
+async fn monitor_positions(
+    position_store: &PositionStore,
+    executor: &KalshiExecutor,
+    fill_watcher: &Option<Arc<crate::ws::kalshi_fills::FillWatcher>>,
+    executed_tickers: &mut std::collections::HashMap<String, std::time::Instant>,
+    daily_loss_cents: &mut i64,
+    thresholds: &ExitThresholds,
+    config: &Option<crate::config::Config>,
+) {
+    let positions = match position_store.load_monitored() {
+        Ok(p) => p,
+        Err(e) => {
+            eprintln!("⚠️ Could not load monitored positions: {}", e);
+            return;
+        }
+    };
+
+    for pos in positions {
+        let Some(id) = pos.id else { continue };
+
+        // A `Settling` position already has an exit order resting — reconcile
+        // it instead of evaluating a fresh exit signal, so a tick that
+        // catches a not-yet-filled order (or resumes after a restart) never
+        // places a second exit order on top of the first.
+        if matches!(pos.state, PositionState::Settling) {
+            let (Some(order_id), Some(exit_price_cents), Some(reason)) =
+                (pos.exit_order_id.clone(), pos.exit_price_cents, pos.exit_reason)
+            else {
+                // Shouldn't happen, but don't get stuck if it does.
+                if let Err(e) = position_store.clear_settling(id) {
+                    eprintln!("⚠️ Could not reset incomplete settling state for {}: {}", id, e);
+                }
+                continue;
+            };
+
+            match executor.get_order_status(&order_id).await {
+                Ok((status, fill_count)) if status == "executed" || fill_count >= pos.count => {
+                    close_filled_exit(
+                        position_store, daily_loss_cents, executed_tickers, config,
+                        &pos, id, reason, exit_price_cents, order_id,
+                    ).await;
+                }
+                Ok((status, _)) if status == "canceled" => {
+                    println!("ℹ️ Stale exit order {} for {} was already cancelled — will retry next cycle", order_id, pos.ticker);
+                    if let Err(e) = position_store.clear_settling(id) {
+                        eprintln!("⚠️ Could not clear settling state for {}: {}", id, e);
+                    }
+                }
+                Ok(_) => {
+                    // Still resting — cancel it so a retry can't stack a second
+                    // live sell order on the same inventory, then pick it back
+                    // up fresh next cycle.
+                    if let Err(e) = executor.cancel_order(&order_id).await {
+                        eprintln!("⚠️ Could not cancel stale exit order {} for {}: {}", order_id, pos.ticker, e);
+                    } else {
+                        println!("🧹 Cancelled stale exit order {} for {} — will retry next cycle", order_id, pos.ticker);
+                        if let Err(e) = position_store.clear_settling(id) {
+                            eprintln!("⚠️ Could not clear settling state for {}: {}", id, e);
+                        }
+                    }
+                }
+                Err(e) => eprintln!("⚠️ Could not check exit order {} status for {}: {}", order_id, pos.ticker, e),
+            }
+            continue;
+        }
+
+        let reason = match fetch_kalshi_market_snapshot(&pos.ticker).await {
+            Some(snapshot) => {
+                let live_price_cents = if pos.side.eq_ignore_ascii_case("yes") {
+                    snapshot.yes_price_cents
+                } else {
+                    snapshot.no_price_cents
+                };
+                match position::check_exit(&pos, live_price_cents, thresholds) {
+                    Some(reason) => (reason, live_price_cents),
+                    None => continue,
+                }
+            }
+            // Kalshi no longer serves this market — treat as settled.
+            None => (position::ExitReason::Settlement, pos.entry_price_cents),
+        };
+        let (reason, exit_price_cents) = reason;
+
+        println!(
+            "🔔 Exit signal for {} ({}): {} — {}c (entry {}c)",
+            pos.ticker, pos.side, reason.as_str(), exit_price_cents, pos.entry_price_cents
+        );
+
+        match executor.place_exit_order(&pos.ticker, &pos.side, pos.count, exit_price_cents).await {
+            Ok(order_id) => {
+                if let Err(e) = position_store.set_settling(id, &order_id, exit_price_cents, reason) {
+                    eprintln!("⚠️ Could not record in-flight exit order {} for {}: {}", order_id, pos.ticker, e);
+                }
+
+                let filled = confirm_fill(executor, fill_watcher, &order_id, pos.count).await;
+                if !filled {
+                    println!("⚠️ Exit order {} not yet filled — will retry next cycle", order_id);
+                    continue;
+                }
+
+                close_filled_exit(
+                    position_store, daily_loss_cents, executed_tickers, config,
+                    &pos, id, reason, exit_price_cents, order_id,
+                ).await;
+            }
+            Err(e) => eprintln!("❌ Exit order failed for {}: {}", pos.ticker, e),
+        }
+    }
+}
+
+/// Record realized P&L for a filled exit, close the position out, and send
+/// the Discord exit alert — shared by the fresh-exit path above and the
+/// `Settling`-reconciliation path that finds a prior exit order already
+/// filled.
+#[allow(clippy::too_many_arguments)]
+async fn close_filled_exit(
+    position_store: &PositionStore,
+    daily_loss_cents: &mut i64,
+    executed_tickers: &mut std::collections::HashMap<String, std::time::Instant>,
+    config: &Option<crate::config::Config>,
+    pos: &Position,
+    id: i64,
+    reason: position::ExitReason,
+    exit_price_cents: i64,
+    order_id: String,
+) {
+    let exit_fee_cents = kalshi_taker_fee_cents(exit_price_cents) * i64::from(pos.count);
+    let total_fees = pos.entry_fee_cents + exit_fee_cents;
+    let pnl = position::realized_pnl_cents(pos.entry_price_cents, exit_price_cents, pos.count, total_fees);
+
+    *daily_loss_cents = (*daily_loss_cents - pnl).max(0);
+    if let Err(e) = position_store.close_position(id, pnl) {
+        eprintln!("⚠️ Could not persist closed position {}: {}", id, e);
+    }
+    executed_tickers.remove(&pos.dedup_key);
+
+    println!(
+        "✅ Closed {} {} — {} — realized PnL ${:.2}",
+        pos.ticker, pos.side, reason.as_str(), pnl as f64 / 100.0
+    );
+
+    if let Some(ref cfg) = config {
+        let url = cfg.webhook_url.as_ref().or(cfg.discord_webhook_url.as_ref());
+        if let Some(url) = url {
+            let exit_alert = crate::alerts::webhook::ExitAlert {
+                kalshi_ticker: pos.ticker.clone(),
+                side: pos.side.clone(),
+                count: pos.count,
+                entry_price_cents: pos.entry_price_cents,
+                exit_price_cents,
+                reason: reason.as_str().to_string(),
+                realized_pnl_cents: pnl,
+                order_id,
+            };
+            crate::alerts::webhook::send_exit_alert(url, &exit_alert).await;
+        }
+    }
+}
+
+/// For every `Active` position whose market is inside `window_hours` of its
+/// `close_time`, close the settling leg and reopen an equivalent-size
+/// position in the next period's market (found by `rollover::plan_rollover`
+/// over an on-demand Kalshi search), subject to the same daily-loss-limit
+/// and reserve guards Gate 8/9 apply to a fresh entry. Runs before
+/// `monitor_positions`' own settlement check each tick, so a position that
+/// rolls successfully never gets treated as "Kalshi stopped quoting it".
+#[allow(clippy::too_many_arguments)]
+async fn monitor_rollovers(
+    position_store: &PositionStore,
+    executor: &KalshiExecutor,
+    fill_watcher: &Option<Arc<crate::ws::kalshi_fills::FillWatcher>>,
+    executed_tickers: &mut std::collections::HashMap<String, std::time::Instant>,
+    daily_loss_cents: &mut i64,
+    day_start_balance_cents: Option<i64>,
+    daily_loss_frac: f64,
+    reserve_frac: f64,
+    window_hours: u32,
+    config: &Option<crate::config::Config>,
+) {
+    let positions = match position_store.load_monitored() {
+        Ok(p) => p,
+        Err(e) => {
+            eprintln!("⚠️ Could not load monitored positions for rollover: {}", e);
+            return;
+        }
+    };
+
+    for pos in positions {
+        let Some(id) = pos.id else { continue };
+        if !matches!(pos.state, PositionState::Active) {
+            continue;
+        }
+
+        let Some(info) = kalshi::fetch_market_info_full(&pos.ticker).await else { continue };
+        let Some(close_time) = info.close_time.as_deref() else { continue };
+        if !rollover::in_rollover_window(close_time, window_hours) {
+            continue;
+        }
+
+        let candidates = kalshi::search_markets(&info.title).await.unwrap_or_default();
+        let Some(plan) = rollover::plan_rollover(&pos.ticker, &info.title, candidates) else {
+            continue;
+        };
+
+        // Gate 8/9 equivalents: a roll is a fresh entry, so it's subject to
+        // the same daily loss limit and bankroll reserve as any other trade.
+        let balance_cents = executor.get_balance().await.unwrap_or(0);
+        let day_start = day_start_balance_cents.unwrap_or(balance_cents);
+        let loss_limit_cents = (day_start as f64 * daily_loss_frac) as i64;
+        if *daily_loss_cents >= loss_limit_cents {
+            println!("🛑 Rollover skipped for {}: daily loss limit hit", pos.ticker);
+            continue;
+        }
+        let reserve_cents = (day_start as f64 * reserve_frac) as i64;
+        let roll_cost_cents = i64::from(pos.count) * pos.entry_price_cents;
+        if balance_cents.saturating_sub(roll_cost_cents) < reserve_cents {
+            println!("⚠️ Rollover skipped for {}: would breach reserve", pos.ticker);
+            continue;
+        }
+
+        let Some(snapshot) = fetch_kalshi_market_snapshot(&pos.ticker).await else { continue };
+        let exit_price_cents = if pos.side.eq_ignore_ascii_case("yes") {
+            snapshot.yes_price_cents
+        } else {
+            snapshot.no_price_cents
+        };
+
+        let Ok(exit_order_id) = executor.place_exit_order(&pos.ticker, &pos.side, pos.count, exit_price_cents).await else {
+            continue;
+        };
+        if !confirm_fill(executor, fill_watcher, &exit_order_id, pos.count).await {
+            println!("⚠️ Rollover exit {} not yet filled — will retry next cycle", exit_order_id);
+            continue;
+        }
+
+        let exit_fee_cents = kalshi_taker_fee_cents(exit_price_cents) * i64::from(pos.count);
+        let total_fees = pos.entry_fee_cents + exit_fee_cents;
+        let pnl = position::realized_pnl_cents(pos.entry_price_cents, exit_price_cents, pos.count, total_fees);
+        *daily_loss_cents = (*daily_loss_cents - pnl).max(0);
+        if let Err(e) = position_store.close_position(id, pnl) {
+            eprintln!("⚠️ Could not persist rolled-out position {}: {}", id, e);
+        }
+        executed_tickers.remove(&pos.dedup_key);
+
+        let Some(into_snapshot) = fetch_kalshi_market_snapshot(&plan.into.ticker).await else { continue };
+        let entry_price_cents = if pos.side.eq_ignore_ascii_case("yes") {
+            into_snapshot.yes_price_cents
+        } else {
+            into_snapshot.no_price_cents
+        }
+        .clamp(1, 99);
+
+        let Ok(entry_order_id) = executor.place_order(&plan.into.ticker, &pos.side, pos.count, entry_price_cents).await else {
+            continue;
+        };
+        if !confirm_fill(executor, fill_watcher, &entry_order_id, pos.count).await {
+            println!("⚠️ Rollover entry {} not yet filled — will retry next cycle", entry_order_id);
+            continue;
+        }
+
+        let entry_fee_cents = kalshi_taker_fee_cents(entry_price_cents) * i64::from(pos.count);
+        let new_dedup_key = match plan.into.ticker.rfind('-') {
+            Some(p) => plan.into.ticker[..p].to_string(),
+            None => plan.into.ticker.clone(),
+        };
+        let new_position = Position {
+            id: None,
+            ticker: plan.into.ticker.clone(),
+            dedup_key: new_dedup_key.clone(),
+            side: pos.side.clone(),
+            entry_price_cents,
+            count: pos.count,
+            entry_fee_cents,
+            state: PositionState::Active,
+            realized_pnl_cents: None,
+            exit_order_id: None,
+            exit_price_cents: None,
+            exit_reason: None,
+        };
+        if let Err(e) = position_store.open_position(&new_position) {
+            eprintln!("⚠️ Could not persist rolled-in position: {}", e);
+        }
+        executed_tickers.insert(new_dedup_key, std::time::Instant::now());
+
+        println!(
+            "🔄 Rolled {} → {} ({} {}c → {}c)",
+            pos.ticker, plan.into.ticker, pos.side, exit_price_cents, entry_price_cents
+        );
+
+        if let Some(ref cfg) = config {
+            let url = cfg.webhook_url.as_ref().or(cfg.discord_webhook_url.as_ref());
+            if let Some(url) = url {
+                let timestamp = chrono::Utc::now().to_rfc3339();
+                let alert_data = AlertData {
+                    platform: "Kalshi",
+                    market_title: Some(plan.into.title.as_str()),
+                    market_id: Some(plan.into.ticker.as_str()),
+                    trade_id: None,
+                    outcome: None,
+                    side: pos.side.as_str(),
+                    value: (entry_price_cents as f64 / 100.0) * f64::from(pos.count),
+                    price: entry_price_cents as f64 / 100.0,
+                    size: f64::from(pos.count),
+                    timestamp: &timestamp,
+                    wallet_id: None,
+                    wallet_activity: None,
+                    market_context: None,
+                    whale_profile: None,
+                    order_book: None,
+                    top_holders: None,
+                    arbitrage: None,
+                    combinatorial: None,
+                    is_rollover: true,
+                };
+                crate::alerts::webhook::send_webhook_alert(url, &alert_data, cfg.text_format.as_deref()).await;
+            }
+        }
+    }
+}
+
+/// Prints and logs a `HybridRouter`-detected cross-venue mispricing as its
+/// own alert — kept separate from the triggering trade's regular whale
+/// entry/exit alert, since the opportunity concerns both platforms rather
+/// than just the one the trade happened on. Shared by both Kalshi call
+/// sites and the Polymarket trade loop.
+async fn emit_arbitrage_alert(
+    pair: &crate::execution::arbitrage::ArbitragePair,
+    timestamp: &str,
+    alert_sinks: &Arc<Vec<Arc<dyn AlertSink>>>,
+) {
+    display::print_arbitrage_alert(pair);
+
+    let alert_data = AlertData {
+        platform: "Arbitrage",
+        market_title: None,
+        market_id: Some(&pair.kalshi_ticker),
+        trade_id: None,
+        outcome: None,
+        side: "ARBITRAGE",
+        value: pair.opportunity.edge,
+        price: if pair.buy_yes_on_polymarket { pair.kalshi_no_price } else { pair.kalshi_yes_price },
+        size: pair.opportunity.break_even_notional,
+        timestamp,
+        wallet_id: None,
+        wallet_activity: None,
+        market_context: None,
+        whale_profile: None,
+        order_book: None,
+        top_holders: None,
+        arbitrage: Some(pair),
+        combinatorial: None,
+        is_rollover: false,
+    };
+
+    let params = history::build_log_params(&alert_data);
+    let sinks_clone = alert_sinks.clone();
+    tokio::task::spawn_blocking(move || history::log_alert_blocking(params, &sinks_clone))
+        .await
+        .ok();
+}
+
+// ── Kalshi trade processing ─────────────────────────────────────────
+
+/// Run one Kalshi trade — from either the live WebSocket stream or a
+/// reconnect gap backfill (`reconcile_kalshi_gap`) — through the same
+/// threshold/category/odds filters and alert/log/candle pipeline, so a
+/// trade recovered after an outage is indistinguishable downstream from one
+/// that arrived live. Records `ticker` → `trade_id` in `kalshi_last_trade_id`
+/// unconditionally (even when the trade itself is filtered out) so
+/// `reconcile_kalshi_gap` has an accurate resume point per ticker.
+#[allow(clippy::too_many_arguments)]
+async fn process_kalshi_trade(
+    ws_trade: crate::ws::kalshi::WsTrade,
+    threshold: u64,
+    category_registry: &CategoryRegistry,
+    selected_categories: &[String],
+    config: &Option<crate::config::Config>,
+    kalshi_market_cache: &mut std::collections::HashMap<String, Option<kalshi::MarketInfo>>,
+    kalshi_context_cache: &mut std::collections::HashMap<String, Option<crate::alerts::MarketContext>>,
+    kalshi_last_trade_id: &mut std::collections::HashMap<String, String>,
+    store: &Arc<dyn AlertStore>,
+    alert_sinks: &Arc<Vec<Arc<dyn AlertSink>>>,
+    hybrid_router: &Arc<crate::execution::arbitrage::HybridRouter>,
+    context_registry: &Option<crate::ws::market_context::ContextRegistry>,
+    trade_store: &Option<Arc<crate::store::PostgresTradeStore>>,
+) {
+    kalshi_last_trade_id.insert(ws_trade.ticker.clone(), ws_trade.trade_id.clone());
+
+    let taker_price = if ws_trade.taker_side.eq_ignore_ascii_case("no") {
+        ws_trade.no_price
+    } else {
+        ws_trade.yes_price
+    };
+    let trade_value = (taker_price / 100.0) * f64::from(ws_trade.count);
+    if trade_value < threshold as f64 {
+        return;
+    }
+
+    let mut trade = kalshi::Trade {
+        trade_id: ws_trade.trade_id.clone(),
+        ticker: ws_trade.ticker.clone(),
+        price: taker_price / 100.0,
+        count: ws_trade.count,
+        yes_price: ws_trade.yes_price,
+        no_price: ws_trade.no_price,
+        taker_side: ws_trade.taker_side.clone(),
+        created_time: ws_trade.created_time.clone(),
+        market_title: None,
+    };
+
+    let ticker = trade.ticker.clone();
+    let market_info = if let Some(info) = kalshi_market_cache.get(&ticker) {
+        info.clone()
+    } else {
+        let info = match tokio::time::timeout(Duration::from_secs(2), kalshi::fetch_market_info_full(&ticker)).await {
+            Ok(res) => res,
+            Err(_) => None,
+        };
+        kalshi_market_cache.insert(ticker.clone(), info.clone());
+        info
+    };
+
+    if let Some(ref info) = market_info {
+        trade.market_title = Some(info.title.clone());
+    }
+
+    // Category filter
+    if let Some(ref title) = trade.market_title {
+        let has_native_match = market_info.as_ref()
+            .and_then(|info| info.category.as_ref())
+            .map(|cat| category_registry.matches_native_category(cat, selected_categories))
+            .unwrap_or(false);
+
+        if !has_native_match {
+            if category_registry
+                .matches_selection(title, selected_categories)
+                .is_none()
+            {
+                if category_registry.categorize(title).is_none() {
+                    store.record_uncategorized_title(title);
+                }
+                return;
+            }
+        }
+    }
+
+    let outcome = kalshi::parse_ticker_details(&trade.ticker, &trade.taker_side);
+    let action = trade.taker_side.to_uppercase();
+
+    // Fetch market context early for filtering. Prefers the live
+    // `ws::market_context` stream (state as of the instant this trade
+    // happened) over the per-run memo cache, which in turn beats a
+    // synchronous REST round-trip.
+    let streamed_ctx = match context_registry {
+        Some(registry) => crate::ws::market_context::context_for(registry, &ticker).await,
+        None => None,
+    };
+    let market_ctx = if streamed_ctx.is_some() {
+        streamed_ctx
+    } else if let Some(ctx) = kalshi_context_cache.get(&ticker) {
+        ctx.clone()
+    } else {
+        let ctx = match tokio::time::timeout(Duration::from_secs(2), kalshi::fetch_market_context(&ticker)).await {
+            Ok(res) => res,
+            Err(_) => None,
+        };
+        kalshi_context_cache.insert(ticker.clone(), ctx.clone());
+        ctx
+    };
+
+    // Odds and spread filter
+    if let Some(ref cfg) = config {
+        if let Some(ref ctx) = market_ctx {
+            // Skip if odds too high (near-certainty)
+            if ctx.yes_price > cfg.max_odds || ctx.no_price > cfg.max_odds {
+                return;
+            }
+            // Skip if spread too low (dead market)
+            if cfg.min_spread > 0.0 && ctx.spread < cfg.min_spread {
+                return;
+            }
+        }
+    }
+
+    // Fetched before the alert prints so a thin book can gate it the same
+    // way the odds/spread filter above does, rather than only being logged
+    // after the fact.
+    let order_book = match tokio::time::timeout(Duration::from_secs(2), kalshi::fetch_order_book(&ticker)).await {
+        Ok(res) => res,
+        Err(_) => None,
+    };
+    if let Some(ref cfg) = config {
+        if !passes_min_depth(&order_book, cfg.min_order_book_depth) {
+            return;
+        }
+    }
+
+    print_kalshi_alert(&trade, trade_value, None);
+
+    if let Some(ref ctx) = market_ctx {
+        print_market_context(ctx);
+
+        let title = trade.market_title.as_deref().unwrap_or(&trade.ticker);
+        if let Some(pair) = hybrid_router.record_kalshi_context(&trade.ticker, title, ctx) {
+            emit_arbitrage_alert(&pair, &trade.created_time, alert_sinks).await;
+        }
+    }
+
+    if let Some(ref ob) = order_book {
+        print_order_book(ob);
+    }
+
+    let alert_data = AlertData {
+        platform: "Kalshi",
+        market_title: trade.market_title.as_deref(),
+        market_id: Some(&trade.ticker),
+        trade_id: Some(&trade.trade_id),
+        outcome: Some(&outcome),
+        side: &action,
+        value: trade_value,
+        price: trade.yes_price / 100.0,
+        size: f64::from(trade.count),
+        timestamp: &trade.created_time,
+        wallet_id: None,
+        wallet_activity: None,
+        market_context: market_ctx.as_ref(),
+        whale_profile: None,
+        order_book: order_book.as_ref(),
+        top_holders: None,
+        arbitrage: None,
+        combinatorial: None,
+        is_rollover: false,
+    };
+
+    let params = history::build_log_params(&alert_data);
+    let sinks_clone = alert_sinks.clone();
+    tokio::task::spawn_blocking(move || {
+        history::log_alert_blocking(params, &sinks_clone)
+    })
+    .await
+    .ok();
+
+    store.record_candle_trade(
+        "Kalshi",
+        &trade.ticker,
+        unix_timestamp(&trade.created_time),
+        trade.yes_price / 100.0,
+        f64::from(trade.count),
+    );
+    record_trade_history(
+        trade_store,
+        "Kalshi",
+        trade.ticker.clone(),
+        action.clone(),
+        trade.yes_price / 100.0,
+        f64::from(trade.count),
+        unix_timestamp(&trade.created_time),
+    );
+}
+
+/// After a Kalshi WS reconnect, refetch each watchlisted ticker's trades
+/// newer than the last `trade_id` `process_kalshi_trade` recorded for it,
+/// and run the missed ones through the same pipeline so an outage doesn't
+/// silently drop whale trades. Bounded to `kalshi_watchlist` tickers rather
+/// than the full firehose: with no ticker scope there's no way to ask
+/// Kalshi for "everything since cursor X" short of walking all market
+/// history, which isn't a bounded catch-up fetch.
+#[allow(clippy::too_many_arguments)]
+async fn reconcile_kalshi_gap(
+    watchlist: &[String],
+    threshold: u64,
+    category_registry: &CategoryRegistry,
+    selected_categories: &[String],
+    config: &Option<crate::config::Config>,
+    kalshi_market_cache: &mut std::collections::HashMap<String, Option<kalshi::MarketInfo>>,
+    kalshi_context_cache: &mut std::collections::HashMap<String, Option<crate::alerts::MarketContext>>,
+    kalshi_last_trade_id: &mut std::collections::HashMap<String, String>,
+    store: &Arc<dyn AlertStore>,
+    alert_sinks: &Arc<Vec<Arc<dyn AlertSink>>>,
+    hybrid_router: &Arc<crate::execution::arbitrage::HybridRouter>,
+    context_registry: &Option<crate::ws::market_context::ContextRegistry>,
+    trade_store: &Option<Arc<crate::store::PostgresTradeStore>>,
+) {
+    if watchlist.is_empty() {
+        eprintln!("⚠️ Kalshi WS reconnected but watchlist is empty — skipping gap backfill (no bounded ticker scope)");
+        return;
+    }
+
+    println!("🔄 Kalshi WS reconnected — checking {} watched ticker(s) for missed trades...", watchlist.len());
+
+    for ticker in watchlist {
+        let query = kalshi::TradeQuery {
+            ticker: Some(ticker.clone()),
+            page_limit: 100,
+            ..Default::default()
+        };
+        let trades = match kalshi::fetch_recent_trades_query(config.as_ref(), query).await {
+            Ok(trades) => trades,
+            Err(e) => {
+                eprintln!("⚠️ Gap backfill fetch failed for {}: {}", ticker, e);
+                continue;
+            }
+        };
+
+        let last_seen = kalshi_last_trade_id.get(ticker).cloned();
+        // Kalshi returns trades newest-first; collect until we reach the
+        // last trade_id already processed for this ticker.
+        let mut to_replay = Vec::new();
+        for trade in trades {
+            if Some(&trade.trade_id) == last_seen.as_ref() {
+                break;
+            }
+            to_replay.push(trade);
+        }
+        if to_replay.is_empty() {
+            continue;
+        }
+        // Oldest-first, so `kalshi_last_trade_id` ends up pointing at the
+        // newest trade once the loop finishes, matching live WS order.
+        to_replay.reverse();
+
+        for trade in to_replay {
+            let ws_trade = crate::ws::kalshi::WsTrade {
+                trade_id: trade.trade_id,
+                ticker: trade.ticker,
+                count: trade.count,
+                yes_price: trade.yes_price,
+                no_price: trade.no_price,
+                taker_side: trade.taker_side,
+                created_time: trade.created_time,
+            };
+            process_kalshi_trade(
+                ws_trade,
+                threshold,
+                category_registry,
+                selected_categories,
+                config,
+                kalshi_market_cache,
+                kalshi_context_cache,
+                kalshi_last_trade_id,
+                store,
+                alert_sinks,
+                hybrid_router,
+                context_registry,
+                trade_store,
+            )
+            .await;
+        }
+    }
+}
+
+/// Run one Polymarket trade — from either the REST polling loop or the
+/// live `ws::polymarket` stream — through the same threshold/category/odds
+/// filters, the Polymarket→Kalshi execution pipeline, and the alert/log/
+/// candle pipeline, mirroring `process_kalshi_trade`'s extraction so a
+/// trade arriving over the socket is indistinguishable downstream from one
+/// picked up by polling. Unlike `process_kalshi_trade`, there is no
+/// `_last_trade_id` map to update here — the polling loop's dedup bookmark
+/// is about *which REST page to resume from*, something only the polling
+/// loop itself does; the WS stream has no such cursor to maintain.
+#[allow(clippy::too_many_arguments)]
+async fn process_polymarket_trade(
+    trade: &polymarket::Trade,
+    threshold: u64,
+    category_registry: &CategoryRegistry,
+    selected_categories: &[String],
+    config: &Option<crate::config::Config>,
+    store: &Arc<dyn AlertStore>,
+    alert_sinks: &Arc<Vec<Arc<dyn AlertSink>>>,
+    hybrid_router: &Arc<crate::execution::arbitrage::HybridRouter>,
+    wallet_tracker: &mut types::WalletTracker,
+    wallet_store: &Arc<dyn crate::store::WalletMemoryStore>,
+    whale_cache: &mut whale_profile::WhaleProfileCache,
+    matcher: &mut MarketMatcher,
+    kalshi_executor: &Option<KalshiExecutor>,
+    fill_watcher: &Option<Arc<crate::ws::kalshi_fills::FillWatcher>>,
+    position_store: &Option<PositionStore>,
+    executed_tickers: &mut std::collections::HashMap<String, std::time::Instant>,
+    day_start_balance_cents: &mut Option<i64>,
+    daily_loss_cents: i64,
+    max_open: usize,
+    max_entry_cents: i64,
+    daily_loss_frac: f64,
+    reserve_frac: f64,
+    max_bet_frac: f64,
+    max_bet_cap: f64,
+    trade_store: &Option<Arc<crate::store::PostgresTradeStore>>,
+    dry_run: bool,
+) {
+    let trade_value = trade.size * trade.price;
+    if trade_value < threshold as f64 {
+        return;
+    }
+
+    // Category filter: skip if market doesn't match selected categories
+    if let Some(ref title) = trade.market_title {
+        if category_registry
+            .matches_selection(title, selected_categories)
+            .is_none()
+        {
+            if category_registry.categorize(title).is_none() {
+                store.record_uncategorized_title(title);
+            }
+            return;
+        }
+    }
+
+    let wallet_activity = if let Some(ref wallet_id) = trade.wallet_id {
+        Some(
+            wallet_tracker
+                .record_and_get_activity(&**wallet_store, wallet_id, trade_value)
+                .await,
+        )
+    } else {
+        None
+    };
+
+    // Check for returning whale (12h memory)
+    let whale_scenario = match trade.wallet_id.as_deref() {
+        Some(wid) => {
+            wallet_tracker
+                .classify_whale_return(
+                    &**wallet_store,
+                    wid,
+                    Some(&trade.asset_id),
+                    trade.outcome.as_deref(),
+                    &trade.side,
+                    trade_value,
+                    trade.price,
+                )
+                .await
+        }
+        None => None,
+    };
+
+    // Fetch market context early for filtering
+    let market_ctx = polymarket::fetch_market_context(&trade.market).await;
+
+    // Odds and spread filter
+    if let Some(ref cfg) = config {
+        if let Some(ref ctx) = market_ctx {
+            // Skip if odds too high (near-certainty)
+            if ctx.yes_price > cfg.max_odds || ctx.no_price > cfg.max_odds {
+                return;
+            }
+            // Skip if spread too low (dead market)
+            if cfg.min_spread > 0.0 && ctx.spread < cfg.min_spread {
+                return;
+            }
+        }
+    }
+
+    // Fetch order book depth before the alert prints, so a thin
+    // book can gate it the same way the odds/spread filter above
+    // does rather than only being logged after the fact.
+    let order_book = polymarket::fetch_order_book(&trade.asset_id).await;
+    if let Some(ref cfg) = config {
+        if !passes_min_depth(&order_book, cfg.min_order_book_depth) {
+            return;
+        }
+    }
+
+    // Print returning whale info if detected
+    if let Some(ref scenario) = whale_scenario {
+        display::print_returning_whale(scenario, "Polymarket");
+    }
+
+    print_whale_alert(
+        "Polymarket",
+        trade,
+        trade_value,
+        wallet_activity.as_ref(),
+    );
+
+    if let Some(ref ctx) = market_ctx {
+        print_market_context(ctx);
+
+        let title = trade.market_title.as_deref().unwrap_or(&trade.market);
+        if let Some(pair) = hybrid_router.record_polymarket_context(&trade.market, title, ctx) {
+            emit_arbitrage_alert(&pair, &trade.timestamp, alert_sinks).await;
+        }
+    }
+
+    // Fetch whale profile (Polymarket only - on-chain wallets)
+    let mut wp = if let Some(ref wallet_id) = trade.wallet_id {
+        whale_profile::fetch_whale_profile(wallet_id, whale_cache).await
+    } else {
+        None
+    };
+    if let Some(ref mut profile) = wp {
+        whale_profile::backfill_from_history(profile, &**store);
+    }
+    if let Some(ref profile) = wp {
+        print_whale_profile(profile);
+    }
+
+    if let Some(ref ob) = order_book {
+        print_order_book(ob);
+    }
+
+    // Fetch top holders
+    let top_holders = polymarket::fetch_top_holders(&trade.market).await;
+    if let Some(ref th) = top_holders {
+        print_top_holders(th);
+    }
+
+    let alert_data = AlertData {
+        platform: "Polymarket",
+        market_title: trade.market_title.as_deref(),
+        market_id: Some(&trade.market),
+        trade_id: Some(&trade.id),
+        outcome: trade.outcome.as_deref(),
+        side: &trade.side,
+        value: trade_value,
+        price: trade.price,
+        size: trade.size,
+        timestamp: &trade.timestamp,
+        wallet_id: trade.wallet_id.as_deref(),
+        wallet_activity: wallet_activity.as_ref(),
+        market_context: market_ctx.as_ref(),
+        whale_profile: wp.as_ref(),
+        order_book: order_book.as_ref(),
+        top_holders: top_holders.as_ref(),
+        arbitrage: None,
+        combinatorial: None,
+        is_rollover: false,
+    };
+
+    let alert_id = {
+        let params = history::build_log_params(&alert_data);
+        let sinks_clone = alert_sinks.clone();
+        tokio::task::spawn_blocking(move || {
+            history::log_alert_blocking(params, &sinks_clone)
+        })
+        .await
+        .ok()
+        .flatten()
+    };
+
+    store.record_candle_trade(
+        "Polymarket",
+        &trade.market,
+        unix_timestamp(&trade.timestamp),
+        trade.price,
+        trade.size,
+    );
+    record_trade_history(
+        trade_store,
+        "Polymarket",
+        trade.market.clone(),
+        trade.side.clone(),
+        trade.price,
+        trade.size,
+        unix_timestamp(&trade.timestamp),
+    );
+
+    // ═══ RISK-MANAGED EXECUTION PIPELINE ═══════════════
+    let whale_win_rate = wp.as_ref().and_then(|p| p.win_rate);
+
+    // Gate 1: Win rate
+    let passes_win_rate = match whale_win_rate {
+        Some(wr) if wr >= 0.85 => {
+            println!("✅ Whale win rate {:.1}% passes 85% threshold", wr * 100.0);
+            true
+        }
+        Some(wr) => {
+            println!("⚠️ Skipping execution: whale win rate {:.1}% < 85%", wr * 100.0);
+            false
+        }
+        None => {
+            println!("⚠️ Skipping execution: whale win rate unknown");
+            false
+        }
+    };
+
+    let poly_title = trade.market_title.as_deref().unwrap_or("");
+    if passes_win_rate && !poly_title.is_empty() {
+        let search_results = kalshi::search_markets(poly_title).await.unwrap_or_default();
+        if let Some(match_result) = matcher.match_market(
+            poly_title,
+            trade.outcome.as_deref().unwrap_or(""),
+            &search_results,
+            None,
+        ).await {
+            println!("{} Matched to Kalshi: {} ({}) Confidence: {:.2}",
+                "🤖 LLM".bright_magenta(),
+                match_result.ticker.bright_cyan(),
+                match_result.side,
+                match_result.confidence.unwrap_or(0.0)
+            );
+
+            let dedup_key = match match_result.ticker.rfind('-') {
+                Some(pos) => match_result.ticker[..pos].to_string(),
+                None => match_result.ticker.clone(),
+            };
+
+            // Gate 2: Event-level dedup
+            if executed_tickers.contains_key(&dedup_key) {
+                println!("⚠️ Already have position on event {} — skipping",
+                    dedup_key);
+            }
+            // Gate 3: Max open positions
+            else if executed_tickers.len() >= max_open {
+                println!("⚠️ Max {} open positions reached — skipping {}",
+                    max_open, match_result.ticker);
+            }
+            // Gate 4: 24h expiry + fetch Kalshi live price
+            else if let Some(snapshot) = fetch_kalshi_market_snapshot(&match_result.ticker).await {
+            if !snapshot.closes_within_24h {
+                println!("⚠️ Skipping {}: does not close within 24 hours",
+                    match_result.ticker);
+            }
+            else if let Some(ref executor) = kalshi_executor {
+                // Gate 5: Live Kalshi position check
+                if executor.has_open_position(&dedup_key).await.unwrap_or(false) {
+                    println!("⚠️ Already have LIVE Kalshi position on {} — skipping",
+                        dedup_key);
+                    executed_tickers.insert(dedup_key.clone(), std::time::Instant::now());
+                } else {
+
+                // ── Fee + EV calculation (using Kalshi live price, not Polymarket) ──
+                let kalshi_price = if match_result.side.eq_ignore_ascii_case("yes") {
+                    snapshot.yes_price_cents
+                } else {
+                    snapshot.no_price_cents
+                };
+                let price_cents = kalshi_price.clamp(1, 99);
+                let fee_cents = kalshi_taker_fee_cents(price_cents);
+                let wr = whale_win_rate.unwrap_or(0.0);
+                let ev_cents = expected_value_cents(wr, price_cents, fee_cents);
+
+                println!("📊 Price: {}c | Fee: {}c/contract | EV: {:.1}c/contract (WR {:.1}%)",
+                    price_cents, fee_cents, ev_cents, wr * 100.0);
+
+                // Gate 6: Max entry price
+                if price_cents > max_entry_cents {
+                    println!("⚠️ Skipping: price {}c > max {}c",
+                        price_cents, max_entry_cents);
+                }
+                // Gate 7: Positive expected value after fees
+                else if ev_cents <= 0.0 {
+                    println!("⚠️ Skipping: negative EV {:.1}c after {}c fee (need WR > {:.0}%)",
+                        ev_cents, fee_cents, (price_cents + fee_cents) as f64);
+                } else {
+
+                // ── Balance + risk sizing ───────────────────────────
+                let balance_cents = executor.get_balance().await.unwrap_or(0);
+
+                if day_start_balance_cents.is_none() {
+                    *day_start_balance_cents = Some(balance_cents);
+                    println!("📋 Day-start balance: ${:.2}", balance_cents as f64 / 100.0);
+                }
+                let day_start = day_start_balance_cents.unwrap_or(balance_cents);
+
+                // Gate 8: Daily loss limit
+                let loss_limit_cents = (day_start as f64 * daily_loss_frac) as i64;
+                if daily_loss_cents >= loss_limit_cents {
+                    println!("🛑 Daily loss limit hit: lost ${:.2} >= ${:.2} limit — halting trades",
+                        daily_loss_cents as f64 / 100.0,
+                        loss_limit_cents as f64 / 100.0);
+                }
+                // Gate 9: Reserve
+                else {
+                let reserve_cents = (day_start as f64 * reserve_frac) as i64;
+
+                // ── Quarter-Kelly sizing ────────────────────────────
+                let kelly_frac = quarter_kelly_fraction(wr, price_cents, fee_cents, max_bet_frac);
+                let kelly_dollars = (balance_cents as f64 / 100.0) * kelly_frac;
+                let bet_size = kelly_dollars
+                    .min(max_bet_cap)
+                    .max(1.0); // $1 floor
+                // Cap by TOTAL cost (price + fees), not just price — fees can add $2+ on cheap contracts
+                let max_count_by_cap = ((max_bet_cap * 100.0) / (price_cents as f64 + fee_cents as f64)).floor() as i32;
+                let count_by_kelly = ((bet_size * 100.0) / price_cents as f64).max(1.0) as i32;
+                let count = count_by_kelly.min(max_count_by_cap.max(1));
+                let trade_cost_cents = (count as i64) * price_cents;
+                let total_cost_with_fees = trade_cost_cents + (count as i64) * fee_cents;
+
+                println!("📐 Kelly: {:.2}% → ${:.2} | {} contracts @ {}c + {}c fee = ${:.2}",
+                    kelly_frac * 100.0,
+                    bet_size,
+                    count,
+                    price_cents,
+                    fee_cents,
+                    total_cost_with_fees as f64 / 100.0);
+
+                if balance_cents.saturating_sub(total_cost_with_fees) < reserve_cents {
+                    println!("⚠️ Skipping: ${:.2} - ${:.2} would breach {:.0}% reserve (${:.2})",
+                        balance_cents as f64 / 100.0,
+                        total_cost_with_fees as f64 / 100.0,
+                        reserve_frac * 100.0,
+                        reserve_cents as f64 / 100.0);
+                } else {
+                    println!("💰 Balance: ${:.2} → cost ${:.2} → ${:.2} remaining",
+                        balance_cents as f64 / 100.0,
+                        total_cost_with_fees as f64 / 100.0,
+                        (balance_cents - total_cost_with_fees) as f64 / 100.0);
+
+                    println!("🚀 EXECUTING: Buy {} {} @ {}c (Qty: {}, ${:.2}, EV: +{:.1}c/contract)",
+                        match_result.side.to_uppercase(),
+                        match_result.ticker,
+                        price_cents,
+                        count,
+                        count as f64 * price_cents as f64 / 100.0,
+                        ev_cents
+                    );
+
+                    if dry_run {
+                        println!("🧪 [DRY RUN] Would place order: {} {} x{} @ {}c (${:.2} incl. ${:.2} fee) — not sent",
+                            match_result.side.to_uppercase(),
+                            match_result.ticker,
+                            count,
+                            price_cents,
+                            total_cost_with_fees as f64 / 100.0,
+                            (count as i64 * fee_cents) as f64 / 100.0
+                        );
+                    } else {
+                    match executor.place_order(
+                        &match_result.ticker,
+                        &match_result.side,
+                        count,
+                        price_cents
+                    ).await {
+                        Ok(order_id) => {
+                            println!("✅ Order Placed: {}", order_id.to_string().green());
+                            executed_tickers.insert(dedup_key.clone(), std::time::Instant::now());
+
+                            if let Some(row_id) = alert_id {
+                                let store_clone = store.clone();
+                                let order_id_s = order_id.to_string();
+                                let ticker = match_result.ticker.clone();
+                                let side = match_result.side.clone();
+                                let fee_dollars = (count as i64 * fee_cents) as f64 / 100.0;
+                                let kelly_pct = kelly_frac * 100.0;
+                                tokio::task::spawn_blocking(move || {
+                                    store_clone.mark_alert_executed(
+                                        row_id,
+                                        &order_id_s,
+                                        &ticker,
+                                        &side,
+                                        bet_size,
+                                        price_cents as f64 / 100.0,
+                                        fee_dollars,
+                                        ev_cents,
+                                        kelly_pct,
+                                    );
+                                })
+                                .await
+                                .ok();
+                            }
+
+                            // Confirm the fill via the push-based fill socket when it's up
+                            // (near-instant), falling back to HTTP polling otherwise — only
+                            // count daily loss & send Discord when filled.
+                            let filled = match kalshi_executor {
+                                Some(ref ex) => confirm_fill(ex, fill_watcher, &order_id, count).await,
+                                None => false,
+                            };
+                            if filled {
+                                println!("✅ Order {} filled ({} contracts)", order_id, count);
+                            }
+                            if !filled {
+                                println!("⚠️ Order {} not yet filled after 10s — not counting against daily loss", order_id);
+                            } else {
+                                // Entry is tracked as an open `Position`, not an immediate
+                                // loss — `monitor_positions` records the real outcome
+                                // against `daily_loss_cents` as realized P&L once it closes.
+                                if let Some(ref position_store) = position_store {
+                                    let entry_fee_cents = (count as i64) * fee_cents;
+                                    let position = Position {
+                                        id: None,
+                                        ticker: match_result.ticker.clone(),
+                                        dedup_key: dedup_key.clone(),
+                                        side: match_result.side.clone(),
+                                        entry_price_cents: price_cents,
+                                        count,
+                                        entry_fee_cents,
+                                        state: PositionState::Active,
+                                        realized_pnl_cents: None,
+                                        exit_order_id: None,
+                                        exit_price_cents: None,
+                                        exit_reason: None,
+                                    };
+                                    if let Err(e) = position_store.open_position(&position) {
+                                        eprintln!("⚠️ Could not persist opened position: {}", e);
+                                    }
+                                }
+                                // Re-fetch the balance post-fill so the ledger's cash posting
+                                // assertion is checked against Kalshi's own running total, not
+                                // just our local arithmetic — falls back to the locally-computed
+                                // balance if the refetch fails so the entry still gets written.
+                                let balance_after = executor.get_balance().await
+                                    .unwrap_or_else(|_| balance_cents.saturating_sub(total_cost_with_fees));
+                                if let Some(ref cfg) = config {
+                                    let exec_alert = crate::alerts::webhook::ExecutionAlert {
+                                        kalshi_ticker: match_result.ticker.clone(),
+                                        side: match_result.side.clone(),
+                                        count,
+                                        price_cents,
+                                        fee_cents,
+                                        total_cost_cents: total_cost_with_fees,
+                                        ev_cents,
+                                        kelly_pct: kelly_frac * 100.0,
+                                        whale_win_rate: wr,
+                                        balance_after_cents: balance_after,
+                                        poly_title: poly_title.to_string(),
+                                        order_id: order_id.to_string(),
+                                    };
+
+                                    if let Some(path) = cfg.ledger_export_path.as_ref() {
+                                        if let Err(e) = crate::alerts::ledger::append_execution(path, &exec_alert) {
+                                            eprintln!("⚠️ Could not write ledger entry: {}", e);
+                                        }
+                                    }
+
+                                    let url = cfg.webhook_url.as_ref()
+                                        .or(cfg.discord_webhook_url.as_ref());
+                                    if let Some(url) = url {
+                                        println!("📨 Sending execution alert...");
+                                        crate::alerts::webhook::send_execution_alert(url, &exec_alert, cfg.text_format.as_deref()).await;
+                                    }
+                                }
+                            }
+                        },
+                        Err(e) => eprintln!("❌ Execution Failed: {}", e),
+                    }
+                    }
+                }
+                }
+                }
+                }
+            } else {
+                println!("⚠️ Execution skipped (No credentials)");
+            }
+            } else {
+                println!("⚠️ Skipping {}: could not fetch Kalshi market data",
+                    match_result.ticker);
+            }
+        }
+    }
+    // ═══ END EXECUTION PIPELINE ════════════════════════
+
+    // Record to wallet memory DB (pooled Postgres when
+    // configured, local SQLite otherwise — see `wallet_store`)
+    if let Some(ref wallet_id) = trade.wallet_id {
+        wallet_tracker
+            .record_to_db(
+                &**wallet_store,
+                wallet_id,
+                trade.market_title.as_deref(),
+                Some(&trade.asset_id),
+                trade.outcome.as_deref(),
+                &trade.side,
+                trade_value,
+                trade.price,
+                "Polymarket",
+            )
+            .await;
+    }
+}
+
+pub async fn watch_whales(threshold: u64, interval: u64, store: Arc<dyn AlertStore>) -> Result<(), Box<dyn std::error::Error>> {
     // Display disclaimer
     println!("{}", "=".repeat(70).bright_yellow());
     println!("{}", "DISCLAIMER".bright_yellow().bold());
@@ -145,7 +1405,7 @@ pub async fn watch_whales(threshold: u64, interval: u64, conn: Arc<Mutex<Connect
     println!("Interval:  {} seconds", interval);
 
     // Initialize category filtering (reloaded each prune cycle)
-    let category_registry = CategoryRegistry::new();
+    let category_registry = CategoryRegistry::load();
     let mut selected_categories: Vec<String> = config
         .as_ref()
         .map(|c| c.categories.clone())
@@ -182,16 +1442,139 @@ pub async fn watch_whales(threshold: u64, interval: u64, conn: Arc<Mutex<Connect
         }
     }
 
-    // Show DB info
-    let alert_count = {
-        let conn = conn.clone();
-        tokio::task::spawn_blocking(move || db::alert_count(&*conn.lock().unwrap()))
-            .await
-            .unwrap_or(0)
+    // Show DB info. When `postgres_alert_url` is set, count against the same
+    // shared database `PostgresSink` writes alerts into instead of this
+    // instance's local SQLite file, so the banner reflects the whole fleet.
+    let alert_count_store: Arc<dyn crate::store::AlertCountStore> = match config
+        .as_ref()
+        .and_then(|c| c.postgres_alert_url.clone())
+    {
+        Some(url) => match crate::store::PostgresAlertCountStore::connect(&url).await {
+            Ok(pg_store) => Arc::new(pg_store),
+            Err(e) => {
+                eprintln!("Warning: Failed to connect alert count store to Postgres: {}", e);
+                Arc::new(crate::store::SqliteAlertCountStore::new(store.clone()))
+            }
+        },
+        None => Arc::new(crate::store::SqliteAlertCountStore::new(store.clone())),
     };
+    let alert_count = alert_count_store.alert_count().await;
     println!("Database:  {} alerts stored", alert_count.to_string().bright_white());
     println!();
 
+    // Alert sinks: JSONL + SQLite always run; Postgres is opt-in via either
+    // `config.postgres_alert_url` (a single DSN, takes priority) or discrete
+    // `WWATCHER_PG_*` environment variables (see `PostgresSink::connect_from_env`),
+    // so the watcher only persists to a shared analytics database when one
+    // of the two is configured.
+    let mut alert_sinks: Vec<Arc<dyn AlertSink>> =
+        vec![Arc::new(JsonlSink), Arc::new(SqliteSink::new(store.clone()))];
+    let (postgres_max_batch, postgres_flush_interval) = config
+        .as_ref()
+        .map(|cfg| (cfg.postgres_max_batch, Duration::from_secs(cfg.postgres_flush_interval_secs)))
+        .unwrap_or((crate::config::default_postgres_max_batch(), Duration::from_secs(crate::config::default_postgres_flush_interval_secs())));
+    if let Some(ref cfg) = config.as_ref().and_then(|c| c.postgres_alert_url.clone()) {
+        alert_sinks.push(Arc::new(PostgresSink::connect_with_batching(
+            cfg.clone(),
+            postgres_max_batch,
+            postgres_flush_interval,
+        )));
+        println!("Postgres:  {}", "Enabled".bright_green());
+    } else if let Some(env_sink) = PostgresSink::connect_from_env(postgres_max_batch, postgres_flush_interval) {
+        alert_sinks.push(Arc::new(env_sink));
+        println!("Postgres:  {}", "Enabled (from environment)".bright_green());
+    }
+    let alert_sinks = Arc::new(alert_sinks);
+
+    // Narrow live candle generation to `Config::candle_intervals` before any
+    // trade hits `record_candle_trade` below.
+    if let Some(ref cfg) = config {
+        store.configure_candle_resolutions(&cfg.candle_intervals);
+    }
+
+    // Cross-platform arbitrage: caches the latest context seen per market on
+    // each platform and flags a combined buy-yes-here/buy-no-there cost
+    // below 1.0 (minus both platforms' fees) whenever a fresh context on one
+    // platform lines up with what's cached for its matched market on the
+    // other. One router instance, shared across the Kalshi and Polymarket
+    // trade loops below.
+    let arbitrage_fees = crate::execution::arbitrage::FeeSchedule {
+        min_fee: config.as_ref().map(|c| c.arbitrage_min_fee).unwrap_or_else(crate::config::default_arbitrage_min_fee),
+        fee_rate: config.as_ref().map(|c| c.arbitrage_fee_rate).unwrap_or_else(crate::config::default_arbitrage_fee_rate),
+    };
+    let arbitrage_notional = config.as_ref().map(|c| c.arbitrage_notional).unwrap_or_else(crate::config::default_arbitrage_notional);
+    let hybrid_router = Arc::new(crate::execution::arbitrage::HybridRouter::new(arbitrage_fees, arbitrage_notional));
+
+    // Read-only HTTP/JSON API (`/tickers`, `/alerts`, `/candles`) over this
+    // same `store`, so a dashboard or bot doesn't have to scrape stdout or
+    // tail the JSONL sink. Runs alongside the trade loops below; a bind
+    // failure (e.g. the port's already taken) is logged but doesn't stop
+    // the watcher — the console/webhook/DB sinks work without it.
+    let api_bind_addr = config.as_ref().map(|c| c.api_bind_addr.clone()).unwrap_or_else(crate::config::default_api_bind_addr);
+    let api_store = store.clone();
+    tokio::spawn(async move {
+        if let Err(e) = crate::commands::api::serve_api(&api_bind_addr, api_store).await {
+            eprintln!("{} {}", "[ERROR] API server:".red(), e);
+        }
+    });
+
+    // Prometheus metrics (`GET /metrics`) over the same process-wide
+    // registry `crate::metrics::metrics()` already writes to. Opt-in via
+    // `Config::metrics_addr` since, unlike the read-only JSON API above,
+    // scraping isn't something every deployment wants running.
+    if let Some(metrics_addr) = config.as_ref().and_then(|c| c.metrics_addr.clone()) {
+        tokio::spawn(async move {
+            match metrics_addr.parse() {
+                Ok(addr) => {
+                    if let Err(e) = crate::metrics::serve_metrics(addr).await {
+                        eprintln!("{} {}", "[ERROR] Metrics server:".red(), e);
+                    }
+                }
+                Err(e) => eprintln!("{} invalid metrics_addr {:?}: {}", "[ERROR]".red(), metrics_addr, e),
+            }
+        });
+    }
+
+    // Wallet memory: pooled Postgres when configured (for multi-instance
+    // deployments), otherwise the local SQLite store via spawn_blocking.
+    let wallet_store: Arc<dyn crate::store::WalletMemoryStore> = match config.as_ref().and_then(|c| c.wallet_memory_store_url.clone()) {
+        Some(url) => {
+            let pool_size = config.as_ref().map(|c| c.wallet_memory_pool_size).unwrap_or(4);
+            match crate::store::PostgresWalletMemoryStore::connect(&url, pool_size).await {
+                Ok(pg_store) => {
+                    println!("Wallet memory: {}", "Pooled Postgres".bright_green());
+                    Arc::new(pg_store)
+                }
+                Err(e) => {
+                    eprintln!("{} {} — falling back to local SQLite", "[ERROR] Wallet memory Postgres pool:".red(), e);
+                    Arc::new(crate::store::SqliteWalletMemoryStore::new(store.clone()))
+                }
+            }
+        }
+        None => Arc::new(crate::store::SqliteWalletMemoryStore::new(store.clone())),
+    };
+
+    // Optional shared Postgres flow database — every logged trade also
+    // lands in `store::PostgresTradeStore`'s `trades`/`candles` tables when
+    // configured, queryable later via `commands::history`. Unset keeps
+    // trade history on the local SQLite `candles` table only.
+    let trade_store: Option<Arc<crate::store::PostgresTradeStore>> = match config.as_ref().and_then(|c| c.trade_store_url.clone()) {
+        Some(url) => {
+            let pool_size = config.as_ref().map(|c| c.trade_store_pool_size).unwrap_or(4);
+            match crate::store::PostgresTradeStore::connect(&url, pool_size).await {
+                Ok(pg_store) => {
+                    println!("Trade history: {}", "Pooled Postgres".bright_green());
+                    Some(Arc::new(pg_store))
+                }
+                Err(e) => {
+                    eprintln!("{} {} — trade history stays local SQLite-only", "[ERROR] Trade store Postgres pool:".red(), e);
+                    None
+                }
+            }
+        }
+        None => None,
+    };
+
     let mut last_polymarket_trade_id: Option<String> = None;
     let mut last_kalshi_trade_id: Option<String> = None;
     let mut first_poll_poly = true;
@@ -206,10 +1589,17 @@ pub async fn watch_whales(threshold: u64, interval: u64, conn: Arc<Mutex<Connect
     let mut reserve_frac = config.as_ref().map(|c| c.reserve_fraction).unwrap_or(0.20);
     let mut max_bet_frac = config.as_ref().map(|c| c.max_bet_fraction).unwrap_or(0.02);
     let mut max_bet_cap = config.as_ref().map(|c| c.max_bet_cap).unwrap_or(10.0);
+    let mut dry_run = config.as_ref().map(|c| c.dry_run).unwrap_or(false);
     let mut max_entry_cents: i64 = config.as_ref().map(|c| c.max_entry_price_cents).unwrap_or(97);
+    let mut rollover_enabled = config.as_ref().map(|c| c.rollover_enabled).unwrap_or(false);
+    let mut rollover_window_hours = config.as_ref().map(|c| c.rollover_window_hours).unwrap_or(2);
     let mut day_start_balance_cents: Option<i64> = None;
     let mut daily_loss_cents: i64 = 0;
     let mut current_trading_day = chrono::Utc::now().date_naive();
+    let mut exit_thresholds = ExitThresholds {
+        take_profit_cents: config.as_ref().map(|c| c.take_profit_cents).unwrap_or(15),
+        stop_loss_cents: config.as_ref().map(|c| c.stop_loss_cents).unwrap_or(10),
+    };
 
     // Initialize Execution Modules (Ollama for Polymarket→Kalshi matching)
     let (ollama_model, ollama_embed_model, ollama_url) = config
@@ -218,11 +1608,21 @@ pub async fn watch_whales(threshold: u64, interval: u64, conn: Arc<Mutex<Connect
         .unwrap_or_else(|| ("llama3".into(), "nomic-embed-text".into(), "http://localhost:11434".into()));
     let mut matcher = MarketMatcher::new(ollama_model, ollama_embed_model, Some(&ollama_url));
     let mut executed_tickers: std::collections::HashMap<String, std::time::Instant> = std::collections::HashMap::new();
-    let kalshi_executor = if let Some(ref cfg) = config {
-        if let (Some(key_id), Some(private_key_input)) = (&cfg.kalshi_api_key_id, &cfg.kalshi_private_key) {
-             let private_key_pem = resolve_pem(private_key_input);
 
-             match KalshiExecutor::new(key_id.clone(), &private_key_pem, cfg.kalshi_is_demo) {
+    // Resolved once at startup — decrypts `kalshi_private_key_encrypted` (prompting
+    // for its passphrase) in preference to the legacy plaintext `kalshi_private_key`.
+    // Shared by the executor below, the fill watcher, and the trade WebSocket so the
+    // operator isn't prompted more than once per run.
+    let resolved_kalshi_private_key_pem: Option<String> = config.as_ref().and_then(|cfg| {
+        crate::keystore::resolve_kalshi_private_key(cfg).unwrap_or_else(|e| {
+            eprintln!("⚠️ Could not unlock Kalshi private key: {}", e);
+            None
+        })
+    });
+
+    let kalshi_executor = if let Some(ref cfg) = config {
+        if let (Some(key_id), Some(private_key_pem)) = (&cfg.kalshi_api_key_id, &resolved_kalshi_private_key_pem) {
+             match KalshiExecutor::new(key_id.clone(), private_key_pem, cfg.kalshi_is_demo) {
                  Ok(ex) => {
                      println!("Execution: {}", "Kalshi Executor Ready".bright_green());
                      Some(ex)
@@ -235,6 +1635,26 @@ pub async fn watch_whales(threshold: u64, interval: u64, conn: Arc<Mutex<Connect
         } else { None }
     } else { None };
 
+    // Tracks every filled Kalshi order through take-profit/stop-loss/
+    // settlement so a restart resumes monitoring whatever was left open.
+    let position_store = match PositionStore::open() {
+        Ok(s) => Some(s),
+        Err(e) => {
+            eprintln!("⚠️ Could not open position store: {}", e);
+            None
+        }
+    };
+
+    // Push-based fill confirmation — `confirm_fill` falls back to HTTP
+    // polling whenever this isn't connected yet (or the credentials needed
+    // to authenticate the fill channel aren't configured at all).
+    let fill_watcher = if kalshi_executor.is_some() {
+        let api_id = config.as_ref().and_then(|c| c.kalshi_api_key_id.clone());
+        Some(Arc::new(crate::ws::kalshi_fills::FillWatcher::spawn(api_id, resolved_kalshi_private_key_pem.clone())))
+    } else {
+        None
+    };
+
     // Seed executed_tickers with existing open Kalshi positions so we don't double-up
     if let Some(ref executor) = kalshi_executor {
         match executor.get_open_event_tickers().await {
@@ -255,17 +1675,50 @@ pub async fn watch_whales(threshold: u64, interval: u64, conn: Arc<Mutex<Connect
     }
 
     // Start Kalshi WebSocket if watching Kalshi
-    let mut kalshi_ws_rx = if watch_kalshi {
+    let (mut kalshi_ws_rx, _kalshi_ws_watchlist_control, mut kalshi_reconnect_rx) = if watch_kalshi {
         println!("Kalshi WS:  {}", "Connecting...".bright_cyan());
-        let (api_id, priv_key_raw) = config.as_ref().map(|c| (c.kalshi_api_key_id.clone(), c.kalshi_private_key.clone())).unwrap_or((None, None));
-        let priv_key = priv_key_raw.map(|k| resolve_pem(&k));
-        Some(crate::ws::kalshi::spawn_kalshi_ws(api_id, priv_key))
+        let api_id = config.as_ref().and_then(|c| c.kalshi_api_key_id.clone());
+        let watchlist = config.as_ref().map(|c| c.kalshi_watchlist.clone()).unwrap_or_default();
+        crate::metrics::metrics().markets_watched.set(watchlist.len() as u64);
+        let (rx, control, reconnect_rx) = crate::ws::kalshi::spawn_kalshi_ws(api_id, resolved_kalshi_private_key_pem.clone(), watchlist);
+        (Some(rx), Some(control), Some(reconnect_rx))
+    } else {
+        (None, None, None)
+    };
+
+    // Live market context (yes/no price, spread, volume) over Kalshi's
+    // `ticker_v2` channel, scoped to the same watchlist the trade feed
+    // uses. `process_kalshi_trade`/`reconcile_kalshi_gap` check this before
+    // falling back to a synchronous `fetch_market_context` REST call, so the
+    // context an alert reports reflects book state at the instant the whale
+    // traded rather than whenever the REST round-trip happens to land.
+    let context_registry: Option<crate::ws::market_context::ContextRegistry> = if watch_kalshi {
+        let watchlist = config.as_ref().map(|c| c.kalshi_watchlist.clone()).unwrap_or_default();
+        let (registry, _context_rx) = crate::ws::market_context::stream_market_contexts(watchlist).await;
+        Some(registry)
     } else {
         None
     };
+
     // Track whether WS is producing trades (for fallback)
     let mut kalshi_ws_last_trade = std::time::Instant::now();
     let kalshi_ws_fallback_threshold = Duration::from_secs(interval * 12); // fall back to HTTP if no WS trades in ~1 min
+    // Last trade id seen per ticker, from either the live WS stream or a
+    // reconnect gap backfill, so `reconcile_kalshi_gap` knows where to
+    // resume and so trades it re-fetches aren't logged a second time.
+    let mut kalshi_last_trade_id: std::collections::HashMap<String, String> = std::collections::HashMap::new();
+
+    // Start the Polymarket trade stream if watching Polymarket. No
+    // credentials to check here (unlike Kalshi's `trade` channel, the feed
+    // is public), so this mirrors `watch_kalshi`'s gate exactly.
+    let mut polymarket_ws_rx = if watch_polymarket {
+        println!("Polymarket WS:  {}", "Connecting...".bright_cyan());
+        Some(crate::ws::polymarket::spawn_polymarket_ws())
+    } else {
+        None
+    };
+    let mut polymarket_ws_last_trade = std::time::Instant::now();
+    let polymarket_ws_fallback_threshold = Duration::from_secs(interval * 12);
 
     let mut tick_interval = time::interval(Duration::from_secs(interval));
     let (shutdown_tx, mut shutdown_rx) = tokio::sync::oneshot::channel::<()>();
@@ -308,6 +1761,40 @@ pub async fn watch_whales(threshold: u64, interval: u64, conn: Arc<Mutex<Connect
             day_start_balance_cents = None; // re-capture on next trade
         }
 
+        // Roll expiring weekly positions into the next period's market
+        // before this tick's take-profit/stop-loss/settlement check, so a
+        // successful roll is seen as still `Active` rather than settled.
+        if rollover_enabled {
+            if let (Some(ref position_store), Some(ref executor)) = (&position_store, &kalshi_executor) {
+                monitor_rollovers(
+                    position_store,
+                    executor,
+                    &fill_watcher,
+                    &mut executed_tickers,
+                    &mut daily_loss_cents,
+                    day_start_balance_cents,
+                    daily_loss_frac,
+                    reserve_frac,
+                    rollover_window_hours,
+                    &config,
+                ).await;
+            }
+        }
+
+        // Check every tracked position for a take-profit/stop-loss/settlement
+        // exit before this tick's new trades are considered.
+        if let (Some(ref position_store), Some(ref executor)) = (&position_store, &kalshi_executor) {
+            monitor_positions(
+                position_store,
+                executor,
+                &fill_watcher,
+                &mut executed_tickers,
+                &mut daily_loss_cents,
+                &exit_thresholds,
+                &config,
+            ).await;
+        }
+
         // Periodic cleanup and cache refresh
         prune_counter += 1;
         if prune_counter >= 60 {
@@ -320,7 +1807,14 @@ pub async fn watch_whales(threshold: u64, interval: u64, conn: Arc<Mutex<Connect
             reserve_frac = config.as_ref().map(|c| c.reserve_fraction).unwrap_or(0.20);
             max_bet_frac = config.as_ref().map(|c| c.max_bet_fraction).unwrap_or(0.02);
             max_bet_cap = config.as_ref().map(|c| c.max_bet_cap).unwrap_or(10.0);
+            dry_run = config.as_ref().map(|c| c.dry_run).unwrap_or(false);
             max_entry_cents = config.as_ref().map(|c| c.max_entry_price_cents).unwrap_or(97);
+            rollover_enabled = config.as_ref().map(|c| c.rollover_enabled).unwrap_or(false);
+            rollover_window_hours = config.as_ref().map(|c| c.rollover_window_hours).unwrap_or(2);
+            exit_thresholds = ExitThresholds {
+                take_profit_cents: config.as_ref().map(|c| c.take_profit_cents).unwrap_or(15),
+                stop_loss_cents: config.as_ref().map(|c| c.stop_loss_cents).unwrap_or(10),
+            };
             selected_categories = config.as_ref().map(|c| c.categories.clone()).unwrap_or_else(|| vec!["all".into()]);
             selected_platforms = config.as_ref().map(|c| c.platforms.clone()).unwrap_or_else(|| vec!["all".into()]);
             watch_polymarket = selected_platforms.iter().any(|p| p == "all" || p == "polymarket");
@@ -329,152 +1823,73 @@ pub async fn watch_whales(threshold: u64, interval: u64, conn: Arc<Mutex<Connect
             matcher.prune_cache();
             let retention = config.as_ref().map(|c| c.history_retention_days).unwrap_or(30);
             {
-                let conn = conn.clone();
+                let store = store.clone();
                 let retention = retention;
                 tokio::task::spawn_blocking(move || {
-                    let guard = conn.lock().unwrap();
-                    db::prune_wallet_memory(&*guard);
-                    db::prune_old_alerts(&*guard, retention);
+                    store.prune_wallet_memory();
+                    store.prune_old_alerts(retention);
                 })
                 .await
                 .ok();
             }
             whale_cache.prune();
+            store.flush_stale_candles();
             kalshi_market_cache.clear();
             kalshi_context_cache.clear();
             // Prune executed tickers older than 25h (markets close within 24h)
             executed_tickers.retain(|_, inserted_at| inserted_at.elapsed() < Duration::from_secs(25 * 3600));
         }
-        wallet_tracker.maybe_refresh_cache(&*conn.lock().unwrap());
+        wallet_tracker.maybe_refresh_cache(&*wallet_store).await;
 
         // Drain Kalshi WebSocket trades (non-blocking)
         if let Some(ref mut rx) = kalshi_ws_rx {
             while let Ok(ws_trade) = rx.try_recv() {
                 kalshi_ws_last_trade = std::time::Instant::now();
+                process_kalshi_trade(
+                    ws_trade,
+                    threshold,
+                    &category_registry,
+                    &selected_categories,
+                    &config,
+                    &mut kalshi_market_cache,
+                    &mut kalshi_context_cache,
+                    &mut kalshi_last_trade_id,
+                    &store,
+                    &alert_sinks,
+                    &hybrid_router,
+                    &context_registry,
+                    &trade_store,
+                )
+                .await;
+            }
+        }
 
-                let taker_price = if ws_trade.taker_side.eq_ignore_ascii_case("no") {
-                    ws_trade.no_price
-                } else {
-                    ws_trade.yes_price
-                };
-                let trade_value = (taker_price / 100.0) * f64::from(ws_trade.count);
-                if trade_value < threshold as f64 {
-                    continue;
-                }
-
-                let mut trade = kalshi::Trade {
-                    trade_id: ws_trade.trade_id.clone(),
-                    ticker: ws_trade.ticker.clone(),
-                    price: taker_price / 100.0,
-                    count: ws_trade.count,
-                    yes_price: ws_trade.yes_price,
-                    no_price: ws_trade.no_price,
-                    taker_side: ws_trade.taker_side.clone(),
-                    created_time: ws_trade.created_time.clone(),
-                    market_title: None,
-                };
-
-                let ticker = trade.ticker.clone();
-                let market_info = if let Some(info) = kalshi_market_cache.get(&ticker) {
-                    info.clone()
-                } else {
-                    let info = match tokio::time::timeout(Duration::from_secs(2), kalshi::fetch_market_info_full(&ticker)).await {
-                        Ok(res) => res,
-                        Err(_) => None,
-                    };
-                    kalshi_market_cache.insert(ticker.clone(), info.clone());
-                    info
-                };
-
-                if let Some(ref info) = market_info {
-                    trade.market_title = Some(info.title.clone());
-                }
-
-                // Category filter
-                if let Some(ref title) = trade.market_title {
-                    let has_native_match = market_info.as_ref()
-                        .and_then(|info| info.category.as_ref())
-                        .map(|cat| category_registry.matches_native_category(cat, &selected_categories))
-                        .unwrap_or(false);
-
-                    if !has_native_match {
-                        if category_registry
-                            .matches_selection(title, &selected_categories)
-                            .is_none()
-                        {
-                            continue;
-                        }
-                    }
-                }
-
-                let outcome = kalshi::parse_ticker_details(&trade.ticker, &trade.taker_side);
-                let action = trade.taker_side.to_uppercase();
-
-                // Fetch market context early for filtering (with cache and timeout)
-                let market_ctx = if let Some(ctx) = kalshi_context_cache.get(&ticker) {
-                    ctx.clone()
-                } else {
-                    let ctx = match tokio::time::timeout(Duration::from_secs(2), kalshi::fetch_market_context(&ticker)).await {
-                        Ok(res) => res,
-                        Err(_) => None,
-                    };
-                    kalshi_context_cache.insert(ticker.clone(), ctx.clone());
-                    ctx
-                };
-
-                // Odds and spread filter
-                if let Some(ref cfg) = config {
-                    if let Some(ref ctx) = market_ctx {
-                        // Skip if odds too high (near-certainty)
-                        if ctx.yes_price > cfg.max_odds || ctx.no_price > cfg.max_odds {
-                            continue;
-                        }
-                        // Skip if spread too low (dead market)
-                        if cfg.min_spread > 0.0 && ctx.spread < cfg.min_spread {
-                            continue;
-                        }
-                    }
-                }
-
-                print_kalshi_alert(&trade, trade_value, None);
-
-                if let Some(ref ctx) = market_ctx {
-                    print_market_context(ctx);
-                }
-
-                let order_book = match tokio::time::timeout(Duration::from_secs(2), kalshi::fetch_order_book(&ticker)).await {
-                    Ok(res) => res,
-                    Err(_) => None,
-                };
-                if let Some(ref ob) = order_book {
-                    print_order_book(ob);
-                }
-
-                let alert_data = AlertData {
-                    platform: "Kalshi",
-                    market_title: trade.market_title.as_deref(),
-                    market_id: Some(&trade.ticker),
-                    outcome: Some(&outcome),
-                    side: &action,
-                    value: trade_value,
-                    price: trade.yes_price / 100.0,
-                    size: f64::from(trade.count),
-                    timestamp: &trade.created_time,
-                    wallet_id: None,
-                    wallet_activity: None,
-                    market_context: market_ctx.as_ref(),
-                    whale_profile: None,
-                    order_book: order_book.as_ref(),
-                    top_holders: None,
-                };
-
-                let params = history::build_log_params(&alert_data);
-                let conn_clone = conn.clone();
-                tokio::task::spawn_blocking(move || {
-                    history::log_alert_blocking(params, &*conn_clone.lock().unwrap())
-                })
-                .await
-                .ok();
+        // A Kalshi reconnect may have missed trades that happened during
+        // the outage — backfill each watched ticker's trades newer than
+        // the last one we saw, through the same filter/alert/log pipeline.
+        if let Some(ref mut reconnect_rx) = kalshi_reconnect_rx {
+            let mut reconnected = false;
+            while reconnect_rx.try_recv().is_ok() {
+                reconnected = true;
+            }
+            if reconnected {
+                let watchlist = config.as_ref().map(|c| c.kalshi_watchlist.clone()).unwrap_or_default();
+                reconcile_kalshi_gap(
+                    &watchlist,
+                    threshold,
+                    &category_registry,
+                    &selected_categories,
+                    &config,
+                    &mut kalshi_market_cache,
+                    &mut kalshi_context_cache,
+                    &mut kalshi_last_trade_id,
+                    &store,
+                    &alert_sinks,
+                    &hybrid_router,
+                    &context_registry,
+                    &trade_store,
+                )
+                .await;
             }
         }
 
@@ -482,9 +1897,53 @@ pub async fn watch_whales(threshold: u64, interval: u64, conn: Arc<Mutex<Connect
         let kalshi_ws_active = kalshi_ws_rx.is_some()
             && kalshi_ws_last_trade.elapsed() < kalshi_ws_fallback_threshold;
 
-        // Check Polymarket
-        if watch_polymarket { match polymarket::fetch_recent_trades(Some(threshold)).await {
+        // Drain Polymarket WebSocket trades (non-blocking), same shape as
+        // the Kalshi drain above. There's no reconnect-gap backfill here:
+        // unlike Kalshi's watchlist-scoped `reconcile_kalshi_gap`, Polymarket
+        // has no bounded ticker scope to replay against on reconnect.
+        if let Some(ref mut rx) = polymarket_ws_rx {
+            while let Ok(trade) = rx.try_recv() {
+                polymarket_ws_last_trade = std::time::Instant::now();
+                process_polymarket_trade(
+                    &trade,
+                    threshold,
+                    &category_registry,
+                    &selected_categories,
+                    &config,
+                    &store,
+                    &alert_sinks,
+                    &hybrid_router,
+                    &mut wallet_tracker,
+                    &wallet_store,
+                    &mut whale_cache,
+                    &mut matcher,
+                    &kalshi_executor,
+                    &fill_watcher,
+                    &position_store,
+                    &mut executed_tickers,
+                    &mut day_start_balance_cents,
+                    daily_loss_cents,
+                    max_open,
+                    max_entry_cents,
+                    daily_loss_frac,
+                    reserve_frac,
+                    max_bet_frac,
+                    max_bet_cap,
+                    &trade_store,
+                    dry_run,
+                )
+                .await;
+            }
+        }
+
+        // Determine if we should use HTTP polling for Polymarket (fallback if WS is silent)
+        let polymarket_ws_active = polymarket_ws_rx.is_some()
+            && polymarket_ws_last_trade.elapsed() < polymarket_ws_fallback_threshold;
+
+        // Check Polymarket (HTTP polling fallback — only when WebSocket isn't active)
+        if watch_polymarket && !polymarket_ws_active { match polymarket::fetch_recent_trades(Some(threshold)).await {
             Ok(mut trades) => {
+                crate::metrics::metrics().trades_fetched.add(trades.len() as u64);
                 if let Some(first_trade) = trades.first() {
                     let new_last_id = first_trade.id.clone();
 
@@ -502,394 +1961,35 @@ pub async fn watch_whales(threshold: u64, interval: u64, conn: Arc<Mutex<Connect
                             }
                         }
 
-                        let trade_value = trade.size * trade.price;
-                        if trade_value >= threshold as f64 {
-                            // Category filter: skip if market doesn't match selected categories
-                            if let Some(ref title) = trade.market_title {
-                                if category_registry
-                                    .matches_selection(title, &selected_categories)
-                                    .is_none()
-                                {
-                                    continue;
-                                }
-                            }
-
-                            let wallet_activity = if let Some(ref wallet_id) = trade.wallet_id {
-                                wallet_tracker.record_transaction(wallet_id, trade_value);
-                                Some(wallet_tracker.get_activity(wallet_id))
-                            } else {
-                                None
-                            };
-
-                            // Check for returning whale (12h memory)
-                            let whale_scenario = trade.wallet_id.as_deref().and_then(|wid| {
-                                wallet_tracker.classify_whale_return(
-                                    &*conn.lock().unwrap(),
-                                    wid,
-                                    Some(&trade.asset_id),
-                                    trade.outcome.as_deref(),
-                                )
-                            });
-
-                            // Fetch market context early for filtering
-                            let market_ctx = polymarket::fetch_market_context(&trade.market).await;
-
-                            // Odds and spread filter
-                            if let Some(ref cfg) = config {
-                                if let Some(ref ctx) = market_ctx {
-                                    // Skip if odds too high (near-certainty)
-                                    if ctx.yes_price > cfg.max_odds || ctx.no_price > cfg.max_odds {
-                                        continue;
-                                    }
-                                    // Skip if spread too low (dead market)
-                                    if cfg.min_spread > 0.0 && ctx.spread < cfg.min_spread {
-                                        continue;
-                                    }
-                                }
-                            }
-
-                            // Print returning whale info if detected
-                            if let Some(ref scenario) = whale_scenario {
-                                display::print_returning_whale(scenario, "Polymarket");
-                            }
-
-                            print_whale_alert(
-                                "Polymarket",
-                                trade,
-                                trade_value,
-                                wallet_activity.as_ref(),
-                            );
-
-                            if let Some(ref ctx) = market_ctx {
-                                print_market_context(ctx);
-                            }
-
-                            // Fetch whale profile (Polymarket only - on-chain wallets)
-                            let wp = if let Some(ref wallet_id) = trade.wallet_id {
-                                whale_profile::fetch_whale_profile(wallet_id, &mut whale_cache).await
-                            } else {
-                                None
-                            };
-                            if let Some(ref profile) = wp {
-                                print_whale_profile(profile);
-                            }
-
-                            // Fetch order book depth
-                            let order_book = polymarket::fetch_order_book(&trade.asset_id).await;
-                            if let Some(ref ob) = order_book {
-                                print_order_book(ob);
-                            }
-
-                            // Fetch top holders
-                            let top_holders = polymarket::fetch_top_holders(&trade.market).await;
-                            if let Some(ref th) = top_holders {
-                                print_top_holders(th);
-                            }
-
-                            let alert_data = AlertData {
-                                platform: "Polymarket",
-                                market_title: trade.market_title.as_deref(),
-                                market_id: Some(&trade.market),
-                                outcome: trade.outcome.as_deref(),
-                                side: &trade.side,
-                                value: trade_value,
-                                price: trade.price,
-                                size: trade.size,
-                                timestamp: &trade.timestamp,
-                                wallet_id: trade.wallet_id.as_deref(),
-                                wallet_activity: wallet_activity.as_ref(),
-                                market_context: market_ctx.as_ref(),
-                                whale_profile: wp.as_ref(),
-                                order_book: order_book.as_ref(),
-                                top_holders: top_holders.as_ref(),
-                            };
-
-                            let alert_id = {
-                                let params = history::build_log_params(&alert_data);
-                                let conn_clone = conn.clone();
-                                tokio::task::spawn_blocking(move || {
-                                    history::log_alert_blocking(params, &*conn_clone.lock().unwrap())
-                                })
-                                .await
-                                .ok()
-                                .flatten()
-                            };
-
-                            // ═══ RISK-MANAGED EXECUTION PIPELINE ═══════════════
-                            let whale_win_rate = wp.as_ref().and_then(|p| p.win_rate);
-
-                            // Gate 1: Win rate
-                            let passes_win_rate = match whale_win_rate {
-                                Some(wr) if wr >= 0.85 => {
-                                    println!("✅ Whale win rate {:.1}% passes 85% threshold", wr * 100.0);
-                                    true
-                                }
-                                Some(wr) => {
-                                    println!("⚠️ Skipping execution: whale win rate {:.1}% < 85%", wr * 100.0);
-                                    false
-                                }
-                                None => {
-                                    println!("⚠️ Skipping execution: whale win rate unknown");
-                                    false
-                                }
-                            };
-
-                            let poly_title = trade.market_title.as_deref().unwrap_or("");
-                            if passes_win_rate && !poly_title.is_empty() {
-                                let search_results = kalshi::search_markets(poly_title).await.unwrap_or_default();
-                                if let Some(match_result) = matcher.match_market(
-                                    poly_title,
-                                    trade.outcome.as_deref().unwrap_or(""),
-                                    &search_results
-                                ).await {
-                                    println!("{} Matched to Kalshi: {} ({}) Confidence: {:.2}",
-                                        "🤖 LLM".bright_magenta(),
-                                        match_result.ticker.bright_cyan(),
-                                        match_result.side,
-                                        match_result.confidence.unwrap_or(0.0)
-                                    );
-
-                                    let dedup_key = match match_result.ticker.rfind('-') {
-                                        Some(pos) => match_result.ticker[..pos].to_string(),
-                                        None => match_result.ticker.clone(),
-                                    };
-
-                                    // Gate 2: Event-level dedup
-                                    if executed_tickers.contains_key(&dedup_key) {
-                                        println!("⚠️ Already have position on event {} — skipping",
-                                            dedup_key);
-                                    }
-                                    // Gate 3: Max open positions
-                                    else if executed_tickers.len() >= max_open {
-                                        println!("⚠️ Max {} open positions reached — skipping {}",
-                                            max_open, match_result.ticker);
-                                    }
-                                    // Gate 4: 24h expiry + fetch Kalshi live price
-                                    else if let Some(snapshot) = fetch_kalshi_market_snapshot(&match_result.ticker).await {
-                                    if !snapshot.closes_within_24h {
-                                        println!("⚠️ Skipping {}: does not close within 24 hours",
-                                            match_result.ticker);
-                                    }
-                                    else if let Some(ref executor) = kalshi_executor {
-                                        // Gate 5: Live Kalshi position check
-                                        if executor.has_open_position(&dedup_key).await.unwrap_or(false) {
-                                            println!("⚠️ Already have LIVE Kalshi position on {} — skipping",
-                                                dedup_key);
-                                            executed_tickers.insert(dedup_key.clone(), std::time::Instant::now());
-                                        } else {
-
-                                        // ── Fee + EV calculation (using Kalshi live price, not Polymarket) ──
-                                        let kalshi_price = if match_result.side.eq_ignore_ascii_case("yes") {
-                                            snapshot.yes_price_cents
-                                        } else {
-                                            snapshot.no_price_cents
-                                        };
-                                        let price_cents = kalshi_price.clamp(1, 99);
-                                        let fee_cents = kalshi_taker_fee_cents(price_cents);
-                                        let wr = whale_win_rate.unwrap_or(0.0);
-                                        let ev_cents = expected_value_cents(wr, price_cents, fee_cents);
-
-                                        println!("📊 Price: {}c | Fee: {}c/contract | EV: {:.1}c/contract (WR {:.1}%)",
-                                            price_cents, fee_cents, ev_cents, wr * 100.0);
-
-                                        // Gate 6: Max entry price
-                                        if price_cents > max_entry_cents {
-                                            println!("⚠️ Skipping: price {}c > max {}c",
-                                                price_cents, max_entry_cents);
-                                        }
-                                        // Gate 7: Positive expected value after fees
-                                        else if ev_cents <= 0.0 {
-                                            println!("⚠️ Skipping: negative EV {:.1}c after {}c fee (need WR > {:.0}%)",
-                                                ev_cents, fee_cents, (price_cents + fee_cents) as f64);
-                                        } else {
-
-                                        // ── Balance + risk sizing ───────────────────────────
-                                        let balance_cents = executor.get_balance().await.unwrap_or(0);
-
-                                        if day_start_balance_cents.is_none() {
-                                            day_start_balance_cents = Some(balance_cents);
-                                            println!("📋 Day-start balance: ${:.2}", balance_cents as f64 / 100.0);
-                                        }
-                                        let day_start = day_start_balance_cents.unwrap_or(balance_cents);
-
-                                        // Gate 8: Daily loss limit
-                                        let loss_limit_cents = (day_start as f64 * daily_loss_frac) as i64;
-                                        if daily_loss_cents >= loss_limit_cents {
-                                            println!("🛑 Daily loss limit hit: lost ${:.2} >= ${:.2} limit — halting trades",
-                                                daily_loss_cents as f64 / 100.0,
-                                                loss_limit_cents as f64 / 100.0);
-                                        }
-                                        // Gate 9: Reserve
-                                        else {
-                                        let reserve_cents = (day_start as f64 * reserve_frac) as i64;
-
-                                        // ── Quarter-Kelly sizing ────────────────────────────
-                                        let kelly_frac = quarter_kelly_fraction(wr, price_cents, fee_cents, max_bet_frac);
-                                        let kelly_dollars = (balance_cents as f64 / 100.0) * kelly_frac;
-                                        let bet_size = kelly_dollars
-                                            .min(max_bet_cap)
-                                            .max(1.0); // $1 floor
-                                        // Cap by TOTAL cost (price + fees), not just price — fees can add $2+ on cheap contracts
-                                        let max_count_by_cap = ((max_bet_cap * 100.0) / (price_cents as f64 + fee_cents as f64)).floor() as i32;
-                                        let count_by_kelly = ((bet_size * 100.0) / price_cents as f64).max(1.0) as i32;
-                                        let count = count_by_kelly.min(max_count_by_cap.max(1));
-                                        let trade_cost_cents = (count as i64) * price_cents;
-                                        let total_cost_with_fees = trade_cost_cents + (count as i64) * fee_cents;
-
-                                        println!("📐 Kelly: {:.2}% → ${:.2} | {} contracts @ {}c + {}c fee = ${:.2}",
-                                            kelly_frac * 100.0,
-                                            bet_size,
-                                            count,
-                                            price_cents,
-                                            fee_cents,
-                                            total_cost_with_fees as f64 / 100.0);
-
-                                        if balance_cents.saturating_sub(total_cost_with_fees) < reserve_cents {
-                                            println!("⚠️ Skipping: ${:.2} - ${:.2} would breach {:.0}% reserve (${:.2})",
-                                                balance_cents as f64 / 100.0,
-                                                total_cost_with_fees as f64 / 100.0,
-                                                reserve_frac * 100.0,
-                                                reserve_cents as f64 / 100.0);
-                                        } else {
-                                            println!("💰 Balance: ${:.2} → cost ${:.2} → ${:.2} remaining",
-                                                balance_cents as f64 / 100.0,
-                                                total_cost_with_fees as f64 / 100.0,
-                                                (balance_cents - total_cost_with_fees) as f64 / 100.0);
-
-                                            println!("🚀 EXECUTING: Buy {} {} @ {}c (Qty: {}, ${:.2}, EV: +{:.1}c/contract)",
-                                                match_result.side.to_uppercase(),
-                                                match_result.ticker,
-                                                price_cents,
-                                                count,
-                                                count as f64 * price_cents as f64 / 100.0,
-                                                ev_cents
-                                            );
-
-                                            match executor.place_order(
-                                                &match_result.ticker,
-                                                &match_result.side,
-                                                count,
-                                                price_cents
-                                            ).await {
-                                                Ok(order_id) => {
-                                                    println!("✅ Order Placed: {}", order_id.to_string().green());
-                                                    executed_tickers.insert(dedup_key.clone(), std::time::Instant::now());
-
-                                                    if let Some(row_id) = alert_id {
-                                                        let conn_clone = conn.clone();
-                                                        let order_id_s = order_id.to_string();
-                                                        let ticker = match_result.ticker.clone();
-                                                        let side = match_result.side.clone();
-                                                        tokio::task::spawn_blocking(move || {
-                                                            let guard = conn_clone.lock().unwrap();
-                                                            db::mark_alert_executed(
-                                                                &*guard,
-                                                                row_id,
-                                                                &order_id_s,
-                                                                &ticker,
-                                                                &side,
-                                                                bet_size,
-                                                                price_cents as f64 / 100.0,
-                                                            );
-                                                        })
-                                                        .await
-                                                        .ok();
-                                                    }
-
-                                                    // Poll for fill (5 attempts, 2s apart) — only count daily loss & send Discord when filled
-                                                    let mut filled = false;
-                                                    for attempt in 1..=5 {
-                                                        tokio::time::sleep(Duration::from_secs(2)).await;
-                                                        if let Ok((status, fill_count)) = executor.get_order_status(&order_id).await {
-                                                            if status == "executed" || fill_count >= count {
-                                                                filled = true;
-                                                                println!("✅ Order {} filled ({} contracts)", order_id, fill_count);
-                                                                break;
-                                                            }
-                                                            if status == "canceled" {
-                                                                println!("⚠️ Order {} was canceled", order_id);
-                                                                break;
-                                                            }
-                                                            if attempt < 5 {
-                                                                println!("   Poll {}/5: status={} fill_count={} — waiting...", attempt, status, fill_count);
-                                                            }
-                                                        }
-                                                    }
-                                                    if !filled {
-                                                        println!("⚠️ Order {} not yet filled after 10s — not counting against daily loss", order_id);
-                                                    } else {
-                                                        daily_loss_cents += trade_cost_cents;
-                                                        let balance_after = balance_cents.saturating_sub(total_cost_with_fees);
-                                                        if let Some(ref cfg) = config {
-                                                            let url = cfg.webhook_url.as_ref()
-                                                                .or(cfg.discord_webhook_url.as_ref());
-                                                            if let Some(url) = url {
-                                                                let exec_alert = crate::alerts::webhook::ExecutionAlert {
-                                                                    kalshi_ticker: match_result.ticker.clone(),
-                                                                    side: match_result.side.clone(),
-                                                                    count,
-                                                                    price_cents,
-                                                                    fee_cents,
-                                                                    total_cost_cents: total_cost_with_fees,
-                                                                    ev_cents,
-                                                                    kelly_pct: kelly_frac * 100.0,
-                                                                    whale_win_rate: wr,
-                                                                    balance_after_cents: balance_after,
-                                                                    poly_title: poly_title.to_string(),
-                                                                    order_id: order_id.to_string(),
-                                                                };
-                                                                println!("📨 Sending execution alert...");
-                                                                crate::alerts::webhook::send_execution_alert(url, &exec_alert).await;
-                                                            }
-                                                        }
-                                                    }
-                                                },
-                                                Err(e) => eprintln!("❌ Execution Failed: {}", e),
-                                            }
-                                        }
-                                        }
-                                        }
-                                        }
-                                    } else {
-                                        println!("⚠️ Execution skipped (No credentials)");
-                                    }
-                                    } else {
-                                        println!("⚠️ Skipping {}: could not fetch Kalshi market data",
-                                            match_result.ticker);
-                                    }
-                                }
-                            }
-                            // ═══ END EXECUTION PIPELINE ════════════════════════
-
-                            // Record to wallet memory DB
-                            if let Some(ref wallet_id) = trade.wallet_id {
-                                let conn_clone = conn.clone();
-                                let wallet_id_s = wallet_id.clone();
-                                let market_title = trade.market_title.clone();
-                                let asset_id = trade.asset_id.clone();
-                                let outcome = trade.outcome.clone();
-                                let side = trade.side.clone();
-                                let trade_value_cp = trade_value;
-                                let price_cp = trade.price;
-                                tokio::task::spawn_blocking(move || {
-                                    let guard = conn_clone.lock().unwrap();
-                                    db::record_wallet_memory(
-                                        &*guard,
-                                        &wallet_id_s,
-                                        market_title.as_deref(),
-                                        Some(&asset_id),
-                                        outcome.as_deref(),
-                                        &side,
-                                        trade_value_cp,
-                                        price_cp,
-                                        "Polymarket",
-                                    );
-                                })
-                                .await
-                                .ok();
-                                wallet_tracker.record_wallet_seen(wallet_id);
-                            }
-                        }
+                        process_polymarket_trade(
+                            trade,
+                            threshold,
+                            &category_registry,
+                            &selected_categories,
+                            &config,
+                            &store,
+                            &alert_sinks,
+                            &hybrid_router,
+                            &mut wallet_tracker,
+                            &wallet_store,
+                            &mut whale_cache,
+                            &mut matcher,
+                            &kalshi_executor,
+                            &fill_watcher,
+                            &position_store,
+                            &mut executed_tickers,
+                            &mut day_start_balance_cents,
+                            daily_loss_cents,
+                            max_open,
+                            max_entry_cents,
+                            daily_loss_frac,
+                            reserve_frac,
+                            max_bet_frac,
+                            max_bet_cap,
+                            &trade_store,
+                            dry_run,
+                        )
+                        .await;
                     }
 
                     last_polymarket_trade_id = Some(new_last_id);
@@ -903,6 +2003,7 @@ pub async fn watch_whales(threshold: u64, interval: u64, conn: Arc<Mutex<Connect
         // Check Kalshi (HTTP polling fallback — only when WebSocket isn't active)
         if watch_kalshi && !kalshi_ws_active { match kalshi::fetch_recent_trades(config.as_ref()).await {
             Ok(mut trades) => {
+                crate::metrics::metrics().trades_fetched.add(trades.len() as u64);
                 if let Some(first_trade) = trades.first() {
                     let new_last_id = first_trade.trade_id.clone();
 
@@ -945,6 +2046,9 @@ pub async fn watch_whales(threshold: u64, interval: u64, conn: Arc<Mutex<Connect
                                         .matches_selection(title, &selected_categories)
                                         .is_none()
                                     {
+                                        if category_registry.categorize(title).is_none() {
+                                            store.record_uncategorized_title(title);
+                                        }
                                         continue;
                                     }
                                 }
@@ -955,8 +2059,15 @@ pub async fn watch_whales(threshold: u64, interval: u64, conn: Arc<Mutex<Connect
 
                             let action = trade.taker_side.to_uppercase();
 
-                            // Fetch market context early for filtering
-                            let market_ctx = kalshi::fetch_market_context(&trade.ticker).await;
+                            // Fetch market context early for filtering. Prefer the
+                            // live `ws::market_context` stream over a fresh REST call.
+                            let market_ctx = match &context_registry {
+                                Some(registry) => match crate::ws::market_context::context_for(registry, &trade.ticker).await {
+                                    Some(ctx) => Some(ctx),
+                                    None => kalshi::fetch_market_context(&trade.ticker).await,
+                                },
+                                None => kalshi::fetch_market_context(&trade.ticker).await,
+                            };
 
                             // Odds and spread filter
                             if let Some(ref cfg) = config {
@@ -972,14 +2083,27 @@ pub async fn watch_whales(threshold: u64, interval: u64, conn: Arc<Mutex<Connect
                                 }
                             }
 
+                            // Fetch order book depth before the alert prints, so a thin book
+                            // can gate it the same way the odds/spread filter above does
+                            // rather than only being logged after the fact.
+                            let order_book = kalshi::fetch_order_book(&trade.ticker).await;
+                            if let Some(ref cfg) = config {
+                                if !passes_min_depth(&order_book, cfg.min_order_book_depth) {
+                                    continue;
+                                }
+                            }
+
                             print_kalshi_alert(trade, trade_value, None);
 
                             if let Some(ref ctx) = market_ctx {
                                 print_market_context(ctx);
+
+                                let title = trade.market_title.as_deref().unwrap_or(&trade.ticker);
+                                if let Some(pair) = hybrid_router.record_kalshi_context(&trade.ticker, title, ctx) {
+                                    emit_arbitrage_alert(&pair, &trade.created_time, &alert_sinks).await;
+                                }
                             }
 
-                            // Fetch order book depth for Kalshi
-                            let order_book = kalshi::fetch_order_book(&trade.ticker).await;
                             if let Some(ref ob) = order_book {
                                 print_order_book(ob);
                             }
@@ -988,6 +2112,7 @@ pub async fn watch_whales(threshold: u64, interval: u64, conn: Arc<Mutex<Connect
                                 platform: "Kalshi",
                                 market_title: trade.market_title.as_deref(),
                                 market_id: Some(&trade.ticker),
+                                trade_id: Some(&trade.trade_id),
                                 outcome: Some(&outcome),
                                 side: &action,
                                 value: trade_value,
@@ -1000,15 +2125,26 @@ pub async fn watch_whales(threshold: u64, interval: u64, conn: Arc<Mutex<Connect
                                 whale_profile: None,
                                 order_book: order_book.as_ref(),
                                 top_holders: None,
+                                arbitrage: None,
+                                combinatorial: None,
+                                is_rollover: false,
                             };
 
                             let params = history::build_log_params(&alert_data);
-                            let conn_clone = conn.clone();
+                            let sinks_clone = alert_sinks.clone();
                             tokio::task::spawn_blocking(move || {
-                                history::log_alert_blocking(params, &*conn_clone.lock().unwrap())
+                                history::log_alert_blocking(params, &sinks_clone)
                             })
                             .await
                             .ok();
+
+                            store.record_candle_trade(
+                                "Kalshi",
+                                &trade.ticker,
+                                unix_timestamp(&trade.created_time),
+                                trade.yes_price / 100.0,
+                                f64::from(trade.count),
+                            );
                         }
                     }
 
@@ -0,0 +1,115 @@
+/// Shell completion generation, following the pattern of tools that expose
+/// a dedicated `completions <shell>` subcommand rather than relying on
+/// whatever script the user's package manager happens to ship. The
+/// `--category` flag on the (illustrative) search command is wired to the
+/// same FIRST/LAST/ANYTIME/TOP/FINISH/PLACE keywords `ticker_rules`
+/// recognizes, so completions for it stay in sync with the parser instead
+/// of drifting as a second hardcoded list.
+use clap::{Command, CommandFactory, Parser, Subcommand, ValueEnum};
+use clap_complete::{generate, Generator, Shell};
+
+use crate::market_filter::MarketFilterArgs;
+
+/// Category keyword this parser's `ticker_rules` grammar matches (see
+/// `ticker_rules::TickerRuleSet::default_rules`). `ValueEnum` gives
+/// `clap_complete` the possible-value list it needs to suggest these on tab.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum TickerCategoryKeyword {
+    First,
+    Last,
+    Anytime,
+    Top,
+    Finish,
+    Place,
+}
+
+impl TickerCategoryKeyword {
+    pub fn all() -> &'static [TickerCategoryKeyword] {
+        use TickerCategoryKeyword::*;
+        &[First, Last, Anytime, Top, Finish, Place]
+    }
+}
+
+#[derive(Parser)]
+#[command(name = "polymaster")]
+struct CompletionsCli {
+    #[command(subcommand)]
+    command: CompletionsCommand,
+}
+
+#[derive(Subcommand)]
+enum CompletionsCommand {
+    /// Search parsed markets (mirrors `market_filter::MarketFilterArgs`,
+    /// plus a category keyword filter for tab-completable ticker terms).
+    Search {
+        #[command(flatten)]
+        filter: MarketFilterArgs,
+
+        /// Restrict to tickers matching one of these ticker_rules category
+        /// keywords (FIRST, LAST, ANYTIME, TOP, FINISH, PLACE).
+        #[arg(long, value_enum)]
+        category: Vec<TickerCategoryKeyword>,
+    },
+    /// Emit a completion script for `shell` on stdout.
+    Completions {
+        #[arg(value_enum)]
+        shell: Shell,
+    },
+}
+
+fn build_command() -> Command {
+    CompletionsCli::command()
+}
+
+/// Write the completion script for `shell` to `writer`, under the binary
+/// name `bin_name`.
+pub fn write_completions<G: Generator>(shell: G, bin_name: &str, writer: &mut dyn std::io::Write) {
+    let mut cmd = build_command();
+    generate(shell, &mut cmd, bin_name.to_string(), writer);
+}
+
+/// Handle the `completions <shell>` subcommand: emit the script for `shell`
+/// on stdout.
+pub fn run_completions_subcommand(shell: Shell) {
+    let mut stdout = std::io::stdout();
+    write_completions(shell, "polymaster", &mut stdout);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn category_keywords_cover_the_ticker_rules_grammar() {
+        let names: Vec<String> = TickerCategoryKeyword::all()
+            .iter()
+            .map(|k| format!("{:?}", k))
+            .collect();
+        for expected in ["First", "Last", "Anytime", "Top", "Finish", "Place"] {
+            assert!(names.iter().any(|n| n == expected), "missing {}", expected);
+        }
+    }
+
+    #[test]
+    fn generates_non_empty_script_for_every_supported_shell() {
+        for shell in Shell::value_variants() {
+            let mut buf = Vec::new();
+            write_completions(*shell, "polymaster", &mut buf);
+            assert!(!buf.is_empty(), "{:?} produced an empty completion script", shell);
+        }
+    }
+
+    #[test]
+    fn search_subcommand_exposes_category_as_a_repeatable_value_enum_flag() {
+        let cmd = build_command();
+        let search = cmd
+            .get_subcommands()
+            .find(|c| c.get_name() == "search")
+            .expect("search subcommand");
+        let category_arg = search
+            .get_arguments()
+            .find(|a| a.get_id() == "category")
+            .expect("category arg");
+        assert!(category_arg.is_multiple_values_set() || category_arg.get_action().takes_values());
+    }
+}
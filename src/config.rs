@@ -6,7 +6,28 @@ use std::path::PathBuf;
 pub struct Config {
     pub kalshi_api_key_id: Option<String>,
     pub kalshi_private_key: Option<String>,
+    /// `kalshi_private_key`, encrypted at rest under a user passphrase (see
+    /// `crate::keystore`). When set, this takes priority over the plaintext
+    /// `kalshi_private_key` field above — `setup` writes both only transiently
+    /// during a migration and clears the plaintext field once it does.
+    #[serde(default)]
+    pub kalshi_private_key_encrypted: Option<crate::keystore::EncryptedKey>,
+    /// Polygon wallet address (0x-prefixed) that signs and funds CLOB orders.
+    /// Unset keeps `polymarket::place_order`/`cancel_order`/`get_open_orders`
+    /// unavailable — those endpoints have no unauthenticated fallback.
+    #[serde(default)]
+    pub polymarket_address: Option<String>,
+    /// Hex-encoded secp256k1 private key (0x-prefixed or not) for the wallet
+    /// above. Used only to sign CLOB orders for the `POLY_SIGNATURE` header —
+    /// never sent anywhere itself.
+    #[serde(default)]
+    pub polymarket_private_key: Option<String>,
     pub webhook_url: Option<String>,
+    /// `host:port` to serve Prometheus metrics (`GET /metrics`) on. Unset
+    /// disables the endpoint entirely rather than binding a default port —
+    /// unlike `api_bind_addr`, scraping isn't something every deployment wants.
+    #[serde(default)]
+    pub metrics_addr: Option<String>,
     #[serde(default)]
     pub kalshi_is_demo: bool,
     #[serde(default)]
@@ -26,6 +47,13 @@ pub struct Config {
     #[serde(default = "default_bet_size")]
     pub bet_size: f64,
     pub discord_webhook_url: Option<String>,
+    /// Explicit override for `alerts::webhook::TextFormat` ("discord",
+    /// "telegram_markdown_v2", or "plain"), read before falling back to
+    /// sniffing the webhook URL. Unset or unrecognized keeps the URL
+    /// heuristic — this only needs to be set for a destination (like
+    /// Telegram) that doesn't have a self-identifying webhook URL.
+    #[serde(default)]
+    pub text_format: Option<String>,
     /// Which platforms to monitor: ["polymarket", "kalshi"] or ["all"]
     #[serde(default = "default_platforms")]
     pub platforms: Vec<String>,
@@ -68,6 +96,120 @@ pub struct Config {
     /// Minimum bankroll reserve as a fraction of day-start balance (default 0.20 = 20%).
     #[serde(default = "default_reserve_fraction")]
     pub reserve_fraction: f64,
+    /// When true, `commands::watch`'s copy-trade pipeline logs the order it
+    /// would place on Kalshi instead of actually calling `place_order` — lets
+    /// an operator watch the gates (win rate, EV, Kelly sizing, reserve) fire
+    /// against live trade flow before risking real capital. Default false.
+    #[serde(default)]
+    pub dry_run: bool,
+    /// Per-request timeout in seconds for the shared HTTP client (default 10).
+    #[serde(default = "default_http_timeout_secs")]
+    pub http_timeout_secs: u64,
+    /// Retry budget for transient HTTP failures (connection/timeout errors and
+    /// 429/5xx responses) before giving up (default 4).
+    #[serde(default = "default_http_max_retries")]
+    pub http_max_retries: u32,
+    /// Global rate budget, in requests/second, shared by every call the
+    /// `platforms::kalshi` module makes (default 8, Kalshi's basic-tier read limit).
+    #[serde(default = "default_kalshi_rate_limit_per_sec")]
+    pub kalshi_rate_limit_per_sec: f64,
+    /// Postgres DSN (e.g. `host=localhost user=wwatcher dbname=analytics`) for
+    /// also persisting alerts to a shared analytics database, and for reading
+    /// the startup banner's alert count from that same database via
+    /// `store::PostgresAlertCountStore` instead of the local SQLite file.
+    /// Unset disables both — alerts still log to SQLite and JSONL either way.
+    /// Takes priority over the `WWATCHER_PG_HOST`/`_PORT`/`_USER`/`_PASSWORD`/
+    /// `_DBNAME`/`_SSL` environment variables that `alerts::sinks::PostgresSink::connect_from_env`
+    /// reads as a fallback when this is unset.
+    #[serde(default)]
+    pub postgres_alert_url: Option<String>,
+    /// Max rows the Postgres sink batches per `INSERT` (default 200).
+    #[serde(default = "default_postgres_max_batch")]
+    pub postgres_max_batch: usize,
+    /// Seconds between forced flushes of the Postgres sink's buffer, even if
+    /// `postgres_max_batch` hasn't been reached (default 5).
+    #[serde(default = "default_postgres_flush_interval_secs")]
+    pub postgres_flush_interval_secs: u64,
+    /// Kalshi `market_ticker`s to scope the trade WebSocket's subscription
+    /// to. Empty subscribes to the full trade firehose (the prior behavior).
+    #[serde(default)]
+    pub kalshi_watchlist: Vec<String>,
+    /// Postgres DSN for wallet-memory reads/writes (`WalletTracker`'s
+    /// `record_to_db`/`get_wallet_history`/`maybe_refresh_cache`). Unset keeps
+    /// wallet memory on the local SQLite store; set this to run several
+    /// watcher instances against one pooled backend instead.
+    #[serde(default)]
+    pub wallet_memory_store_url: Option<String>,
+    /// Connections to open in the pooled Postgres wallet-memory store
+    /// (default 4). Ignored when `wallet_memory_store_url` is unset.
+    #[serde(default = "default_wallet_memory_pool_size")]
+    pub wallet_memory_pool_size: usize,
+    /// Take-profit threshold in cents of price movement from entry, past
+    /// which an open Kalshi position is closed (default 15c).
+    #[serde(default = "default_take_profit_cents")]
+    pub take_profit_cents: i64,
+    /// Stop-loss threshold in cents of price movement from entry, past
+    /// which an open Kalshi position is closed (default 10c).
+    #[serde(default = "default_stop_loss_cents")]
+    pub stop_loss_cents: i64,
+    /// Each platform's proportional trading fee, as a fraction of notional,
+    /// used by `execution::arbitrage::HybridRouter` to decide whether a
+    /// cross-venue spread survives both legs' fees (default 1%).
+    #[serde(default = "default_arbitrage_fee_rate")]
+    pub arbitrage_fee_rate: f64,
+    /// Each platform's flat per-trade fee floor in dollars, added to
+    /// `arbitrage_fee_rate` (default $0.01).
+    #[serde(default = "default_arbitrage_min_fee")]
+    pub arbitrage_min_fee: f64,
+    /// Notional in dollars `HybridRouter` sizes a detected opportunity at
+    /// when checking whether its edge clears the fee floor (default $100).
+    #[serde(default = "default_arbitrage_notional")]
+    pub arbitrage_notional: f64,
+    /// Address `commands::api::serve_api` binds its read-only HTTP/JSON
+    /// endpoints to (default `127.0.0.1:7878`, loopback-only). Set this to
+    /// `0.0.0.0:<port>` to let a dashboard on another host reach it.
+    #[serde(default = "default_api_bind_addr")]
+    pub api_bind_addr: String,
+    /// Minimum dollar notional resting on the thinner side of the order
+    /// book (within `fetch_order_book`'s returned levels) for a trade to
+    /// still alert. Default 0.0 (disabled) — unlike `min_spread`, a low
+    /// default here would silently drop markets most Kalshi series don't
+    /// carry deep books for.
+    #[serde(default = "default_min_order_book_depth")]
+    pub min_order_book_depth: f64,
+    /// Candle resolutions (`candles::Resolution::as_str` forms: "1m", "5m",
+    /// "15m", "1h", "4h", "1d") the live `CandleCache` maintains. Defaults
+    /// to all six; an unrecognized entry is ignored, and an empty list
+    /// falls back to the same default rather than disabling candles.
+    #[serde(default = "default_candle_intervals")]
+    pub candle_intervals: Vec<String>,
+    /// Auto-roll an expiring weekly position into the next period's
+    /// equivalent market instead of letting it settle with the dedup slot
+    /// simply freeing up. Off by default — rolling is a second live order
+    /// per position, and not every deployment runs recurring weekly series.
+    #[serde(default)]
+    pub rollover_enabled: bool,
+    /// How close to a position's `close_time` (in hours) `monitor_rollovers`
+    /// starts looking for a successor market (default 2).
+    #[serde(default = "default_rollover_window_hours")]
+    pub rollover_window_hours: u32,
+    /// Postgres DSN for `store::PostgresTradeStore`, which persists every
+    /// normalized trade into a `trades` table and upserts it into a
+    /// `candles` table at each of `candle_intervals`. Unset keeps trade
+    /// history on the local SQLite `candles` table only (see
+    /// `candles::CandleCache`) — set this to also flow trades into a
+    /// shared analytics database queryable with `history`.
+    #[serde(default)]
+    pub trade_store_url: Option<String>,
+    /// Connections to open in the pooled Postgres trade store (default 4).
+    /// Ignored when `trade_store_url` is unset.
+    #[serde(default = "default_trade_store_pool_size")]
+    pub trade_store_pool_size: usize,
+    /// Path to append a Ledger-CLI journal entry (see `alerts::ledger`) to on
+    /// every executed Kalshi trade. Unset disables the journal entirely —
+    /// the Discord/generic webhook embed remains the only record.
+    #[serde(default)]
+    pub ledger_export_path: Option<String>,
 }
 
 fn default_categories() -> Vec<String> {
@@ -134,6 +276,70 @@ fn default_reserve_fraction() -> f64 {
     0.20
 }
 
+fn default_http_timeout_secs() -> u64 {
+    10
+}
+
+fn default_http_max_retries() -> u32 {
+    4
+}
+
+fn default_kalshi_rate_limit_per_sec() -> f64 {
+    8.0
+}
+
+pub fn default_postgres_max_batch() -> usize {
+    200
+}
+
+pub fn default_postgres_flush_interval_secs() -> u64 {
+    5
+}
+
+fn default_take_profit_cents() -> i64 {
+    15
+}
+
+fn default_stop_loss_cents() -> i64 {
+    10
+}
+
+fn default_wallet_memory_pool_size() -> usize {
+    4
+}
+
+pub fn default_arbitrage_fee_rate() -> f64 {
+    0.01
+}
+
+pub fn default_arbitrage_min_fee() -> f64 {
+    0.01
+}
+
+pub fn default_arbitrage_notional() -> f64 {
+    100.0
+}
+
+pub fn default_api_bind_addr() -> String {
+    "127.0.0.1:7878".to_string()
+}
+
+fn default_min_order_book_depth() -> f64 {
+    0.0
+}
+
+fn default_candle_intervals() -> Vec<String> {
+    vec!["1m".into(), "5m".into(), "15m".into(), "1h".into(), "4h".into(), "1d".into()]
+}
+
+fn default_rollover_window_hours() -> u32 {
+    2
+}
+
+fn default_trade_store_pool_size() -> usize {
+    4
+}
+
 fn config_path() -> Result<PathBuf, Box<dyn std::error::Error>> {
     let config_dir = dirs::config_dir().ok_or("Could not determine config directory")?;
 
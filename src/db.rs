@@ -1,6 +1,11 @@
-use rusqlite::{Connection, params};
+use rusqlite::{Connection, OptionalExtension, params};
 use sha2::{Sha256, Digest};
-use std::path::PathBuf;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+
+use crate::candles::CandleCache;
 
 pub fn wallet_hash(wallet_id: &str) -> String {
     let mut hasher = Sha256::new();
@@ -32,11 +37,36 @@ pub fn open_db() -> Result<Connection, Box<dyn std::error::Error>> {
          PRAGMA busy_timeout=5000;"
     )?;
 
-    init_schema(&conn)?;
+    run_migrations(&conn)?;
     Ok(conn)
 }
 
-fn init_schema(conn: &Connection) -> Result<(), Box<dyn std::error::Error>> {
+/// A single forward-only schema change. `up` must be idempotent (safe to run
+/// against a fresh DB as well as one already partway upgraded) since it always
+/// runs inside its own transaction against whatever state `version - 1` left.
+struct Migration {
+    version: u32,
+    up: fn(&Connection) -> rusqlite::Result<()>,
+}
+
+/// Ordered schema history. Add new migrations to the end with the next version
+/// number; never edit or reorder an already-released entry, since `run_migrations`
+/// only applies versions greater than what's recorded in `metadata.schema_version`.
+const MIGRATIONS: &[Migration] = &[
+    Migration { version: 1, up: migrate_v1_initial_schema },
+    Migration { version: 2, up: migrate_v2_alerts_execution_columns },
+    Migration { version: 3, up: migrate_v3_uncategorized_titles },
+    Migration { version: 4, up: migrate_v4_analytics_views },
+    Migration { version: 5, up: migrate_v5_fee_tracking },
+    Migration { version: 6, up: migrate_v6_candles },
+    Migration { version: 7, up: migrate_v7_candles_platform },
+    Migration { version: 8, up: migrate_v8_alert_ev_kelly },
+    Migration { version: 9, up: migrate_v9_performance_buckets },
+    Migration { version: 10, up: migrate_v10_alert_trade_id },
+    Migration { version: 11, up: migrate_v11_raw_trades },
+];
+
+fn migrate_v1_initial_schema(conn: &Connection) -> rusqlite::Result<()> {
     conn.execute_batch(
         "CREATE TABLE IF NOT EXISTS alerts (
             id INTEGER PRIMARY KEY AUTOINCREMENT,
@@ -61,6 +91,7 @@ fn init_schema(conn: &Connection) -> Result<(), Box<dyn std::error::Error>> {
             status TEXT DEFAULT 'OPEN',
             settled_outcome TEXT,
             pnl_value REAL,
+            fee_value REAL DEFAULT 0,
             shadow_bet_amount REAL,
             shadow_active INTEGER DEFAULT 0
         );
@@ -86,25 +117,12 @@ fn init_schema(conn: &Connection) -> Result<(), Box<dyn std::error::Error>> {
         );
 
         CREATE INDEX IF NOT EXISTS idx_wallet_memory_hash ON wallet_memory(wallet_hash);
-        CREATE INDEX IF NOT EXISTS idx_wallet_memory_seen ON wallet_memory(seen_at);
-
-        CREATE TABLE IF NOT EXISTS metadata (
-            key TEXT PRIMARY KEY,
-            value TEXT
-        );
-
-        INSERT OR IGNORE INTO metadata (key, value) VALUES ('schema_version', '2');
-        INSERT OR IGNORE INTO metadata (key, value) VALUES ('created_at', strftime('%s', 'now'));"
-    )?;
-
-    // Migration: add execution-tracking columns to existing alerts tables
-    migrate_alerts_execution_columns(conn)?;
-
-    Ok(())
+        CREATE INDEX IF NOT EXISTS idx_wallet_memory_seen ON wallet_memory(seen_at);"
+    )
 }
 
-/// Add execution-tracking columns to alerts if missing (for DBs created before schema v2).
-fn migrate_alerts_execution_columns(conn: &Connection) -> Result<(), Box<dyn std::error::Error>> {
+/// Add execution-tracking columns to alerts (for DBs created before schema v2).
+fn migrate_v2_alerts_execution_columns(conn: &Connection) -> rusqlite::Result<()> {
     let columns = [
         "live_trade_id TEXT",
         "status TEXT DEFAULT 'OPEN'",
@@ -126,31 +144,690 @@ fn migrate_alerts_execution_columns(conn: &Connection) -> Result<(), Box<dyn std
     Ok(())
 }
 
-/// Insert an alert into the alerts table
-pub fn insert_alert(
-    conn: &Connection,
-    platform: &str,
-    alert_type: &str,
-    action: &str,
-    value: f64,
-    price: f64,
-    size: f64,
-    market_title: Option<&str>,
-    market_id: Option<&str>,
-    outcome: Option<&str>,
-    wallet_id: Option<&str>,
-    timestamp: &str,
-    market_context_json: Option<&str>,
-    wallet_activity_json: Option<&str>,
-) -> Option<i64> {
-    let w_hash = wallet_id.map(wallet_hash);
-
-    let result = conn.execute(
-        "INSERT INTO alerts (platform, alert_type, action, value, price, size,
-         market_title, market_id, outcome, wallet_hash, wallet_id, timestamp,
-         market_context, wallet_activity)
-         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14)",
-        params![
+fn migrate_v3_uncategorized_titles(conn: &Connection) -> rusqlite::Result<()> {
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS uncategorized_titles (
+            title TEXT PRIMARY KEY,
+            hit_count INTEGER NOT NULL DEFAULT 1,
+            last_seen INTEGER NOT NULL
+        );
+
+        CREATE INDEX IF NOT EXISTS idx_uncategorized_titles_hits ON uncategorized_titles(hit_count);"
+    )
+}
+
+/// Derived views so the dashboard can query wallet leaderboards and live
+/// exposure directly instead of reconstructing them from raw `alerts` rows.
+fn migrate_v4_analytics_views(conn: &Connection) -> rusqlite::Result<()> {
+    conn.execute_batch(
+        "CREATE VIEW IF NOT EXISTS v_wallet_performance AS
+         SELECT
+             wallet_hash,
+             MAX(wallet_id) AS wallet_id,
+             SUM(pnl_value) AS total_pnl,
+             COUNT(*) AS settled_trades,
+             SUM(CASE WHEN settled_outcome = outcome THEN 1 ELSE 0 END) AS win_count,
+             CAST(SUM(CASE WHEN settled_outcome = outcome THEN 1 ELSE 0 END) AS REAL) / COUNT(*) AS win_rate
+         FROM alerts
+         WHERE wallet_hash IS NOT NULL AND settled_outcome IS NOT NULL
+         GROUP BY wallet_hash;
+
+         CREATE VIEW IF NOT EXISTS v_open_positions AS
+         SELECT
+             id,
+             wallet_hash,
+             wallet_id,
+             platform,
+             market_title,
+             market_id,
+             outcome,
+             shadow_bet_amount,
+             price AS entry_price,
+             status,
+             created_at
+         FROM alerts
+         WHERE status = 'OPEN' OR status = 'EXECUTED';"
+    )
+}
+
+/// Add per-trade fee tracking (for DBs created before schema v5) and a
+/// net-of-fees view. Kalshi charges a per-contract taker fee, so `pnl_value`
+/// alone overstates realized PnL.
+fn migrate_v5_fee_tracking(conn: &Connection) -> rusqlite::Result<()> {
+    if let Err(e) = conn.execute("ALTER TABLE alerts ADD COLUMN fee_value REAL DEFAULT 0;", []) {
+        if !e.to_string().contains("duplicate column") {
+            eprintln!("Warning: migration add column fee_value: {}", e);
+        }
+    }
+
+    conn.execute_batch(
+        "CREATE VIEW IF NOT EXISTS v_transactions AS
+         SELECT
+             id,
+             platform,
+             alert_type,
+             action,
+             wallet_hash,
+             wallet_id,
+             market_title,
+             market_id,
+             outcome,
+             value,
+             price,
+             size,
+             pnl_value,
+             fee_value,
+             (COALESCE(pnl_value, 0) - COALESCE(fee_value, 0)) AS net_pnl,
+             status,
+             created_at
+         FROM alerts;"
+    )
+}
+
+/// Backing table for `crate::candles`' OHLCV rollups. `last_ts` tracks the
+/// timestamp of the latest trade folded into the bucket so a replayed/
+/// out-of-order trade only overwrites `close` when it's actually newer.
+fn migrate_v6_candles(conn: &Connection) -> rusqlite::Result<()> {
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS candles (
+            market TEXT NOT NULL,
+            resolution TEXT NOT NULL,
+            start_ts INTEGER NOT NULL,
+            open REAL NOT NULL,
+            high REAL NOT NULL,
+            low REAL NOT NULL,
+            close REAL NOT NULL,
+            volume REAL NOT NULL DEFAULT 0,
+            trade_count INTEGER NOT NULL DEFAULT 0,
+            last_ts INTEGER NOT NULL,
+            PRIMARY KEY (market, resolution, start_ts)
+        );
+
+        CREATE INDEX IF NOT EXISTS idx_candles_market_resolution ON candles(market, resolution, start_ts);"
+    )
+}
+
+/// Widens `candles`' key to `(platform, market, resolution, start_ts)` so the
+/// same ticker string on Kalshi and Polymarket can't collide, now that
+/// `crate::candles` rolls up both platforms' trades instead of just Kalshi's.
+/// Existing rows predate multi-platform ingestion, so they're backfilled as
+/// `'Kalshi'` (the only source `migrate_v6_candles` ever had).
+fn migrate_v7_candles_platform(conn: &Connection) -> rusqlite::Result<()> {
+    conn.execute_batch(
+        "ALTER TABLE candles RENAME TO candles_v6;
+
+        CREATE TABLE IF NOT EXISTS candles (
+            platform TEXT NOT NULL,
+            market TEXT NOT NULL,
+            resolution TEXT NOT NULL,
+            start_ts INTEGER NOT NULL,
+            open REAL NOT NULL,
+            high REAL NOT NULL,
+            low REAL NOT NULL,
+            close REAL NOT NULL,
+            volume REAL NOT NULL DEFAULT 0,
+            trade_count INTEGER NOT NULL DEFAULT 0,
+            last_ts INTEGER NOT NULL,
+            PRIMARY KEY (platform, market, resolution, start_ts)
+        );
+
+        INSERT INTO candles (platform, market, resolution, start_ts, open, high, low, close, volume, trade_count, last_ts)
+        SELECT 'Kalshi', market, resolution, start_ts, open, high, low, close, volume, trade_count, last_ts FROM candles_v6;
+
+        DROP TABLE candles_v6;
+
+        CREATE INDEX IF NOT EXISTS idx_candles_platform_market_resolution ON candles(platform, market, resolution, start_ts);"
+    )
+}
+
+/// Add the Gate 7 EV and quarter-Kelly figures the execution pipeline
+/// computes before placing an order, so `performance::backfill` can later
+/// compare them against the realized outcome instead of only ever seeing it
+/// after the fact (for DBs created before schema v8).
+fn migrate_v8_alert_ev_kelly(conn: &Connection) -> rusqlite::Result<()> {
+    for col_def in ["ev_cents REAL", "kelly_pct REAL"] {
+        let col_name = col_def.split_whitespace().next().unwrap_or("");
+        let sql = format!("ALTER TABLE alerts ADD COLUMN {};", col_def);
+        if let Err(e) = conn.execute(&sql, []) {
+            if !e.to_string().contains("duplicate column") {
+                eprintln!("Warning: migration add column {}: {}", col_name, e);
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Backing table for `crate::performance`'s realized-P&L rollups, one row
+/// per `(ticker, resolution, start_ts)` bucket. `ev_cents_sum`/`kelly_pct_sum`
+/// accumulate the Gate 7 EV and quarter-Kelly figures `migrate_v8_alert_ev_kelly`
+/// started persisting per alert, so a bucket's average can be recovered as
+/// `sum / trades` without re-joining `alerts`.
+fn migrate_v9_performance_buckets(conn: &Connection) -> rusqlite::Result<()> {
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS performance_buckets (
+            ticker TEXT NOT NULL,
+            resolution TEXT NOT NULL,
+            start_ts INTEGER NOT NULL,
+            trades INTEGER NOT NULL DEFAULT 0,
+            wins INTEGER NOT NULL DEFAULT 0,
+            losses INTEGER NOT NULL DEFAULT 0,
+            realized_pnl_cents INTEGER NOT NULL DEFAULT 0,
+            fees_cents INTEGER NOT NULL DEFAULT 0,
+            ev_cents_sum REAL NOT NULL DEFAULT 0,
+            kelly_pct_sum REAL NOT NULL DEFAULT 0,
+            PRIMARY KEY (ticker, resolution, start_ts)
+        );
+
+        CREATE INDEX IF NOT EXISTS idx_performance_buckets_resolution ON performance_buckets(resolution, start_ts);"
+    )
+}
+
+/// Add the platform's own trade id to `alerts` (for DBs created before
+/// schema v10), with a unique index over `(platform, trade_id)` so
+/// `SqliteStore::insert_alert`'s `INSERT OR IGNORE` can dedup a re-run of
+/// `commands::backfill` over an overlapping time window. Rows logged before
+/// this migration (or from the live watcher before it threaded trade ids
+/// through) have `trade_id = NULL`, which the index's uniqueness constraint
+/// never applies to.
+fn migrate_v10_alert_trade_id(conn: &Connection) -> rusqlite::Result<()> {
+    if let Err(e) = conn.execute("ALTER TABLE alerts ADD COLUMN trade_id TEXT;", []) {
+        if !e.to_string().contains("duplicate column") {
+            eprintln!("Warning: migration add column trade_id: {}", e);
+        }
+    }
+
+    conn.execute_batch(
+        "CREATE UNIQUE INDEX IF NOT EXISTS idx_alerts_trade_id_unique
+         ON alerts(platform, trade_id) WHERE trade_id IS NOT NULL;"
+    )
+}
+
+/// Backing table for `commands::backfill`'s raw-trade phase, separate from
+/// `alerts` (which only ever holds trades that cleared the threshold/
+/// category/odds filters) so `candles::rebuild_from_raw_trades` can recompute
+/// every bucket a window touches from the full, unfiltered trade set rather
+/// than just the subset that happened to alert. `trade_id` is deduped the
+/// same way `migrate_v10_alert_trade_id` dedups `alerts`, for the same
+/// reason: re-running backfill over an overlapping window shouldn't insert
+/// the same trade twice.
+fn migrate_v11_raw_trades(conn: &Connection) -> rusqlite::Result<()> {
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS backfill_trades (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            platform TEXT NOT NULL,
+            market TEXT NOT NULL,
+            trade_id TEXT,
+            side TEXT NOT NULL,
+            price REAL NOT NULL,
+            size REAL NOT NULL,
+            ts_unix INTEGER NOT NULL
+        );
+
+        CREATE UNIQUE INDEX IF NOT EXISTS idx_backfill_trades_trade_id_unique
+         ON backfill_trades(platform, trade_id) WHERE trade_id IS NOT NULL;
+
+        CREATE INDEX IF NOT EXISTS idx_backfill_trades_platform_market_ts
+         ON backfill_trades(platform, market, ts_unix);"
+    )
+}
+
+/// Bring the database up to the latest schema version, one migration at a
+/// time. Each migration runs in its own transaction so a failure partway
+/// through doesn't leave `schema_version` pointing past a half-applied change.
+pub(crate) fn run_migrations(conn: &Connection) -> Result<(), Box<dyn std::error::Error>> {
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS metadata (
+            key TEXT PRIMARY KEY,
+            value TEXT
+        );"
+    )?;
+
+    let current_version: u32 = conn
+        .query_row(
+            "SELECT value FROM metadata WHERE key = 'schema_version'",
+            [],
+            |row| row.get::<_, String>(0),
+        )
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(0);
+
+    for migration in MIGRATIONS {
+        if migration.version <= current_version {
+            continue;
+        }
+
+        let tx = conn.unchecked_transaction()?;
+        (migration.up)(&tx)?;
+        tx.execute(
+            "INSERT INTO metadata (key, value) VALUES ('schema_version', ?1)
+             ON CONFLICT(key) DO UPDATE SET value = excluded.value",
+            params![migration.version.to_string()],
+        )?;
+        tx.commit()?;
+    }
+
+    conn.execute(
+        "INSERT OR IGNORE INTO metadata (key, value) VALUES ('created_at', strftime('%s', 'now'))",
+        [],
+    )?;
+
+    Ok(())
+}
+
+/// A row from `wallet_memory`, as seen by a wallet-hash history lookup.
+#[derive(Debug, Clone)]
+pub struct WalletMemoryRow {
+    pub wallet_id: String,
+    pub market_title: Option<String>,
+    pub market_id: Option<String>,
+    pub outcome: Option<String>,
+    pub action: Option<String>,
+    pub value: f64,
+    pub price: f64,
+    pub platform: String,
+    pub seen_at: i64,
+}
+
+/// Persistence boundary for alerts, wallet memory, and the analytics views
+/// built on top of them. Separating this from `rusqlite::Connection` lets the
+/// watcher and its tests run against an `InMemoryStore` without touching disk,
+/// and leaves room for an alternate backend (e.g. an append-only store) later.
+pub trait AlertStore: Send + Sync {
+    /// Insert an alert into the alerts table, returning its row id. When
+    /// `trade_id` is `Some`, a row already recorded for the same
+    /// `(platform, trade_id)` is silently skipped (returns `None`) instead of
+    /// inserted again — `commands::backfill` relies on this so re-running
+    /// over an overlapping time window never double-counts a trade.
+    #[allow(clippy::too_many_arguments)]
+    fn insert_alert(
+        &self,
+        platform: &str,
+        alert_type: &str,
+        action: &str,
+        value: f64,
+        price: f64,
+        size: f64,
+        market_title: Option<&str>,
+        market_id: Option<&str>,
+        outcome: Option<&str>,
+        wallet_id: Option<&str>,
+        timestamp: &str,
+        market_context_json: Option<&str>,
+        wallet_activity_json: Option<&str>,
+        trade_id: Option<&str>,
+    ) -> Option<i64>;
+
+    /// Mark an alert as executed. `ev_cents`/`kelly_pct` are the Gate 7/
+    /// quarter-Kelly figures the pipeline computed before placing the order,
+    /// persisted so `performance::backfill` can later compare them against
+    /// the realized outcome instead of only ever seeing it after the fact.
+    #[allow(clippy::too_many_arguments)]
+    fn mark_alert_executed(
+        &self,
+        alert_id: i64,
+        order_id: &str,
+        ticker: &str,
+        side: &str,
+        bet_amount: f64,
+        price: f64,
+        fee: f64,
+        ev_cents: f64,
+        kelly_pct: f64,
+    );
+
+    /// Query recent alerts for display.
+    fn query_alerts(
+        &self,
+        limit: usize,
+        platform_filter: &str,
+    ) -> Result<Vec<serde_json::Value>, Box<dyn std::error::Error>>;
+
+    /// Like `query_alerts`, but with the extra predicates `commands::api`'s
+    /// `/alerts` endpoint needs and that callers browsing history in a
+    /// terminal don't: a minimum `value`, and a `created_at` window in unix
+    /// seconds. Each row also carries its `created_at` so the handler can
+    /// report it back. `None` bounds are unconstrained.
+    #[allow(clippy::too_many_arguments)]
+    fn query_alerts_filtered(
+        &self,
+        limit: usize,
+        platform_filter: &str,
+        min_value: Option<f64>,
+        since_unix: Option<i64>,
+        until_unix: Option<i64>,
+    ) -> Result<Vec<serde_json::Value>, Box<dyn std::error::Error>>;
+
+    /// Latest cached `market_context` and trailing-24h whale volume per
+    /// `(platform, market_title)`, for `commands::api`'s `/tickers` endpoint.
+    /// `InMemoryStore` sums every alert it holds rather than just the last
+    /// 24h, the same "wall clock doesn't apply to the in-memory store"
+    /// tradeoff `prune_old_alerts` already makes there.
+    fn query_ticker_summary(
+        &self,
+        platform_filter: &str,
+    ) -> Result<Vec<serde_json::Value>, Box<dyn std::error::Error>>;
+
+    /// Most recent `limit` candles for `market` on `platform` at
+    /// `resolution` (one of `candles::Resolution::as_str`'s forms), newest
+    /// first. `InMemoryStore` always returns an empty series — it doesn't
+    /// back a `candles` table to read from.
+    fn query_candles(
+        &self,
+        platform: &str,
+        market: &str,
+        resolution: &str,
+        limit: u32,
+    ) -> Result<Vec<serde_json::Value>, Box<dyn std::error::Error>>;
+
+    /// Candles for `market` on `platform` at `resolution` whose bucket
+    /// starts in `[from_unix, to_unix]`, oldest first — backs a CLI query
+    /// command that wants a specific window rather than just the last N
+    /// bars. `InMemoryStore` always returns an empty series, same as
+    /// `query_candles`.
+    fn query_candles_range(
+        &self,
+        platform: &str,
+        market: &str,
+        resolution: &str,
+        from_unix: i64,
+        to_unix: i64,
+    ) -> Result<Vec<serde_json::Value>, Box<dyn std::error::Error>>;
+
+    /// Narrow which resolutions the live `CandleCache` maintains to
+    /// `Config::candle_intervals` going forward. No-op on `InMemoryStore`,
+    /// which doesn't maintain candles at all.
+    fn configure_candle_resolutions(&self, intervals: &[String]);
+
+    /// Per-wallet leaderboard: realized PnL, settled trade count, win rate.
+    fn query_wallet_performance(
+        &self,
+        limit: usize,
+    ) -> Result<Vec<serde_json::Value>, Box<dyn std::error::Error>>;
+
+    /// Currently live exposure (alerts still `OPEN` or `EXECUTED`).
+    fn query_open_positions(&self) -> Result<Vec<serde_json::Value>, Box<dyn std::error::Error>>;
+
+    /// Prune old alerts based on retention days. 0 = keep forever.
+    fn prune_old_alerts(&self, retention_days: u32);
+
+    /// Record or update a wallet's sighting in a given market. Returns
+    /// `false` if the write failed, so callers can surface it (e.g. as a
+    /// metric) instead of it only reaching stderr.
+    #[allow(clippy::too_many_arguments)]
+    fn record_wallet_memory(
+        &self,
+        wallet_id: &str,
+        market_title: Option<&str>,
+        market_id: Option<&str>,
+        outcome: Option<&str>,
+        action: &str,
+        value: f64,
+        price: f64,
+        platform: &str,
+    ) -> bool;
+
+    /// Prune expired wallet memory (12h window).
+    fn prune_wallet_memory(&self);
+
+    /// A wallet's activity in the last 12h, most recent first.
+    fn wallet_history(&self, wallet_hash: &str) -> Vec<WalletMemoryRow>;
+
+    /// Distinct wallet hashes seen in the last 12h.
+    fn known_wallet_hashes(&self) -> Vec<String>;
+
+    /// Rolling 1h/24h transaction count and value for `wallet_hash`,
+    /// reconstructed directly from `wallet_memory`. What `types::WalletTracker`
+    /// falls back to on a wallet's first sighting since process start, so a
+    /// known heavy/repeat actor doesn't reset to "normal" just because this
+    /// process is new.
+    fn wallet_activity(&self, wallet_hash: &str) -> crate::types::WalletActivity;
+
+    /// Win rate and settled-trade count for `wallet_hash` from
+    /// `v_wallet_performance` — this wallet's own accumulated alert history.
+    /// Backs `WhaleProfile::win_rate`/`markets_traded` when a live API call
+    /// can't (or doesn't) have the same data. `None` if the wallet has no
+    /// settled trades on record yet.
+    fn wallet_performance_for(&self, wallet_hash: &str) -> Option<(f64, i64)>;
+
+    /// Total alert count in the store.
+    fn alert_count(&self) -> i64;
+
+    /// Record a market title that couldn't be categorized, bumping its hit
+    /// counter if already seen.
+    fn record_uncategorized_title(&self, title: &str);
+
+    /// Most frequently seen uncategorized titles, as (title, hit_count, last_seen).
+    fn top_uncategorized_titles(&self, limit: usize) -> Vec<(String, i64, i64)>;
+
+    /// Fold one trade into every resolution's in-memory open candle (see
+    /// `candles::CandleCache`), flushing a bucket to the `candles` table once
+    /// a later trade completes it. No-op on `InMemoryStore`, which doesn't
+    /// back a SQLite connection to flush into.
+    fn record_candle_trade(&self, platform: &str, market: &str, timestamp: i64, price: f64, size: f64);
+
+    /// Flush every still-open candle to the `candles` table. Meant to run on
+    /// the existing periodic prune cycle so a quiet market's open candle
+    /// isn't lost if the process restarts before its bucket completes.
+    fn flush_stale_candles(&self);
+
+    /// Read a value previously written by `set_metadata`, e.g. a backfill
+    /// resume checkpoint. Backed by the same `metadata` table `run_migrations`
+    /// uses for `schema_version`/`created_at`.
+    fn get_metadata(&self, key: &str) -> Option<String>;
+
+    /// Persist an arbitrary key/value pair in `metadata`, overwriting any
+    /// prior value for `key`.
+    fn set_metadata(&self, key: &str, value: &str);
+
+    /// Persist one raw trade into `backfill_trades` ahead of candle
+    /// generation, so `rebuild_candles` has something to recompute from
+    /// without re-hitting either platform's API. `trade_id` is deduped the
+    /// same way `insert_alert` dedups on `(platform, trade_id)` — a re-run
+    /// of `commands::backfill` over an overlapping window inserts nothing
+    /// new for trades it already has. Returns `false` on a write error, not
+    /// on a dedup skip. No-op on `InMemoryStore`, which doesn't back a raw
+    /// trade table to recompute from.
+    #[allow(clippy::too_many_arguments)]
+    fn record_raw_trade(
+        &self,
+        platform: &str,
+        market: &str,
+        trade_id: Option<&str>,
+        side: &str,
+        price: f64,
+        size: f64,
+        timestamp: i64,
+    ) -> bool;
+
+    /// Recompute every `candles` row touched by `backfill_trades` rows for
+    /// `platform` with `ts_unix` in `[from_unix, to_unix]`, across every
+    /// resolution in `candles::ALL_RESOLUTIONS`. Unlike `record_candle_trade`
+    /// (which accumulates into whatever's already in the bucket), this
+    /// replaces each bucket's open/high/low/close/volume/trade_count outright
+    /// from the full set of raw trades it's built from — re-running it over
+    /// the same window always lands on the same values, so a backfill
+    /// interrupted mid-rebuild can simply restart from `from_unix` instead of
+    /// needing its own checkpoint. No-op on `InMemoryStore`.
+    fn rebuild_candles(&self, platform: &str, from_unix: i64, to_unix: i64);
+}
+
+/// Connections in `SqliteStore`'s read-only pool (see `SqliteReaderPool`).
+/// Matches `store::PostgresWalletMemoryStore`'s pool-size convention — a
+/// handful of connections is plenty for this query volume.
+const SQLITE_READER_POOL_SIZE: usize = 4;
+
+/// Small round-robin pool of extra connections onto the same on-disk
+/// database, used only for `wallet_activity`/`wallet_performance_for` — the
+/// aggregation queries `types::WalletTracker`'s hot path and whale-profile
+/// backing now run against SQLite directly. Kept separate from `SqliteStore`'s
+/// single writer `conn` so a wallet-activity lookup never waits on (or blocks)
+/// an in-flight `insert_alert`/`record_wallet_memory` — WAL mode (set in
+/// `open_db`) already allows any number of concurrent readers alongside the
+/// one writer; this just gives them their own connections instead of sharing
+/// (and serializing on) the writer's `Mutex`.
+struct SqliteReaderPool {
+    readers: Vec<Mutex<Connection>>,
+    next: AtomicUsize,
+}
+
+impl SqliteReaderPool {
+    fn open(path: &Path, size: usize) -> rusqlite::Result<Self> {
+        let mut readers = Vec::with_capacity(size.max(1));
+        for _ in 0..size.max(1) {
+            let conn = Connection::open(path)?;
+            conn.execute_batch("PRAGMA busy_timeout=5000;")?;
+            readers.push(Mutex::new(conn));
+        }
+        Ok(Self { readers, next: AtomicUsize::new(0) })
+    }
+
+    fn with_conn<T>(&self, f: impl FnOnce(&Connection) -> T) -> T {
+        let idx = self.next.fetch_add(1, Ordering::Relaxed) % self.readers.len();
+        let conn = self.readers[idx].lock().unwrap();
+        f(&conn)
+    }
+}
+
+/// SQLite-backed `AlertStore`. Wraps the connection in a `Mutex` so a single
+/// store can be shared across the watcher's `spawn_blocking` tasks.
+pub struct SqliteStore {
+    conn: Mutex<Connection>,
+    candle_cache: CandleCache,
+    /// `None` when built from an already-open connection (`from_connection`,
+    /// e.g. an in-memory test DB) that doesn't have a file path to reopen.
+    readers: Option<SqliteReaderPool>,
+}
+
+impl SqliteStore {
+    /// Open the on-disk database at `db_path()`, creating and migrating it as needed.
+    pub fn open() -> Result<Self, Box<dyn std::error::Error>> {
+        let path = db_path()?;
+        let readers = SqliteReaderPool::open(&path, SQLITE_READER_POOL_SIZE).ok();
+        Ok(Self { conn: Mutex::new(open_db()?), candle_cache: CandleCache::new(), readers })
+    }
+
+    /// Wrap an already-open connection, running migrations against it first.
+    /// Useful for an in-memory `Connection::open_in_memory()` in tests.
+    pub fn from_connection(conn: Connection) -> Result<Self, Box<dyn std::error::Error>> {
+        run_migrations(&conn)?;
+        Ok(Self { conn: Mutex::new(conn), candle_cache: CandleCache::new(), readers: None })
+    }
+
+    /// Run `f` against a reader-pool connection if one is available, falling
+    /// back to the writer connection (e.g. for an in-memory test DB with no
+    /// reader pool) otherwise.
+    fn with_reader<T>(&self, f: impl FnOnce(&Connection) -> T) -> T {
+        match &self.readers {
+            Some(pool) => pool.with_conn(f),
+            None => f(&self.conn.lock().unwrap()),
+        }
+    }
+
+    /// Migrate existing JSONL history to SQLite.
+    #[allow(dead_code)]
+    pub fn migrate_jsonl_if_exists(&self) {
+        let config_dir = match dirs::config_dir() {
+            Some(d) => d,
+            None => return,
+        };
+
+        let jsonl_path = config_dir.join("wwatcher").join("alert_history.jsonl");
+        if !jsonl_path.exists() {
+            return;
+        }
+
+        let contents = match std::fs::read_to_string(&jsonl_path) {
+            Ok(c) => c,
+            Err(_) => return,
+        };
+
+        let mut count = 0u32;
+        for line in contents.lines() {
+            if let Ok(alert) = serde_json::from_str::<serde_json::Value>(line) {
+                let platform = alert.get("platform").and_then(|v| v.as_str()).unwrap_or("Unknown");
+                let alert_type = alert.get("alert_type").and_then(|v| v.as_str()).unwrap_or("UNKNOWN");
+                let action = alert.get("action").and_then(|v| v.as_str()).unwrap_or("UNKNOWN");
+                let value = alert.get("value").and_then(|v| v.as_f64()).unwrap_or(0.0);
+                let price = alert.get("price").and_then(|v| v.as_f64()).unwrap_or(0.0);
+                let size = alert.get("size").and_then(|v| v.as_f64()).unwrap_or(0.0);
+                let market_title = alert.get("market_title").and_then(|v| v.as_str());
+                let outcome = alert.get("outcome").and_then(|v| v.as_str());
+                let wallet_id = alert.get("wallet_id").and_then(|v| v.as_str());
+                let timestamp = alert.get("timestamp").and_then(|v| v.as_str()).unwrap_or("");
+
+                let wa_json = alert.get("wallet_activity").map(|v| v.to_string());
+
+                self.insert_alert(
+                    platform,
+                    alert_type,
+                    action,
+                    value,
+                    price,
+                    size,
+                    market_title,
+                    None,
+                    outcome,
+                    wallet_id,
+                    timestamp,
+                    None,
+                    wa_json.as_deref(),
+                    None,
+                );
+                count += 1;
+            }
+        }
+
+        if count > 0 {
+            let bak_path = config_dir.join("wwatcher").join("alert_history.jsonl.bak");
+            if std::fs::rename(&jsonl_path, &bak_path).is_ok() {
+                eprintln!("Migrated {} alerts from JSONL to SQLite database.", count);
+                eprintln!("Old file backed up to: alert_history.jsonl.bak");
+            }
+        }
+    }
+}
+
+impl AlertStore for SqliteStore {
+    fn insert_alert(
+        &self,
+        platform: &str,
+        alert_type: &str,
+        action: &str,
+        value: f64,
+        price: f64,
+        size: f64,
+        market_title: Option<&str>,
+        market_id: Option<&str>,
+        outcome: Option<&str>,
+        wallet_id: Option<&str>,
+        timestamp: &str,
+        market_context_json: Option<&str>,
+        wallet_activity_json: Option<&str>,
+        trade_id: Option<&str>,
+    ) -> Option<i64> {
+        let w_hash = wallet_id.map(wallet_hash);
+        let conn = self.conn.lock().unwrap();
+
+        // `OR IGNORE` plus `idx_alerts_trade_id_unique` (see
+        // `migrate_v10_alert_trade_id`) makes a re-run over an overlapping
+        // backfill window a no-op instead of a duplicate row: `execute`
+        // returns 0 rows changed when the `(platform, trade_id)` pair
+        // already exists.
+        let mut stmt = match conn.prepare_cached(
+            "INSERT OR IGNORE INTO alerts (platform, alert_type, action, value, price, size,
+             market_title, market_id, outcome, wallet_hash, wallet_id, timestamp,
+             market_context, wallet_activity, trade_id)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15)",
+        ) {
+            Ok(stmt) => stmt,
+            Err(e) => {
+                eprintln!("Warning: Failed to log alert to database: {}", e);
+                return None;
+            }
+        };
+
+        let result = stmt.execute(params![
             platform,
             alert_type,
             action,
@@ -165,38 +842,53 @@ pub fn insert_alert(
             timestamp,
             market_context_json,
             wallet_activity_json,
-        ],
-    );
+            trade_id,
+        ]);
 
-    match result {
-        Ok(_) => Some(conn.last_insert_rowid()),
-        Err(e) => {
-            eprintln!("Warning: Failed to log alert to database: {}", e);
-            None
+        match result {
+            Ok(0) => None,
+            Ok(_) => Some(conn.last_insert_rowid()),
+            Err(e) => {
+                eprintln!("Warning: Failed to log alert to database: {}", e);
+                None
+            }
         }
     }
-}
 
-/// Mark an alert as executed in the database
-pub fn mark_alert_executed(
-    conn: &Connection,
-    alert_id: i64,
-    order_id: &str,
-    ticker: &str,
-    side: &str,
-    bet_amount: f64,
-    price: f64,
-) {
-    let result = conn.execute(
-        "UPDATE alerts 
-         SET live_trade_id = ?1, 
-             shadow_bet_amount = ?2,
-             status = 'EXECUTED',
-             market_id = ?3,
-             outcome = ?4,
-             price = ?5
-         WHERE id = ?6",
-        params![
+    fn mark_alert_executed(
+        &self,
+        alert_id: i64,
+        order_id: &str,
+        ticker: &str,
+        side: &str,
+        bet_amount: f64,
+        price: f64,
+        fee: f64,
+        ev_cents: f64,
+        kelly_pct: f64,
+    ) {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = match conn.prepare_cached(
+            "UPDATE alerts
+             SET live_trade_id = ?1,
+                 shadow_bet_amount = ?2,
+                 status = 'EXECUTED',
+                 market_id = ?3,
+                 outcome = ?4,
+                 price = ?5,
+                 fee_value = ?6,
+                 ev_cents = ?7,
+                 kelly_pct = ?8
+             WHERE id = ?9",
+        ) {
+            Ok(stmt) => stmt,
+            Err(e) => {
+                eprintln!("Warning: Failed to mark alert as executed: {}", e);
+                return;
+            }
+        };
+
+        let result = stmt.execute(params![
             order_id,
             bet_amount,
             ticker,  // Overwrite market_id with Kalshi ticker for clarity? Or keep separate?
@@ -204,224 +896,1327 @@ pub fn mark_alert_executed(
                      // Let's use ticker for clarity if it was null.
             side,
             price,
+            fee,
+            ev_cents,
+            kelly_pct,
             alert_id
-        ],
-    );
+        ]);
 
-    if let Err(e) = result {
-        eprintln!("Warning: Failed to mark alert as executed: {}", e);
-    } else {
-        println!("✅ Database updated: Alert #{} marked as EXECUTED", alert_id);
+        if let Err(e) = result {
+            eprintln!("Warning: Failed to mark alert as executed: {}", e);
+        } else {
+            println!("✅ Database updated: Alert #{} marked as EXECUTED", alert_id);
+        }
     }
-}
 
-/// Query recent alerts for display
-pub fn query_alerts(
-    conn: &Connection,
-    limit: usize,
-    platform_filter: &str,
-) -> Result<Vec<serde_json::Value>, Box<dyn std::error::Error>> {
-    let mut alerts = Vec::new();
-
-    let (sql, filter_params): (String, Vec<Box<dyn rusqlite::types::ToSql>>) = if platform_filter == "all" {
-        (
-            "SELECT platform, alert_type, action, value, price, size,
-                    market_title, outcome, wallet_id, timestamp,
-                    wallet_activity, market_context
-             FROM alerts ORDER BY created_at DESC LIMIT ?1".to_string(),
-            vec![Box::new(limit as i64)],
-        )
-    } else {
-        (
-            "SELECT platform, alert_type, action, value, price, size,
-                    market_title, outcome, wallet_id, timestamp,
-                    wallet_activity, market_context
-             FROM alerts WHERE LOWER(platform) = LOWER(?1)
-             ORDER BY created_at DESC LIMIT ?2".to_string(),
-            vec![
-                Box::new(platform_filter.to_string()),
-                Box::new(limit as i64),
-            ],
-        )
-    };
-
-    let params_refs: Vec<&dyn rusqlite::types::ToSql> = filter_params.iter().map(|p| p.as_ref()).collect();
-    let mut stmt = conn.prepare(&sql)?;
-    let rows = stmt.query_map(params_refs.as_slice(), |row| {
-        let platform: String = row.get(0)?;
-        let alert_type: String = row.get(1)?;
-        let action: String = row.get(2)?;
-        let value: f64 = row.get(3)?;
-        let price: f64 = row.get(4)?;
-        let size: f64 = row.get(5)?;
-        let market_title: Option<String> = row.get(6)?;
-        let outcome: Option<String> = row.get(7)?;
-        let wallet_id: Option<String> = row.get(8)?;
-        let timestamp: String = row.get(9)?;
-        let wallet_activity_json: Option<String> = row.get(10)?;
-        let market_context_json: Option<String> = row.get(11)?;
-
-        let mut alert = serde_json::json!({
-            "platform": platform,
-            "alert_type": alert_type,
-            "action": action,
-            "value": value,
-            "price": price,
-            "size": size,
-            "timestamp": timestamp,
-            "market_title": market_title,
-            "outcome": outcome,
-        });
+    fn query_alerts(
+        &self,
+        limit: usize,
+        platform_filter: &str,
+    ) -> Result<Vec<serde_json::Value>, Box<dyn std::error::Error>> {
+        let mut alerts = Vec::new();
+        let conn = self.conn.lock().unwrap();
+
+        let (sql, filter_params): (String, Vec<Box<dyn rusqlite::types::ToSql>>) = if platform_filter == "all" {
+            (
+                "SELECT platform, alert_type, action, value, price, size,
+                        market_title, outcome, wallet_id, timestamp,
+                        wallet_activity, market_context, pnl_value, fee_value
+                 FROM alerts ORDER BY created_at DESC LIMIT ?1".to_string(),
+                vec![Box::new(limit as i64)],
+            )
+        } else {
+            (
+                "SELECT platform, alert_type, action, value, price, size,
+                        market_title, outcome, wallet_id, timestamp,
+                        wallet_activity, market_context, pnl_value, fee_value
+                 FROM alerts WHERE LOWER(platform) = LOWER(?1)
+                 ORDER BY created_at DESC LIMIT ?2".to_string(),
+                vec![
+                    Box::new(platform_filter.to_string()),
+                    Box::new(limit as i64),
+                ],
+            )
+        };
+
+        let params_refs: Vec<&dyn rusqlite::types::ToSql> = filter_params.iter().map(|p| p.as_ref()).collect();
+        let mut stmt = conn.prepare(&sql)?;
+        let rows = stmt.query_map(params_refs.as_slice(), |row| {
+            let platform: String = row.get(0)?;
+            let alert_type: String = row.get(1)?;
+            let action: String = row.get(2)?;
+            let value: f64 = row.get(3)?;
+            let price: f64 = row.get(4)?;
+            let size: f64 = row.get(5)?;
+            let market_title: Option<String> = row.get(6)?;
+            let outcome: Option<String> = row.get(7)?;
+            let wallet_id: Option<String> = row.get(8)?;
+            let timestamp: String = row.get(9)?;
+            let wallet_activity_json: Option<String> = row.get(10)?;
+            let market_context_json: Option<String> = row.get(11)?;
+            let pnl_value: Option<f64> = row.get(12)?;
+            let fee_value: Option<f64> = row.get(13)?;
+
+            let mut alert = serde_json::json!({
+                "platform": platform,
+                "alert_type": alert_type,
+                "action": action,
+                "value": value,
+                "price": price,
+                "size": size,
+                "timestamp": timestamp,
+                "market_title": market_title,
+                "outcome": outcome,
+                "pnl_value": pnl_value,
+                "fee_value": fee_value,
+                "net_pnl": pnl_value.map(|p| p - fee_value.unwrap_or(0.0)),
+            });
+
+            if let Some(wid) = wallet_id {
+                alert["wallet_id"] = serde_json::json!(wid);
+            }
+
+            if let Some(wa_json) = wallet_activity_json {
+                if let Ok(wa) = serde_json::from_str::<serde_json::Value>(&wa_json) {
+                    alert["wallet_activity"] = wa;
+                }
+            }
+
+            if let Some(mc_json) = market_context_json {
+                if let Ok(mc) = serde_json::from_str::<serde_json::Value>(&mc_json) {
+                    alert["market_context"] = mc;
+                }
+            }
+
+            Ok(alert)
+        })?;
+
+        for row in rows {
+            if let Ok(alert) = row {
+                alerts.push(alert);
+            }
+        }
+
+        Ok(alerts)
+    }
+
+    fn query_alerts_filtered(
+        &self,
+        limit: usize,
+        platform_filter: &str,
+        min_value: Option<f64>,
+        since_unix: Option<i64>,
+        until_unix: Option<i64>,
+    ) -> Result<Vec<serde_json::Value>, Box<dyn std::error::Error>> {
+        let mut alerts = Vec::new();
+        let conn = self.conn.lock().unwrap();
+
+        let mut sql = "SELECT platform, alert_type, action, value, price, size,
+                               market_title, outcome, wallet_id, timestamp,
+                               wallet_activity, market_context, created_at
+                        FROM alerts WHERE 1=1"
+            .to_string();
+        let mut filter_params: Vec<Box<dyn rusqlite::types::ToSql>> = Vec::new();
+
+        if platform_filter != "all" {
+            sql.push_str(" AND LOWER(platform) = LOWER(?)");
+            filter_params.push(Box::new(platform_filter.to_string()));
+        }
+        if let Some(min_value) = min_value {
+            sql.push_str(" AND value >= ?");
+            filter_params.push(Box::new(min_value));
+        }
+        if let Some(since_unix) = since_unix {
+            sql.push_str(" AND created_at >= ?");
+            filter_params.push(Box::new(since_unix));
+        }
+        if let Some(until_unix) = until_unix {
+            sql.push_str(" AND created_at <= ?");
+            filter_params.push(Box::new(until_unix));
+        }
+        sql.push_str(" ORDER BY created_at DESC LIMIT ?");
+        filter_params.push(Box::new(limit as i64));
+
+        let params_refs: Vec<&dyn rusqlite::types::ToSql> = filter_params.iter().map(|p| p.as_ref()).collect();
+        let mut stmt = conn.prepare(&sql)?;
+        let rows = stmt.query_map(params_refs.as_slice(), |row| {
+            let platform: String = row.get(0)?;
+            let alert_type: String = row.get(1)?;
+            let action: String = row.get(2)?;
+            let value: f64 = row.get(3)?;
+            let price: f64 = row.get(4)?;
+            let size: f64 = row.get(5)?;
+            let market_title: Option<String> = row.get(6)?;
+            let outcome: Option<String> = row.get(7)?;
+            let wallet_id: Option<String> = row.get(8)?;
+            let timestamp: String = row.get(9)?;
+            let wallet_activity_json: Option<String> = row.get(10)?;
+            let market_context_json: Option<String> = row.get(11)?;
+            let created_at: i64 = row.get(12)?;
 
-        if let Some(wid) = wallet_id {
-            alert["wallet_id"] = serde_json::json!(wid);
+            let mut alert = serde_json::json!({
+                "platform": platform,
+                "alert_type": alert_type,
+                "action": action,
+                "value": value,
+                "price": price,
+                "size": size,
+                "timestamp": timestamp,
+                "created_at": created_at,
+                "market_title": market_title,
+                "outcome": outcome,
+            });
+
+            if let Some(wid) = wallet_id {
+                alert["wallet_id"] = serde_json::json!(wid);
+            }
+            if let Some(wa_json) = wallet_activity_json {
+                if let Ok(wa) = serde_json::from_str::<serde_json::Value>(&wa_json) {
+                    alert["wallet_activity"] = wa;
+                }
+            }
+            if let Some(mc_json) = market_context_json {
+                if let Ok(mc) = serde_json::from_str::<serde_json::Value>(&mc_json) {
+                    alert["market_context"] = mc;
+                }
+            }
+
+            Ok(alert)
+        })?;
+
+        for row in rows {
+            if let Ok(alert) = row {
+                alerts.push(alert);
+            }
         }
 
-        if let Some(wa_json) = wallet_activity_json {
-            if let Ok(wa) = serde_json::from_str::<serde_json::Value>(&wa_json) {
-                alert["wallet_activity"] = wa;
+        Ok(alerts)
+    }
+
+    fn query_ticker_summary(
+        &self,
+        platform_filter: &str,
+    ) -> Result<Vec<serde_json::Value>, Box<dyn std::error::Error>> {
+        let conn = self.conn.lock().unwrap();
+
+        let (sql, filter_params): (&str, Vec<Box<dyn rusqlite::types::ToSql>>) = if platform_filter == "all" {
+            (
+                "SELECT platform, market_title,
+                        (SELECT market_context FROM alerts a2
+                         WHERE a2.platform = a1.platform AND a2.market_title = a1.market_title
+                           AND a2.market_context IS NOT NULL
+                         ORDER BY a2.created_at DESC LIMIT 1) AS latest_context,
+                        SUM(CASE WHEN created_at >= strftime('%s', 'now') - 86400 THEN value ELSE 0 END) AS volume_24h,
+                        MAX(created_at) AS last_seen
+                 FROM alerts a1
+                 WHERE market_title IS NOT NULL
+                 GROUP BY platform, market_title
+                 ORDER BY last_seen DESC",
+                vec![],
+            )
+        } else {
+            (
+                "SELECT platform, market_title,
+                        (SELECT market_context FROM alerts a2
+                         WHERE a2.platform = a1.platform AND a2.market_title = a1.market_title
+                           AND a2.market_context IS NOT NULL
+                         ORDER BY a2.created_at DESC LIMIT 1) AS latest_context,
+                        SUM(CASE WHEN created_at >= strftime('%s', 'now') - 86400 THEN value ELSE 0 END) AS volume_24h,
+                        MAX(created_at) AS last_seen
+                 FROM alerts a1
+                 WHERE market_title IS NOT NULL AND LOWER(platform) = LOWER(?1)
+                 GROUP BY platform, market_title
+                 ORDER BY last_seen DESC",
+                vec![Box::new(platform_filter.to_string())],
+            )
+        };
+
+        let params_refs: Vec<&dyn rusqlite::types::ToSql> = filter_params.iter().map(|p| p.as_ref()).collect();
+        let mut stmt = conn.prepare(sql)?;
+        let rows = stmt.query_map(params_refs.as_slice(), |row| {
+            let platform: String = row.get(0)?;
+            let market_title: String = row.get(1)?;
+            let latest_context_json: Option<String> = row.get(2)?;
+            let volume_24h: f64 = row.get(3)?;
+            let last_seen: i64 = row.get(4)?;
+
+            let mut ticker = serde_json::json!({
+                "platform": platform,
+                "market_title": market_title,
+                "volume_24h": volume_24h,
+                "last_seen": last_seen,
+            });
+            if let Some(ctx_json) = latest_context_json {
+                if let Ok(ctx) = serde_json::from_str::<serde_json::Value>(&ctx_json) {
+                    ticker["market_context"] = ctx;
+                }
+            }
+            Ok(ticker)
+        })?;
+
+        let mut tickers = Vec::new();
+        for row in rows {
+            if let Ok(ticker) = row {
+                tickers.push(ticker);
             }
         }
+        Ok(tickers)
+    }
+
+    fn query_candles(
+        &self,
+        platform: &str,
+        market: &str,
+        resolution: &str,
+        limit: u32,
+    ) -> Result<Vec<serde_json::Value>, Box<dyn std::error::Error>> {
+        let resolution = crate::candles::Resolution::from_str(resolution)
+            .ok_or_else(|| format!("unknown candle resolution: {}", resolution))?;
+        let conn = self.conn.lock().unwrap();
+        let candles = crate::candles::get_candles(&conn, platform, market, resolution, limit)?;
+        Ok(candles
+            .into_iter()
+            .map(|c| {
+                serde_json::json!({
+                    "platform": c.platform,
+                    "market": c.market,
+                    "resolution": c.resolution.as_str(),
+                    "start_ts": c.start_ts,
+                    "open": c.open,
+                    "high": c.high,
+                    "low": c.low,
+                    "close": c.close,
+                    "volume": c.volume,
+                    "trade_count": c.trade_count,
+                    "last_ts": c.last_ts,
+                })
+            })
+            .collect())
+    }
+
+    fn query_candles_range(
+        &self,
+        platform: &str,
+        market: &str,
+        resolution: &str,
+        from_unix: i64,
+        to_unix: i64,
+    ) -> Result<Vec<serde_json::Value>, Box<dyn std::error::Error>> {
+        let resolution = crate::candles::Resolution::from_str(resolution)
+            .ok_or_else(|| format!("unknown candle resolution: {}", resolution))?;
+        let conn = self.conn.lock().unwrap();
+        let candles = crate::candles::build_candles(&conn, platform, market, resolution, from_unix, to_unix)?;
+        Ok(candles
+            .into_iter()
+            .map(|c| {
+                serde_json::json!({
+                    "platform": c.platform,
+                    "market": c.market,
+                    "resolution": c.resolution.as_str(),
+                    "start_ts": c.start_ts,
+                    "open": c.open,
+                    "high": c.high,
+                    "low": c.low,
+                    "close": c.close,
+                    "volume": c.volume,
+                    "trade_count": c.trade_count,
+                    "last_ts": c.last_ts,
+                })
+            })
+            .collect())
+    }
+
+    fn configure_candle_resolutions(&self, intervals: &[String]) {
+        let resolutions: Vec<crate::candles::Resolution> = intervals
+            .iter()
+            .filter_map(|s| crate::candles::Resolution::from_str(s))
+            .collect();
+        self.candle_cache.set_active_resolutions(resolutions);
+    }
+
+    fn query_wallet_performance(
+        &self,
+        limit: usize,
+    ) -> Result<Vec<serde_json::Value>, Box<dyn std::error::Error>> {
+        let mut rows_out = Vec::new();
+        let conn = self.conn.lock().unwrap();
+
+        let mut stmt = conn.prepare(
+            "SELECT wallet_hash, wallet_id, total_pnl, settled_trades, win_count, win_rate
+             FROM v_wallet_performance
+             ORDER BY total_pnl DESC LIMIT ?1",
+        )?;
+
+        let rows = stmt.query_map(params![limit as i64], |row| {
+            let wallet_hash: String = row.get(0)?;
+            let wallet_id: Option<String> = row.get(1)?;
+            let total_pnl: f64 = row.get(2)?;
+            let settled_trades: i64 = row.get(3)?;
+            let win_count: i64 = row.get(4)?;
+            let win_rate: f64 = row.get(5)?;
+
+            Ok(serde_json::json!({
+                "wallet_hash": wallet_hash,
+                "wallet_id": wallet_id,
+                "total_pnl": total_pnl,
+                "settled_trades": settled_trades,
+                "win_count": win_count,
+                "win_rate": win_rate,
+            }))
+        })?;
 
-        if let Some(mc_json) = market_context_json {
-            if let Ok(mc) = serde_json::from_str::<serde_json::Value>(&mc_json) {
-                alert["market_context"] = mc;
+        for row in rows {
+            if let Ok(perf) = row {
+                rows_out.push(perf);
             }
         }
 
-        Ok(alert)
-    })?;
+        Ok(rows_out)
+    }
+
+    fn query_open_positions(&self) -> Result<Vec<serde_json::Value>, Box<dyn std::error::Error>> {
+        let mut positions = Vec::new();
+        let conn = self.conn.lock().unwrap();
+
+        let mut stmt = conn.prepare(
+            "SELECT id, wallet_hash, wallet_id, platform, market_title, market_id,
+                    outcome, shadow_bet_amount, entry_price, status, created_at
+             FROM v_open_positions
+             ORDER BY created_at DESC",
+        )?;
 
-    for row in rows {
-        if let Ok(alert) = row {
-            alerts.push(alert);
+        let rows = stmt.query_map([], |row| {
+            let id: i64 = row.get(0)?;
+            let wallet_hash: Option<String> = row.get(1)?;
+            let wallet_id: Option<String> = row.get(2)?;
+            let platform: String = row.get(3)?;
+            let market_title: Option<String> = row.get(4)?;
+            let market_id: Option<String> = row.get(5)?;
+            let outcome: Option<String> = row.get(6)?;
+            let shadow_bet_amount: Option<f64> = row.get(7)?;
+            let entry_price: f64 = row.get(8)?;
+            let status: Option<String> = row.get(9)?;
+            let created_at: i64 = row.get(10)?;
+
+            Ok(serde_json::json!({
+                "id": id,
+                "wallet_hash": wallet_hash,
+                "wallet_id": wallet_id,
+                "platform": platform,
+                "market_title": market_title,
+                "market_id": market_id,
+                "outcome": outcome,
+                "shadow_bet_amount": shadow_bet_amount,
+                "entry_price": entry_price,
+                "status": status,
+                "created_at": created_at,
+            }))
+        })?;
+
+        for row in rows {
+            if let Ok(position) = row {
+                positions.push(position);
+            }
         }
+
+        Ok(positions)
     }
 
-    Ok(alerts)
-}
+    fn prune_old_alerts(&self, retention_days: u32) {
+        if retention_days == 0 {
+            return;
+        }
+        let seconds = retention_days as i64 * 86400;
+        let conn = self.conn.lock().unwrap();
+        let result = conn.execute(
+            "DELETE FROM alerts WHERE created_at < (strftime('%s', 'now') - ?1)",
+            params![seconds],
+        );
+        if let Err(e) = result {
+            eprintln!("Warning: Failed to prune old alerts: {}", e);
+        }
+    }
 
-/// Prune old alerts based on retention days. 0 = keep forever.
-pub fn prune_old_alerts(conn: &Connection, retention_days: u32) {
-    if retention_days == 0 {
-        return;
+    fn record_wallet_memory(
+        &self,
+        wallet_id: &str,
+        market_title: Option<&str>,
+        market_id: Option<&str>,
+        outcome: Option<&str>,
+        action: &str,
+        value: f64,
+        price: f64,
+        platform: &str,
+    ) -> bool {
+        let hash = wallet_hash(wallet_id);
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_secs() as i64;
+
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = match conn.prepare_cached(
+            "INSERT INTO wallet_memory
+             (wallet_hash, wallet_id, market_title, market_id, outcome, action, value, price, platform, seen_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)
+             ON CONFLICT(wallet_hash, market_id, seen_at) DO UPDATE SET
+                 value = excluded.value,
+                 price = excluded.price,
+                 action = excluded.action",
+        ) {
+            Ok(stmt) => stmt,
+            Err(e) => {
+                eprintln!("Warning: Failed to record wallet memory: {}", e);
+                return false;
+            }
+        };
+
+        let result = stmt.execute(params![
+            hash, wallet_id, market_title, market_id, outcome, action, value, price, platform, now,
+        ]);
+
+        if let Err(e) = &result {
+            eprintln!("Warning: Failed to record wallet memory: {}", e);
+        }
+        result.is_ok()
     }
-    let seconds = retention_days as i64 * 86400;
-    let result = conn.execute(
-        "DELETE FROM alerts WHERE created_at < (strftime('%s', 'now') - ?1)",
-        params![seconds],
-    );
-    if let Err(e) = result {
-        eprintln!("Warning: Failed to prune old alerts: {}", e);
+
+    fn prune_wallet_memory(&self) {
+        let conn = self.conn.lock().unwrap();
+        let result = conn.execute(
+            "DELETE FROM wallet_memory WHERE seen_at < (strftime('%s', 'now') - 43200)",
+            [],
+        );
+        if let Err(e) = result {
+            eprintln!("Warning: Failed to prune wallet memory: {}", e);
+        }
+    }
+
+    fn wallet_history(&self, wallet_hash: &str) -> Vec<WalletMemoryRow> {
+        let mut entries = Vec::new();
+        let conn = self.conn.lock().unwrap();
+
+        let result = conn.prepare(
+            "SELECT wallet_id, market_title, market_id, outcome, action, value, price, platform, seen_at
+             FROM wallet_memory
+             WHERE wallet_hash = ?1 AND seen_at > (strftime('%s', 'now') - 43200)
+             ORDER BY seen_at DESC"
+        );
+
+        if let Ok(mut stmt) = result {
+            let rows = stmt.query_map(params![wallet_hash], |row| {
+                Ok(WalletMemoryRow {
+                    wallet_id: row.get(0)?,
+                    market_title: row.get(1)?,
+                    market_id: row.get(2)?,
+                    outcome: row.get(3)?,
+                    action: row.get(4)?,
+                    value: row.get(5)?,
+                    price: row.get(6)?,
+                    platform: row.get(7)?,
+                    seen_at: row.get(8)?,
+                })
+            });
+
+            if let Ok(rows) = rows {
+                for row in rows.flatten() {
+                    entries.push(row);
+                }
+            }
+        }
+
+        entries
+    }
+
+    fn known_wallet_hashes(&self) -> Vec<String> {
+        let mut hashes = Vec::new();
+        let conn = self.conn.lock().unwrap();
+
+        let result = conn.prepare(
+            "SELECT DISTINCT wallet_hash FROM wallet_memory
+             WHERE seen_at > (strftime('%s', 'now') - 43200)"
+        );
+
+        if let Ok(mut stmt) = result {
+            let rows = stmt.query_map([], |row| row.get::<_, String>(0));
+            if let Ok(rows) = rows {
+                for row in rows.flatten() {
+                    hashes.push(row);
+                }
+            }
+        }
+
+        hashes
+    }
+
+    fn wallet_activity(&self, wallet_hash: &str) -> crate::types::WalletActivity {
+        self.with_reader(|conn| {
+            conn.query_row(
+                "SELECT
+                    SUM(CASE WHEN seen_at > (strftime('%s', 'now') - 3600) THEN 1 ELSE 0 END),
+                    COUNT(*),
+                    SUM(CASE WHEN seen_at > (strftime('%s', 'now') - 3600) THEN value ELSE 0 END),
+                    SUM(value)
+                 FROM wallet_memory
+                 WHERE wallet_hash = ?1 AND seen_at > (strftime('%s', 'now') - 86400)",
+                params![wallet_hash],
+                |row| {
+                    let txns_hour: i64 = row.get::<_, Option<i64>>(0)?.unwrap_or(0);
+                    let txns_day: i64 = row.get(1)?;
+                    let value_hour: f64 = row.get::<_, Option<f64>>(2)?.unwrap_or(0.0);
+                    let value_day: f64 = row.get::<_, Option<f64>>(3)?.unwrap_or(0.0);
+                    Ok(crate::types::WalletActivity {
+                        transactions_last_hour: txns_hour as usize,
+                        transactions_last_day: txns_day as usize,
+                        total_value_hour: value_hour,
+                        total_value_day: value_day,
+                        is_repeat_actor: txns_hour > 1,
+                        is_heavy_actor: txns_day >= 5,
+                    })
+                },
+            )
+        })
+        .unwrap_or_default()
+    }
+
+    fn wallet_performance_for(&self, wallet_hash: &str) -> Option<(f64, i64)> {
+        self.with_reader(|conn| {
+            conn.query_row(
+                "SELECT win_rate, settled_trades FROM v_wallet_performance WHERE wallet_hash = ?1",
+                params![wallet_hash],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .optional()
+        })
+        .ok()
+        .flatten()
+    }
+
+    fn alert_count(&self) -> i64 {
+        let conn = self.conn.lock().unwrap();
+        conn.query_row("SELECT COUNT(*) FROM alerts", [], |row| row.get(0))
+            .unwrap_or(0)
+    }
+
+    fn record_uncategorized_title(&self, title: &str) {
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_secs() as i64;
+
+        let conn = self.conn.lock().unwrap();
+        let result = conn.execute(
+            "INSERT INTO uncategorized_titles (title, hit_count, last_seen)
+             VALUES (?1, 1, ?2)
+             ON CONFLICT(title) DO UPDATE SET hit_count = hit_count + 1, last_seen = ?2",
+            params![title, now],
+        );
+
+        if let Err(e) = result {
+            eprintln!("Warning: Failed to record uncategorized title: {}", e);
+        }
+    }
+
+    fn top_uncategorized_titles(&self, limit: usize) -> Vec<(String, i64, i64)> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = match conn.prepare(
+            "SELECT title, hit_count, last_seen FROM uncategorized_titles
+             ORDER BY hit_count DESC, last_seen DESC LIMIT ?1",
+        ) {
+            Ok(stmt) => stmt,
+            Err(_) => return Vec::new(),
+        };
+
+        let rows = stmt.query_map(params![limit as i64], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, i64>(1)?, row.get::<_, i64>(2)?))
+        });
+
+        match rows {
+            Ok(rows) => rows.filter_map(Result::ok).collect(),
+            Err(_) => Vec::new(),
+        }
+    }
+
+    fn record_candle_trade(&self, platform: &str, market: &str, timestamp: i64, price: f64, size: f64) {
+        let conn = self.conn.lock().unwrap();
+        if let Err(e) = self.candle_cache.record_at_all_resolutions(&conn, platform, market, timestamp, price, size) {
+            eprintln!("Warning: Failed to record candle trade: {}", e);
+        }
+    }
+
+    fn flush_stale_candles(&self) {
+        let conn = self.conn.lock().unwrap();
+        if let Err(e) = self.candle_cache.flush_stale(&conn) {
+            eprintln!("Warning: Failed to flush stale candles: {}", e);
+        }
+    }
+
+    fn get_metadata(&self, key: &str) -> Option<String> {
+        let conn = self.conn.lock().unwrap();
+        conn.query_row(
+            "SELECT value FROM metadata WHERE key = ?1",
+            params![key],
+            |row| row.get::<_, String>(0),
+        )
+        .ok()
+    }
+
+    fn set_metadata(&self, key: &str, value: &str) {
+        let conn = self.conn.lock().unwrap();
+        if let Err(e) = conn.execute(
+            "INSERT INTO metadata (key, value) VALUES (?1, ?2)
+             ON CONFLICT(key) DO UPDATE SET value = excluded.value",
+            params![key, value],
+        ) {
+            eprintln!("Warning: Failed to set metadata {}: {}", key, e);
+        }
+    }
+
+    fn record_raw_trade(
+        &self,
+        platform: &str,
+        market: &str,
+        trade_id: Option<&str>,
+        side: &str,
+        price: f64,
+        size: f64,
+        timestamp: i64,
+    ) -> bool {
+        let conn = self.conn.lock().unwrap();
+        let result = conn.execute(
+            "INSERT OR IGNORE INTO backfill_trades (platform, market, trade_id, side, price, size, ts_unix)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+            params![platform, market, trade_id, side, price, size, timestamp],
+        );
+
+        if let Err(e) = &result {
+            eprintln!("Warning: Failed to record raw trade: {}", e);
+        }
+        result.is_ok()
+    }
+
+    fn rebuild_candles(&self, platform: &str, from_unix: i64, to_unix: i64) {
+        let conn = self.conn.lock().unwrap();
+        if let Err(e) = crate::candles::rebuild_from_raw_trades(&conn, platform, from_unix, to_unix) {
+            eprintln!("Warning: Failed to rebuild candles: {}", e);
+        }
     }
 }
 
-/// Insert into wallet_memory (for spawn_blocking; WalletTracker.record_to_db uses this)
-pub fn record_wallet_memory(
-    conn: &Connection,
-    wallet_id: &str,
-    market_title: Option<&str>,
-    market_id: Option<&str>,
-    outcome: Option<&str>,
-    action: &str,
+/// Plain in-memory `AlertStore`, for unit-testing watcher logic without
+/// touching disk. Mirrors `SqliteStore`'s observable behavior (upserts,
+/// 12h wallet-memory window, hit-count bumping) but not its SQL internals.
+#[derive(Default)]
+pub struct InMemoryStore {
+    alerts: Mutex<Vec<InMemoryAlert>>,
+    wallet_memory: Mutex<Vec<WalletMemoryRow>>,
+    uncategorized: Mutex<Vec<(String, i64, i64)>>,
+    metadata: Mutex<HashMap<String, String>>,
+}
+
+struct InMemoryAlert {
+    id: i64,
+    platform: String,
+    alert_type: String,
+    action: String,
     value: f64,
     price: f64,
-    platform: &str,
-) {
-    let hash = wallet_hash(wallet_id);
-    let now = std::time::SystemTime::now()
-        .duration_since(std::time::UNIX_EPOCH)
-        .unwrap()
-        .as_secs() as i64;
-
-    let result = conn.execute(
-        "INSERT OR REPLACE INTO wallet_memory
-         (wallet_hash, wallet_id, market_title, market_id, outcome, action, value, price, platform, seen_at)
-         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
-        params![hash, wallet_id, market_title, market_id, outcome, action, value, price, platform, now],
-    );
+    size: f64,
+    market_title: Option<String>,
+    market_id: Option<String>,
+    trade_id: Option<String>,
+    outcome: Option<String>,
+    wallet_id: Option<String>,
+    timestamp: String,
+    market_context_json: Option<String>,
+    wallet_activity_json: Option<String>,
+    live_trade_id: Option<String>,
+    status: String,
+    settled_outcome: Option<String>,
+    pnl_value: Option<f64>,
+    fee_value: Option<f64>,
+    shadow_bet_amount: Option<f64>,
+    ev_cents: Option<f64>,
+    kelly_pct: Option<f64>,
+}
 
-    if let Err(e) = result {
-        eprintln!("Warning: Failed to record wallet memory: {}", e);
+impl InMemoryStore {
+    pub fn new() -> Self {
+        Self::default()
     }
 }
 
-/// Prune expired wallet memory (12h window)
-pub fn prune_wallet_memory(conn: &Connection) {
-    let result = conn.execute(
-        "DELETE FROM wallet_memory WHERE seen_at < (strftime('%s', 'now') - 43200)",
-        [],
-    );
-    if let Err(e) = result {
-        eprintln!("Warning: Failed to prune wallet memory: {}", e);
+impl AlertStore for InMemoryStore {
+    fn insert_alert(
+        &self,
+        platform: &str,
+        alert_type: &str,
+        action: &str,
+        value: f64,
+        price: f64,
+        size: f64,
+        market_title: Option<&str>,
+        market_id: Option<&str>,
+        outcome: Option<&str>,
+        wallet_id: Option<&str>,
+        timestamp: &str,
+        market_context_json: Option<&str>,
+        wallet_activity_json: Option<&str>,
+        trade_id: Option<&str>,
+    ) -> Option<i64> {
+        let mut alerts = self.alerts.lock().unwrap();
+        if let Some(tid) = trade_id {
+            if alerts.iter().any(|a| a.platform == platform && a.trade_id.as_deref() == Some(tid)) {
+                return None;
+            }
+        }
+        let id = alerts.len() as i64 + 1;
+        alerts.push(InMemoryAlert {
+            id,
+            platform: platform.to_string(),
+            alert_type: alert_type.to_string(),
+            action: action.to_string(),
+            value,
+            price,
+            size,
+            market_title: market_title.map(String::from),
+            market_id: market_id.map(String::from),
+            trade_id: trade_id.map(String::from),
+            outcome: outcome.map(String::from),
+            wallet_id: wallet_id.map(String::from),
+            timestamp: timestamp.to_string(),
+            market_context_json: market_context_json.map(String::from),
+            wallet_activity_json: wallet_activity_json.map(String::from),
+            live_trade_id: None,
+            status: "OPEN".to_string(),
+            settled_outcome: None,
+            pnl_value: None,
+            fee_value: None,
+            shadow_bet_amount: None,
+            ev_cents: None,
+            kelly_pct: None,
+        });
+        Some(id)
     }
-}
 
-/// Migrate existing JSONL history to SQLite
-#[allow(dead_code)]
-pub fn migrate_jsonl_if_exists(conn: &Connection) {
-    let config_dir = match dirs::config_dir() {
-        Some(d) => d,
-        None => return,
-    };
-
-    let jsonl_path = config_dir.join("wwatcher").join("alert_history.jsonl");
-    if !jsonl_path.exists() {
-        return;
-    }
-
-    let contents = match std::fs::read_to_string(&jsonl_path) {
-        Ok(c) => c,
-        Err(_) => return,
-    };
-
-    let mut count = 0u32;
-    for line in contents.lines() {
-        if let Ok(alert) = serde_json::from_str::<serde_json::Value>(line) {
-            let platform = alert.get("platform").and_then(|v| v.as_str()).unwrap_or("Unknown");
-            let alert_type = alert.get("alert_type").and_then(|v| v.as_str()).unwrap_or("UNKNOWN");
-            let action = alert.get("action").and_then(|v| v.as_str()).unwrap_or("UNKNOWN");
-            let value = alert.get("value").and_then(|v| v.as_f64()).unwrap_or(0.0);
-            let price = alert.get("price").and_then(|v| v.as_f64()).unwrap_or(0.0);
-            let size = alert.get("size").and_then(|v| v.as_f64()).unwrap_or(0.0);
-            let market_title = alert.get("market_title").and_then(|v| v.as_str());
-            let outcome = alert.get("outcome").and_then(|v| v.as_str());
-            let wallet_id = alert.get("wallet_id").and_then(|v| v.as_str());
-            let timestamp = alert.get("timestamp").and_then(|v| v.as_str()).unwrap_or("");
-
-            let wa_json = alert.get("wallet_activity").map(|v| v.to_string());
-
-            insert_alert(
-                conn,
-                platform,
-                alert_type,
-                action,
+    fn mark_alert_executed(
+        &self,
+        alert_id: i64,
+        order_id: &str,
+        ticker: &str,
+        side: &str,
+        bet_amount: f64,
+        price: f64,
+        fee: f64,
+        ev_cents: f64,
+        kelly_pct: f64,
+    ) {
+        let mut alerts = self.alerts.lock().unwrap();
+        if let Some(alert) = alerts.iter_mut().find(|a| a.id == alert_id) {
+            alert.live_trade_id = Some(order_id.to_string());
+            alert.shadow_bet_amount = Some(bet_amount);
+            alert.status = "EXECUTED".to_string();
+            alert.market_id = Some(ticker.to_string());
+            alert.outcome = Some(side.to_string());
+            alert.price = price;
+            alert.fee_value = Some(fee);
+            alert.ev_cents = Some(ev_cents);
+            alert.kelly_pct = Some(kelly_pct);
+        }
+    }
+
+    fn query_alerts(
+        &self,
+        limit: usize,
+        platform_filter: &str,
+    ) -> Result<Vec<serde_json::Value>, Box<dyn std::error::Error>> {
+        let alerts = self.alerts.lock().unwrap();
+        let mut matched: Vec<&InMemoryAlert> = alerts
+            .iter()
+            .rev()
+            .filter(|a| platform_filter == "all" || a.platform.eq_ignore_ascii_case(platform_filter))
+            .collect();
+        matched.truncate(limit);
+
+        Ok(matched
+            .into_iter()
+            .map(|alert| {
+                let mut out = serde_json::json!({
+                    "platform": alert.platform,
+                    "alert_type": alert.alert_type,
+                    "action": alert.action,
+                    "value": alert.value,
+                    "price": alert.price,
+                    "size": alert.size,
+                    "timestamp": alert.timestamp,
+                    "market_title": alert.market_title,
+                    "outcome": alert.outcome,
+                    "pnl_value": alert.pnl_value,
+                    "fee_value": alert.fee_value,
+                    "net_pnl": alert.pnl_value.map(|p| p - alert.fee_value.unwrap_or(0.0)),
+                });
+                if let Some(ref wid) = alert.wallet_id {
+                    out["wallet_id"] = serde_json::json!(wid);
+                }
+                if let Some(ref wa) = alert.wallet_activity_json {
+                    if let Ok(wa) = serde_json::from_str::<serde_json::Value>(wa) {
+                        out["wallet_activity"] = wa;
+                    }
+                }
+                if let Some(ref mc) = alert.market_context_json {
+                    if let Ok(mc) = serde_json::from_str::<serde_json::Value>(mc) {
+                        out["market_context"] = mc;
+                    }
+                }
+                out
+            })
+            .collect())
+    }
+
+    /// `since_unix`/`until_unix` are ignored here — `InMemoryAlert` doesn't
+    /// track a creation timestamp, only the caller-supplied `timestamp`
+    /// string, so there's no wall clock to filter against (same tradeoff
+    /// `prune_old_alerts` makes on this store). `min_value` still applies.
+    fn query_alerts_filtered(
+        &self,
+        limit: usize,
+        platform_filter: &str,
+        min_value: Option<f64>,
+        _since_unix: Option<i64>,
+        _until_unix: Option<i64>,
+    ) -> Result<Vec<serde_json::Value>, Box<dyn std::error::Error>> {
+        let alerts = self.alerts.lock().unwrap();
+        let mut matched: Vec<&InMemoryAlert> = alerts
+            .iter()
+            .rev()
+            .filter(|a| platform_filter == "all" || a.platform.eq_ignore_ascii_case(platform_filter))
+            .filter(|a| min_value.map(|min| a.value >= min).unwrap_or(true))
+            .collect();
+        matched.truncate(limit);
+
+        Ok(matched
+            .into_iter()
+            .map(|alert| {
+                let mut out = serde_json::json!({
+                    "platform": alert.platform,
+                    "alert_type": alert.alert_type,
+                    "action": alert.action,
+                    "value": alert.value,
+                    "price": alert.price,
+                    "size": alert.size,
+                    "timestamp": alert.timestamp,
+                    "market_title": alert.market_title,
+                    "outcome": alert.outcome,
+                });
+                if let Some(ref wid) = alert.wallet_id {
+                    out["wallet_id"] = serde_json::json!(wid);
+                }
+                if let Some(ref mc) = alert.market_context_json {
+                    if let Ok(mc) = serde_json::from_str::<serde_json::Value>(mc) {
+                        out["market_context"] = mc;
+                    }
+                }
+                out
+            })
+            .collect())
+    }
+
+    /// Sums every alert this store holds rather than just the trailing 24h —
+    /// see `query_alerts_filtered`'s note on why wall-clock windows don't
+    /// apply here.
+    fn query_ticker_summary(
+        &self,
+        platform_filter: &str,
+    ) -> Result<Vec<serde_json::Value>, Box<dyn std::error::Error>> {
+        let alerts = self.alerts.lock().unwrap();
+        let mut by_market: HashMap<(String, String), (f64, Option<String>)> = HashMap::new();
+
+        for alert in alerts
+            .iter()
+            .filter(|a| platform_filter == "all" || a.platform.eq_ignore_ascii_case(platform_filter))
+        {
+            let Some(ref title) = alert.market_title else { continue };
+            let entry = by_market
+                .entry((alert.platform.clone(), title.clone()))
+                .or_insert((0.0, None));
+            entry.0 += alert.value;
+            if alert.market_context_json.is_some() {
+                entry.1 = alert.market_context_json.clone();
+            }
+        }
+
+        Ok(by_market
+            .into_iter()
+            .map(|((platform, market_title), (volume_24h, context_json))| {
+                let mut ticker = serde_json::json!({
+                    "platform": platform,
+                    "market_title": market_title,
+                    "volume_24h": volume_24h,
+                });
+                if let Some(ctx_json) = context_json {
+                    if let Ok(ctx) = serde_json::from_str::<serde_json::Value>(&ctx_json) {
+                        ticker["market_context"] = ctx;
+                    }
+                }
+                ticker
+            })
+            .collect())
+    }
+
+    /// Always empty — this store doesn't back a `candles` table.
+    fn query_candles(
+        &self,
+        _platform: &str,
+        _market: &str,
+        _resolution: &str,
+        _limit: u32,
+    ) -> Result<Vec<serde_json::Value>, Box<dyn std::error::Error>> {
+        Ok(Vec::new())
+    }
+
+    /// Always empty, for the same reason as `query_candles`.
+    fn query_candles_range(
+        &self,
+        _platform: &str,
+        _market: &str,
+        _resolution: &str,
+        _from_unix: i64,
+        _to_unix: i64,
+    ) -> Result<Vec<serde_json::Value>, Box<dyn std::error::Error>> {
+        Ok(Vec::new())
+    }
+
+    fn configure_candle_resolutions(&self, _intervals: &[String]) {}
+
+    fn query_wallet_performance(
+        &self,
+        limit: usize,
+    ) -> Result<Vec<serde_json::Value>, Box<dyn std::error::Error>> {
+        let alerts = self.alerts.lock().unwrap();
+        let mut by_wallet: HashMap<String, (Option<String>, f64, i64, i64)> = HashMap::new();
+
+        for alert in alerts.iter().filter(|a| a.settled_outcome.is_some()) {
+            let Some(ref wid) = alert.wallet_id else { continue };
+            let hash = wallet_hash(wid);
+            let entry = by_wallet.entry(hash).or_insert((Some(wid.clone()), 0.0, 0, 0));
+            entry.1 += alert.pnl_value.unwrap_or(0.0);
+            entry.2 += 1;
+            if alert.settled_outcome == alert.outcome {
+                entry.3 += 1;
+            }
+        }
+
+        let mut rows: Vec<serde_json::Value> = by_wallet
+            .into_iter()
+            .map(|(hash, (wallet_id, total_pnl, settled_trades, win_count))| {
+                let win_rate = if settled_trades > 0 {
+                    win_count as f64 / settled_trades as f64
+                } else {
+                    0.0
+                };
+                serde_json::json!({
+                    "wallet_hash": hash,
+                    "wallet_id": wallet_id,
+                    "total_pnl": total_pnl,
+                    "settled_trades": settled_trades,
+                    "win_count": win_count,
+                    "win_rate": win_rate,
+                })
+            })
+            .collect();
+
+        rows.sort_by(|a, b| {
+            let pa = a["total_pnl"].as_f64().unwrap_or(0.0);
+            let pb = b["total_pnl"].as_f64().unwrap_or(0.0);
+            pb.partial_cmp(&pa).unwrap_or(std::cmp::Ordering::Equal)
+        });
+        rows.truncate(limit);
+        Ok(rows)
+    }
+
+    fn query_open_positions(&self) -> Result<Vec<serde_json::Value>, Box<dyn std::error::Error>> {
+        let alerts = self.alerts.lock().unwrap();
+        Ok(alerts
+            .iter()
+            .rev()
+            .filter(|a| a.status == "OPEN" || a.status == "EXECUTED")
+            .map(|alert| {
+                serde_json::json!({
+                    "id": alert.id,
+                    "wallet_hash": alert.wallet_id.as_deref().map(wallet_hash),
+                    "wallet_id": alert.wallet_id,
+                    "platform": alert.platform,
+                    "market_title": alert.market_title,
+                    "market_id": alert.market_id,
+                    "outcome": alert.outcome,
+                    "shadow_bet_amount": alert.shadow_bet_amount,
+                    "entry_price": alert.price,
+                    "status": alert.status,
+                })
+            })
+            .collect())
+    }
+
+    fn prune_old_alerts(&self, _retention_days: u32) {
+        // Retention is a wall-clock concept the in-memory store doesn't model;
+        // tests construct exactly the alerts they want to see.
+    }
+
+    fn record_wallet_memory(
+        &self,
+        wallet_id: &str,
+        market_title: Option<&str>,
+        market_id: Option<&str>,
+        outcome: Option<&str>,
+        action: &str,
+        value: f64,
+        price: f64,
+        platform: &str,
+    ) -> bool {
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_secs() as i64;
+
+        let mut memory = self.wallet_memory.lock().unwrap();
+        let hash = wallet_hash(wallet_id);
+        if let Some(existing) = memory
+            .iter_mut()
+            .find(|e| wallet_hash(&e.wallet_id) == hash && e.market_id.as_deref() == market_id && e.seen_at == now)
+        {
+            existing.value = value;
+            existing.price = price;
+            existing.action = Some(action.to_string());
+        } else {
+            memory.push(WalletMemoryRow {
+                wallet_id: wallet_id.to_string(),
+                market_title: market_title.map(String::from),
+                market_id: market_id.map(String::from),
+                outcome: outcome.map(String::from),
+                action: Some(action.to_string()),
                 value,
                 price,
-                size,
-                market_title,
-                None,
-                outcome,
-                wallet_id,
-                timestamp,
-                None,
-                wa_json.as_deref(),
-            );
-            count += 1;
+                platform: platform.to_string(),
+                seen_at: now,
+            });
+        }
+        true
+    }
+
+    fn prune_wallet_memory(&self) {
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_secs() as i64;
+        let mut memory = self.wallet_memory.lock().unwrap();
+        memory.retain(|e| now - e.seen_at < 43200);
+    }
+
+    fn wallet_history(&self, wallet_hash: &str) -> Vec<WalletMemoryRow> {
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_secs() as i64;
+        let memory = self.wallet_memory.lock().unwrap();
+        let mut matched: Vec<WalletMemoryRow> = memory
+            .iter()
+            .filter(|e| self::wallet_hash(&e.wallet_id) == wallet_hash && now - e.seen_at < 43200)
+            .cloned()
+            .collect();
+        matched.sort_by(|a, b| b.seen_at.cmp(&a.seen_at));
+        matched
+    }
+
+    fn known_wallet_hashes(&self) -> Vec<String> {
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_secs() as i64;
+        let memory = self.wallet_memory.lock().unwrap();
+        let mut hashes: Vec<String> = memory
+            .iter()
+            .filter(|e| now - e.seen_at < 43200)
+            .map(|e| wallet_hash(&e.wallet_id))
+            .collect();
+        hashes.sort();
+        hashes.dedup();
+        hashes
+    }
+
+    fn wallet_activity(&self, wallet_hash: &str) -> crate::types::WalletActivity {
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_secs() as i64;
+        let memory = self.wallet_memory.lock().unwrap();
+        let matching = memory
+            .iter()
+            .filter(|e| wallet_hash(&e.wallet_id) == wallet_hash && now - e.seen_at < 86400);
+
+        let mut activity = crate::types::WalletActivity::default();
+        for entry in matching {
+            activity.transactions_last_day += 1;
+            activity.total_value_day += entry.value;
+            if now - entry.seen_at < 3600 {
+                activity.transactions_last_hour += 1;
+                activity.total_value_hour += entry.value;
+            }
+        }
+        activity.is_repeat_actor = activity.transactions_last_hour > 1;
+        activity.is_heavy_actor = activity.transactions_last_day >= 5;
+        activity
+    }
+
+    fn wallet_performance_for(&self, wallet_hash: &str) -> Option<(f64, i64)> {
+        let alerts = self.alerts.lock().unwrap();
+        let settled: Vec<_> = alerts
+            .iter()
+            .filter(|a| {
+                a.settled_outcome.is_some()
+                    && a.wallet_id.as_deref().map(self::wallet_hash).as_deref() == Some(wallet_hash)
+            })
+            .collect();
+        if settled.is_empty() {
+            return None;
         }
+        let total = settled.len() as i64;
+        let wins = settled
+            .iter()
+            .filter(|a| a.settled_outcome == a.outcome)
+            .count() as i64;
+        Some((wins as f64 / total as f64, total))
+    }
+
+    fn alert_count(&self) -> i64 {
+        self.alerts.lock().unwrap().len() as i64
     }
 
-    if count > 0 {
-        let bak_path = config_dir.join("wwatcher").join("alert_history.jsonl.bak");
-        if std::fs::rename(&jsonl_path, &bak_path).is_ok() {
-            eprintln!("Migrated {} alerts from JSONL to SQLite database.", count);
-            eprintln!("Old file backed up to: alert_history.jsonl.bak");
+    fn record_uncategorized_title(&self, title: &str) {
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_secs() as i64;
+
+        let mut titles = self.uncategorized.lock().unwrap();
+        if let Some(entry) = titles.iter_mut().find(|(t, _, _)| t == title) {
+            entry.1 += 1;
+            entry.2 = now;
+        } else {
+            titles.push((title.to_string(), 1, now));
         }
     }
+
+    fn top_uncategorized_titles(&self, limit: usize) -> Vec<(String, i64, i64)> {
+        let mut titles = self.uncategorized.lock().unwrap().clone();
+        titles.sort_by(|a, b| b.1.cmp(&a.1).then(b.2.cmp(&a.2)));
+        titles.truncate(limit);
+        titles
+    }
+
+    // No SQLite connection to flush candles into — tests that need candle
+    // behavior exercise `candles::CandleCache` directly against an in-memory
+    // `Connection` instead.
+    fn record_candle_trade(&self, _platform: &str, _market: &str, _timestamp: i64, _price: f64, _size: f64) {}
+
+    fn flush_stale_candles(&self) {}
+
+    fn get_metadata(&self, key: &str) -> Option<String> {
+        self.metadata.lock().unwrap().get(key).cloned()
+    }
+
+    fn set_metadata(&self, key: &str, value: &str) {
+        self.metadata.lock().unwrap().insert(key.to_string(), value.to_string());
+    }
+
+    // No SQLite connection to persist raw trades into or rebuild candles
+    // from — same tradeoff as `record_candle_trade`/`flush_stale_candles`.
+    fn record_raw_trade(
+        &self,
+        _platform: &str,
+        _market: &str,
+        _trade_id: Option<&str>,
+        _side: &str,
+        _price: f64,
+        _size: f64,
+        _timestamp: i64,
+    ) -> bool {
+        false
+    }
+
+    fn rebuild_candles(&self, _platform: &str, _from_unix: i64, _to_unix: i64) {}
 }
 
-/// Get alert count in database
-pub fn alert_count(conn: &Connection) -> i64 {
-    conn.query_row("SELECT COUNT(*) FROM alerts", [], |row| row.get(0))
-        .unwrap_or(0)
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn in_memory_insert_and_query_alerts() {
+        let store = InMemoryStore::new();
+        store.insert_alert(
+            "Polymarket", "WHALE_ENTRY", "BUY", 50000.0, 0.65, 76923.0,
+            Some("Will it rain?"), Some("mkt1"), Some("Yes"), Some("0xabc"),
+            "2026-01-01T00:00:00Z", None, None, None,
+        );
+
+        let alerts = store.query_alerts(10, "all").unwrap();
+        assert_eq!(alerts.len(), 1);
+        assert_eq!(alerts[0]["market_title"], "Will it rain?");
+    }
+
+    #[test]
+    fn in_memory_query_alerts_filters_by_platform() {
+        let store = InMemoryStore::new();
+        store.insert_alert("Polymarket", "WHALE_ENTRY", "BUY", 1.0, 1.0, 1.0, None, None, None, None, "t", None, None, None);
+        store.insert_alert("Kalshi", "WHALE_ENTRY", "BUY", 1.0, 1.0, 1.0, None, None, None, None, "t", None, None, None);
+
+        assert_eq!(store.query_alerts(10, "kalshi").unwrap().len(), 1);
+        assert_eq!(store.query_alerts(10, "all").unwrap().len(), 2);
+    }
+
+    #[test]
+    fn in_memory_record_wallet_memory_upserts_without_clobbering() {
+        let store = InMemoryStore::new();
+        store.record_wallet_memory("0xabc", Some("Market"), Some("mkt1"), Some("Yes"), "BUY", 100.0, 0.5, "Polymarket");
+        store.record_wallet_memory("0xabc", Some("Market"), Some("mkt1"), Some("Yes"), "SELL", 200.0, 0.6, "Polymarket");
+
+        let history = store.wallet_history(&wallet_hash("0xabc"));
+        assert_eq!(history.len(), 1);
+        assert_eq!(history[0].value, 200.0);
+        assert_eq!(history[0].action.as_deref(), Some("SELL"));
+    }
+
+    #[test]
+    fn in_memory_wallet_activity_counts_last_hour_and_day() {
+        let store = InMemoryStore::new();
+        store.record_wallet_memory("0xabc", Some("Market"), Some("mkt1"), Some("Yes"), "BUY", 100.0, 0.5, "Polymarket");
+        store.record_wallet_memory("0xabc", Some("Market"), Some("mkt2"), Some("No"), "BUY", 50.0, 0.3, "Polymarket");
+
+        let activity = store.wallet_activity(&wallet_hash("0xabc"));
+        assert_eq!(activity.transactions_last_hour, 2);
+        assert_eq!(activity.transactions_last_day, 2);
+        assert_eq!(activity.total_value_day, 150.0);
+        assert!(activity.is_repeat_actor);
+        assert!(!activity.is_heavy_actor);
+    }
+
+    #[test]
+    fn in_memory_wallet_activity_is_empty_for_unknown_wallet() {
+        let store = InMemoryStore::new();
+        let activity = store.wallet_activity(&wallet_hash("0xnever-seen"));
+        assert_eq!(activity.transactions_last_day, 0);
+        assert!(!activity.is_repeat_actor);
+    }
+
+    #[test]
+    fn in_memory_record_uncategorized_title_bumps_hit_count() {
+        let store = InMemoryStore::new();
+        store.record_uncategorized_title("Some new market");
+        store.record_uncategorized_title("Some new market");
+        store.record_uncategorized_title("Other market");
+
+        let top = store.top_uncategorized_titles(10);
+        assert_eq!(top[0].0, "Some new market");
+        assert_eq!(top[0].1, 2);
+    }
 }
@@ -0,0 +1,441 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use crate::execution::matcher::MatchResult;
+
+// ── Types ───────────────────────────────────────────────────────────────
+
+/// A platform's trading fee: proportional to trade size, with a flat floor
+/// (the fee equivalent of a dust threshold) so small trades don't get priced
+/// as if fees were negligible.
+#[derive(Debug, Clone, Copy)]
+pub struct FeeSchedule {
+    pub min_fee: f64,
+    pub fee_rate: f64,
+}
+
+impl FeeSchedule {
+    pub fn fee(&self, notional: f64) -> f64 {
+        (notional * self.fee_rate).max(self.min_fee)
+    }
+}
+
+/// One side of an arbitrage trade: which market, which outcome, at what price.
+#[derive(Debug, Clone)]
+pub struct ArbitrageLeg {
+    pub ticker: String,
+    pub side: String,
+    pub price: f64,
+}
+
+/// A confirmed, fee-covering arbitrage opportunity between the two legs of a
+/// matched pair.
+#[derive(Debug, Clone)]
+pub struct ArbitrageOpportunity {
+    pub leg_a: ArbitrageLeg,
+    pub leg_b: ArbitrageLeg,
+    /// Net profit at `notional`, after both platforms' fees.
+    pub edge: f64,
+    /// The smallest notional at which the proportional edge first clears the
+    /// combined fee floor.
+    pub break_even_notional: f64,
+}
+
+// ── Detection ─────────────────────────────────────────────────────────────
+
+/// Detect a guaranteed, fee-covering profit from buying YES on platform A at
+/// `price_a` and NO on platform B at `price_b` for a confirmed cross-platform
+/// match. `notional` is the intended trade size; an opportunity is only
+/// returned when `notional * (1 - (price_a + price_b))`, the proportional
+/// edge, exceeds both platforms' fees at that size.
+pub fn detect_arbitrage(
+    matched: &MatchResult,
+    poly_label: &str,
+    price_a: f64,
+    fees_a: &FeeSchedule,
+    price_b: f64,
+    fees_b: &FeeSchedule,
+    notional: f64,
+) -> Option<ArbitrageOpportunity> {
+    if !matched.r#match || matched.ticker.is_empty() {
+        return None;
+    }
+
+    let edge_per_unit = 1.0 - (price_a + price_b);
+    if edge_per_unit <= 0.0 {
+        return None;
+    }
+
+    let total_fees = fees_a.fee(notional) + fees_b.fee(notional);
+    let gross_edge = notional * edge_per_unit;
+    if gross_edge <= total_fees {
+        return None;
+    }
+
+    let break_even_notional = (fees_a.min_fee + fees_b.min_fee) / edge_per_unit;
+
+    Some(ArbitrageOpportunity {
+        leg_a: ArbitrageLeg {
+            ticker: poly_label.to_string(),
+            side: "yes".to_string(),
+            price: price_a,
+        },
+        leg_b: ArbitrageLeg {
+            ticker: matched.ticker.clone(),
+            side: "no".to_string(),
+            price: price_b,
+        },
+        edge: gross_edge - total_fees,
+        break_even_notional,
+    })
+}
+
+// ── Cross-platform context routing ───────────────────────────────────────
+
+/// Manual Kalshi-ticker ↔ Polymarket-market pairing, for markets whose
+/// titles don't normalize to the same keyword set (e.g. "Fed holds rates in
+/// March" vs. "FOMC March decision: no change") but are still the same
+/// real-world bet. Loaded once from
+/// `~/.config/wwatcher/equivalent_markets.json`, a JSON array of
+/// `{"kalshi": "...", "polymarket": "..."}` objects; a missing or
+/// unparseable file just means no manual overrides, not an error.
+#[derive(Debug, Clone, serde::Deserialize)]
+struct EquivalentMarketOverride {
+    kalshi: String,
+    polymarket: String,
+}
+
+fn load_overrides() -> HashMap<String, String> {
+    let Some(config_dir) = dirs::config_dir() else { return HashMap::new() };
+    let path = config_dir.join("wwatcher").join("equivalent_markets.json");
+    let Ok(text) = std::fs::read_to_string(path) else { return HashMap::new() };
+    let overrides: Vec<EquivalentMarketOverride> = serde_json::from_str(&text).unwrap_or_default();
+    overrides.into_iter().map(|o| (o.kalshi, o.polymarket)).collect()
+}
+
+/// The least number of shared `matcher::expand_keywords` tokens two titles
+/// need before `best_title_match` treats them as the same real-world market.
+/// Below this, two markets about the same city or sport would collide on a
+/// couple of generic words alone.
+const MIN_SHARED_KEYWORDS: usize = 3;
+
+/// The last `MarketContext` seen for one platform's market, cached so a
+/// context pulled on the other platform has something to compare against
+/// even though the two feeds rarely land in the same tick.
+struct CachedContext {
+    title: String,
+    context: crate::alerts::MarketContext,
+}
+
+/// Normalized-keyword overlap match: the cached market whose title shares
+/// the most tokens with `title`. Mirrors `matcher::build_query_graph`'s
+/// tokenization (lowercased, stop words and abbreviations expanded) without
+/// the LLM rerank step — `HybridRouter` runs on every context fetch, so it
+/// needs to stay cheap.
+fn best_title_match(title: &str, cache: &HashMap<String, CachedContext>) -> Option<String> {
+    let query = crate::execution::matcher::expand_keywords(title);
+    if query.len() < MIN_SHARED_KEYWORDS {
+        return None;
+    }
+
+    cache
+        .iter()
+        .map(|(key, cached)| {
+            let candidate = crate::execution::matcher::expand_keywords(&cached.title);
+            let shared = query.iter().filter(|w| candidate.contains(w)).count();
+            (key.clone(), shared)
+        })
+        .filter(|(_, shared)| *shared >= MIN_SHARED_KEYWORDS)
+        .max_by_key(|(_, shared)| *shared)
+        .map(|(key, _)| key)
+}
+
+/// A confirmed cross-venue opportunity found by `HybridRouter`, with both
+/// platforms' raw prices attached for `AlertData::arbitrage`'s
+/// webhook/log/display payloads, plus the underlying `ArbitrageOpportunity`
+/// for its edge/break-even math.
+#[derive(Debug, Clone)]
+pub struct ArbitragePair {
+    pub kalshi_ticker: String,
+    pub polymarket_market: String,
+    pub kalshi_yes_price: f64,
+    pub kalshi_no_price: f64,
+    pub polymarket_yes_price: f64,
+    pub polymarket_no_price: f64,
+    /// `true` when the cheaper leg is buying YES on Polymarket and NO on
+    /// Kalshi; `false` for the other direction.
+    pub buy_yes_on_polymarket: bool,
+    pub opportunity: ArbitrageOpportunity,
+}
+
+/// Maintains the Kalshi↔Polymarket equivalent-market mapping (manual
+/// overrides plus normalized-title matches) and the latest `MarketContext`
+/// seen per market, so that whenever `commands::watch` pulls a fresh context
+/// on one platform, it can check it against whatever was last cached for
+/// the matched market on the other. One instance is shared across both
+/// platforms' trade loops.
+pub struct HybridRouter {
+    overrides: HashMap<String, String>,
+    kalshi_contexts: Mutex<HashMap<String, CachedContext>>,
+    polymarket_contexts: Mutex<HashMap<String, CachedContext>>,
+    fees: FeeSchedule,
+    /// Notional `detect_arbitrage` sizes the opportunity at — this only
+    /// decides whether the proportional edge clears the fee floor, not how
+    /// large a real trade would be.
+    notional: f64,
+}
+
+impl HybridRouter {
+    pub fn new(fees: FeeSchedule, notional: f64) -> Self {
+        Self {
+            overrides: load_overrides(),
+            kalshi_contexts: Mutex::new(HashMap::new()),
+            polymarket_contexts: Mutex::new(HashMap::new()),
+            fees,
+            notional,
+        }
+    }
+
+    /// Record a fresh Kalshi context and, if its matched Polymarket market
+    /// already has a cached context, check the pair for arbitrage.
+    pub fn record_kalshi_context(
+        &self,
+        ticker: &str,
+        title: &str,
+        context: &crate::alerts::MarketContext,
+    ) -> Option<ArbitragePair> {
+        self.kalshi_contexts.lock().unwrap().insert(
+            ticker.to_string(),
+            CachedContext { title: title.to_string(), context: context.clone() },
+        );
+
+        let poly_contexts = self.polymarket_contexts.lock().unwrap();
+        let poly_key = self.overrides.get(ticker).filter(|m| poly_contexts.contains_key(m.as_str())).cloned()
+            .or_else(|| best_title_match(title, &poly_contexts))?;
+        let poly = poly_contexts.get(&poly_key)?;
+        self.best_pair(ticker, context, &poly_key, &poly.context)
+    }
+
+    /// Record a fresh Polymarket context and, if its matched Kalshi ticker
+    /// already has a cached context, check the pair for arbitrage.
+    pub fn record_polymarket_context(
+        &self,
+        market: &str,
+        title: &str,
+        context: &crate::alerts::MarketContext,
+    ) -> Option<ArbitragePair> {
+        self.polymarket_contexts.lock().unwrap().insert(
+            market.to_string(),
+            CachedContext { title: title.to_string(), context: context.clone() },
+        );
+
+        let kalshi_contexts = self.kalshi_contexts.lock().unwrap();
+        let kalshi_key = self.overrides.iter().find(|(_, poly)| poly.as_str() == market).map(|(k, _)| k.clone())
+            .filter(|t| kalshi_contexts.contains_key(t.as_str()))
+            .or_else(|| best_title_match(title, &kalshi_contexts))?;
+        let kalshi = kalshi_contexts.get(&kalshi_key)?;
+        self.best_pair(&kalshi_key, &kalshi.context, market, context)
+    }
+
+    /// Check both directions (buy YES on Kalshi + NO on Polymarket, and the
+    /// reverse) and return whichever clears the fee floor, if either does.
+    fn best_pair(
+        &self,
+        kalshi_ticker: &str,
+        kalshi_ctx: &crate::alerts::MarketContext,
+        poly_market: &str,
+        poly_ctx: &crate::alerts::MarketContext,
+    ) -> Option<ArbitragePair> {
+        let matched_kalshi = MatchResult {
+            r#match: true,
+            ticker: kalshi_ticker.to_string(),
+            side: "yes".to_string(),
+            confidence: Some(1.0),
+            reasoning: None,
+        };
+        let matched_poly = MatchResult {
+            r#match: true,
+            ticker: poly_market.to_string(),
+            side: "yes".to_string(),
+            confidence: Some(1.0),
+            reasoning: None,
+        };
+
+        let make_pair = |buy_yes_on_polymarket: bool, opportunity: ArbitrageOpportunity| ArbitragePair {
+            kalshi_ticker: kalshi_ticker.to_string(),
+            polymarket_market: poly_market.to_string(),
+            kalshi_yes_price: kalshi_ctx.yes_price,
+            kalshi_no_price: kalshi_ctx.no_price,
+            polymarket_yes_price: poly_ctx.yes_price,
+            polymarket_no_price: poly_ctx.no_price,
+            buy_yes_on_polymarket,
+            opportunity,
+        };
+
+        if let Some(opp) = detect_arbitrage(
+            &matched_kalshi, poly_market, poly_ctx.yes_price, &self.fees, kalshi_ctx.no_price, &self.fees, self.notional,
+        ) {
+            return Some(make_pair(true, opp));
+        }
+
+        detect_arbitrage(
+            &matched_poly, kalshi_ticker, kalshi_ctx.yes_price, &self.fees, poly_ctx.no_price, &self.fees, self.notional,
+        )
+        .map(|opp| make_pair(false, opp))
+    }
+}
+
+// ── Tests ───────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn matched(ticker: &str) -> MatchResult {
+        MatchResult {
+            r#match: true,
+            ticker: ticker.to_string(),
+            side: "yes".to_string(),
+            confidence: Some(0.97),
+            reasoning: None,
+        }
+    }
+
+    #[test]
+    fn flags_profitable_spread_above_fees() {
+        let fees = FeeSchedule { min_fee: 0.01, fee_rate: 0.01 };
+        // 0.40 + 0.50 = 0.90 < 1.0 → 0.10 edge per contract
+        let opp = detect_arbitrage(&matched("KXFB-WIN"), "Will the Falcons win?", 0.40, &fees, 0.50, &fees, 100.0);
+        assert!(opp.is_some());
+        let opp = opp.unwrap();
+        assert!(opp.edge > 0.0);
+        assert_eq!(opp.leg_b.ticker, "KXFB-WIN");
+    }
+
+    #[test]
+    fn rejects_spread_that_sums_to_at_least_one() {
+        let fees = FeeSchedule { min_fee: 0.01, fee_rate: 0.01 };
+        let opp = detect_arbitrage(&matched("KXFB-WIN"), "Will the Falcons win?", 0.55, &fees, 0.50, &fees, 100.0);
+        assert!(opp.is_none());
+    }
+
+    #[test]
+    fn rejects_when_fees_eat_the_whole_edge_at_this_size() {
+        let fees = FeeSchedule { min_fee: 5.0, fee_rate: 0.01 };
+        // 0.10 edge per contract, but a $10 combined fee floor swamps a $1 notional
+        let opp = detect_arbitrage(&matched("KXFB-WIN"), "Will the Falcons win?", 0.40, &fees, 0.50, &fees, 1.0);
+        assert!(opp.is_none());
+    }
+
+    #[test]
+    fn rejects_unmatched_result() {
+        let mut unmatched = matched("KXFB-WIN");
+        unmatched.r#match = false;
+        let fees = FeeSchedule { min_fee: 0.01, fee_rate: 0.01 };
+        let opp = detect_arbitrage(&unmatched, "Will the Falcons win?", 0.40, &fees, 0.50, &fees, 100.0);
+        assert!(opp.is_none());
+    }
+
+    #[test]
+    fn break_even_notional_scales_with_fee_floor() {
+        let cheap_fees = FeeSchedule { min_fee: 0.01, fee_rate: 0.01 };
+        let steep_fees = FeeSchedule { min_fee: 1.0, fee_rate: 0.01 };
+        let cheap = detect_arbitrage(&matched("A"), "q", 0.40, &cheap_fees, 0.50, &cheap_fees, 1000.0).unwrap();
+        let steep = detect_arbitrage(&matched("A"), "q", 0.40, &steep_fees, 0.50, &steep_fees, 1000.0).unwrap();
+        assert!(steep.break_even_notional > cheap.break_even_notional);
+    }
+
+    fn ctx(yes: f64, no: f64) -> crate::alerts::MarketContext {
+        crate::alerts::MarketContext {
+            yes_price: yes,
+            no_price: no,
+            spread: 0.0,
+            volume_24h: 0.0,
+            open_interest: 0.0,
+            price_change_24h: 0.0,
+            liquidity: 0.0,
+            tags: Vec::new(),
+            fees: crate::alerts::Fees { maker: 0.0, taker: 0.0 },
+            precision: crate::alerts::Precision { tick_size: 0.01, lot_size: 1.0 },
+            outcomes: None,
+        }
+    }
+
+    #[test]
+    fn hybrid_router_matches_by_title_and_flags_arbitrage() {
+        let fees = FeeSchedule { min_fee: 0.01, fee_rate: 0.01 };
+        let router = HybridRouter::new(fees, 100.0);
+
+        // No match yet for the Kalshi side - nothing cached on Polymarket.
+        assert!(router
+            .record_kalshi_context("KXFED-MAR", "Fed holds rates steady in March decision", &ctx(0.40, 0.60))
+            .is_none());
+
+        // Same real-world market, different title wording on Polymarket -
+        // still enough shared expanded keywords (fed, federal, reserve,
+        // march...) to match, and the combined cost (0.40 + 0.50 = 0.90)
+        // clears the fee floor.
+        let pair = router.record_polymarket_context(
+            "poly-fed-march",
+            "Will the Fed hold rates steady at the March decision?",
+            &ctx(0.50, 0.50),
+        );
+        assert!(pair.is_some());
+        let pair = pair.unwrap();
+        assert_eq!(pair.kalshi_ticker, "KXFED-MAR");
+        assert_eq!(pair.polymarket_market, "poly-fed-march");
+        assert!(pair.opportunity.edge > 0.0);
+    }
+
+    #[test]
+    fn hybrid_router_ignores_unrelated_titles() {
+        let fees = FeeSchedule { min_fee: 0.01, fee_rate: 0.01 };
+        let router = HybridRouter::new(fees, 100.0);
+
+        router.record_kalshi_context("KXNBA-LAL", "Lakers win tonight's game", &ctx(0.40, 0.60));
+        let pair = router.record_polymarket_context(
+            "poly-weather",
+            "Will it rain in New York tomorrow?",
+            &ctx(0.50, 0.50),
+        );
+        assert!(pair.is_none());
+    }
+
+    #[test]
+    fn hybrid_router_flags_reverse_direction_with_correctly_paired_legs() {
+        let fees = FeeSchedule { min_fee: 0.01, fee_rate: 0.01 };
+        let router = HybridRouter::new(fees, 100.0);
+
+        // Forward (poly yes + kalshi no) doesn't clear: 0.60 + 0.55 > 1.0.
+        // Reverse (kalshi yes + poly no) does: 0.30 + 0.35 = 0.65.
+        router.record_kalshi_context("KXFED-MAR", "Fed holds rates steady in March decision", &ctx(0.30, 0.55));
+        let pair = router.record_polymarket_context(
+            "poly-fed-march",
+            "Will the Fed hold rates steady at the March decision?",
+            &ctx(0.60, 0.35),
+        );
+
+        assert!(pair.is_some());
+        let pair = pair.unwrap();
+        assert!(!pair.buy_yes_on_polymarket);
+        assert_eq!(pair.opportunity.leg_a.ticker, "KXFED-MAR");
+        assert_eq!(pair.opportunity.leg_a.price, 0.30);
+        assert_eq!(pair.opportunity.leg_b.ticker, "poly-fed-march");
+        assert_eq!(pair.opportunity.leg_b.price, 0.35);
+    }
+
+    #[test]
+    fn hybrid_router_respects_manual_override_even_with_no_title_overlap() {
+        let fees = FeeSchedule { min_fee: 0.01, fee_rate: 0.01 };
+        let mut router = HybridRouter::new(fees, 100.0);
+        router.overrides.insert("KXOVERRIDE".to_string(), "poly-override".to_string());
+
+        router.record_kalshi_context("KXOVERRIDE", "totally unrelated title wording here", &ctx(0.40, 0.60));
+        let pair = router.record_polymarket_context(
+            "poly-override",
+            "completely different wording with no shared keywords",
+            &ctx(0.50, 0.50),
+        );
+        assert!(pair.is_some());
+    }
+}
@@ -0,0 +1,281 @@
+//! Parses comma-separated "parlay" titles such as
+//! `"yes Michigan St.,yes Saint Peter's,no Iona wins by over 5.5 Points"`
+//! into individual legs and prices the "all legs hit" ticket from each leg's
+//! implied probability. `AlertData` otherwise treats a market's title as one
+//! opaque string, so this lives alongside `execution::arbitrage` as a second,
+//! independent source of edge rather than a change to the alert pipeline's
+//! core fields.
+
+/// Clamp used before any `ln`/`exp` so a leg quoted at or near 0/1 can't
+/// drive the joint probability or the LMSR cost to `0.0`/`inf`/`NaN`.
+const EPSILON: f64 = 1e-6;
+
+fn clamp_prob(p: f64) -> f64 {
+    p.clamp(EPSILON, 1.0 - EPSILON)
+}
+
+/// Which side of a leg's underlying market the parlay ticket requires.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LegSide {
+    Yes,
+    No,
+}
+
+/// One leg of a decomposed parlay: the side required and the underlying
+/// market's description, e.g. `(Yes, "Michigan St.")`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Leg {
+    pub side: LegSide,
+    pub description: String,
+}
+
+/// Split a parlay title into its legs. Each comma-separated segment is
+/// expected to start with a `yes `/`no ` side marker (case-insensitive);
+/// segments without one are skipped rather than guessed at. Returns an empty
+/// `Vec` for a title with no recognizable legs (e.g. an ordinary
+/// single-market title), so callers can use `is_empty()` to decide whether a
+/// title is a parlay at all.
+pub fn parse_legs(title: &str) -> Vec<Leg> {
+    title
+        .split(',')
+        .filter_map(|segment| {
+            let segment = segment.trim();
+            let lower = segment.to_lowercase();
+            if let Some(rest) = lower.strip_prefix("yes ") {
+                Some(Leg {
+                    side: LegSide::Yes,
+                    description: segment[segment.len() - rest.len()..].to_string(),
+                })
+            } else if let Some(rest) = lower.strip_prefix("no ") {
+                Some(Leg {
+                    side: LegSide::No,
+                    description: segment[segment.len() - rest.len()..].to_string(),
+                })
+            } else {
+                None
+            }
+        })
+        .collect()
+}
+
+/// Joint implied probability of every leg hitting, assuming independence:
+/// the product of each leg's (clamped) implied probability. `leg_prices[i]`
+/// is the market price of leg `i`'s required side (a Yes leg's YES price, a
+/// No leg's NO price) — the caller, not this function, resolves which side's
+/// price that is.
+pub fn joint_probability(leg_prices: &[f64]) -> f64 {
+    leg_prices.iter().map(|p| clamp_prob(*p)).product()
+}
+
+/// Expected value of paying `cost` per ticket for a parlay that pays
+/// `payout` when every leg hits: `payout * joint_probability(leg_prices) -
+/// cost`.
+pub fn joint_edge(leg_prices: &[f64], cost: f64, payout: f64) -> f64 {
+    payout * joint_probability(leg_prices) - cost
+}
+
+/// LMSR (logarithmic market scoring rule) cost of a basket of quantities
+/// `q`, `C(q) = b * ln(sum(exp(q_i / b)))`. Guards the exponential against
+/// overflow by subtracting the max exponent first:
+/// `C = m + b * ln(sum(exp((q_i - m) / b)))` where `m = max(q_i)`, the
+/// standard log-sum-exp trick — without it, a handful of large quantities
+/// pushes `exp(q_i / b)` to `inf` and the whole cost to `NaN`.
+pub fn lmsr_cost(q: &[f64], b: f64) -> f64 {
+    if q.is_empty() {
+        return 0.0;
+    }
+    let m = q.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
+    let sum: f64 = q.iter().map(|qi| ((qi - m) / b).exp()).sum();
+    m + b * sum.ln()
+}
+
+/// A parlay title decomposed into legs, paired with the joint probability of
+/// all of them hitting.
+#[derive(Debug, Clone)]
+pub struct CombinatorialMarket {
+    pub legs: Vec<Leg>,
+    pub joint_probability: f64,
+}
+
+/// Decompose `title` and compute its joint probability from `leg_prices`.
+/// Returns `None` when `title` has no parseable legs, or when `leg_prices`
+/// doesn't have exactly one price per leg — the caller supplied mismatched
+/// data rather than this being an ordinary (non-parlay) title.
+pub fn decompose(title: &str, leg_prices: &[f64]) -> Option<CombinatorialMarket> {
+    let legs = parse_legs(title);
+    if legs.is_empty() || legs.len() != leg_prices.len() {
+        return None;
+    }
+    Some(CombinatorialMarket {
+        joint_probability: joint_probability(leg_prices),
+        legs,
+    })
+}
+
+// ── N-way outcome-set mispricing ───────────────────────────────────────────
+//
+// Distinct from the parlay decomposition above: this looks across every
+// outcome of a *single* N-way event (e.g. a multi-candidate election market)
+// rather than across legs of a synthesized parlay ticket. A complete,
+// mutually-exclusive partition of one event should price to ~1.0 in total
+// best-ask implied probability; a material deviation either way is mispriced
+// rather than ordinary bid/ask spread.
+
+/// Default deviation from 100% that must be cleared before `detect_overround`
+/// flags anything. Chosen to clear typical spread on liquid election/sports
+/// books without also flagging normal two-sided quoting noise.
+pub const DEFAULT_OVERROUND_THRESHOLD: f64 = 0.02;
+
+/// Which direction an outcome set's total implied probability is mispriced.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OverroundSignal {
+    /// Total implied probability sits materially below 100% — buying every
+    /// outcome's best ask locks in a profit regardless of which one resolves.
+    DutchBook,
+    /// Total implied probability sits materially above 100% — selling/laying
+    /// every outcome locks in a profit regardless of which one resolves.
+    Overround,
+}
+
+/// A flagged mispricing across a mutually-exclusive outcome set.
+#[derive(Debug, Clone)]
+pub struct OverroundOpportunity {
+    pub signal: OverroundSignal,
+    /// Sum of every outcome's best-ask implied probability (should be ~1.0).
+    pub total_probability: f64,
+    /// `|total_probability - 1.0|` expressed in cents per $1 of total stake.
+    pub edge_cents: f64,
+}
+
+/// Sum `outcomes`' best-ask implied probabilities and flag a dutch-book or
+/// overround opportunity if the total deviates from 1.0 by more than
+/// `threshold` (e.g. `DEFAULT_OVERROUND_THRESHOLD`). Returns `None` for a
+/// set with fewer than two outcomes, or one within `threshold` of fair.
+pub fn detect_overround(
+    outcomes: &[crate::alerts::OutcomeQuote],
+    threshold: f64,
+) -> Option<OverroundOpportunity> {
+    if outcomes.len() < 2 {
+        return None;
+    }
+
+    let total_probability: f64 = outcomes.iter().map(|o| o.price).sum();
+    let deviation = total_probability - 1.0;
+    if deviation.abs() <= threshold {
+        return None;
+    }
+
+    let signal = if deviation < 0.0 {
+        OverroundSignal::DutchBook
+    } else {
+        OverroundSignal::Overround
+    };
+
+    Some(OverroundOpportunity {
+        signal,
+        total_probability,
+        edge_cents: deviation.abs() * 100.0,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PARLAY_TITLE: &str = "yes Michigan St.,yes Saint Peter's,yes Harvard wins by over 5.5 Points,no Iona wins by over 5.5 Points,no Boise St. wins by over 9.5 Points";
+
+    #[test]
+    fn parses_legs_from_the_sample_parlay_title() {
+        let legs = parse_legs(PARLAY_TITLE);
+        assert_eq!(legs.len(), 5);
+        assert_eq!(legs[0].side, LegSide::Yes);
+        assert_eq!(legs[0].description, "Michigan St.");
+        assert_eq!(legs[3].side, LegSide::No);
+        assert_eq!(legs[3].description, "Iona wins by over 5.5 Points");
+    }
+
+    #[test]
+    fn returns_empty_for_a_non_parlay_title() {
+        assert!(parse_legs("Bitcoin price on Jan 16, 2026?").is_empty());
+    }
+
+    #[test]
+    fn joint_probability_is_the_product_of_legs() {
+        let p = joint_probability(&[0.5, 0.5, 0.5]);
+        assert!((p - 0.125).abs() < 1e-9);
+    }
+
+    #[test]
+    fn joint_probability_clamps_extreme_inputs() {
+        // Without clamping this would be exactly 0.0, masking any edge.
+        let p = joint_probability(&[0.0, 1.0, 0.999999999]);
+        assert!(p > 0.0 && p.is_finite());
+    }
+
+    #[test]
+    fn joint_edge_is_positive_when_ticket_underpriced() {
+        // Three legs at 0.5 -> fair price 0.125, paying 0.05 for a $1 payout.
+        let edge = joint_edge(&[0.5, 0.5, 0.5], 0.05, 1.0);
+        assert!((edge - 0.075).abs() < 1e-9);
+    }
+
+    #[test]
+    fn lmsr_cost_does_not_overflow_on_large_quantities() {
+        let cost = lmsr_cost(&[1000.0, 999.0, 0.0], 10.0);
+        assert!(cost.is_finite());
+    }
+
+    #[test]
+    fn lmsr_cost_of_empty_basket_is_zero() {
+        assert_eq!(lmsr_cost(&[], 10.0), 0.0);
+    }
+
+    #[test]
+    fn decompose_rejects_mismatched_leg_and_price_counts() {
+        assert!(decompose(PARLAY_TITLE, &[0.5, 0.5]).is_none());
+    }
+
+    #[test]
+    fn decompose_succeeds_with_matching_counts() {
+        let market = decompose(PARLAY_TITLE, &[0.9, 0.6, 0.7, 0.8, 0.7]).unwrap();
+        assert_eq!(market.legs.len(), 5);
+        assert!(market.joint_probability > 0.0);
+    }
+
+    fn outcome(label: &str, price: f64) -> crate::alerts::OutcomeQuote {
+        crate::alerts::OutcomeQuote {
+            label: label.to_string(),
+            price,
+            volume: 0.0,
+        }
+    }
+
+    #[test]
+    fn detect_overround_flags_a_dutch_book_when_underpriced() {
+        let outcomes = vec![outcome("A", 0.30), outcome("B", 0.30), outcome("C", 0.30)];
+        let opp = detect_overround(&outcomes, DEFAULT_OVERROUND_THRESHOLD).unwrap();
+        assert_eq!(opp.signal, OverroundSignal::DutchBook);
+        assert!((opp.total_probability - 0.90).abs() < 1e-9);
+        assert!((opp.edge_cents - 10.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn detect_overround_flags_overround_when_overpriced() {
+        let outcomes = vec![outcome("A", 0.45), outcome("B", 0.45), outcome("C", 0.20)];
+        let opp = detect_overround(&outcomes, DEFAULT_OVERROUND_THRESHOLD).unwrap();
+        assert_eq!(opp.signal, OverroundSignal::Overround);
+        assert!((opp.total_probability - 1.10).abs() < 1e-9);
+    }
+
+    #[test]
+    fn detect_overround_ignores_deviation_within_threshold() {
+        let outcomes = vec![outcome("A", 0.50), outcome("B", 0.505)];
+        assert!(detect_overround(&outcomes, DEFAULT_OVERROUND_THRESHOLD).is_none());
+    }
+
+    #[test]
+    fn detect_overround_requires_at_least_two_outcomes() {
+        let outcomes = vec![outcome("A", 0.50)];
+        assert!(detect_overround(&outcomes, DEFAULT_OVERROUND_THRESHOLD).is_none());
+    }
+}
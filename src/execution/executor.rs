@@ -0,0 +1,322 @@
+use crate::execution::matcher::MatchResult;
+use rusqlite::{params, Connection};
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Mutex;
+
+// ── Types ───────────────────────────────────────────────────────────────
+
+/// Where an `ExecutableMatch`'s pair of legs stands. A pair only ever reaches
+/// `Filled` when both legs succeed — one leg succeeding alone is rolled back
+/// rather than left as a half-open position.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MatchState {
+    Pending,
+    Filled,
+    Failed,
+    RolledBack,
+}
+
+impl MatchState {
+    fn as_str(self) -> &'static str {
+        match self {
+            MatchState::Pending => "PENDING",
+            MatchState::Filled => "FILLED",
+            MatchState::Failed => "FAILED",
+            MatchState::RolledBack => "ROLLED_BACK",
+        }
+    }
+
+    fn from_str(s: &str) -> Self {
+        match s {
+            "FILLED" => MatchState::Filled,
+            "FAILED" => MatchState::Failed,
+            "ROLLED_BACK" => MatchState::RolledBack,
+            _ => MatchState::Pending,
+        }
+    }
+}
+
+/// One side of a cross-platform trade: which platform, which ticker, which
+/// side of it.
+#[derive(Debug, Clone)]
+pub struct MatchLeg {
+    pub platform: String,
+    pub ticker: String,
+    pub side: String,
+}
+
+/// A committed `MatchResult` promoted into something the executor can act
+/// on: both legs plus a lifecycle state, persisted so a restart can resume
+/// or roll back whatever was still in flight.
+#[derive(Debug, Clone)]
+pub struct ExecutableMatch {
+    pub id: Option<i64>,
+    pub source_query: String,
+    pub leg_a: MatchLeg,
+    pub leg_b: MatchLeg,
+    pub state: MatchState,
+}
+
+impl ExecutableMatch {
+    /// Build the pending pair from the Polymarket-side leg plus the `MatchResult`
+    /// a `MarketMatcher` already confirmed on the Kalshi side.
+    pub fn from_match(source_query: impl Into<String>, leg_a: MatchLeg, matched: &MatchResult) -> Self {
+        Self {
+            id: None,
+            source_query: source_query.into(),
+            leg_a,
+            leg_b: MatchLeg {
+                platform: "kalshi".to_string(),
+                ticker: matched.ticker.clone(),
+                side: matched.side.clone(),
+            },
+            state: MatchState::Pending,
+        }
+    }
+}
+
+/// Platform-specific leg execution, implemented once per platform so
+/// `commit_match` stays platform-agnostic. Returns boxed futures rather than
+/// requiring an `async-trait`-style dependency this crate doesn't have.
+pub trait LegFiller: Send + Sync {
+    fn fill<'a>(&'a self, leg: &'a MatchLeg) -> Pin<Box<dyn Future<Output = Result<(), String>> + Send + 'a>>;
+
+    /// Undo a successful fill (e.g. close the position) when the partner leg
+    /// failed, so the pair doesn't end up half-open.
+    fn unwind<'a>(&'a self, leg: &'a MatchLeg) -> Pin<Box<dyn Future<Output = Result<(), String>> + Send + 'a>>;
+}
+
+// ── Persistence ───────────────────────────────────────────────────────────
+
+/// Tracks in-flight cross-platform matches so a restart can resume or roll
+/// back whatever `commit_match` left `Pending`.
+pub struct MatchExecutor {
+    conn: Mutex<Connection>,
+}
+
+impl MatchExecutor {
+    /// Open the shared on-disk database, creating the `pending_matches`
+    /// table if needed.
+    pub fn open() -> Result<Self, Box<dyn std::error::Error>> {
+        Self::from_connection(crate::db::open_db()?)
+    }
+
+    /// Wrap an already-open connection (e.g. an in-memory one in tests).
+    pub fn from_connection(conn: Connection) -> Result<Self, Box<dyn std::error::Error>> {
+        conn.execute_batch(
+            "CREATE TABLE IF NOT EXISTS pending_matches (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                source_query TEXT NOT NULL,
+                leg_a_platform TEXT NOT NULL,
+                leg_a_ticker TEXT NOT NULL,
+                leg_a_side TEXT NOT NULL,
+                leg_b_platform TEXT NOT NULL,
+                leg_b_ticker TEXT NOT NULL,
+                leg_b_side TEXT NOT NULL,
+                state TEXT NOT NULL DEFAULT 'PENDING'
+            );",
+        )?;
+        Ok(Self { conn: Mutex::new(conn) })
+    }
+
+    fn persist(&self, m: &ExecutableMatch) -> rusqlite::Result<i64> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "INSERT INTO pending_matches
+                (source_query, leg_a_platform, leg_a_ticker, leg_a_side, leg_b_platform, leg_b_ticker, leg_b_side, state)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+            params![
+                m.source_query,
+                m.leg_a.platform,
+                m.leg_a.ticker,
+                m.leg_a.side,
+                m.leg_b.platform,
+                m.leg_b.ticker,
+                m.leg_b.side,
+                m.state.as_str(),
+            ],
+        )?;
+        Ok(conn.last_insert_rowid())
+    }
+
+    fn set_state(&self, id: i64, state: MatchState) -> rusqlite::Result<()> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "UPDATE pending_matches SET state = ?1 WHERE id = ?2",
+            params![state.as_str(), id],
+        )?;
+        Ok(())
+    }
+
+    /// Matches a prior run left `Pending` — e.g. the process was killed
+    /// between the two legs filling. Callers should resolve each of these
+    /// (check actual fill status, then roll back or re-commit) before
+    /// resuming normal operation.
+    pub fn load_resumable(&self) -> rusqlite::Result<Vec<ExecutableMatch>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT id, source_query, leg_a_platform, leg_a_ticker, leg_a_side,
+                    leg_b_platform, leg_b_ticker, leg_b_side, state
+             FROM pending_matches WHERE state = 'PENDING'",
+        )?;
+        let rows = stmt.query_map([], |row| {
+            Ok(ExecutableMatch {
+                id: Some(row.get(0)?),
+                source_query: row.get(1)?,
+                leg_a: MatchLeg {
+                    platform: row.get(2)?,
+                    ticker: row.get(3)?,
+                    side: row.get(4)?,
+                },
+                leg_b: MatchLeg {
+                    platform: row.get(5)?,
+                    ticker: row.get(6)?,
+                    side: row.get(7)?,
+                },
+                state: MatchState::from_str(&row.get::<_, String>(8)?),
+            })
+        })?;
+        rows.collect()
+    }
+}
+
+// ── Execution ─────────────────────────────────────────────────────────────
+
+/// Persist `exec_match` as `Pending`, then attempt both legs optimistically
+/// and in parallel. If one leg fails, unwind the leg that succeeded instead
+/// of leaving a half-open position. The final state is persisted before
+/// returning.
+pub async fn commit_match(
+    executor: &MatchExecutor,
+    mut exec_match: ExecutableMatch,
+    filler_a: &dyn LegFiller,
+    filler_b: &dyn LegFiller,
+) -> ExecutableMatch {
+    exec_match.id = executor.persist(&exec_match).ok();
+
+    let (result_a, result_b) =
+        tokio::join!(filler_a.fill(&exec_match.leg_a), filler_b.fill(&exec_match.leg_b));
+
+    exec_match.state = match (&result_a, &result_b) {
+        (Ok(()), Ok(())) => MatchState::Filled,
+        (Ok(()), Err(e)) => {
+            eprintln!("⚠️ Leg B failed ({}), rolling back leg A", e);
+            if let Err(e) = filler_a.unwind(&exec_match.leg_a).await {
+                eprintln!("⚠️ Failed to unwind leg A: {}", e);
+            }
+            MatchState::RolledBack
+        }
+        (Err(e), Ok(())) => {
+            eprintln!("⚠️ Leg A failed ({}), rolling back leg B", e);
+            if let Err(e) = filler_b.unwind(&exec_match.leg_b).await {
+                eprintln!("⚠️ Failed to unwind leg B: {}", e);
+            }
+            MatchState::RolledBack
+        }
+        (Err(_), Err(_)) => MatchState::Failed,
+    };
+
+    if let Some(id) = exec_match.id {
+        let _ = executor.set_state(id, exec_match.state);
+    }
+
+    exec_match
+}
+
+// ── Tests ───────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn matched(ticker: &str) -> MatchResult {
+        MatchResult {
+            r#match: true,
+            ticker: ticker.to_string(),
+            side: "yes".to_string(),
+            confidence: Some(0.96),
+            reasoning: None,
+        }
+    }
+
+    fn leg_a() -> MatchLeg {
+        MatchLeg { platform: "polymarket".to_string(), ticker: "will-falcons-win".to_string(), side: "yes".to_string() }
+    }
+
+    struct AlwaysFills;
+    impl LegFiller for AlwaysFills {
+        fn fill<'a>(&'a self, _leg: &'a MatchLeg) -> Pin<Box<dyn Future<Output = Result<(), String>> + Send + 'a>> {
+            Box::pin(async { Ok(()) })
+        }
+        fn unwind<'a>(&'a self, _leg: &'a MatchLeg) -> Pin<Box<dyn Future<Output = Result<(), String>> + Send + 'a>> {
+            Box::pin(async { Ok(()) })
+        }
+    }
+
+    struct AlwaysFails;
+    impl LegFiller for AlwaysFails {
+        fn fill<'a>(&'a self, _leg: &'a MatchLeg) -> Pin<Box<dyn Future<Output = Result<(), String>> + Send + 'a>> {
+            Box::pin(async { Err("no liquidity".to_string()) })
+        }
+        fn unwind<'a>(&'a self, _leg: &'a MatchLeg) -> Pin<Box<dyn Future<Output = Result<(), String>> + Send + 'a>> {
+            Box::pin(async { Ok(()) })
+        }
+    }
+
+    fn test_executor() -> MatchExecutor {
+        MatchExecutor::from_connection(Connection::open_in_memory().unwrap()).unwrap()
+    }
+
+    #[tokio::test]
+    async fn commit_match_fills_when_both_legs_succeed() {
+        let executor = test_executor();
+        let exec_match = ExecutableMatch::from_match("Will the Falcons win?", leg_a(), &matched("KXFB-WIN"));
+
+        let result = commit_match(&executor, exec_match, &AlwaysFills, &AlwaysFills).await;
+
+        assert_eq!(result.state, MatchState::Filled);
+    }
+
+    #[tokio::test]
+    async fn commit_match_rolls_back_when_one_leg_fails() {
+        let executor = test_executor();
+        let exec_match = ExecutableMatch::from_match("Will the Falcons win?", leg_a(), &matched("KXFB-WIN"));
+
+        let result = commit_match(&executor, exec_match, &AlwaysFills, &AlwaysFails).await;
+
+        assert_eq!(result.state, MatchState::RolledBack);
+    }
+
+    #[tokio::test]
+    async fn commit_match_fails_when_both_legs_fail() {
+        let executor = test_executor();
+        let exec_match = ExecutableMatch::from_match("Will the Falcons win?", leg_a(), &matched("KXFB-WIN"));
+
+        let result = commit_match(&executor, exec_match, &AlwaysFails, &AlwaysFails).await;
+
+        assert_eq!(result.state, MatchState::Failed);
+    }
+
+    #[tokio::test]
+    async fn committed_match_persists_final_state_for_resume() {
+        let executor = test_executor();
+        let exec_match = ExecutableMatch::from_match("Will the Falcons win?", leg_a(), &matched("KXFB-WIN"));
+
+        commit_match(&executor, exec_match, &AlwaysFills, &AlwaysFails).await;
+
+        // Rolled back, not pending, so a restart shouldn't try to resume it.
+        assert!(executor.load_resumable().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn restart_can_resume_a_still_pending_match() {
+        let executor = test_executor();
+        let exec_match = ExecutableMatch::from_match("Will the Falcons win?", leg_a(), &matched("KXFB-WIN"));
+        executor.persist(&exec_match).unwrap();
+
+        let resumable = executor.load_resumable().unwrap();
+        assert_eq!(resumable.len(), 1);
+        assert_eq!(resumable[0].leg_b.ticker, "KXFB-WIN");
+    }
+}
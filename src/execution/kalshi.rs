@@ -1,22 +1,12 @@
-use base64::{engine::general_purpose, Engine as _};
+use crate::kalshi::KalshiSigner;
 use reqwest::header::{HeaderMap, HeaderValue, CONTENT_TYPE};
-use rsa::{
-    pkcs8::DecodePrivateKey,
-    pkcs1::DecodeRsaPrivateKey,
-    pss::BlindedSigningKey,
-    sha2::Sha256,
-    signature::{RandomizedSigner, SignatureEncoding},
-    RsaPrivateKey,
-};
 use serde::{Deserialize, Serialize};
-use std::time::{SystemTime, UNIX_EPOCH};
 
 #[derive(Clone)]
 pub struct KalshiExecutor {
     client: reqwest::Client,
     base_url: String,
-    key_id: String,
-    signing_key: BlindedSigningKey<Sha256>,
+    signer: KalshiSigner,
 }
 
 #[derive(Serialize)]
@@ -50,9 +40,7 @@ struct OrderObj {
 
 impl KalshiExecutor {
     pub fn new(key_id: String, private_key_pem: &str, is_demo: bool) -> Result<Self, Box<dyn std::error::Error>> {
-        let private_key = RsaPrivateKey::from_pkcs8_pem(private_key_pem)
-            .or_else(|_| RsaPrivateKey::from_pkcs1_pem(private_key_pem))?;
-        let signing_key = BlindedSigningKey::<Sha256>::new(private_key);
+        let signer = KalshiSigner::new(key_id, private_key_pem)?;
         let base_url = if is_demo {
             "https://demo-api.kalshi.co/trade-api/v2".to_string()
         } else {
@@ -62,28 +50,15 @@ impl KalshiExecutor {
         Ok(Self {
             client: reqwest::Client::new(),
             base_url,
-            key_id,
-            signing_key,
+            signer,
         })
     }
 
-    fn sign_request(&self, method: &str, path: &str, timestamp: &str) -> Result<String, Box<dyn std::error::Error>> {
-        let msg = format!("{}{}{}", timestamp, method, path);
-        let mut rng = rand::thread_rng();
-        let signature = self.signing_key.sign_with_rng(&mut rng, msg.as_bytes());
-        Ok(general_purpose::STANDARD.encode(signature.to_bytes()))
-    }
-
     fn auth_headers(&self, method: &str, path: &str) -> Result<HeaderMap, Box<dyn std::error::Error>> {
-        let timestamp = SystemTime::now()
-            .duration_since(UNIX_EPOCH)?
-            .as_millis()
-            .to_string();
-        let signature = self.sign_request(method, path, &timestamp)?;
         let mut headers = HeaderMap::new();
-        headers.insert("KALSHI-ACCESS-KEY", HeaderValue::from_str(&self.key_id)?);
-        headers.insert("KALSHI-ACCESS-SIGNATURE", HeaderValue::from_str(&signature)?);
-        headers.insert("KALSHI-ACCESS-TIMESTAMP", HeaderValue::from_str(&timestamp)?);
+        for (name, value) in self.signer.auth_headers(method, path)? {
+            headers.insert(name, HeaderValue::from_str(&value)?);
+        }
         Ok(headers)
     }
 
@@ -264,6 +239,50 @@ impl KalshiExecutor {
         }
     }
 
+    /// Same as `place_order` but submits a `sell` action, for closing an
+    /// open position on take-profit/stop-loss/settlement rather than
+    /// opening a new one.
+    pub async fn place_exit_order(
+        &self,
+        ticker: &str,
+        side: &str,
+        count: i32,
+        price_cents: i64,
+    ) -> Result<String, Box<dyn std::error::Error>> {
+        let path = "/trade-api/v2/portfolio/orders";
+        let url = format!("{}/portfolio/orders", self.base_url);
+        let mut headers = self.auth_headers("POST", path)?;
+        headers.insert(CONTENT_TYPE, HeaderValue::from_static("application/json"));
+
+        let body = CreateOrderRequest {
+            ticker: ticker.to_string(),
+            action: "sell".to_string(),
+            order_type: "limit".to_string(),
+            side: side.to_lowercase(),
+            count,
+            yes_price: if side.to_lowercase() == "yes" { Some(price_cents) } else { None },
+            no_price: if side.to_lowercase() == "no" { Some(price_cents) } else { None },
+            client_order_id: uuid::Uuid::new_v4().to_string(),
+        };
+
+        let resp = self.client
+            .post(&url)
+            .headers(headers)
+            .json(&body)
+            .send()
+            .await?;
+
+        if resp.status().is_success() {
+            let order_resp: OrderResponse = resp.json().await?;
+            println!("✅ EXIT ORDER PLACED: {} {} @ {}c (ID: {})", side.to_uppercase(), ticker, price_cents, order_resp.order.order_id);
+            Ok(order_resp.order.order_id)
+        } else {
+            let err_text = resp.text().await?;
+            eprintln!("❌ EXIT ORDER FAILED: {}", err_text);
+            Err(format!("API Error: {}", err_text).into())
+        }
+    }
+
     /// Fetch order status. Returns (status, fill_count). Used to verify fills before counting against daily loss.
     pub async fn get_order_status(&self, order_id: &str) -> Result<(String, i32), Box<dyn std::error::Error>> {
         let path = format!("/trade-api/v2/portfolio/orders/{}", order_id);
@@ -289,4 +308,22 @@ impl KalshiExecutor {
             Err(format!("Order status check failed: {}", err_text).into())
         }
     }
+
+    /// Cancel a resting order, e.g. a stale exit that never filled before
+    /// `monitor_positions` wants to try again at a fresh price. Kalshi
+    /// reports a cancel against an already-filled/already-cancelled order as
+    /// a non-2xx, which is fine to treat as success here — either way
+    /// nothing is left resting under `order_id`.
+    pub async fn cancel_order(&self, order_id: &str) -> Result<(), Box<dyn std::error::Error>> {
+        let path = format!("/trade-api/v2/portfolio/orders/{}", order_id);
+        let url = format!("{}/portfolio/orders/{}", self.base_url, order_id);
+        let headers = self.auth_headers("DELETE", &path)?;
+
+        self.client
+            .delete(&url)
+            .headers(headers)
+            .send()
+            .await?;
+        Ok(())
+    }
 }
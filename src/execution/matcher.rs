@@ -2,10 +2,17 @@ use serde::{Deserialize, Serialize};
 use serde_json::json;
 use std::collections::HashMap;
 use std::hash::{Hash, Hasher};
+use std::sync::Arc;
 use std::time::{Duration, Instant};
 
 const DEFAULT_TOP_K: usize = 15;
 const CACHE_TTL_SECS: u64 = 3600;
+const DEFAULT_RRF_K: u32 = 60;
+/// Matches below this confidence don't go out on the broadcast feed, so a
+/// low-confidence LLM rerank doesn't spam subscribers. Mirrors
+/// `ConfidenceTier::Related`'s lower bound.
+const DEFAULT_STREAM_THRESHOLD: f64 = 0.80;
+const MATCH_EVENT_CHANNEL_CAPACITY: usize = 256;
 
 // ── Types ───────────────────────────────────────────────────────────────
 
@@ -18,6 +25,13 @@ pub struct MarketMatcher {
     embedding_index: HashMap<String, Vec<f32>>,
     match_cache: HashMap<u64, (MatchResult, Instant)>,
     cache_ttl: Duration,
+    match_strategy: MatchStrategy,
+    ranking_rules: Vec<RankingRule>,
+    fusion_mode: FusionMode,
+    rrf_k: u32,
+    match_events: tokio::sync::broadcast::Sender<MatchEvent>,
+    stream_threshold: f64,
+    tracked_queries: Vec<(String, String)>,
 }
 
 #[derive(Deserialize, Debug)]
@@ -41,6 +55,35 @@ pub struct MatchResult {
     pub reasoning: Option<String>,
 }
 
+/// Emitted on `MarketMatcher`'s broadcast feed whenever a match at or above
+/// `stream_threshold` is found, whether from a direct `match_market` call or
+/// from the background monitor re-attempting a tracked query as fresh
+/// markets appear.
+#[derive(Debug, Clone)]
+pub struct MatchEvent {
+    pub query: String,
+    pub result: MatchResult,
+    pub timestamp: chrono::DateTime<chrono::Utc>,
+}
+
+/// One source outcome resolved to a binary market on the other platform, as
+/// part of a `match_partition` result.
+#[derive(Debug, Clone, Serialize)]
+pub struct OutcomeAssignment {
+    pub outcome: String,
+    pub result: MatchResult,
+}
+
+/// The result of covering a categorical source market's outcome set with
+/// binary markets on the other platform. `assignments` is a valid partition —
+/// each ticker is claimed by at most one outcome — and `uncovered` lists the
+/// source outcomes that found no confident match and should be kept as-is.
+#[derive(Debug, Clone, Serialize)]
+pub struct PartitionResult {
+    pub assignments: Vec<OutcomeAssignment>,
+    pub uncovered: Vec<String>,
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum ConfidenceTier {
     Exact,   // >= 0.95
@@ -48,8 +91,166 @@ pub enum ConfidenceTier {
     None,    // < 0.80
 }
 
+/// Stage-1 term-matching strategy, borrowed from MeiliSearch's
+/// `TermsMatchingStrategy`. Controls how strictly `retrieve_candidates`
+/// requires expanded keywords to appear in a candidate's title.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum MatchStrategy {
+    /// Every expanded keyword must appear in the title, or the candidate is dropped.
+    All,
+    /// Require all keywords, then progressively drop the last one and re-score
+    /// until `DEFAULT_TOP_K` candidates accumulate or one keyword remains.
+    Last,
+    /// The original blended keyword + cosine score, with no hard term requirement.
+    #[default]
+    Fuzzy,
+}
+
+/// A single Stage-1 ranking criterion, modeled on MeiliSearch's ordered
+/// ranking rules: candidates are sorted by the first rule, ties broken by
+/// the second, and so on, rather than collapsed into one blended score.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RankingRule {
+    /// Fraction of query-graph node weight present in the title (see
+    /// `query_graph_score`).
+    ExactKeyword,
+    /// Cosine similarity between the query and title embeddings.
+    Embedding,
+    /// Rewards candidates whose matched keyword tokens appear close
+    /// together: `1.0 / (1.0 + min_covering_span)`.
+    Proximity,
+    /// Rewards candidates that close sooner.
+    Freshness,
+}
+
+/// How `retrieve_fuzzy` combines the keyword and embedding signals.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum FusionMode {
+    /// Sort by the configured `ranking_rules` pipeline (keyword score,
+    /// cosine, etc. compared lexicographically).
+    #[default]
+    Linear,
+    /// Reciprocal Rank Fusion: rank candidates by keyword score and by
+    /// cosine similarity independently, then combine `1/(k + rank)` from
+    /// each list. Scale-free — unlike a weighted blend, it doesn't care
+    /// whether keyword scores or cosine scores happen to have a wider
+    /// numeric spread for a given title.
+    Rrf,
+}
+
+/// Cheap, declarative candidate prefilter evaluated before the expensive
+/// keyword/embedding pipeline ever sees a candidate. Operators load a
+/// `Predicate` tree from a JSON rules file to scope matching per market
+/// domain (e.g. only sports tickers closing this week) without recompiling.
+/// Defined alongside `MarketInfo` in `platforms::kalshi`, which also uses it
+/// to filter `search_markets` results and fetched trades.
+pub use crate::platforms::kalshi::Predicate;
+
+/// How often `MarketMatcher::start_monitor`'s background task re-fetches the
+/// market set and refreshes the index.
+#[derive(Debug, Clone, Copy)]
+pub enum RefreshSchedule {
+    /// Refresh every `Duration`, regardless of wall-clock time.
+    Interval(Duration),
+    /// Refresh once a week at a fixed UTC weekday/hour (when platforms
+    /// typically list new markets), with `event_dense_interval` as a shorter
+    /// fallback cadence so event-dense days aren't stuck waiting out the rest
+    /// of the week.
+    Weekly {
+        weekday: chrono::Weekday,
+        hour: u32,
+        event_dense_interval: Duration,
+    },
+}
+
+impl RefreshSchedule {
+    /// How long the monitor should sleep before its next refresh cycle.
+    fn next_wait(&self, now: chrono::DateTime<chrono::Utc>) -> Duration {
+        match self {
+            RefreshSchedule::Interval(d) => *d,
+            RefreshSchedule::Weekly { weekday, hour, event_dense_interval } => {
+                duration_until_weekday_hour(now, *weekday, *hour).min(*event_dense_interval)
+            }
+        }
+    }
+}
+
+/// How long until the next occurrence of `weekday` at `hour:00` UTC,
+/// strictly in the future (today only counts if `hour` hasn't passed yet).
+fn duration_until_weekday_hour(
+    now: chrono::DateTime<chrono::Utc>,
+    weekday: chrono::Weekday,
+    hour: u32,
+) -> Duration {
+    use chrono::Datelike;
+
+    let target_today = now.date_naive().and_hms_opt(hour, 0, 0).unwrap().and_utc();
+    let mut days_ahead =
+        (weekday.num_days_from_monday() as i64 - now.weekday().num_days_from_monday() as i64 + 7) % 7;
+    if days_ahead == 0 && now >= target_today {
+        days_ahead = 7;
+    }
+
+    let target = (now.date_naive() + chrono::Duration::days(days_ahead))
+        .and_hms_opt(hour, 0, 0)
+        .unwrap()
+        .and_utc();
+    (target - now).to_std().unwrap_or(Duration::ZERO)
+}
+
+/// A market is stale once it closes or passes its resolution time; the
+/// monitor evicts these instead of continuing to recommend dead tickers.
+fn is_expired(market: &crate::platforms::kalshi::MarketInfo, now: chrono::DateTime<chrono::Utc>) -> bool {
+    market
+        .close_time
+        .as_deref()
+        .and_then(|s| chrono::DateTime::parse_from_rfc3339(s).ok())
+        .is_some_and(|close| close.with_timezone(&chrono::Utc) <= now)
+}
+
 // ── Pure functions (testable) ───────────────────────────────────────────
 
+/// Resolve one `match_market` result per source outcome into a valid
+/// partition: each ticker claimed by at most one outcome, with ties broken by
+/// confidence and losers (plus outcomes that matched nothing) reported as
+/// `uncovered`. Separated from `match_partition` so the combinatorial
+/// invariants are testable without a live Ollama instance.
+fn resolve_partition(
+    source_outcomes: &[String],
+    per_outcome: Vec<(String, Option<MatchResult>)>,
+) -> PartitionResult {
+    let mut claims: HashMap<String, OutcomeAssignment> = HashMap::new();
+
+    for (outcome, result) in per_outcome {
+        let Some(result) = result else { continue };
+        if !result.r#match || result.ticker.is_empty() {
+            continue;
+        }
+
+        let confidence = result.confidence.unwrap_or(0.0);
+        let better = match claims.get(&result.ticker) {
+            Some(existing) => confidence > existing.result.confidence.unwrap_or(0.0),
+            None => true,
+        };
+        if better {
+            claims.insert(result.ticker.clone(), OutcomeAssignment { outcome, result });
+        }
+    }
+
+    let covered: std::collections::HashSet<&str> =
+        claims.values().map(|a| a.outcome.as_str()).collect();
+    let uncovered: Vec<String> = source_outcomes
+        .iter()
+        .filter(|o| !covered.contains(o.as_str()))
+        .cloned()
+        .collect();
+
+    let mut assignments: Vec<OutcomeAssignment> = claims.into_values().collect();
+    assignments.sort_by(|a, b| a.outcome.cmp(&b.outcome));
+
+    PartitionResult { assignments, uncovered }
+}
+
 pub fn cosine_similarity(a: &[f32], b: &[f32]) -> f32 {
     if a.len() != b.len() || a.is_empty() {
         return 0.0;
@@ -131,31 +332,284 @@ static EXPANSIONS: &[(&str, &[&str])] = &[
     ("wizards", &["washington", "was"]),
 ];
 
-/// Expand common prediction-market abbreviations and remove stop words.
-pub fn expand_keywords(title: &str) -> Vec<String> {
-    let mut words: Vec<String> = title
+/// A relevance weight for generic query tokens vs. ones that pin down a
+/// specific real-world entity (team names, abbreviations with a known
+/// synonym mapping).
+const GENERIC_NODE_WEIGHT: f32 = 1.0;
+const ENTITY_NODE_WEIGHT: f32 = 2.0;
+
+/// One node of a query graph: a source token plus its synonym/abbreviation
+/// alternatives, in the spirit of MeiliSearch's query tree. Scoring credits
+/// at most one hit per node, so a token and its expansion (e.g. "btc" and
+/// "bitcoin") don't both count as independent keyword hits.
+#[derive(Debug, Clone)]
+pub struct QueryNode {
+    pub alternatives: Vec<String>,
+    pub weight: f32,
+}
+
+/// Tokenize `title` into a query graph: one node per non-stop-word token,
+/// each holding the token and its `EXPANSIONS` as alternatives. Nodes whose
+/// token has a known synonym/entity mapping are weighted higher than
+/// generic tokens, since they pin down a specific real-world entity rather
+/// than a filler word.
+pub fn build_query_graph(title: &str) -> Vec<QueryNode> {
+    title
         .to_lowercase()
         .split_whitespace()
         .filter(|w| w.len() > 2)
         .map(|w| w.trim_matches(|c: char| !c.is_alphanumeric()).to_string())
+        .filter(|w| !w.is_empty() && !STOP_WORDS.contains(&w.as_str()))
+        .map(|word| {
+            let expansions = EXPANSIONS.iter().find_map(|&(abbr, exp)| (abbr == word).then_some(exp));
+
+            let mut alternatives = vec![word];
+            for exp in expansions.unwrap_or(&[]) {
+                let exp = exp.to_string();
+                if !alternatives.contains(&exp) {
+                    alternatives.push(exp);
+                }
+            }
+
+            QueryNode {
+                alternatives,
+                weight: if expansions.is_some() {
+                    ENTITY_NODE_WEIGHT
+                } else {
+                    GENERIC_NODE_WEIGHT
+                },
+            }
+        })
+        .collect()
+}
+
+/// Expand common prediction-market abbreviations and remove stop words.
+/// Thin wrapper over `build_query_graph` that flattens every node's
+/// alternatives into one deduped bag, kept for the existing tests and
+/// `alias_hint`'s flat-list rendering.
+pub fn expand_keywords(title: &str) -> Vec<String> {
+    let mut words = Vec::new();
+    for node in build_query_graph(title) {
+        for alt in node.alternatives {
+            if !words.contains(&alt) {
+                words.push(alt);
+            }
+        }
+    }
+    words
+}
+
+/// A token containing a digit ("100k", "2.5") is never fuzzy-matched — only
+/// an exact match counts, so "$100k" and "$200k" markets don't collapse.
+fn is_numeric_token(token: &str) -> bool {
+    token.chars().any(|c| c.is_ascii_digit())
+}
+
+/// Edit distance allowed before a keyword is considered "too far" to be a
+/// typo of a title token, scaled by keyword length.
+fn max_edit_distance(keyword_len: usize) -> usize {
+    if keyword_len <= 4 {
+        0
+    } else if keyword_len <= 8 {
+        1
+    } else {
+        2
+    }
+}
+
+/// Bounded Levenshtein distance via a two-row DP. Returns `max_dist + 1` as
+/// soon as the cheapest distance in a row exceeds `max_dist`, so a mismatched
+/// pair bails out long before the full O(len_a·len_b) table is filled.
+fn levenshtein_distance_bounded(a: &str, b: &str, max_dist: usize) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+
+    if a.len().abs_diff(b.len()) > max_dist {
+        return max_dist + 1;
+    }
+
+    let mut prev: Vec<usize> = (0..=b.len()).collect();
+    let mut curr = vec![0usize; b.len() + 1];
+
+    for i in 1..=a.len() {
+        curr[0] = i;
+        let mut row_min = curr[0];
+        for j in 1..=b.len() {
+            let cost = if a[i - 1] == b[j - 1] { 0 } else { 1 };
+            curr[j] = (prev[j] + 1).min(curr[j - 1] + 1).min(prev[j - 1] + cost);
+            row_min = row_min.min(curr[j]);
+        }
+        if row_min > max_dist {
+            return max_dist + 1;
+        }
+        std::mem::swap(&mut prev, &mut curr);
+    }
+
+    prev[b.len()]
+}
+
+/// Score how well `keyword` is represented among `title_tokens`: 1.0 for an
+/// exact match, `1.0 - d/(len+1)` for a typo-distance `d` within the
+/// length-scaled bound, or 0.0 if nothing is close enough. Numeric keywords
+/// only ever score via exact match.
+fn keyword_hit_score(keyword: &str, title_tokens: &[&str]) -> f32 {
+    if is_numeric_token(keyword) {
+        return if title_tokens.contains(&keyword) { 1.0 } else { 0.0 };
+    }
+
+    let max_dist = max_edit_distance(keyword.len());
+    let best_distance = title_tokens
+        .iter()
+        .filter(|tok| !is_numeric_token(tok))
+        .filter_map(|tok| {
+            let d = levenshtein_distance_bounded(keyword, tok, max_dist);
+            (d <= max_dist).then_some(d)
+        })
+        .min();
+
+    match best_distance {
+        Some(d) => 1.0 - (d as f32) / (keyword.len() as f32 + 1.0),
+        None => 0.0,
+    }
+}
+
+/// Tokenize a title the same way `expand_keywords` tokenizes queries (lowercase,
+/// whitespace-split, punctuation-trimmed) so keyword hit-scoring compares like
+/// with like.
+fn tokenize_title(title_lower: &str) -> Vec<&str> {
+    title_lower
+        .split_whitespace()
+        .map(|w| w.trim_matches(|c: char| !c.is_alphanumeric()))
         .filter(|w| !w.is_empty())
-        .collect();
+        .collect()
+}
 
-    let originals = words.clone();
-    for word in &originals {
-        for &(abbr, expansions) in EXPANSIONS {
-            if word == abbr {
-                for exp in expansions {
-                    if !words.contains(&exp.to_string()) {
-                        words.push(exp.to_string());
-                    }
-                }
+/// BM25 free parameters (Robertson/Sparck Jones defaults): `k1` controls term
+/// frequency saturation, `b` controls how strongly document length is
+/// normalized against the corpus average.
+const BM25_K1: f32 = 1.2;
+const BM25_B: f32 = 0.75;
+
+/// Corpus-level statistics BM25 needs: how many documents there are, how long
+/// they are on average, and how many documents each term appears in. Built
+/// once per `keyword_filter` call over the candidate set so rare, distinctive
+/// tokens score higher than common ones shared by most candidates.
+struct Bm25Stats<'a> {
+    doc_count: usize,
+    avg_doc_len: f32,
+    doc_freq: HashMap<&'a str, usize>,
+}
+
+impl<'a> Bm25Stats<'a> {
+    fn build(doc_tokens: &[Vec<&'a str>]) -> Self {
+        let doc_count = doc_tokens.len();
+        let avg_doc_len = if doc_count == 0 {
+            0.0
+        } else {
+            doc_tokens.iter().map(|t| t.len()).sum::<usize>() as f32 / doc_count as f32
+        };
+
+        let mut doc_freq: HashMap<&'a str, usize> = HashMap::new();
+        for tokens in doc_tokens {
+            let unique: std::collections::HashSet<&'a str> = tokens.iter().copied().collect();
+            for term in unique {
+                *doc_freq.entry(term).or_insert(0) += 1;
             }
         }
+
+        Self { doc_count, avg_doc_len, doc_freq }
     }
 
-    words.retain(|w| !STOP_WORDS.contains(&w.as_str()));
-    words
+    /// Inverse document frequency: terms that appear in fewer documents score
+    /// higher, so "Lille" outweighs "win" even though "win" is the more
+    /// frequent token across the corpus.
+    fn idf(&self, term: &str) -> f32 {
+        let n_t = self.doc_freq.get(term).copied().unwrap_or(0) as f32;
+        let n = self.doc_count as f32;
+        ((n - n_t + 0.5) / (n_t + 0.5) + 1.0).ln()
+    }
+
+    /// BM25 score of a single document against the (already expanded) query
+    /// terms.
+    fn score(&self, query_terms: &[String], doc_tokens: &[&str]) -> f32 {
+        let doc_len = doc_tokens.len() as f32;
+        let avg_doc_len = self.avg_doc_len.max(1.0);
+        query_terms
+            .iter()
+            .map(|term| {
+                let f = doc_tokens.iter().filter(|t| **t == term.as_str()).count() as f32;
+                if f == 0.0 {
+                    return 0.0;
+                }
+                let idf = self.idf(term);
+                idf * (f * (BM25_K1 + 1.0))
+                    / (f + BM25_K1 * (1.0 - BM25_B + BM25_B * doc_len / avg_doc_len))
+            })
+            .sum()
+    }
+}
+
+/// Score how well a query graph is represented in a title: the fraction of
+/// total node weight whose node has at least one alternative present
+/// (typo-tolerant via `keyword_hit_score`). A node with a synonym hit and a
+/// node with its original-word hit both count once, not twice.
+fn query_graph_score(nodes: &[QueryNode], title_tokens: &[&str]) -> f32 {
+    let total_weight: f32 = nodes.iter().map(|n| n.weight).sum();
+    if total_weight <= 0.0 {
+        return 0.0;
+    }
+
+    let hit_weight: f32 = nodes
+        .iter()
+        .filter(|n| {
+            n.alternatives
+                .iter()
+                .any(|alt| keyword_hit_score(alt, title_tokens) > 0.0)
+        })
+        .map(|n| n.weight)
+        .sum();
+
+    hit_weight / total_weight
+}
+
+/// The minimum span of token positions (inclusive) covering every query
+/// node with at least one alternative present in the title, for the
+/// `Proximity` ranking rule. `None` if nothing matched.
+fn min_covering_span(nodes: &[QueryNode], title_tokens: &[&str]) -> Option<usize> {
+    let positions: Vec<usize> = title_tokens
+        .iter()
+        .enumerate()
+        .filter(|&(_, tok)| {
+            nodes.iter().any(|n| {
+                n.alternatives
+                    .iter()
+                    .any(|alt| keyword_hit_score(alt, std::slice::from_ref(tok)) > 0.0)
+            })
+        })
+        .map(|(i, _)| i)
+        .collect();
+
+    if positions.is_empty() {
+        return None;
+    }
+    let min = *positions.iter().min().unwrap();
+    let max = *positions.iter().max().unwrap();
+    Some(max - min + 1)
+}
+
+/// `Freshness` ranking rule: candidates closing sooner score higher.
+/// Candidates with no known close time score 0.0, the same as a market
+/// closing arbitrarily far in the future.
+fn freshness_score(market: &crate::platforms::kalshi::MarketInfo) -> f32 {
+    market
+        .close_time
+        .as_deref()
+        .and_then(|s| chrono::DateTime::parse_from_rfc3339(s).ok())
+        .map(|close| {
+            let days_left = (close.with_timezone(&chrono::Utc) - chrono::Utc::now()).num_days();
+            1.0 / (1.0 + days_left.max(0) as f32)
+        })
+        .unwrap_or(0.0)
 }
 
 /// Strip markdown code fences that Ollama sometimes wraps around JSON.
@@ -185,6 +639,7 @@ impl MarketMatcher {
         let base = base_url
             .unwrap_or("http://localhost:11434")
             .trim_end_matches('/');
+        let (match_events, _) = tokio::sync::broadcast::channel(MATCH_EVENT_CHANNEL_CAPACITY);
         Self {
             client: reqwest::Client::new(),
             model,
@@ -194,6 +649,81 @@ impl MarketMatcher {
             embedding_index: HashMap::new(),
             match_cache: HashMap::new(),
             cache_ttl: Duration::from_secs(CACHE_TTL_SECS),
+            match_strategy: MatchStrategy::default(),
+            ranking_rules: vec![RankingRule::ExactKeyword, RankingRule::Embedding],
+            fusion_mode: FusionMode::default(),
+            rrf_k: DEFAULT_RRF_K,
+            match_events,
+            stream_threshold: DEFAULT_STREAM_THRESHOLD,
+            tracked_queries: Vec::new(),
+        }
+    }
+
+    /// Set the Stage-1 term-matching strategy (defaults to `Fuzzy`, the
+    /// original blended keyword + cosine behavior).
+    #[allow(dead_code)]
+    pub fn with_match_strategy(mut self, strategy: MatchStrategy) -> Self {
+        self.match_strategy = strategy;
+        self
+    }
+
+    /// Set the Stage-1 ranking-rule pipeline (defaults to
+    /// `[ExactKeyword, Embedding]`, which preserves today's keyword-first
+    /// ordering). Only applies under `MatchStrategy::Fuzzy`.
+    #[allow(dead_code)]
+    pub fn with_ranking_rules(mut self, rules: Vec<RankingRule>) -> Self {
+        self.ranking_rules = rules;
+        self
+    }
+
+    /// Pick linear (ranking-rule pipeline) vs RRF fusion for `retrieve_fuzzy`
+    /// (defaults to `Linear`).
+    #[allow(dead_code)]
+    pub fn with_fusion_mode(mut self, mode: FusionMode) -> Self {
+        self.fusion_mode = mode;
+        self
+    }
+
+    /// Override RRF's `k` constant (defaults to `DEFAULT_RRF_K`). Only
+    /// meaningful under `FusionMode::Rrf`.
+    #[allow(dead_code)]
+    pub fn with_rrf_k(mut self, k: u32) -> Self {
+        self.rrf_k = k;
+        self
+    }
+
+    /// Set the minimum confidence a match needs to go out on the broadcast
+    /// feed (defaults to `DEFAULT_STREAM_THRESHOLD`).
+    #[allow(dead_code)]
+    pub fn with_stream_threshold(mut self, threshold: f64) -> Self {
+        self.stream_threshold = threshold;
+        self
+    }
+
+    /// Subscribe to the live `MatchEvent` feed: every future match at or
+    /// above `stream_threshold`, from either a direct `match_market` call or
+    /// the background monitor re-checking a tracked query. Lagging
+    /// subscribers drop the oldest unread events once the channel fills.
+    #[allow(dead_code)]
+    pub fn subscribe(&self) -> tokio::sync::broadcast::Receiver<MatchEvent> {
+        self.match_events.subscribe()
+    }
+
+    /// Register a query for the background monitor (`start_monitor`) to
+    /// re-attempt on every refresh cycle, so subscribers learn about matches
+    /// that only become possible once a new market appears.
+    #[allow(dead_code)]
+    pub fn track_query(&mut self, poly_title: impl Into<String>, poly_outcome: impl Into<String>) {
+        self.tracked_queries.push((poly_title.into(), poly_outcome.into()));
+    }
+
+    fn emit_if_confident(&self, query: &str, result: &MatchResult) {
+        if result.r#match && result.confidence.unwrap_or(0.0) >= self.stream_threshold {
+            let _ = self.match_events.send(MatchEvent {
+                query: query.to_string(),
+                result: result.clone(),
+                timestamp: chrono::Utc::now(),
+            });
         }
     }
 
@@ -264,14 +794,83 @@ impl MarketMatcher {
         self.match_cache.len()
     }
 
+    /// One refresh cycle: evict index and cache entries for markets that have
+    /// closed or dropped out of `markets`, then incrementally embed whatever
+    /// is new (`build_index` already skips tickers it has indexed).
+    #[allow(dead_code)]
+    pub async fn refresh_index(&mut self, markets: &[crate::platforms::kalshi::MarketInfo]) {
+        let now = chrono::Utc::now();
+        let live_tickers: std::collections::HashSet<&str> = markets
+            .iter()
+            .filter(|m| !is_expired(m, now))
+            .map(|m| m.ticker.as_str())
+            .collect();
+
+        self.embedding_index
+            .retain(|ticker, _| live_tickers.contains(ticker.as_str()));
+        self.match_cache
+            .retain(|_, (result, _)| result.ticker.is_empty() || live_tickers.contains(result.ticker.as_str()));
+
+        let live_markets: Vec<crate::platforms::kalshi::MarketInfo> = markets
+            .iter()
+            .filter(|m| live_tickers.contains(m.ticker.as_str()))
+            .cloned()
+            .collect();
+        self.build_index(&live_markets).await;
+
+        // Re-attempt tracked queries now that fresh markets are indexed, so
+        // subscribers hear about matches that only became possible this
+        // cycle. `match_market`'s cache means an unchanged query won't
+        // re-emit until its cache entry expires.
+        let tracked = self.tracked_queries.clone();
+        for (title, outcome) in tracked {
+            self.match_market(&title, &outcome, &live_markets, None).await;
+        }
+    }
+
+    /// Hand `self` to a spawned background task that calls `fetch_markets` on
+    /// `schedule` and runs `refresh_index` with the result, so a long-running
+    /// matcher doesn't keep recommending tickers that have since resolved.
+    /// Returns the shared handle callers use for `match_market`/`match_partition`
+    /// alongside the task's `JoinHandle`.
+    #[allow(dead_code)]
+    pub fn start_monitor<F, Fut>(
+        self,
+        fetch_markets: F,
+        schedule: RefreshSchedule,
+    ) -> (Arc<tokio::sync::Mutex<Self>>, tokio::task::JoinHandle<()>)
+    where
+        F: Fn() -> Fut + Send + 'static,
+        Fut: std::future::Future<Output = Vec<crate::platforms::kalshi::MarketInfo>> + Send,
+    {
+        let shared = Arc::new(tokio::sync::Mutex::new(self));
+        let monitored = shared.clone();
+        let handle = tokio::spawn(async move {
+            loop {
+                let wait = schedule.next_wait(chrono::Utc::now());
+                tokio::time::sleep(wait).await;
+
+                let markets = fetch_markets().await;
+                let mut matcher = monitored.lock().await;
+                matcher.refresh_index(&markets).await;
+            }
+        });
+        (shared, handle)
+    }
+
     // ── Public entry point ──────────────────────────────────────────────
 
     /// Two-stage matching: embedding retrieval → LLM rerank, with caching.
+    /// `prefilter`, when given, is applied to `candidates` before the
+    /// keyword/embedding pipeline runs, so operators can scope matching per
+    /// market domain (e.g. only this week's sports tickers) via a loaded
+    /// rules file without recompiling.
     pub async fn match_market(
         &mut self,
         poly_title: &str,
         poly_outcome: &str,
         candidates: &[crate::platforms::kalshi::MarketInfo],
+        prefilter: Option<&Predicate>,
     ) -> Option<MatchResult> {
         if candidates.is_empty() {
             return None;
@@ -286,6 +885,18 @@ impl MarketMatcher {
             }
         }
 
+        let filtered: Vec<crate::platforms::kalshi::MarketInfo>;
+        let candidates = if let Some(pred) = prefilter {
+            filtered = candidates.iter().filter(|m| pred.eval(m)).cloned().collect();
+            filtered.as_slice()
+        } else {
+            candidates
+        };
+        if candidates.is_empty() {
+            println!("⚠️ No candidates survived the prefilter for: {}", poly_title);
+            return None;
+        }
+
         // ── Stage 1: retrieve candidates ───────────────────────────────
         let shortlist = self.retrieve_candidates(poly_title, candidates).await;
         if shortlist.is_empty() {
@@ -304,11 +915,35 @@ impl MarketMatcher {
 
         if let Some(ref r) = result {
             self.match_cache.insert(key, (r.clone(), Instant::now()));
+            self.emit_if_confident(poly_title, r);
         }
 
         result
     }
 
+    /// Cover a categorical source market's `source_outcomes` with binary
+    /// markets from `candidates`, running retrieval/rerank once per outcome
+    /// via `match_market` and then resolving the combinatorial invariants:
+    /// each ticker is claimed by at most one outcome (disjoint), and outcomes
+    /// with no confident match are reported in `uncovered` rather than forced
+    /// onto a low-quality candidate. When two outcomes both resolve to the
+    /// same ticker, the higher-confidence assignment wins and the loser falls
+    /// back to uncovered.
+    #[allow(dead_code)]
+    pub async fn match_partition(
+        &mut self,
+        source_outcomes: &[String],
+        candidates: &[crate::platforms::kalshi::MarketInfo],
+        prefilter: Option<&Predicate>,
+    ) -> PartitionResult {
+        let mut per_outcome = Vec::with_capacity(source_outcomes.len());
+        for outcome in source_outcomes {
+            let result = self.match_market(outcome, "", candidates, prefilter).await;
+            per_outcome.push((outcome.clone(), result));
+        }
+        resolve_partition(source_outcomes, per_outcome)
+    }
+
     // ── Embedding helpers ───────────────────────────────────────────────
 
     async fn embed_batch(&self, texts: &[&str]) -> Option<Vec<Vec<f32>>> {
@@ -349,8 +984,8 @@ impl MarketMatcher {
         poly_title: &str,
         candidates: &'a [crate::platforms::kalshi::MarketInfo],
     ) -> Vec<&'a crate::platforms::kalshi::MarketInfo> {
+        let query_nodes = build_query_graph(poly_title);
         let keywords = expand_keywords(poly_title);
-        let max_kw = keywords.len().max(1) as f32;
 
         let query_emb = if !self.embedding_index.is_empty() {
             self.embed_single(poly_title).await
@@ -358,35 +993,133 @@ impl MarketMatcher {
             None
         };
 
-        // Score each candidate with a hybrid of keyword overlap + cosine similarity.
-        // Keyword overlap captures entity names (team, ticker, date); cosine captures
-        // semantic meaning ("BTC" vs "Bitcoin").  When embeddings are unavailable the
-        // keyword score alone drives ranking.
-        let mut scored: Vec<(&crate::platforms::kalshi::MarketInfo, f32)> = candidates
+        match self.match_strategy {
+            MatchStrategy::Fuzzy => {
+                self.retrieve_fuzzy(&query_nodes, query_emb.as_ref(), candidates)
+            }
+            MatchStrategy::All => {
+                self.retrieve_by_required_terms(&keywords, query_emb.as_ref(), candidates, false)
+            }
+            MatchStrategy::Last => {
+                self.retrieve_by_required_terms(&keywords, query_emb.as_ref(), candidates, true)
+            }
+        }
+    }
+
+    /// `MatchStrategy::Fuzzy` retrieval: dispatches to the configured
+    /// `fusion_mode` to combine the keyword and embedding signals.
+    fn retrieve_fuzzy<'a>(
+        &self,
+        query_nodes: &[QueryNode],
+        query_emb: Option<&Vec<f32>>,
+        candidates: &'a [crate::platforms::kalshi::MarketInfo],
+    ) -> Vec<&'a crate::platforms::kalshi::MarketInfo> {
+        match self.fusion_mode {
+            FusionMode::Linear => self.retrieve_by_ranking_rules(query_nodes, query_emb, candidates),
+            FusionMode::Rrf => self.retrieve_rrf(query_nodes, query_emb, candidates),
+        }
+    }
+
+    /// Score and sort candidates by `self.ranking_rules` in order — each
+    /// rule only breaks ties left by the previous one, rather than
+    /// collapsing everything into one blended float. Keeps a candidate only
+    /// if at least one rule found something.
+    fn retrieve_by_ranking_rules<'a>(
+        &self,
+        query_nodes: &[QueryNode],
+        query_emb: Option<&Vec<f32>>,
+        candidates: &'a [crate::platforms::kalshi::MarketInfo],
+    ) -> Vec<&'a crate::platforms::kalshi::MarketInfo> {
+        let mut scored: Vec<(&crate::platforms::kalshi::MarketInfo, Vec<f32>)> = candidates
             .iter()
             .map(|m| {
                 let title_lower = m.title.to_lowercase();
-                let kw_hits = keywords
+                let tokens = tokenize_title(&title_lower);
+                let scores = self
+                    .ranking_rules
                     .iter()
-                    .filter(|kw| title_lower.contains(kw.as_str()))
-                    .count() as f32;
-                let kw_score = kw_hits / max_kw; // 0..1
-
-                let emb_score = query_emb
-                    .as_ref()
-                    .and_then(|qe| {
-                        self.embedding_index
-                            .get(&m.ticker)
-                            .map(|de| cosine_similarity(qe, de))
-                    })
-                    .unwrap_or(0.0);
-
-                // Weighted combination: keywords dominate when entity names exist;
-                // embeddings help when keywords miss (abbreviation gaps, synonyms).
-                let combined = 0.6 * kw_score + 0.4 * emb_score;
-                (m, combined)
+                    .map(|rule| self.score_ranking_rule(*rule, m, query_nodes, &tokens, query_emb))
+                    .collect();
+                (m, scores)
+            })
+            .filter(|(_, scores)| scores.iter().any(|&s| s > 0.01))
+            .collect();
+
+        scored.sort_by(|a, b| {
+            for (sa, sb) in a.1.iter().zip(b.1.iter()) {
+                match sb.partial_cmp(sa).unwrap_or(std::cmp::Ordering::Equal) {
+                    std::cmp::Ordering::Equal => continue,
+                    ord => return ord,
+                }
+            }
+            std::cmp::Ordering::Equal
+        });
+
+        scored
+            .into_iter()
+            .take(DEFAULT_TOP_K)
+            .map(|(m, _)| m)
+            .collect()
+    }
+
+    /// Reciprocal Rank Fusion: rank candidates by keyword score and by
+    /// cosine similarity independently, then combine
+    /// `1/(k + rank_keyword) + 1/(k + rank_cosine)`. A candidate missing
+    /// from one of the two ranked lists simply contributes 0 for that term.
+    fn retrieve_rrf<'a>(
+        &self,
+        query_nodes: &[QueryNode],
+        query_emb: Option<&Vec<f32>>,
+        candidates: &'a [crate::platforms::kalshi::MarketInfo],
+    ) -> Vec<&'a crate::platforms::kalshi::MarketInfo> {
+        let mut by_keyword: Vec<(&str, f32)> = candidates
+            .iter()
+            .map(|m| {
+                let title_lower = m.title.to_lowercase();
+                let tokens = tokenize_title(&title_lower);
+                (m.ticker.as_str(), query_graph_score(query_nodes, &tokens))
+            })
+            .filter(|(_, score)| *score > 0.0)
+            .collect();
+        by_keyword.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
+
+        let mut by_cosine: Vec<(&str, f32)> = candidates
+            .iter()
+            .filter_map(|m| {
+                let score = query_emb.and_then(|qe| {
+                    self.embedding_index
+                        .get(&m.ticker)
+                        .map(|de| cosine_similarity(qe, de))
+                })?;
+                (score > 0.0).then_some((m.ticker.as_str(), score))
+            })
+            .collect();
+        by_cosine.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
+
+        let keyword_rank: HashMap<&str, usize> = by_keyword
+            .iter()
+            .enumerate()
+            .map(|(i, (ticker, _))| (*ticker, i + 1))
+            .collect();
+        let cosine_rank: HashMap<&str, usize> = by_cosine
+            .iter()
+            .enumerate()
+            .map(|(i, (ticker, _))| (*ticker, i + 1))
+            .collect();
+
+        let k = self.rrf_k as f32;
+        let mut scored: Vec<(&crate::platforms::kalshi::MarketInfo, f32)> = candidates
+            .iter()
+            .filter_map(|m| {
+                let kw_rank = keyword_rank.get(m.ticker.as_str());
+                let cos_rank = cosine_rank.get(m.ticker.as_str());
+                if kw_rank.is_none() && cos_rank.is_none() {
+                    return None;
+                }
+                let rrf = kw_rank.map(|&r| 1.0 / (k + r as f32)).unwrap_or(0.0)
+                    + cos_rank.map(|&r| 1.0 / (k + r as f32)).unwrap_or(0.0);
+                Some((m, rrf))
             })
-            .filter(|(_, score)| *score > 0.01)
             .collect();
 
         scored.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
@@ -397,6 +1130,91 @@ impl MarketMatcher {
             .collect()
     }
 
+    /// Evaluate one `RankingRule` for a candidate, given its lowercased
+    /// title tokens.
+    fn score_ranking_rule(
+        &self,
+        rule: RankingRule,
+        market: &crate::platforms::kalshi::MarketInfo,
+        query_nodes: &[QueryNode],
+        title_tokens: &[&str],
+        query_emb: Option<&Vec<f32>>,
+    ) -> f32 {
+        match rule {
+            RankingRule::ExactKeyword => query_graph_score(query_nodes, title_tokens),
+            RankingRule::Embedding => query_emb
+                .and_then(|qe| {
+                    self.embedding_index
+                        .get(&market.ticker)
+                        .map(|de| cosine_similarity(qe, de))
+                })
+                .unwrap_or(0.0),
+            RankingRule::Proximity => min_covering_span(query_nodes, title_tokens)
+                .map(|span| 1.0 / (1.0 + span as f32))
+                .unwrap_or(0.0),
+            RankingRule::Freshness => freshness_score(market),
+        }
+    }
+
+    /// `MatchStrategy::All` / `MatchStrategy::Last` retrieval: a candidate
+    /// only survives a tier if every keyword still required at that tier
+    /// appears in its title. With `progressive`, the last keyword is dropped
+    /// and the remaining candidates re-scored until `DEFAULT_TOP_K` accumulate
+    /// or one keyword remains; with `All` only the full-keyword tier runs.
+    /// Tier (more required terms matched first) ranks above tier; cosine
+    /// similarity breaks ties within a tier.
+    fn retrieve_by_required_terms<'a>(
+        &self,
+        keywords: &[String],
+        query_emb: Option<&Vec<f32>>,
+        candidates: &'a [crate::platforms::kalshi::MarketInfo],
+        progressive: bool,
+    ) -> Vec<&'a crate::platforms::kalshi::MarketInfo> {
+        let emb_score = |m: &crate::platforms::kalshi::MarketInfo| {
+            query_emb
+                .and_then(|qe| {
+                    self.embedding_index
+                        .get(&m.ticker)
+                        .map(|de| cosine_similarity(qe, de))
+                })
+                .unwrap_or(0.0)
+        };
+
+        let mut required: Vec<&str> = keywords.iter().map(|s| s.as_str()).collect();
+        let mut seen = std::collections::HashSet::new();
+        let mut tiered: Vec<(&'a crate::platforms::kalshi::MarketInfo, usize, f32)> = Vec::new();
+
+        loop {
+            let tier = required.len();
+            for m in candidates {
+                if seen.contains(&m.ticker) {
+                    continue;
+                }
+                let title_lower = m.title.to_lowercase();
+                let tokens = tokenize_title(&title_lower);
+                if required.iter().all(|kw| keyword_hit_score(*kw, &tokens) > 0.0) {
+                    seen.insert(m.ticker.clone());
+                    tiered.push((m, tier, emb_score(m)));
+                }
+            }
+
+            if !progressive || tiered.len() >= DEFAULT_TOP_K || required.len() <= 1 {
+                break;
+            }
+            required.pop();
+        }
+
+        tiered.sort_by(|a, b| {
+            b.1.cmp(&a.1)
+                .then_with(|| b.2.partial_cmp(&a.2).unwrap_or(std::cmp::Ordering::Equal))
+        });
+        tiered
+            .into_iter()
+            .take(DEFAULT_TOP_K)
+            .map(|(m, _, _)| m)
+            .collect()
+    }
+
     // ── Stage 2: LLM rerank ────────────────────────────────────────────
 
     async fn llm_rerank(
@@ -515,6 +1333,9 @@ Output ONLY valid JSON:
 
 // ── Keyword filter (standalone for fallback and tests) ──────────────────
 
+/// Rank candidates by BM25 over the expanded query keywords rather than raw
+/// overlap counts, so a rare distinctive token (a team name, a specific
+/// ticker) outweighs a term that shows up in most of the corpus.
 #[allow(dead_code)]
 pub fn keyword_filter<'a>(
     poly_title: &str,
@@ -525,20 +1346,18 @@ pub fn keyword_filter<'a>(
         return candidates.iter().take(DEFAULT_TOP_K).collect();
     }
 
-    let mut scored: Vec<(&crate::platforms::kalshi::MarketInfo, usize)> = candidates
+    let titles_lower: Vec<String> = candidates.iter().map(|c| c.title.to_lowercase()).collect();
+    let doc_tokens: Vec<Vec<&str>> = titles_lower.iter().map(|t| tokenize_title(t)).collect();
+    let stats = Bm25Stats::build(&doc_tokens);
+
+    let mut scored: Vec<(&crate::platforms::kalshi::MarketInfo, f32)> = candidates
         .iter()
-        .map(|c| {
-            let title_lower = c.title.to_lowercase();
-            let count = keywords
-                .iter()
-                .filter(|kw| title_lower.contains(kw.as_str()))
-                .count();
-            (c, count)
-        })
-        .filter(|(_, count)| *count > 0)
+        .zip(doc_tokens.iter())
+        .map(|(c, tokens)| (c, stats.score(&keywords, tokens)))
+        .filter(|(_, score)| *score > 0.0)
         .collect();
 
-    scored.sort_by(|a, b| b.1.cmp(&a.1));
+    scored.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
     scored
         .into_iter()
         .take(DEFAULT_TOP_K + 10)
@@ -668,6 +1487,95 @@ mod tests {
         assert!(words.contains(&"100".to_string()));
     }
 
+    // ── build_query_graph / query_graph_score ───────────────────────────
+
+    #[test]
+    fn query_graph_gives_entity_nodes_higher_weight() {
+        let nodes = build_query_graph("Will BTC reach $100k?");
+        let btc_node = nodes.iter().find(|n| n.alternatives.contains(&"btc".to_string())).unwrap();
+        let numeric_node = nodes.iter().find(|n| n.alternatives.contains(&"100k".to_string())).unwrap();
+        assert!(btc_node.weight > numeric_node.weight);
+        assert!(btc_node.alternatives.contains(&"bitcoin".to_string()));
+    }
+
+    #[test]
+    fn query_graph_score_credits_at_most_one_hit_per_node() {
+        let nodes = build_query_graph("Will BTC reach $100k?");
+        // Title repeats both the abbreviation and its expansion; the node
+        // should still only count once, not twice.
+        let tokens = tokenize_title("bitcoin btc reach 100k");
+        let score = query_graph_score(&nodes, &tokens);
+        assert!((score - 1.0).abs() < 1e-5, "expected full score, got {score}");
+    }
+
+    #[test]
+    fn query_graph_score_zero_when_nothing_matches() {
+        let nodes = build_query_graph("Will BTC reach $100k?");
+        let tokens = tokenize_title("ethereum staking rewards");
+        assert_eq!(query_graph_score(&nodes, &tokens), 0.0);
+    }
+
+    // ── levenshtein_distance_bounded / keyword_hit_score ────────────────
+
+    #[test]
+    fn levenshtein_identical_strings_is_zero() {
+        assert_eq!(levenshtein_distance_bounded("warriors", "warriors", 2), 0);
+    }
+
+    #[test]
+    fn levenshtein_single_substitution() {
+        assert_eq!(levenshtein_distance_bounded("sixers", "sixer5", 2), 1);
+    }
+
+    #[test]
+    fn levenshtein_exceeding_bound_returns_sentinel() {
+        // "warriors" vs "celtics" is nowhere near within distance 2.
+        assert_eq!(
+            levenshtein_distance_bounded("warriors", "celtics", 2),
+            3,
+            "should bail out at max_dist + 1"
+        );
+    }
+
+    #[test]
+    fn levenshtein_length_gap_shortcuts_without_scanning() {
+        assert_eq!(levenshtein_distance_bounded("a", "abcdef", 1), 2);
+    }
+
+    #[test]
+    fn max_edit_distance_scales_with_keyword_length() {
+        assert_eq!(max_edit_distance(3), 0);
+        assert_eq!(max_edit_distance(4), 0);
+        assert_eq!(max_edit_distance(5), 1);
+        assert_eq!(max_edit_distance(8), 1);
+        assert_eq!(max_edit_distance(9), 2);
+    }
+
+    #[test]
+    fn keyword_hit_score_exact_match_is_one() {
+        assert_eq!(keyword_hit_score("sixers", &["philadelphia", "sixers"]), 1.0);
+    }
+
+    #[test]
+    fn keyword_hit_score_typo_scores_less_than_one() {
+        // "76ers" vs "sixers" isn't a typo of each other, but a trailing
+        // plural drop ("sixer" vs "sixers") is within the length-scaled bound.
+        let score = keyword_hit_score("sixers", &["sixer"]);
+        assert!(score > 0.0 && score < 1.0, "expected a partial score, got {}", score);
+    }
+
+    #[test]
+    fn keyword_hit_score_no_match_is_zero() {
+        assert_eq!(keyword_hit_score("sixers", &["celtics", "warriors"]), 0.0);
+    }
+
+    #[test]
+    fn keyword_hit_score_numeric_requires_exact_match() {
+        assert_eq!(keyword_hit_score("100k", &["100k"]), 1.0);
+        // "100k" vs "200k" is distance 1 but numeric tokens never get fuzzy credit.
+        assert_eq!(keyword_hit_score("100k", &["200k"]), 0.0);
+    }
+
     // ── strip_json_fences ───────────────────────────────────────────────
 
     #[test]
@@ -726,6 +1634,9 @@ mod tests {
             title: title.to_string(),
             category: None,
             tags: vec![],
+            close_time: None,
+            status: None,
+            yes_price: None,
         }
     }
 
@@ -780,6 +1691,469 @@ mod tests {
         );
     }
 
+    #[test]
+    fn keyword_filter_weighs_rare_terms_over_common_ones() {
+        // "win" appears in every candidate title; "lille" appears in only one.
+        // BM25's IDF term should make the Lille match outrank the others even
+        // though every title shares the word "win".
+        let markets = vec![
+            make_market("A", "Will France win the match"),
+            make_market("B", "Will Germany win the match"),
+            make_market("C", "Will Lille win the match"),
+        ];
+        let results = keyword_filter("Lille win", &markets);
+        assert_eq!(results[0].ticker, "C");
+    }
+
+    // ── MatchStrategy ───────────────────────────────────────────────────
+    // Query keywords below avoid the NBA/crypto EXPANSIONS table so the
+    // required-term set matches exactly what's typed (lille, osc, win,
+    // february, 14), keeping the assertions straightforward.
+
+    #[tokio::test]
+    async fn match_strategy_all_requires_every_keyword() {
+        let matcher = MarketMatcher::new("llama3".into(), "nomic-embed-text".into(), None)
+            .with_match_strategy(MatchStrategy::All);
+
+        let markets = vec![
+            make_market("A", "Lille OSC to win on February 14"),
+            make_market("B", "Lille OSC vs Monaco in February"),
+        ];
+
+        let candidates = matcher
+            .retrieve_candidates("Will Lille OSC win on February 14?", &markets)
+            .await;
+
+        assert_eq!(candidates.len(), 1);
+        assert_eq!(candidates[0].ticker, "A");
+    }
+
+    #[tokio::test]
+    async fn match_strategy_all_drops_candidates_missing_any_keyword() {
+        let matcher = MarketMatcher::new("llama3".into(), "nomic-embed-text".into(), None)
+            .with_match_strategy(MatchStrategy::All);
+
+        let markets = vec![make_market("A", "Lille OSC vs Monaco in February")];
+
+        let candidates = matcher
+            .retrieve_candidates("Will Lille OSC win on February 14?", &markets)
+            .await;
+
+        assert!(candidates.is_empty());
+    }
+
+    #[tokio::test]
+    async fn match_strategy_last_relaxes_until_candidates_accumulate() {
+        let matcher = MarketMatcher::new("llama3".into(), "nomic-embed-text".into(), None)
+            .with_match_strategy(MatchStrategy::Last);
+
+        // Missing "14", so the full 5-keyword tier matches nothing; `Last`
+        // should drop it and re-score against the remaining 4 keywords.
+        let markets = vec![make_market("A", "Lille OSC win expected in February")];
+
+        let candidates = matcher
+            .retrieve_candidates("Will Lille OSC win on February 14?", &markets)
+            .await;
+
+        assert!(
+            !candidates.is_empty(),
+            "Last strategy should relax until it finds candidates"
+        );
+    }
+
+    #[tokio::test]
+    async fn match_strategy_last_ranks_more_matched_terms_first() {
+        let matcher = MarketMatcher::new("llama3".into(), "nomic-embed-text".into(), None)
+            .with_match_strategy(MatchStrategy::Last);
+
+        let markets = vec![
+            make_market("PARTIAL", "Lille OSC win expected in February"),
+            make_market("FULL", "Lille OSC to win on February 14"),
+        ];
+
+        let candidates = matcher
+            .retrieve_candidates("Will Lille OSC win on February 14?", &markets)
+            .await;
+
+        assert_eq!(
+            candidates[0].ticker, "FULL",
+            "Candidate matching all keywords should rank above a partial match"
+        );
+    }
+
+    // ── Predicate ────────────────────────────────────────────────────────
+
+    #[test]
+    fn predicate_category_equals_is_case_insensitive() {
+        let mut m = make_market("A", "Lille OSC to win");
+        m.category = Some("Sports".into());
+        assert!(Predicate::CategoryEquals("sports".into()).eval(&m));
+        assert!(!Predicate::CategoryEquals("politics".into()).eval(&m));
+    }
+
+    #[test]
+    fn predicate_ticker_prefix_is_case_insensitive() {
+        let m = make_market("KXFB-26FEB14-LILLEWIN", "Lille OSC to win");
+        assert!(Predicate::TickerPrefix("kxfb".into()).eval(&m));
+        assert!(!Predicate::TickerPrefix("kxpol".into()).eval(&m));
+    }
+
+    #[test]
+    fn predicate_title_contains_is_case_insensitive() {
+        let m = make_market("A", "Lille OSC to win on February 14");
+        assert!(Predicate::TitleMatches("LILLE OSC".into()).eval(&m));
+        assert!(!Predicate::TitleMatches("Monaco".into()).eval(&m));
+    }
+
+    #[test]
+    fn predicate_closes_within_days_checks_close_time() {
+        let mut soon = make_market("A", "Closes soon");
+        soon.close_time = Some((chrono::Utc::now() + chrono::Duration::days(2)).to_rfc3339());
+        let mut far = make_market("B", "Closes later");
+        far.close_time = Some((chrono::Utc::now() + chrono::Duration::days(30)).to_rfc3339());
+        let unknown = make_market("C", "No close time");
+
+        let pred = Predicate::ClosesWithinDays(7);
+        assert!(pred.eval(&soon));
+        assert!(!pred.eval(&far));
+        assert!(!pred.eval(&unknown));
+    }
+
+    #[test]
+    fn predicate_not_inverts() {
+        let m = make_market("A", "Lille OSC to win");
+        assert!(Predicate::Not(Box::new(Predicate::TitleMatches("Monaco".into()))).eval(&m));
+    }
+
+    #[test]
+    fn predicate_any_of_and_all_of_combinators() {
+        let m = make_market("KXFB-26FEB14-LILLEWIN", "Lille OSC to win");
+
+        let any = Predicate::AnyOf(vec![
+            Predicate::TitleMatches("Monaco".into()),
+            Predicate::TickerPrefix("kxfb".into()),
+        ]);
+        assert!(any.eval(&m));
+
+        let all = Predicate::AllOf(vec![
+            Predicate::TitleMatches("Lille".into()),
+            Predicate::TickerPrefix("kxpol".into()),
+        ]);
+        assert!(!all.eval(&m));
+    }
+
+    #[test]
+    fn predicate_deserializes_from_tagged_json_rules_file() {
+        let json = r#"{"type": "AllOf", "args": [
+            {"type": "TickerPrefix", "args": "KXFB"},
+            {"type": "ClosesWithinDays", "args": 7}
+        ]}"#;
+        let pred: Predicate = serde_json::from_str(json).expect("valid rules file");
+        match pred {
+            Predicate::AllOf(preds) => assert_eq!(preds.len(), 2),
+            other => panic!("expected AllOf, got {:?}", other),
+        }
+    }
+
+    // ── Monitor / refresh ────────────────────────────────────────────────
+
+    #[test]
+    fn is_expired_true_once_close_time_passes() {
+        let mut closed = make_market("A", "Resolved market");
+        closed.close_time = Some((chrono::Utc::now() - chrono::Duration::hours(1)).to_rfc3339());
+        let mut open = make_market("B", "Still open");
+        open.close_time = Some((chrono::Utc::now() + chrono::Duration::hours(1)).to_rfc3339());
+        let unknown = make_market("C", "No close time");
+
+        let now = chrono::Utc::now();
+        assert!(is_expired(&closed, now));
+        assert!(!is_expired(&open, now));
+        assert!(!is_expired(&unknown, now));
+    }
+
+    #[tokio::test]
+    async fn refresh_index_evicts_expired_markets_and_cache_entries() {
+        let mut matcher = MarketMatcher::new("llama3".into(), "nomic-embed-text".into(), None);
+        matcher.embedding_index.insert("STALE".into(), vec![1.0, 0.0]);
+        matcher.embedding_index.insert("FRESH".into(), vec![0.0, 1.0]);
+        matcher.match_cache.insert(
+            cache_key("stale query", "yes"),
+            (
+                MatchResult {
+                    r#match: true,
+                    ticker: "STALE".into(),
+                    side: "yes".into(),
+                    confidence: Some(0.9),
+                    reasoning: None,
+                },
+                Instant::now(),
+            ),
+        );
+
+        let mut fresh = make_market("FRESH", "Still open");
+        fresh.close_time = Some((chrono::Utc::now() + chrono::Duration::days(1)).to_rfc3339());
+
+        matcher.refresh_index(&[fresh]).await;
+
+        assert_eq!(matcher.index_size(), 1);
+        assert!(matcher.embedding_index.contains_key("FRESH"));
+        assert_eq!(matcher.cache_size(), 0);
+    }
+
+    #[test]
+    fn refresh_schedule_interval_returns_fixed_duration() {
+        let schedule = RefreshSchedule::Interval(Duration::from_secs(300));
+        assert_eq!(schedule.next_wait(chrono::Utc::now()), Duration::from_secs(300));
+    }
+
+    #[test]
+    fn duration_until_weekday_hour_picks_next_occurrence_not_today() {
+        use chrono::Datelike;
+        // Anchor on a Wednesday 12:00 UTC; asking for Wednesday 09:00 (already
+        // passed today) should land 7 days out, not "zero" or negative.
+        let wednesday_noon = chrono::DateTime::parse_from_rfc3339("2026-07-29T12:00:00Z")
+            .unwrap()
+            .with_timezone(&chrono::Utc);
+        assert_eq!(wednesday_noon.weekday(), chrono::Weekday::Wed);
+
+        let wait = duration_until_weekday_hour(wednesday_noon, chrono::Weekday::Wed, 9);
+        assert_eq!(wait, Duration::from_secs(7 * 24 * 3600 - 3 * 3600));
+    }
+
+    #[test]
+    fn duration_until_weekday_hour_same_day_still_ahead() {
+        let wednesday_noon = chrono::DateTime::parse_from_rfc3339("2026-07-29T12:00:00Z")
+            .unwrap()
+            .with_timezone(&chrono::Utc);
+
+        let wait = duration_until_weekday_hour(wednesday_noon, chrono::Weekday::Wed, 15);
+        assert_eq!(wait, Duration::from_secs(3 * 3600));
+    }
+
+    #[test]
+    fn refresh_schedule_weekly_uses_shorter_of_weekly_and_event_dense() {
+        let schedule = RefreshSchedule::Weekly {
+            weekday: chrono::Weekday::Sun,
+            hour: 15,
+            event_dense_interval: Duration::from_secs(600),
+        };
+        // From Wednesday noon the next Sunday 15:00 is days away, so the
+        // event-dense interval (10 min) is the shorter wait and wins.
+        let wednesday_noon = chrono::DateTime::parse_from_rfc3339("2026-07-29T12:00:00Z")
+            .unwrap()
+            .with_timezone(&chrono::Utc);
+        assert_eq!(schedule.next_wait(wednesday_noon), Duration::from_secs(600));
+    }
+
+    // ── MatchEvent / streaming ───────────────────────────────────────────
+
+    fn mk_match(ticker: &str, confidence: f64) -> MatchResult {
+        MatchResult {
+            r#match: true,
+            ticker: ticker.to_string(),
+            side: "yes".into(),
+            confidence: Some(confidence),
+            reasoning: None,
+        }
+    }
+
+    #[test]
+    fn subscribe_receives_confident_match_event() {
+        let matcher = MarketMatcher::new("llama3".into(), "nomic-embed-text".into(), None)
+            .with_stream_threshold(0.80);
+        let mut rx = matcher.subscribe();
+
+        matcher.emit_if_confident("Will Lille OSC win?", &mk_match("KXFB-WIN", 0.95));
+
+        let event = rx.try_recv().expect("should have received a match event");
+        assert_eq!(event.query, "Will Lille OSC win?");
+        assert_eq!(event.result.ticker, "KXFB-WIN");
+    }
+
+    #[test]
+    fn stream_threshold_filters_low_confidence_matches() {
+        let matcher = MarketMatcher::new("llama3".into(), "nomic-embed-text".into(), None)
+            .with_stream_threshold(0.90);
+        let mut rx = matcher.subscribe();
+
+        matcher.emit_if_confident("Will Lille OSC win?", &mk_match("KXFB-WIN", 0.81));
+
+        assert!(rx.try_recv().is_err(), "below-threshold match should not be emitted");
+    }
+
+    #[test]
+    fn emit_if_confident_ignores_non_matches() {
+        let matcher = MarketMatcher::new("llama3".into(), "nomic-embed-text".into(), None);
+        let mut rx = matcher.subscribe();
+
+        let mut no_match = mk_match("", 0.99);
+        no_match.r#match = false;
+        matcher.emit_if_confident("Will Lille OSC win?", &no_match);
+
+        assert!(rx.try_recv().is_err());
+    }
+
+    #[test]
+    fn track_query_registers_for_monitor_replay() {
+        let mut matcher = MarketMatcher::new("llama3".into(), "nomic-embed-text".into(), None);
+        matcher.track_query("Will Lille OSC win?", "Yes");
+        assert_eq!(matcher.tracked_queries.len(), 1);
+        assert_eq!(matcher.tracked_queries[0].0, "Will Lille OSC win?");
+    }
+
+    // ── RankingRule ──────────────────────────────────────────────────────
+
+    #[tokio::test]
+    async fn ranking_rules_default_prefers_keyword_match() {
+        let matcher = MarketMatcher::new("llama3".into(), "nomic-embed-text".into(), None);
+
+        let markets = vec![
+            make_market("KEYWORD", "Lille OSC to win on February 14"),
+            make_market("UNRELATED", "Fed interest rate decision"),
+        ];
+
+        let candidates = matcher
+            .retrieve_candidates("Will Lille OSC win on February 14?", &markets)
+            .await;
+
+        assert_eq!(candidates[0].ticker, "KEYWORD");
+    }
+
+    #[tokio::test]
+    async fn ranking_rules_proximity_favors_tighter_span() {
+        let matcher = MarketMatcher::new("llama3".into(), "nomic-embed-text".into(), None)
+            .with_ranking_rules(vec![RankingRule::Proximity]);
+
+        let markets = vec![
+            make_market("TIGHT", "Lille OSC win"),
+            make_market("LOOSE", "Lille fans expect a tough but winnable OSC game"),
+        ];
+
+        let candidates = matcher
+            .retrieve_candidates("Lille OSC win", &markets)
+            .await;
+
+        assert_eq!(
+            candidates[0].ticker, "TIGHT",
+            "matched tokens packed closer together should rank first"
+        );
+    }
+
+    #[test]
+    fn ranking_rule_freshness_prefers_sooner_close() {
+        let mut soon = make_market("SOON", "Soon market");
+        soon.close_time = Some((chrono::Utc::now() + chrono::Duration::days(1)).to_rfc3339());
+        let mut later = make_market("LATER", "Later market");
+        later.close_time = Some((chrono::Utc::now() + chrono::Duration::days(30)).to_rfc3339());
+        let unknown = make_market("UNKNOWN", "Unknown close market");
+
+        assert!(freshness_score(&soon) > freshness_score(&later));
+        assert_eq!(freshness_score(&unknown), 0.0);
+    }
+
+    #[test]
+    fn min_covering_span_is_none_when_nothing_matches() {
+        let nodes = build_query_graph("Will BTC reach $100k?");
+        let tokens = tokenize_title("ethereum staking rewards");
+        assert_eq!(min_covering_span(&nodes, &tokens), None);
+    }
+
+    // ── FusionMode / RRF ─────────────────────────────────────────────────
+
+    #[test]
+    fn rrf_ranks_above_a_single_list_hit() {
+        let mut matcher = MarketMatcher::new("llama3".into(), "nomic-embed-text".into(), None)
+            .with_fusion_mode(FusionMode::Rrf);
+
+        let markets = vec![
+            make_market("BOTH", "Lille OSC win"),
+            make_market("KEYWORD_ONLY", "Lille OSC vs Monaco in February"),
+        ];
+        // BOTH is indexed and points straight at the query embedding, so it
+        // ranks #1 in the cosine list too; KEYWORD_ONLY is absent from the
+        // embedding index and only shows up in the keyword-ranked list.
+        matcher.embedding_index.insert("BOTH".into(), vec![1.0, 0.0]);
+
+        let query_nodes = build_query_graph("Lille OSC win");
+        let query_emb = vec![1.0, 0.0];
+        let candidates = matcher.retrieve_fuzzy(&query_nodes, Some(&query_emb), &markets);
+
+        assert_eq!(
+            candidates[0].ticker, "BOTH",
+            "a candidate ranked in both lists should beat one ranked in only one"
+        );
+    }
+
+    #[test]
+    fn rrf_k_constant_dampens_rank_contribution() {
+        let k_small = 1.0_f32;
+        let k_large = 60.0_f32;
+        let rank = 1.0_f32;
+        assert!(1.0 / (k_small + rank) > 1.0 / (k_large + rank));
+    }
+
+    // ── Partition matching ───────────────────────────────────────────────
+
+    fn mk_result(ticker: &str, confidence: f64) -> MatchResult {
+        MatchResult {
+            r#match: true,
+            ticker: ticker.to_string(),
+            side: "yes".to_string(),
+            confidence: Some(confidence),
+            reasoning: None,
+        }
+    }
+
+    #[test]
+    fn resolve_partition_covers_disjoint_outcomes() {
+        let outcomes = vec!["Player A".to_string(), "Player B".to_string()];
+        let per_outcome = vec![
+            ("Player A".to_string(), Some(mk_result("MVP-A", 0.9))),
+            ("Player B".to_string(), Some(mk_result("MVP-B", 0.85))),
+        ];
+        let result = resolve_partition(&outcomes, per_outcome);
+        assert!(result.uncovered.is_empty());
+        assert_eq!(result.assignments.len(), 2);
+    }
+
+    #[test]
+    fn resolve_partition_reports_uncovered_remainder() {
+        let outcomes = vec!["Player A".to_string(), "Player C".to_string()];
+        let per_outcome = vec![
+            ("Player A".to_string(), Some(mk_result("MVP-A", 0.9))),
+            ("Player C".to_string(), None),
+        ];
+        let result = resolve_partition(&outcomes, per_outcome);
+        assert_eq!(result.assignments.len(), 1);
+        assert_eq!(result.uncovered, vec!["Player C".to_string()]);
+    }
+
+    #[test]
+    fn resolve_partition_rejects_duplicate_ticker_claims() {
+        let outcomes = vec!["Player A".to_string(), "Player B".to_string()];
+        // Both outcomes resolve to the same ticker — only the
+        // higher-confidence one should keep the claim.
+        let per_outcome = vec![
+            ("Player A".to_string(), Some(mk_result("MVP-X", 0.7))),
+            ("Player B".to_string(), Some(mk_result("MVP-X", 0.95))),
+        ];
+        let result = resolve_partition(&outcomes, per_outcome);
+        assert_eq!(result.assignments.len(), 1);
+        assert_eq!(result.assignments[0].outcome, "Player B");
+        assert_eq!(result.uncovered, vec!["Player A".to_string()]);
+    }
+
+    #[test]
+    fn resolve_partition_drops_non_matches() {
+        let outcomes = vec!["Player A".to_string()];
+        let mut no_match = mk_result("", 0.0);
+        no_match.r#match = false;
+        let per_outcome = vec![("Player A".to_string(), Some(no_match))];
+        let result = resolve_partition(&outcomes, per_outcome);
+        assert!(result.assignments.is_empty());
+        assert_eq!(result.uncovered, vec!["Player A".to_string()]);
+    }
+
     // ── MatchResult JSON parsing ────────────────────────────────────────
 
     #[test]
@@ -1059,7 +2433,7 @@ mod tests {
         matcher.build_index(&markets).await;
 
         let result = matcher
-            .match_market("Will Lille OSC win on 2026-02-14?", "Yes", &markets)
+            .match_market("Will Lille OSC win on 2026-02-14?", "Yes", &markets, None)
             .await;
 
         assert!(result.is_some(), "Should find a match for Lille OSC");
@@ -1091,7 +2465,7 @@ mod tests {
         matcher.build_index(&markets).await;
 
         let result = matcher
-            .match_market("Will BTC reach $100k?", "Yes", &markets)
+            .match_market("Will BTC reach $100k?", "Yes", &markets, None)
             .await;
 
         assert!(result.is_some(), "Should find a match for BTC 100k");
@@ -1120,6 +2494,7 @@ mod tests {
                 "Russia x Ukraine ceasefire by June 30, 2026?",
                 "Yes",
                 &markets,
+                None,
             )
             .await;
 
@@ -1148,7 +2523,7 @@ mod tests {
 
         // First call — cold
         let r1 = matcher
-            .match_market("Will Lille OSC win on 2026-02-14?", "Yes", &markets)
+            .match_market("Will Lille OSC win on 2026-02-14?", "Yes", &markets, None)
             .await;
         assert!(r1.is_some());
         assert_eq!(matcher.cache_size(), 1);
@@ -1156,7 +2531,7 @@ mod tests {
         // Second call — should be instant cache hit
         let start = Instant::now();
         let r2 = matcher
-            .match_market("Will Lille OSC win on 2026-02-14?", "Yes", &markets)
+            .match_market("Will Lille OSC win on 2026-02-14?", "Yes", &markets, None)
             .await;
         let elapsed = start.elapsed();
 
@@ -1195,6 +2570,7 @@ mod tests {
                 "Will Taylor Swift release a new album in 2026?",
                 "Yes",
                 &markets,
+                None,
             )
             .await;
 
@@ -0,0 +1,371 @@
+use rusqlite::{params, Connection};
+use std::sync::Mutex;
+
+// ── Types ───────────────────────────────────────────────────────────────
+
+/// Where a filled order stands, the same shape `execution::executor`'s
+/// `MatchState` uses for cross-platform matches: `Open` (order filled, not
+/// yet confirmed live), `Active` (confirmed, being monitored for an exit
+/// signal), `Settling` (an exit order is in flight or the market settled),
+/// `Closed` (realized P&L recorded and the `dedup_key` slot freed).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PositionState {
+    Open,
+    Active,
+    Settling,
+    Closed,
+}
+
+impl PositionState {
+    fn as_str(self) -> &'static str {
+        match self {
+            PositionState::Open => "OPEN",
+            PositionState::Active => "ACTIVE",
+            PositionState::Settling => "SETTLING",
+            PositionState::Closed => "CLOSED",
+        }
+    }
+
+    fn from_str(s: &str) -> Self {
+        match s {
+            "ACTIVE" => PositionState::Active,
+            "SETTLING" => PositionState::Settling,
+            "CLOSED" => PositionState::Closed,
+            _ => PositionState::Open,
+        }
+    }
+}
+
+/// Why `check_exit` decided a position should be closed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExitReason {
+    TakeProfit,
+    StopLoss,
+    Settlement,
+}
+
+impl ExitReason {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ExitReason::TakeProfit => "take_profit",
+            ExitReason::StopLoss => "stop_loss",
+            ExitReason::Settlement => "settlement",
+        }
+    }
+
+    /// Parse back `as_str`'s output, e.g. when reloading a `Settling`
+    /// position's recorded reason across a restart. Unrecognized values
+    /// fall back to `Settlement`, same as `PositionState::from_str`.
+    pub fn from_str(s: &str) -> Self {
+        match s {
+            "take_profit" => ExitReason::TakeProfit,
+            "stop_loss" => ExitReason::StopLoss,
+            _ => ExitReason::Settlement,
+        }
+    }
+}
+
+/// A filled Kalshi order tracked through its lifecycle. `dedup_key` is the
+/// same event-level key `commands::watch`'s Gate 2/Gate 3 use, so closing a
+/// position can free that slot back up.
+#[derive(Debug, Clone)]
+pub struct Position {
+    pub id: Option<i64>,
+    pub ticker: String,
+    pub dedup_key: String,
+    pub side: String,
+    pub entry_price_cents: i64,
+    pub count: i32,
+    pub entry_fee_cents: i64,
+    pub state: PositionState,
+    pub realized_pnl_cents: Option<i64>,
+    /// The resting exit order's id while `state` is `Settling`, so a later
+    /// tick that finds it still unfilled can cancel that specific order
+    /// instead of stacking a fresh one on top of it.
+    pub exit_order_id: Option<String>,
+    /// The price that order was placed at, so a later tick that finds it
+    /// filled (e.g. after a restart mid-wait) can still compute realized
+    /// P&L without re-deriving the exit price from scratch.
+    pub exit_price_cents: Option<i64>,
+    /// The reason that exit was triggered, so a later tick reconciling a
+    /// filled `Settling` order can still report/alert the real reason
+    /// instead of a generic placeholder.
+    pub exit_reason: Option<ExitReason>,
+}
+
+/// Configurable exit thresholds, in cents of price movement per contract
+/// from entry.
+#[derive(Debug, Clone, Copy)]
+pub struct ExitThresholds {
+    pub take_profit_cents: i64,
+    pub stop_loss_cents: i64,
+}
+
+// ── Exit decisions ──────────────────────────────────────────────────────
+
+/// Given a position's entry price and the current live price for its side,
+/// decide whether an exit should trigger. Take-profit is checked first, so
+/// a price move that clears both thresholds at once (a thin book gapping
+/// through both) is reported as a win rather than a loss.
+pub fn check_exit(position: &Position, live_price_cents: i64, thresholds: &ExitThresholds) -> Option<ExitReason> {
+    let move_cents = live_price_cents - position.entry_price_cents;
+    if move_cents >= thresholds.take_profit_cents {
+        Some(ExitReason::TakeProfit)
+    } else if move_cents <= -thresholds.stop_loss_cents {
+        Some(ExitReason::StopLoss)
+    } else {
+        None
+    }
+}
+
+/// Realized P&L for a closing position, in cents: `(exit_price -
+/// entry_price) * count`, minus every fee paid on both legs. Replaces the
+/// old `daily_loss_cents += trade_cost_cents` entry-cost accounting, which
+/// counted every opened position as a full loss regardless of outcome.
+pub fn realized_pnl_cents(entry_price_cents: i64, exit_price_cents: i64, count: i32, total_fees_cents: i64) -> i64 {
+    (exit_price_cents - entry_price_cents) * i64::from(count) - total_fees_cents
+}
+
+// ── Persistence ───────────────────────────────────────────────────────────
+
+/// Tracks every position opened by the execution pipeline so a restart can
+/// resume monitoring whatever was left `Open`/`Active`/`Settling`, mirroring
+/// `execution::executor::MatchExecutor`'s `pending_matches` table.
+pub struct PositionStore {
+    conn: Mutex<Connection>,
+}
+
+impl PositionStore {
+    /// Open the shared on-disk database, creating the `positions` table if
+    /// needed.
+    pub fn open() -> Result<Self, Box<dyn std::error::Error>> {
+        Self::from_connection(crate::db::open_db()?)
+    }
+
+    /// Wrap an already-open connection (e.g. an in-memory one in tests).
+    pub fn from_connection(conn: Connection) -> Result<Self, Box<dyn std::error::Error>> {
+        conn.execute_batch(
+            "CREATE TABLE IF NOT EXISTS positions (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                ticker TEXT NOT NULL,
+                dedup_key TEXT NOT NULL,
+                side TEXT NOT NULL,
+                entry_price_cents INTEGER NOT NULL,
+                count INTEGER NOT NULL,
+                entry_fee_cents INTEGER NOT NULL,
+                state TEXT NOT NULL DEFAULT 'OPEN',
+                realized_pnl_cents INTEGER,
+                exit_order_id TEXT,
+                exit_price_cents INTEGER,
+                exit_reason TEXT
+            );",
+        )?;
+        for (col, def) in [("exit_order_id", "TEXT"), ("exit_price_cents", "INTEGER"), ("exit_reason", "TEXT")] {
+            if let Err(e) = conn.execute(&format!("ALTER TABLE positions ADD COLUMN {} {};", col, def), []) {
+                if !e.to_string().contains("duplicate column") {
+                    eprintln!("Warning: migration add column {}: {}", col, e);
+                }
+            }
+        }
+        Ok(Self { conn: Mutex::new(conn) })
+    }
+
+    pub fn open_position(&self, position: &Position) -> rusqlite::Result<i64> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "INSERT INTO positions
+                (ticker, dedup_key, side, entry_price_cents, count, entry_fee_cents, state)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+            params![
+                position.ticker,
+                position.dedup_key,
+                position.side,
+                position.entry_price_cents,
+                position.count,
+                position.entry_fee_cents,
+                position.state.as_str(),
+            ],
+        )?;
+        Ok(conn.last_insert_rowid())
+    }
+
+    pub fn set_state(&self, id: i64, state: PositionState) -> rusqlite::Result<()> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "UPDATE positions SET state = ?1 WHERE id = ?2",
+            params![state.as_str(), id],
+        )?;
+        Ok(())
+    }
+
+    /// Move a position to `Settling` and record the exit order now resting
+    /// against it (the price it was placed at and why), so the next tick
+    /// knows an exit is already in flight instead of placing a second one
+    /// on top of it.
+    pub fn set_settling(
+        &self,
+        id: i64,
+        exit_order_id: &str,
+        exit_price_cents: i64,
+        exit_reason: ExitReason,
+    ) -> rusqlite::Result<()> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "UPDATE positions SET state = ?1, exit_order_id = ?2, exit_price_cents = ?3, exit_reason = ?4 WHERE id = ?5",
+            params![PositionState::Settling.as_str(), exit_order_id, exit_price_cents, exit_reason.as_str(), id],
+        )?;
+        Ok(())
+    }
+
+    /// Record the exit outcome and move the position to `Closed`, freeing
+    /// its `dedup_key` back up for Gate 3's open-position count.
+    pub fn close_position(&self, id: i64, realized_pnl_cents: i64) -> rusqlite::Result<()> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "UPDATE positions SET state = ?1, realized_pnl_cents = ?2, exit_order_id = NULL, exit_price_cents = NULL, exit_reason = NULL WHERE id = ?3",
+            params![PositionState::Closed.as_str(), realized_pnl_cents, id],
+        )?;
+        Ok(())
+    }
+
+    /// Clear a Settling position's stale exit-order bookkeeping (the order
+    /// was cancelled or has already terminated) and move it back to
+    /// `Active`, so the next tick is free to place a fresh exit order.
+    pub fn clear_settling(&self, id: i64) -> rusqlite::Result<()> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "UPDATE positions SET state = ?1, exit_order_id = NULL, exit_price_cents = NULL, exit_reason = NULL WHERE id = ?2",
+            params![PositionState::Active.as_str(), id],
+        )?;
+        Ok(())
+    }
+
+    /// Positions still open, active or settling — what a restarted watcher needs to
+    /// resume monitoring for exits.
+    pub fn load_monitored(&self) -> rusqlite::Result<Vec<Position>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT id, ticker, dedup_key, side, entry_price_cents, count, entry_fee_cents, state, realized_pnl_cents, exit_order_id, exit_price_cents, exit_reason
+             FROM positions WHERE state IN ('OPEN', 'ACTIVE', 'SETTLING')",
+        )?;
+        let rows = stmt.query_map([], |row| {
+            Ok(Position {
+                id: row.get(0)?,
+                ticker: row.get(1)?,
+                dedup_key: row.get(2)?,
+                side: row.get(3)?,
+                entry_price_cents: row.get(4)?,
+                count: row.get(5)?,
+                entry_fee_cents: row.get(6)?,
+                state: PositionState::from_str(&row.get::<_, String>(7)?),
+                realized_pnl_cents: row.get(8)?,
+                exit_order_id: row.get(9)?,
+                exit_price_cents: row.get(10)?,
+                exit_reason: row.get::<_, Option<String>>(11)?.as_deref().map(ExitReason::from_str),
+            })
+        })?;
+        rows.collect()
+    }
+}
+
+// ── Tests ─────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn position(entry_price_cents: i64) -> Position {
+        Position {
+            id: None,
+            ticker: "KXFB-WIN".to_string(),
+            dedup_key: "KXFB".to_string(),
+            side: "yes".to_string(),
+            entry_price_cents,
+            count: 10,
+            entry_fee_cents: 2,
+            state: PositionState::Active,
+            realized_pnl_cents: None,
+            exit_order_id: None,
+            exit_price_cents: None,
+            exit_reason: None,
+        }
+    }
+
+    fn thresholds() -> ExitThresholds {
+        ExitThresholds { take_profit_cents: 10, stop_loss_cents: 5 }
+    }
+
+    #[test]
+    fn triggers_take_profit_once_price_moves_up_enough() {
+        let pos = position(40);
+        assert_eq!(check_exit(&pos, 51, &thresholds()), Some(ExitReason::TakeProfit));
+    }
+
+    #[test]
+    fn triggers_stop_loss_once_price_moves_down_enough() {
+        let pos = position(40);
+        assert_eq!(check_exit(&pos, 34, &thresholds()), Some(ExitReason::StopLoss));
+    }
+
+    #[test]
+    fn no_exit_within_both_thresholds() {
+        let pos = position(40);
+        assert_eq!(check_exit(&pos, 42, &thresholds()), None);
+    }
+
+    #[test]
+    fn realized_pnl_accounts_for_fees_on_both_legs() {
+        // Bought 10 @ 40c, sold 10 @ 50c, 2c entry fee + 2c exit fee total.
+        let pnl = realized_pnl_cents(40, 50, 10, 4);
+        assert_eq!(pnl, 96); // (50-40)*10 - 4
+    }
+
+    #[test]
+    fn open_and_close_position_round_trips_through_sqlite() {
+        let conn = Connection::open_in_memory().unwrap();
+        let store = PositionStore::from_connection(conn).unwrap();
+        let id = store.open_position(&position(40)).unwrap();
+
+        let monitored = store.load_monitored().unwrap();
+        assert_eq!(monitored.len(), 1);
+        assert_eq!(monitored[0].id, Some(id));
+
+        store.close_position(id, 96).unwrap();
+        assert!(store.load_monitored().unwrap().is_empty());
+    }
+
+    #[test]
+    fn settling_position_is_still_monitored_with_its_exit_order_id_recorded() {
+        let conn = Connection::open_in_memory().unwrap();
+        let store = PositionStore::from_connection(conn).unwrap();
+        let id = store.open_position(&position(40)).unwrap();
+
+        store.set_settling(id, "order-abc", 50, ExitReason::TakeProfit).unwrap();
+
+        let monitored = store.load_monitored().unwrap();
+        assert_eq!(monitored.len(), 1);
+        assert_eq!(monitored[0].state, PositionState::Settling);
+        assert_eq!(monitored[0].exit_order_id.as_deref(), Some("order-abc"));
+        assert_eq!(monitored[0].exit_price_cents, Some(50));
+        assert_eq!(monitored[0].exit_reason, Some(ExitReason::TakeProfit));
+
+        store.close_position(id, 96).unwrap();
+        assert!(store.load_monitored().unwrap().is_empty());
+    }
+
+    #[test]
+    fn clearing_a_settling_position_frees_it_up_for_a_fresh_exit_attempt() {
+        let conn = Connection::open_in_memory().unwrap();
+        let store = PositionStore::from_connection(conn).unwrap();
+        let id = store.open_position(&position(40)).unwrap();
+        store.set_settling(id, "order-abc", 50, ExitReason::TakeProfit).unwrap();
+
+        store.clear_settling(id).unwrap();
+
+        let monitored = store.load_monitored().unwrap();
+        assert_eq!(monitored.len(), 1);
+        assert_eq!(monitored[0].state, PositionState::Active);
+        assert!(monitored[0].exit_order_id.is_none());
+        assert!(monitored[0].exit_price_cents.is_none());
+    }
+}
@@ -0,0 +1,114 @@
+//! Weekly Kalshi markets (e.g. "NFL Week 7 winner") settle and free their
+//! dedup slot, but nothing re-enters the next period's equivalent market
+//! automatically. `in_rollover_window` decides whether an open position's
+//! market is close enough to its `close_time` to act on, and `plan_rollover`
+//! picks the successor market from a batch of `kalshi::search_markets`
+//! results — the same title-similarity ranking `platforms::kalshi::
+//! match_markets` uses for Polymarket→Kalshi matching, just pointed at
+//! Kalshi-vs-Kalshi titles and excluding the position's own ticker so a
+//! current-period market can't "roll into itself".
+use crate::platforms::kalshi::{match_markets, MarketInfo};
+
+/// Similarity cutoff for candidate successor markets. Stricter than
+/// `platforms::kalshi::DEFAULT_MATCH_THRESHOLD` (0.2): a same-platform
+/// successor title (e.g. "NFL Week 7: Chiefs win" vs "NFL Week 8: Chiefs
+/// win" differ only in the week number) scores well above it, while an
+/// unrelated market sharing a few common words falls below it.
+const ROLLOVER_MATCH_THRESHOLD: f64 = 0.3;
+
+/// A decision to close the position on `from_ticker` and reopen an
+/// equivalent-size position on `into`, produced by `plan_rollover`.
+#[derive(Debug, Clone)]
+pub struct RolloverPlan {
+    pub into: MarketInfo,
+}
+
+/// True once `close_time` (RFC3339) is within `window_hours` of now and
+/// hasn't already passed — a market already past `close_time` is a
+/// settlement, which `monitor_positions` handles on its own.
+pub fn in_rollover_window(close_time: &str, window_hours: u32) -> bool {
+    let Ok(close) = chrono::DateTime::parse_from_rfc3339(close_time) else {
+        return false;
+    };
+    let secs_left = (close.with_timezone(&chrono::Utc) - chrono::Utc::now()).num_seconds();
+    secs_left >= 0 && secs_left <= i64::from(window_hours) * 3600
+}
+
+/// Rank `candidates` (an on-demand Kalshi search for `current_title`) by
+/// title similarity to `current_title` and return the closest match that
+/// isn't `current_ticker` itself and isn't already closed.
+pub fn plan_rollover(
+    current_ticker: &str,
+    current_title: &str,
+    candidates: Vec<MarketInfo>,
+) -> Option<RolloverPlan> {
+    let candidates: Vec<MarketInfo> = candidates
+        .into_iter()
+        .filter(|c| c.ticker != current_ticker)
+        .filter(|c| {
+            c.status
+                .as_deref()
+                .map(|s| s.eq_ignore_ascii_case("open"))
+                .unwrap_or(true)
+        })
+        .collect();
+
+    match_markets(current_title, candidates, ROLLOVER_MATCH_THRESHOLD)
+        .into_iter()
+        .next()
+        .map(|(into, _score)| RolloverPlan { into })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn market(ticker: &str, title: &str, status: &str) -> MarketInfo {
+        MarketInfo {
+            ticker: ticker.to_string(),
+            title: title.to_string(),
+            status: Some(status.to_string()),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn in_window_when_close_time_is_within_the_next_few_hours() {
+        let close = (chrono::Utc::now() + chrono::Duration::hours(1)).to_rfc3339();
+        assert!(in_rollover_window(&close, 2));
+    }
+
+    #[test]
+    fn not_in_window_when_close_time_is_days_away() {
+        let close = (chrono::Utc::now() + chrono::Duration::days(3)).to_rfc3339();
+        assert!(!in_rollover_window(&close, 2));
+    }
+
+    #[test]
+    fn not_in_window_once_close_time_has_already_passed() {
+        let close = (chrono::Utc::now() - chrono::Duration::hours(1)).to_rfc3339();
+        assert!(!in_rollover_window(&close, 2));
+    }
+
+    #[test]
+    fn plan_rollover_picks_the_closest_titled_successor_and_skips_itself() {
+        let candidates = vec![
+            market("KXNFLGAME-25W07-KC", "NFL Week 7: Chiefs win", "open"),
+            market("KXNFLGAME-25W08-KC", "NFL Week 8: Chiefs win", "open"),
+            market("KXNBAGAME-25-LAL", "Lakers win tonight", "open"),
+        ];
+        let plan = plan_rollover(
+            "KXNFLGAME-25W07-KC",
+            "NFL Week 7: Chiefs win",
+            candidates,
+        )
+        .expect("expected a rollover candidate");
+        assert_eq!(plan.into.ticker, "KXNFLGAME-25W08-KC");
+    }
+
+    #[test]
+    fn plan_rollover_returns_none_with_no_close_enough_candidate() {
+        let candidates = vec![market("KXNBAGAME-25-LAL", "Lakers win tonight", "open")];
+        assert!(plan_rollover("KXNFLGAME-25W07-KC", "NFL Week 7: Chiefs win", candidates).is_none());
+    }
+}
@@ -0,0 +1,350 @@
+use crate::execution::matcher::MatchResult;
+
+// ── Types ───────────────────────────────────────────────────────────────
+
+/// Which venue a slice of an `ExecutionPlan` routes to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Venue {
+    Kalshi,
+    Polymarket,
+}
+
+impl Venue {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Venue::Kalshi => "kalshi",
+            Venue::Polymarket => "polymarket",
+        }
+    }
+}
+
+/// One price level a venue's book can absorb size at, in cents, best price
+/// first. Mirrors `fetch_order_book`'s top-of-book summary widened into a
+/// walkable ladder, so a bet larger than the best level alone can be split
+/// across deeper levels instead of assuming it all fills at the top price.
+#[derive(Debug, Clone, Copy)]
+pub struct BookLevel {
+    pub price_cents: i64,
+    pub contracts: i32,
+}
+
+/// A venue's available liquidity for one side of a matched market.
+#[derive(Debug, Clone)]
+pub struct VenueBook {
+    pub venue: Venue,
+    pub levels: Vec<BookLevel>,
+}
+
+/// One slice of a sized bet routed to a single venue at a single price.
+/// `plan_execution` returns a `Vec` of these rather than one venue/price
+/// pair so a bet that exceeds one venue's top-of-book depth can be split
+/// across levels, or across both venues, instead of overpaying to fill at
+/// a single quote.
+#[derive(Debug, Clone)]
+pub struct ExecutionPlan {
+    pub venue: Venue,
+    pub size: i32,
+    pub limit_price: i64,
+}
+
+// ── Fees ──────────────────────────────────────────────────────────────
+
+/// Kalshi taker fee per contract in cents: ceil(7 × P × (100-P) / 10000), capped at 2c.
+/// Duplicated from `commands::watch`'s private fn of the same name — `router`
+/// lives in `execution`, not the unwired `commands` module, so it can't
+/// import across that boundary.
+fn kalshi_taker_fee_cents(price_cents: i64) -> i64 {
+    let p = price_cents;
+    let q = 100 - price_cents;
+    let raw = 7 * p * q; // scaled by 10000
+    let fee = (raw + 9999) / 10000; // ceiling division
+    fee.min(2).max(0)
+}
+
+/// Polymarket charges no explicit taker fee on CLOB trades — gas/relayer
+/// costs are absorbed by the relayer, not billed per-trade. Kept as a named
+/// function rather than a literal `0` at call sites so a future fee change
+/// (or a per-market override) is a one-line edit here instead of a search
+/// across every caller.
+fn polymarket_taker_fee_cents(_price_cents: i64) -> i64 {
+    0
+}
+
+fn fee_cents_for(venue: Venue, price_cents: i64) -> i64 {
+    match venue {
+        Venue::Kalshi => kalshi_taker_fee_cents(price_cents),
+        Venue::Polymarket => polymarket_taker_fee_cents(price_cents),
+    }
+}
+
+// ── Routing ───────────────────────────────────────────────────────────
+
+/// Expected value per contract, in cents, at `price_cents` after `fee_cents`,
+/// given a whale's win rate. Same formula as `commands::watch`'s private
+/// `expected_value_cents`.
+fn expected_value_cents(win_rate: f64, price_cents: i64, fee_cents: i64) -> f64 {
+    100.0 * win_rate - price_cents as f64 - fee_cents as f64
+}
+
+/// Walk `book` from its best (first) level, taking as many contracts as
+/// each level offers — up to `remaining` — as long as that level's EV is
+/// still positive. Levels are assumed best-price-first, so EV only gets
+/// worse deeper into the book; the walk stops at the first level that no
+/// longer clears EV rather than skipping over it to check cheaper ones
+/// further down that don't exist in a sorted book.
+fn walk_book(book: &VenueBook, win_rate: f64, remaining: i32) -> (Vec<ExecutionPlan>, i32) {
+    let mut plans = Vec::new();
+    let mut remaining = remaining;
+    for level in &book.levels {
+        if remaining <= 0 {
+            break;
+        }
+        let fee_cents = fee_cents_for(book.venue, level.price_cents);
+        if expected_value_cents(win_rate, level.price_cents, fee_cents) <= 0.0 {
+            break;
+        }
+        let take = level.contracts.min(remaining);
+        if take <= 0 {
+            continue;
+        }
+        plans.push(ExecutionPlan {
+            venue: book.venue,
+            size: take,
+            limit_price: level.price_cents,
+        });
+        remaining -= take;
+    }
+    (plans, remaining)
+}
+
+/// Route an already-sized bet (`total_contracts`, typically the quarter-Kelly
+/// count `commands::watch` already computes) across a matched Polymarket/
+/// Kalshi pair's order books. Fills from whichever venue's best level has
+/// higher EV first, then spills remaining size into the other venue — and
+/// deeper levels of either — so one thin book doesn't cap the whole bet or
+/// force it all through a single worse-than-necessary price. Gates on
+/// `matched.r#match` the same way `arbitrage::detect_arbitrage` does.
+pub fn plan_execution(
+    matched: &MatchResult,
+    kalshi_book: &VenueBook,
+    polymarket_book: &VenueBook,
+    win_rate: f64,
+    total_contracts: i32,
+) -> Vec<ExecutionPlan> {
+    if !matched.r#match || matched.ticker.is_empty() || total_contracts <= 0 {
+        return Vec::new();
+    }
+
+    let best_ev = |book: &VenueBook| -> f64 {
+        book.levels
+            .first()
+            .map(|l| expected_value_cents(win_rate, l.price_cents, fee_cents_for(book.venue, l.price_cents)))
+            .unwrap_or(f64::NEG_INFINITY)
+    };
+
+    let (first, second) = if best_ev(kalshi_book) >= best_ev(polymarket_book) {
+        (kalshi_book, polymarket_book)
+    } else {
+        (polymarket_book, kalshi_book)
+    };
+
+    let mut plans = Vec::new();
+    let (first_plans, remaining) = walk_book(first, win_rate, total_contracts);
+    plans.extend(first_plans);
+
+    if remaining > 0 {
+        let (second_plans, _still_remaining) = walk_book(second, win_rate, remaining);
+        plans.extend(second_plans);
+    }
+
+    plans
+}
+
+// ── Depth-aware routing ─────────────────────────────────────────────────
+
+/// Order-routing strategy for a single venue. `FlatLimit` is the original
+/// behavior — one order for the full size at the top-of-book price, which
+/// overstates EV on a thin book once the size walks past the first level.
+/// `DepthSweep` instead prices and sizes the order off the realized
+/// volume-weighted average fill price from `depth_sweep`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RoutingMode {
+    FlatLimit,
+    DepthSweep,
+}
+
+/// Result of sweeping a book for `target_count` contracts under
+/// `depth_sweep`: the realized volume-weighted average price, how many
+/// contracts the swept levels actually filled (may be less than
+/// `target_count` if the book runs out within the slippage cap), the child
+/// orders to submit, and EV recomputed against that VWAP.
+#[derive(Debug, Clone)]
+pub struct DepthSweepResult {
+    pub vwap_price_cents: i64,
+    pub filled_count: i32,
+    pub child_orders: Vec<ExecutionPlan>,
+    pub ev_cents: f64,
+}
+
+/// Walk `book` from its best level, filling up to `target_count` contracts
+/// but never paying more than `max_slippage_cents` above the best level's
+/// price — a marketable-limit sweep, not an unbounded market order. Returns
+/// `None` if the realized VWAP's EV is non-positive (the Gate 7 check this
+/// is meant to feed), so a caller can abort the trade the same way it would
+/// on a `FlatLimit` negative-EV result.
+pub fn depth_sweep(
+    book: &VenueBook,
+    target_count: i32,
+    win_rate: f64,
+    max_slippage_cents: i64,
+) -> Option<DepthSweepResult> {
+    let best_price = book.levels.first()?.price_cents;
+    let ceiling = best_price + max_slippage_cents.max(0);
+
+    let mut child_orders = Vec::new();
+    let mut filled = 0i32;
+    let mut cost_cents: i64 = 0;
+
+    for level in &book.levels {
+        if filled >= target_count || level.price_cents > ceiling {
+            break;
+        }
+        let take = level.contracts.min(target_count - filled);
+        if take <= 0 {
+            continue;
+        }
+        child_orders.push(ExecutionPlan {
+            venue: book.venue,
+            size: take,
+            limit_price: level.price_cents,
+        });
+        filled += take;
+        cost_cents += take as i64 * level.price_cents;
+    }
+
+    if filled == 0 {
+        return None;
+    }
+
+    let vwap_price_cents = (cost_cents as f64 / filled as f64).round() as i64;
+    let fee_cents = fee_cents_for(book.venue, vwap_price_cents);
+    let ev_cents = expected_value_cents(win_rate, vwap_price_cents, fee_cents);
+    if ev_cents <= 0.0 {
+        return None;
+    }
+
+    Some(DepthSweepResult {
+        vwap_price_cents,
+        filled_count: filled,
+        child_orders,
+        ev_cents,
+    })
+}
+
+// ── Tests ─────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn matched(ticker: &str) -> MatchResult {
+        MatchResult {
+            r#match: true,
+            ticker: ticker.to_string(),
+            side: "yes".to_string(),
+            confidence: Some(0.97),
+            reasoning: None,
+        }
+    }
+
+    fn book(venue: Venue, levels: &[(i64, i32)]) -> VenueBook {
+        VenueBook {
+            venue,
+            levels: levels
+                .iter()
+                .map(|&(price_cents, contracts)| BookLevel { price_cents, contracts })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn routes_to_the_higher_ev_venue_first() {
+        // At 90% win rate: Kalshi 40c + 2c fee = EV 48c; Polymarket 60c + 0 fee = EV 30c.
+        let kalshi = book(Venue::Kalshi, &[(40, 10)]);
+        let polymarket = book(Venue::Polymarket, &[(60, 10)]);
+        let plans = plan_execution(&matched("KXFB-WIN"), &kalshi, &polymarket, 0.90, 5);
+        assert_eq!(plans.len(), 1);
+        assert_eq!(plans[0].venue, Venue::Kalshi);
+        assert_eq!(plans[0].size, 5);
+    }
+
+    #[test]
+    fn spills_into_the_other_venue_when_the_best_books_depth_runs_out() {
+        let kalshi = book(Venue::Kalshi, &[(40, 3)]);
+        let polymarket = book(Venue::Polymarket, &[(45, 10)]);
+        let plans = plan_execution(&matched("KXFB-WIN"), &kalshi, &polymarket, 0.90, 8);
+        assert_eq!(plans.len(), 2);
+        assert_eq!(plans[0].venue, Venue::Kalshi);
+        assert_eq!(plans[0].size, 3);
+        assert_eq!(plans[1].venue, Venue::Polymarket);
+        assert_eq!(plans[1].size, 5);
+    }
+
+    #[test]
+    fn stops_walking_a_book_once_ev_turns_negative() {
+        // 50% win rate: 60c level is EV-negative (50 - 60 - fee < 0), should be skipped.
+        let kalshi = book(Venue::Kalshi, &[(40, 2), (60, 10)]);
+        let polymarket = book(Venue::Polymarket, &[(70, 10)]);
+        let plans = plan_execution(&matched("KXFB-WIN"), &kalshi, &polymarket, 0.50, 10);
+        assert_eq!(plans.len(), 1);
+        assert_eq!(plans[0].size, 2);
+    }
+
+    #[test]
+    fn rejects_unmatched_result() {
+        let mut unmatched = matched("KXFB-WIN");
+        unmatched.r#match = false;
+        let kalshi = book(Venue::Kalshi, &[(40, 10)]);
+        let polymarket = book(Venue::Polymarket, &[(45, 10)]);
+        assert!(plan_execution(&unmatched, &kalshi, &polymarket, 0.90, 5).is_empty());
+    }
+
+    #[test]
+    fn rejects_nonpositive_size() {
+        let kalshi = book(Venue::Kalshi, &[(40, 10)]);
+        let polymarket = book(Venue::Polymarket, &[(45, 10)]);
+        assert!(plan_execution(&matched("KXFB-WIN"), &kalshi, &polymarket, 0.90, 0).is_empty());
+    }
+
+    #[test]
+    fn depth_sweep_computes_vwap_across_levels() {
+        // 5 @ 40c + 5 @ 44c => VWAP = 42c.
+        let kalshi = book(Venue::Kalshi, &[(40, 5), (44, 5)]);
+        let result = depth_sweep(&kalshi, 10, 0.90, 10).unwrap();
+        assert_eq!(result.vwap_price_cents, 42);
+        assert_eq!(result.filled_count, 10);
+        assert_eq!(result.child_orders.len(), 2);
+    }
+
+    #[test]
+    fn depth_sweep_stops_at_the_slippage_cap() {
+        // Best is 40c, cap is 2c, so the 50c level is out of reach.
+        let kalshi = book(Venue::Kalshi, &[(40, 3), (50, 10)]);
+        let result = depth_sweep(&kalshi, 10, 0.90, 2).unwrap();
+        assert_eq!(result.filled_count, 3);
+        assert_eq!(result.vwap_price_cents, 40);
+    }
+
+    #[test]
+    fn depth_sweep_rejects_negative_realized_ev() {
+        // 50% win rate against a 60c VWAP is EV-negative after fees.
+        let kalshi = book(Venue::Kalshi, &[(60, 10)]);
+        assert!(depth_sweep(&kalshi, 10, 0.50, 10).is_none());
+    }
+
+    #[test]
+    fn depth_sweep_on_empty_book_is_none() {
+        let kalshi = book(Venue::Kalshi, &[]);
+        assert!(depth_sweep(&kalshi, 10, 0.90, 10).is_none());
+    }
+}
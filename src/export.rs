@@ -0,0 +1,123 @@
+use crate::platforms::kalshi::{parse_ticker_details, Trade};
+
+/// Output format for `export_trades`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    /// Double-entry Ledger-CLI transactions: each trade becomes a dated
+    /// posting pair, the contract as an asset account balanced against a
+    /// brokerage cash account.
+    Ledger,
+    /// Flat CSV, one row per trade.
+    Csv,
+}
+
+/// Render `trades` for record-keeping/tax prep in `format`. The dollar amount
+/// for a trade is `count * price`, where `price` is whichever of
+/// `yes_price`/`no_price` the taker actually paid, converted from cents.
+pub fn export_trades(trades: &[Trade], format: ExportFormat) -> String {
+    match format {
+        ExportFormat::Ledger => export_ledger(trades),
+        ExportFormat::Csv => export_csv(trades),
+    }
+}
+
+/// The price (in cents) the taker actually paid, based on which side they took.
+fn taker_price_cents(trade: &Trade) -> f64 {
+    if trade.taker_side.eq_ignore_ascii_case("no") {
+        trade.no_price
+    } else {
+        trade.yes_price
+    }
+}
+
+fn trade_date(trade: &Trade) -> &str {
+    trade.created_time.split('T').next().unwrap_or(&trade.created_time)
+}
+
+fn export_csv(trades: &[Trade]) -> String {
+    let mut out = String::from("date,ticker,description,side,count,price,amount\n");
+    for trade in trades {
+        let description = parse_ticker_details(&trade.ticker, &trade.taker_side);
+        let price = taker_price_cents(trade) / 100.0;
+        let amount = price * trade.count as f64;
+        out.push_str(&format!(
+            "{},{},\"{}\",{},{},{:.2},{:.2}\n",
+            trade_date(trade),
+            trade.ticker,
+            description.replace('"', "'"),
+            trade.taker_side.to_uppercase(),
+            trade.count,
+            price,
+            amount,
+        ));
+    }
+    out
+}
+
+fn export_ledger(trades: &[Trade]) -> String {
+    let mut out = String::new();
+    for trade in trades {
+        let description = trade
+            .market_title
+            .clone()
+            .unwrap_or_else(|| parse_ticker_details(&trade.ticker, &trade.taker_side));
+        let price = taker_price_cents(trade) / 100.0;
+        let amount = price * trade.count as f64;
+
+        out.push_str(&format!("{} * Kalshi: {}\n", trade_date(trade), description));
+        out.push_str(&format!(
+            "    Assets:Kalshi:Contracts:{}    {} \"{}\" @ ${:.2}\n",
+            trade.ticker,
+            trade.count,
+            trade.taker_side.to_uppercase(),
+            price,
+        ));
+        out.push_str(&format!("    Assets:Kalshi:Cash              ${:.2}\n\n", -amount));
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn make_trade(ticker: &str, side: &str, count: i32, yes_price: f64, no_price: f64) -> Trade {
+        Trade {
+            trade_id: "t1".to_string(),
+            ticker: ticker.to_string(),
+            price: yes_price,
+            count,
+            yes_price,
+            no_price,
+            taker_side: side.to_string(),
+            created_time: "2024-01-15T18:30:00Z".to_string(),
+            market_title: None,
+        }
+    }
+
+    #[test]
+    fn csv_includes_header_and_one_row_per_trade() {
+        let trades = vec![make_trade("KXNBAGAME-A", "yes", 10, 55.0, 45.0)];
+        let csv = export_trades(&trades, ExportFormat::Csv);
+        assert_eq!(csv.lines().count(), 2);
+        assert!(csv.starts_with("date,ticker,description,side,count,price,amount"));
+        assert!(csv.contains("2024-01-15,KXNBAGAME-A"));
+        assert!(csv.contains("5.50"));
+    }
+
+    #[test]
+    fn csv_uses_no_price_for_no_side_takers() {
+        let trades = vec![make_trade("KXNBAGAME-A", "no", 4, 55.0, 45.0)];
+        let csv = export_trades(&trades, ExportFormat::Csv);
+        assert!(csv.contains(",4,0.45,1.80\n"));
+    }
+
+    #[test]
+    fn ledger_balances_contract_and_cash_postings() {
+        let trades = vec![make_trade("KXNBAGAME-A", "yes", 10, 55.0, 45.0)];
+        let ledger = export_trades(&trades, ExportFormat::Ledger);
+        assert!(ledger.contains("2024-01-15 * Kalshi:"));
+        assert!(ledger.contains("Assets:Kalshi:Contracts:KXNBAGAME-A"));
+        assert!(ledger.contains("Assets:Kalshi:Cash              $-5.50"));
+    }
+}
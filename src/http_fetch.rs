@@ -0,0 +1,207 @@
+/// Injectable HTTP layer so modules that talk to live APIs (Kalshi, Polymarket,
+/// whale profiles) can be unit-tested offline against canned JSON responses.
+use async_trait::async_trait;
+use rand::Rng;
+use std::collections::HashMap;
+use std::time::Duration;
+
+#[async_trait]
+pub trait HttpFetch: Send + Sync {
+    /// GET `url` with optional query params and headers, returning the raw body text.
+    ///
+    /// An `Err` here means the request could not be satisfied even after the
+    /// fetcher's retry budget was exhausted (or a non-retryable error occurred) —
+    /// callers should treat it as "unknown", not "confirmed empty".
+    async fn get_json(
+        &self,
+        url: &str,
+        query: &[(&str, &str)],
+        headers: &[(&str, &str)],
+    ) -> Result<String, String>;
+}
+
+/// Real network-backed implementation, wrapping the shared `reqwest::Client`.
+///
+/// Retries connection/timeout errors and HTTP 429/5xx with exponential backoff plus
+/// jitter, honoring a `Retry-After` header when the server sends one.
+pub struct ReqwestFetch {
+    client: reqwest::Client,
+    max_attempts: u32,
+    base_delay: Duration,
+}
+
+impl ReqwestFetch {
+    pub fn new(client: reqwest::Client) -> Self {
+        Self::with_retry_budget(client, 4, Duration::from_millis(200))
+    }
+
+    pub fn with_retry_budget(client: reqwest::Client, max_attempts: u32, base_delay: Duration) -> Self {
+        Self {
+            client,
+            max_attempts: max_attempts.max(1),
+            base_delay,
+        }
+    }
+
+    /// Build from the user's `Config`, so the timeout and retry budget follow
+    /// `http_timeout_secs` / `http_max_retries` instead of being hard-coded.
+    pub fn from_config(config: &crate::config::Config) -> Self {
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(config.http_timeout_secs))
+            .build()
+            .unwrap_or_default();
+        Self::with_retry_budget(client, config.http_max_retries, Duration::from_millis(200))
+    }
+
+    fn backoff_delay(&self, attempt: u32) -> Duration {
+        let exp = self.base_delay.saturating_mul(1 << attempt.saturating_sub(1).min(16));
+        let jitter_ms = rand::thread_rng().gen_range(0..=(exp.as_millis() as u64 / 2).max(1));
+        exp + Duration::from_millis(jitter_ms)
+    }
+}
+
+impl Default for ReqwestFetch {
+    fn default() -> Self {
+        Self::new(reqwest::Client::new())
+    }
+}
+
+#[async_trait]
+impl HttpFetch for ReqwestFetch {
+    async fn get_json(
+        &self,
+        url: &str,
+        query: &[(&str, &str)],
+        headers: &[(&str, &str)],
+    ) -> Result<String, String> {
+        let mut last_err = String::new();
+
+        for attempt in 1..=self.max_attempts {
+            let mut request = self.client.get(url).query(query);
+            for (name, value) in headers {
+                request = request.header(*name, *value);
+            }
+
+            match request.send().await {
+                Ok(response) => {
+                    let status = response.status();
+                    if status.is_success() {
+                        return response.text().await.map_err(|e| e.to_string());
+                    }
+
+                    let retryable = status.as_u16() == 429 || status.is_server_error();
+                    last_err = format!("HTTP {} for {}", status, url);
+                    if !retryable || attempt == self.max_attempts {
+                        return Err(last_err);
+                    }
+
+                    let retry_after = response
+                        .headers()
+                        .get(reqwest::header::RETRY_AFTER)
+                        .and_then(|v| v.to_str().ok())
+                        .and_then(|s| s.parse::<u64>().ok())
+                        .map(Duration::from_secs);
+                    tokio::time::sleep(retry_after.unwrap_or_else(|| self.backoff_delay(attempt))).await;
+                }
+                Err(e) => {
+                    last_err = e.to_string();
+                    if !(e.is_timeout() || e.is_connect()) || attempt == self.max_attempts {
+                        return Err(last_err);
+                    }
+                    tokio::time::sleep(self.backoff_delay(attempt)).await;
+                }
+            }
+        }
+
+        Err(last_err)
+    }
+}
+
+/// In-memory mock that returns canned JSON responses keyed by URL, for offline
+/// tests of parsing logic, cursor handling, and pagination.
+#[derive(Default)]
+pub struct MockFetch {
+    responses: HashMap<String, String>,
+    /// Per-URL queue of responses consumed in order (for pagination tests).
+    sequences: std::sync::Mutex<HashMap<String, Vec<String>>>,
+}
+
+impl MockFetch {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register a canned response for an exact URL (ignoring query string).
+    pub fn with_response(mut self, url: impl Into<String>, body: impl Into<String>) -> Self {
+        self.responses.insert(url.into(), body.into());
+        self
+    }
+
+    /// Register a sequence of responses to return one-by-one for repeated calls
+    /// to the same URL (e.g. paginated endpoints).
+    pub fn with_sequence(mut self, url: impl Into<String>, bodies: Vec<String>) -> Self {
+        self.sequences
+            .get_mut()
+            .expect("mutex poisoned during setup")
+            .insert(url.into(), bodies);
+        self
+    }
+}
+
+#[async_trait]
+impl HttpFetch for MockFetch {
+    async fn get_json(
+        &self,
+        url: &str,
+        _query: &[(&str, &str)],
+        _headers: &[(&str, &str)],
+    ) -> Result<String, String> {
+        if let Ok(mut sequences) = self.sequences.lock() {
+            if let Some(queue) = sequences.get_mut(url) {
+                if !queue.is_empty() {
+                    return Ok(queue.remove(0));
+                }
+            }
+        }
+
+        self.responses
+            .get(url)
+            .cloned()
+            .ok_or_else(|| format!("MockFetch: no canned response for {}", url))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn mock_returns_canned_response() {
+        let mock = MockFetch::new().with_response("https://example.com/a", "{\"ok\":true}");
+        let body = mock.get_json("https://example.com/a", &[], &[]).await.unwrap();
+        assert_eq!(body, "{\"ok\":true}");
+    }
+
+    #[tokio::test]
+    async fn mock_errors_on_unknown_url() {
+        let mock = MockFetch::new();
+        let result = mock.get_json("https://example.com/missing", &[], &[]).await;
+        assert!(result.is_err());
+    }
+
+    #[tokio::test]
+    async fn mock_sequence_consumed_in_order() {
+        let mock = MockFetch::new().with_sequence(
+            "https://example.com/page",
+            vec!["page1".to_string(), "page2".to_string()],
+        );
+        assert_eq!(
+            mock.get_json("https://example.com/page", &[], &[]).await.unwrap(),
+            "page1"
+        );
+        assert_eq!(
+            mock.get_json("https://example.com/page", &[], &[]).await.unwrap(),
+            "page2"
+        );
+    }
+}
@@ -1,5 +1,16 @@
 use crate::config::Config;
+use crate::http_fetch::{HttpFetch, ReqwestFetch};
+use base64::{engine::general_purpose, Engine as _};
+use rsa::{
+    pkcs1::DecodeRsaPrivateKey,
+    pkcs8::DecodePrivateKey,
+    pss::BlindedSigningKey,
+    sha2::Sha256,
+    signature::{RandomizedSigner, SignatureEncoding},
+    RsaPrivateKey,
+};
 use serde::{Deserialize, Serialize};
+use std::time::{SystemTime, UNIX_EPOCH};
 use thiserror::Error;
 
 #[derive(Error, Debug)]
@@ -12,6 +23,48 @@ pub enum KalshiError {
     AuthError(String),
 }
 
+/// Signs Kalshi API requests with RSA-PSS/SHA-256, per Kalshi's auth scheme:
+/// message = timestamp_ms + HTTP_METHOD + request_path, base64-encoded signature.
+/// Shared by the public-data fetchers here and by `KalshiExecutor`.
+#[derive(Clone)]
+pub struct KalshiSigner {
+    key_id: String,
+    signing_key: BlindedSigningKey<Sha256>,
+}
+
+impl KalshiSigner {
+    pub fn new(key_id: String, private_key_pem: &str) -> Result<Self, KalshiError> {
+        let private_key = RsaPrivateKey::from_pkcs8_pem(private_key_pem)
+            .or_else(|_| RsaPrivateKey::from_pkcs1_pem(private_key_pem))
+            .map_err(|e| KalshiError::AuthError(format!("Failed to parse private key: {}", e)))?;
+        Ok(Self {
+            key_id,
+            signing_key: BlindedSigningKey::<Sha256>::new(private_key),
+        })
+    }
+
+    /// Sign `method` + `path` (e.g. "/trade-api/v2/markets/trades") and return the
+    /// three `KALSHI-ACCESS-*` headers to attach to the request.
+    pub fn auth_headers(&self, method: &str, path: &str) -> Result<[(&'static str, String); 3], KalshiError> {
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_err(|e| KalshiError::AuthError(e.to_string()))?
+            .as_millis()
+            .to_string();
+
+        let msg = format!("{}{}{}", timestamp, method, path);
+        let mut rng = rand::thread_rng();
+        let signature = self.signing_key.sign_with_rng(&mut rng, msg.as_bytes());
+        let signature_b64 = general_purpose::STANDARD.encode(signature.to_bytes());
+
+        Ok([
+            ("KALSHI-ACCESS-KEY", self.key_id.clone()),
+            ("KALSHI-ACCESS-SIGNATURE", signature_b64),
+            ("KALSHI-ACCESS-TIMESTAMP", timestamp),
+        ])
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct Trade {
     #[serde(rename = "trade_id")]
@@ -42,45 +95,140 @@ struct TradesResponse {
     cursor: Option<String>,
 }
 
+/// The real, network-backed fetcher used outside of tests. Timeout and retry
+/// budget follow `Config::http_timeout_secs`/`http_max_retries`.
+fn shared_fetch() -> &'static ReqwestFetch {
+    static CLIENT: std::sync::OnceLock<ReqwestFetch> = std::sync::OnceLock::new();
+    CLIENT.get_or_init(|| {
+        let config = crate::config::load_config().unwrap_or_default();
+        ReqwestFetch::from_config(&config)
+    })
+}
+
+/// Narrows and pages through `fetch_recent_trades`. Mirrors the before/until + limit
+/// cursor style used by signature-history RPC queries: pages keep coming while the
+/// API returns a non-empty `cursor`, and the walk stops at the first empty cursor or
+/// once `max_total` trades have been collected, whichever comes first.
+#[derive(Debug, Clone, Default)]
+pub struct TradeQuery {
+    /// Restrict to a ticker. Kalshi's `ticker` param accepts a single value, so only
+    /// the first entry is sent; any further tickers are ignored.
+    pub tickers: Vec<String>,
+    /// Only trades at or after this unix-ms timestamp.
+    pub min_ts: Option<i64>,
+    /// Only trades at or before this unix-ms timestamp.
+    pub max_ts: Option<i64>,
+    /// Only trades taken on this side ("yes" or "no").
+    pub taker_side: Option<String>,
+    /// Trades per page (Kalshi caps this at 1000).
+    pub page_limit: u32,
+    /// Stop paginating once this many trades have been collected; `None` means walk
+    /// until the API returns an empty cursor.
+    pub max_total: Option<usize>,
+}
+
+impl TradeQuery {
+    fn page_limit_or_default(&self) -> u32 {
+        if self.page_limit == 0 { 100 } else { self.page_limit }
+    }
+}
+
 pub async fn fetch_recent_trades(config: Option<&Config>) -> Result<Vec<Trade>, KalshiError> {
-    let client = reqwest::Client::new();
-    
+    fetch_recent_trades_query(config, TradeQuery::default()).await
+}
+
+/// Same as `fetch_recent_trades` but accepts a `TradeQuery` to filter by ticker/time
+/// window/side and to page past the first 100 results.
+pub async fn fetch_recent_trades_query(
+    config: Option<&Config>,
+    query: TradeQuery,
+) -> Result<Vec<Trade>, KalshiError> {
+    fetch_recent_trades_with(shared_fetch(), config, query).await
+}
+
+/// Same as `fetch_recent_trades_query` but with an injectable fetcher, so tests can
+/// feed canned Kalshi payloads instead of hitting the live API.
+pub async fn fetch_recent_trades_with(
+    fetch: &dyn HttpFetch,
+    config: Option<&Config>,
+    query: TradeQuery,
+) -> Result<Vec<Trade>, KalshiError> {
     // Kalshi's public trades endpoint
-    let url = "https://api.elections.kalshi.com/trade-api/v2/markets/trades";
-    
-    let mut request = client
-        .get(url)
-        .query(&[("limit", "100")])
-        .header("Accept", "application/json");
-
-    // Add authentication if credentials are provided
+    let path = "/trade-api/v2/markets/trades";
+    let url = format!("https://api.elections.kalshi.com{}", path);
+
+    let mut headers = vec![("Accept".to_string(), "application/json".to_string())];
+
+    // Add authentication if credentials are provided, so private/portfolio-scoped
+    // trade data is accessible rather than just the public feed.
     if let Some(cfg) = config {
-        if let (Some(key_id), Some(_private_key)) = (&cfg.kalshi_api_key_id, &cfg.kalshi_private_key) {
-            // For simplicity, we'll use basic auth
-            // In production, you'd implement proper HMAC signature
-            request = request.header("KALSHI-ACCESS-KEY", key_id);
+        if let (Some(key_id), Some(private_key)) = (&cfg.kalshi_api_key_id, &cfg.kalshi_private_key) {
+            let signer = KalshiSigner::new(key_id.clone(), private_key)
+                .map_err(|e| KalshiError::AuthError(e.to_string()))?;
+            for (name, value) in signer.auth_headers("GET", path)? {
+                headers.push((name.to_string(), value));
+            }
         }
     }
+    let header_refs: Vec<(&str, &str)> = headers.iter().map(|(k, v)| (k.as_str(), v.as_str())).collect();
 
-    let response = request.send().await?;
+    let page_limit = query.page_limit_or_default().to_string();
+    let min_ts = query.min_ts.map(|t| t.to_string());
+    let max_ts = query.max_ts.map(|t| t.to_string());
+    let ticker = query.tickers.first().cloned();
 
-    if !response.status().is_success() {
-        return Err(KalshiError::ParseError(format!(
-            "API returned status: {}",
-            response.status()
-        )));
-    }
+    let mut all_trades: Vec<Trade> = Vec::new();
+    let mut cursor: Option<String> = None;
+
+    loop {
+        let mut params: Vec<(&str, &str)> = vec![("limit", page_limit.as_str())];
+        if let Some(ref c) = cursor {
+            params.push(("cursor", c.as_str()));
+        }
+        if let Some(ref t) = ticker {
+            params.push(("ticker", t.as_str()));
+        }
+        if let Some(ref t) = min_ts {
+            params.push(("min_ts", t.as_str()));
+        }
+        if let Some(ref t) = max_ts {
+            params.push(("max_ts", t.as_str()));
+        }
+        if let Some(ref side) = query.taker_side {
+            params.push(("taker_side", side.as_str()));
+        }
+
+        let text = fetch
+            .get_json(&url, &params, &header_refs)
+            .await
+            .map_err(KalshiError::ParseError)?;
+
+        let page: TradesResponse = match serde_json::from_str(&text) {
+            Ok(page) => page,
+            Err(e) => {
+                // If parsing fails, return whatever we already gathered so the tool
+                // can continue rather than losing earlier pages.
+                eprintln!("Warning: Failed to parse Kalshi response: {}", e);
+                break;
+            }
+        };
+
+        all_trades.extend(page.trades);
+
+        if let Some(max_total) = query.max_total {
+            if all_trades.len() >= max_total {
+                all_trades.truncate(max_total);
+                break;
+            }
+        }
 
-    let text = response.text().await?;
-    
-    match serde_json::from_str::<TradesResponse>(&text) {
-        Ok(response) => Ok(response.trades),
-        Err(e) => {
-            // If parsing fails, return empty list to allow tool to continue
-            eprintln!("Warning: Failed to parse Kalshi response: {}", e);
-            Ok(Vec::new())
+        match page.cursor {
+            Some(c) if !c.is_empty() => cursor = Some(c),
+            _ => break,
         }
     }
+
+    Ok(all_trades)
 }
 
 #[derive(Debug, Deserialize)]
@@ -95,20 +243,290 @@ struct MarketData {
 }
 
 pub async fn fetch_market_info(ticker: &str) -> Option<String> {
-    let client = reqwest::Client::new();
+    fetch_market_info_with(shared_fetch(), ticker).await
+}
+
+/// Same as `fetch_market_info` but with an injectable fetcher, so tests can feed
+/// canned Kalshi payloads instead of hitting the live API.
+pub async fn fetch_market_info_with(fetch: &dyn HttpFetch, ticker: &str) -> Option<String> {
     let url = format!("https://api.elections.kalshi.com/trade-api/v2/markets/{}", ticker);
-    
-    match client.get(&url).send().await {
-        Ok(response) if response.status().is_success() => {
-            if let Ok(text) = response.text().await {
-                if let Ok(market_response) = serde_json::from_str::<MarketResponse>(&text) {
-                    return market_response.market.title
-                        .or(market_response.market.subtitle);
+    let text = fetch.get_json(&url, &[], &[]).await.ok()?;
+    let market_response: MarketResponse = serde_json::from_str(&text).ok()?;
+    market_response.market.title.or(market_response.market.subtitle)
+}
+
+// ─── Authenticated portfolio endpoints ─────────────────────────────────
+
+/// Builds a `KalshiSigner` from `config` and signs `method`+`path`, failing with
+/// `AuthError` if credentials aren't configured. Unlike `fetch_recent_trades`,
+/// these portfolio endpoints have no public fallback, so a signer is mandatory.
+fn signed_headers(config: &Config, method: &str, path: &str) -> Result<Vec<(String, String)>, KalshiError> {
+    let (key_id, private_key) = config
+        .kalshi_api_key_id
+        .as_ref()
+        .zip(config.kalshi_private_key.as_ref())
+        .ok_or_else(|| KalshiError::AuthError("missing kalshi_api_key_id/kalshi_private_key in config".to_string()))?;
+
+    let signer = KalshiSigner::new(key_id.clone(), private_key)?;
+    let mut headers = vec![("Accept".to_string(), "application/json".to_string())];
+    for (name, value) in signer.auth_headers(method, path)? {
+        headers.push((name.to_string(), value));
+    }
+    Ok(headers)
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Fill {
+    pub trade_id: String,
+    pub order_id: String,
+    pub ticker: String,
+    pub side: String,
+    pub action: String,
+    pub count: i32,
+    pub yes_price: i64,
+    pub no_price: i64,
+    pub is_taker: bool,
+    pub created_time: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct FillsResponse {
+    #[serde(default)]
+    fills: Vec<Fill>,
+    #[serde(default)]
+    cursor: Option<String>,
+}
+
+/// Fetch the authenticated account's own fills (executed trades), paging until
+/// the API returns an empty cursor. Requires `kalshi_api_key_id`/`kalshi_private_key`
+/// in `config` — there is no public equivalent of this endpoint.
+pub async fn fetch_my_fills(config: &Config) -> Result<Vec<Fill>, KalshiError> {
+    fetch_my_fills_with(shared_fetch(), config).await
+}
+
+/// Same as `fetch_my_fills` but with an injectable fetcher, so tests can feed
+/// canned Kalshi payloads instead of hitting the live API.
+pub async fn fetch_my_fills_with(fetch: &dyn HttpFetch, config: &Config) -> Result<Vec<Fill>, KalshiError> {
+    let path = "/trade-api/v2/portfolio/fills";
+    let url = format!("https://api.elections.kalshi.com{}", path);
+
+    let mut all_fills: Vec<Fill> = Vec::new();
+    let mut cursor: Option<String> = None;
+
+    loop {
+        let headers = signed_headers(config, "GET", path)?;
+        let header_refs: Vec<(&str, &str)> = headers.iter().map(|(k, v)| (k.as_str(), v.as_str())).collect();
+
+        let mut params: Vec<(&str, &str)> = vec![("limit", "100")];
+        if let Some(ref c) = cursor {
+            params.push(("cursor", c.as_str()));
+        }
+
+        let text = fetch
+            .get_json(&url, &params, &header_refs)
+            .await
+            .map_err(KalshiError::ParseError)?;
+
+        let page: FillsResponse = serde_json::from_str(&text).map_err(|e| KalshiError::ParseError(e.to_string()))?;
+        all_fills.extend(page.fills);
+
+        match page.cursor {
+            Some(c) if !c.is_empty() => cursor = Some(c),
+            _ => break,
+        }
+    }
+
+    Ok(all_fills)
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Position {
+    pub ticker: String,
+    pub position: i64,
+    pub market_exposure: i64,
+    pub realized_pnl: i64,
+    pub fees_paid: i64,
+}
+
+#[derive(Debug, Deserialize)]
+struct PositionsResponse {
+    #[serde(default)]
+    market_positions: Vec<Position>,
+    #[serde(default)]
+    cursor: Option<String>,
+}
+
+/// Fetch the authenticated account's current market positions, paging until the
+/// API returns an empty cursor. Requires `kalshi_api_key_id`/`kalshi_private_key`
+/// in `config` — there is no public equivalent of this endpoint.
+pub async fn fetch_positions(config: &Config) -> Result<Vec<Position>, KalshiError> {
+    fetch_positions_with(shared_fetch(), config).await
+}
+
+/// Same as `fetch_positions` but with an injectable fetcher, so tests can feed
+/// canned Kalshi payloads instead of hitting the live API.
+pub async fn fetch_positions_with(fetch: &dyn HttpFetch, config: &Config) -> Result<Vec<Position>, KalshiError> {
+    let path = "/trade-api/v2/portfolio/positions";
+    let url = format!("https://api.elections.kalshi.com{}", path);
+
+    let mut all_positions: Vec<Position> = Vec::new();
+    let mut cursor: Option<String> = None;
+
+    loop {
+        let headers = signed_headers(config, "GET", path)?;
+        let header_refs: Vec<(&str, &str)> = headers.iter().map(|(k, v)| (k.as_str(), v.as_str())).collect();
+
+        let mut params: Vec<(&str, &str)> = vec![("limit", "100"), ("count_filter", "position")];
+        if let Some(ref c) = cursor {
+            params.push(("cursor", c.as_str()));
+        }
+
+        let text = fetch
+            .get_json(&url, &params, &header_refs)
+            .await
+            .map_err(KalshiError::ParseError)?;
+
+        let page: PositionsResponse = serde_json::from_str(&text).map_err(|e| KalshiError::ParseError(e.to_string()))?;
+        all_positions.extend(page.market_positions);
+
+        match page.cursor {
+            Some(c) if !c.is_empty() => cursor = Some(c),
+            _ => break,
+        }
+    }
+
+    Ok(all_positions)
+}
+
+// ─── Real-time trade stream (WebSocket) ───────────────────────────────
+
+const TRADE_WS_URL: &str = "wss://api.elections.kalshi.com/trade-api/ws/v2";
+const STREAM_RECONNECT_BASE: std::time::Duration = std::time::Duration::from_secs(2);
+const STREAM_RECONNECT_MAX: std::time::Duration = std::time::Duration::from_secs(60);
+
+#[derive(Debug, Deserialize)]
+struct WsEnvelope {
+    #[serde(rename = "type")]
+    msg_type: Option<String>,
+    #[serde(default)]
+    msg: Option<WsTradeMsg>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum WsTradeMsg {
+    Batch { trades: Vec<Trade> },
+    Single(Trade),
+}
+
+/// Subscribe command for the `trade` channel, optionally scoped to specific tickers.
+fn subscribe_cmd(tickers: &[String]) -> String {
+    let mut params = serde_json::json!({ "channels": ["trade"] });
+    if !tickers.is_empty() {
+        params["market_tickers"] = serde_json::json!(tickers);
+    }
+    serde_json::json!({ "id": 1, "cmd": "subscribe", "params": params }).to_string()
+}
+
+/// Open a real-time trade stream over Kalshi's WebSocket feed. Replaces one-shot
+/// REST polling of `fetch_recent_trades` with a push feed so whale-sized trades
+/// aren't missed between polls and the public endpoint isn't hammered.
+///
+/// Reconnects with exponential backoff and resubscribes on every (re)connect.
+/// When `config` carries credentials, the connection is signed so authenticated
+/// channels (e.g. private fills) can be layered on later.
+pub fn stream_trades(
+    tickers: Vec<String>,
+    config: Option<Config>,
+) -> impl futures_util::Stream<Item = Result<Trade, KalshiError>> {
+    let (tx, rx) = tokio::sync::mpsc::unbounded_channel();
+
+    tokio::spawn(async move {
+        let mut backoff = STREAM_RECONNECT_BASE;
+        loop {
+            match connect_and_stream(&tickers, config.as_ref(), &tx).await {
+                Ok(()) => {
+                    eprintln!("[WS] Kalshi trade stream disconnected, reconnecting...");
+                    backoff = STREAM_RECONNECT_BASE;
+                }
+                Err(e) => {
+                    eprintln!(
+                        "[WS] Kalshi trade stream error: {}, reconnecting in {:?}...",
+                        e, backoff
+                    );
+                    if tx.send(Err(KalshiError::ParseError(e.to_string()))).is_err() {
+                        return;
+                    }
+                    tokio::time::sleep(backoff).await;
+                    backoff = (backoff * 2).min(STREAM_RECONNECT_MAX);
                 }
             }
         }
-        _ => {}
+    });
+
+    tokio_stream::wrappers::UnboundedReceiverStream::new(rx)
+}
+
+async fn connect_and_stream(
+    tickers: &[String],
+    config: Option<&Config>,
+    tx: &tokio::sync::mpsc::UnboundedSender<Result<Trade, KalshiError>>,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    use futures_util::{SinkExt, StreamExt};
+    use tokio_tungstenite::tungstenite::Message;
+
+    let mut builder = http::Request::builder()
+        .method("GET")
+        .uri(TRADE_WS_URL)
+        .header("Host", "api.elections.kalshi.com")
+        .header("Connection", "Upgrade")
+        .header("Upgrade", "websocket")
+        .header("Sec-WebSocket-Version", "13")
+        .header(
+            "Sec-WebSocket-Key",
+            tokio_tungstenite::tungstenite::handshake::client::generate_key(),
+        );
+
+    if let Some(cfg) = config {
+        if let (Some(key_id), Some(private_key)) = (&cfg.kalshi_api_key_id, &cfg.kalshi_private_key) {
+            let signer = KalshiSigner::new(key_id.clone(), private_key)?;
+            for (name, value) in signer.auth_headers("GET", "/trade-api/ws/v2")? {
+                builder = builder.header(name, value);
+            }
+        }
     }
-    
-    None
+
+    let request = builder.body(())?;
+    let (ws_stream, _) = tokio_tungstenite::connect_async(request).await?;
+    let (mut write, mut read) = ws_stream.split();
+
+    write.send(Message::Text(subscribe_cmd(tickers))).await?;
+
+    while let Some(msg) = read.next().await {
+        match msg {
+            Ok(Message::Text(text)) => {
+                if let Ok(envelope) = serde_json::from_str::<WsEnvelope>(&text) {
+                    if envelope.msg_type.as_deref() == Some("trade") {
+                        if let Some(payload) = envelope.msg {
+                            let trades = match payload {
+                                WsTradeMsg::Batch { trades } => trades,
+                                WsTradeMsg::Single(trade) => vec![trade],
+                            };
+                            for trade in trades {
+                                if tx.send(Ok(trade)).is_err() {
+                                    return Ok(());
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+            Ok(Message::Ping(_)) | Ok(Message::Pong(_)) => {}
+            Ok(Message::Close(_)) => break,
+            Err(e) => return Err(Box::new(e)),
+            _ => {}
+        }
+    }
+
+    Ok(())
 }
@@ -0,0 +1,158 @@
+/// Quick slicing over parsed markets without piping through external
+/// `grep`: a `--filter a,b,c` keeps anything whose ticker or description
+/// contains at least one term, plus independent flags for resolution status
+/// and which field to print.
+use clap::Args;
+
+#[derive(Debug, Args)]
+pub struct MarketFilterArgs {
+    /// Comma-separated terms (case-insensitive); keep a market if its ticker
+    /// or description contains at least one. Omit to keep everything.
+    #[arg(long)]
+    pub filter: Option<String>,
+
+    /// Keep only resolved/settled markets.
+    #[arg(long)]
+    pub solved: bool,
+
+    /// Keep only open/unresolved markets.
+    #[arg(long)]
+    pub unsolved: bool,
+
+    /// Print the raw ticker instead of the human description.
+    #[arg(long)]
+    pub names: bool,
+
+    /// Print the human description instead of the raw ticker (default).
+    #[arg(long)]
+    pub paths: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ResolutionFilter {
+    Any,
+    SolvedOnly,
+    UnsolvedOnly,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PrintMode {
+    Ticker,
+    Description,
+}
+
+/// A parsed, ready-to-apply filter built from `MarketFilterArgs`.
+pub struct MarketFilter {
+    terms: Vec<String>,
+    resolution: ResolutionFilter,
+    print_mode: PrintMode,
+}
+
+impl From<&MarketFilterArgs> for MarketFilter {
+    fn from(args: &MarketFilterArgs) -> Self {
+        let terms = args
+            .filter
+            .as_deref()
+            .unwrap_or("")
+            .split(',')
+            .map(|term| term.trim().to_lowercase())
+            .filter(|term| !term.is_empty())
+            .collect();
+
+        // `--solved` and `--unsolved` are independent flags, not an
+        // exclusive pair; if both are set neither narrows the result.
+        let resolution = match (args.solved, args.unsolved) {
+            (true, false) => ResolutionFilter::SolvedOnly,
+            (false, true) => ResolutionFilter::UnsolvedOnly,
+            _ => ResolutionFilter::Any,
+        };
+
+        let print_mode = if args.names { PrintMode::Ticker } else { PrintMode::Description };
+
+        Self { terms, resolution, print_mode }
+    }
+}
+
+impl MarketFilter {
+    /// Whether a market with this ticker/description/resolved status
+    /// survives the filter.
+    pub fn matches(&self, ticker: &str, description: &str, resolved: bool) -> bool {
+        let resolution_ok = match self.resolution {
+            ResolutionFilter::Any => true,
+            ResolutionFilter::SolvedOnly => resolved,
+            ResolutionFilter::UnsolvedOnly => !resolved,
+        };
+        if !resolution_ok {
+            return false;
+        }
+
+        if self.terms.is_empty() {
+            return true;
+        }
+
+        let ticker = ticker.to_lowercase();
+        let description = description.to_lowercase();
+        self.terms.iter().any(|term| ticker.contains(term.as_str()) || description.contains(term.as_str()))
+    }
+
+    /// Which field to print for a surviving market, per `--names`/`--paths`.
+    pub fn print_mode(&self) -> PrintMode {
+        self.print_mode
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn filter(filter_str: Option<&str>, solved: bool, unsolved: bool, names: bool) -> MarketFilter {
+        let args = MarketFilterArgs {
+            filter: filter_str.map(str::to_string),
+            solved,
+            unsolved,
+            names,
+            paths: false,
+        };
+        MarketFilter::from(&args)
+    }
+
+    #[test]
+    fn no_filter_keeps_everything() {
+        let f = filter(None, false, false, false);
+        assert!(f.matches("KXNFLTD-A", "A scores first TD", false));
+    }
+
+    #[test]
+    fn comma_terms_are_trimmed_lowercased_and_ored() {
+        let f = filter(Some(" First , TD ,, chiefs"), false, false, false);
+        assert!(f.matches("KXNFLTD-A", "scores first TD", false));
+        assert!(!f.matches("KXNHLGAME-A", "carolina wins", false));
+    }
+
+    #[test]
+    fn solved_flag_keeps_only_resolved_markets() {
+        let f = filter(None, true, false, false);
+        assert!(f.matches("KXNFLTD-A", "desc", true));
+        assert!(!f.matches("KXNFLTD-A", "desc", false));
+    }
+
+    #[test]
+    fn unsolved_flag_keeps_only_open_markets() {
+        let f = filter(None, false, true, false);
+        assert!(!f.matches("KXNFLTD-A", "desc", true));
+        assert!(f.matches("KXNFLTD-A", "desc", false));
+    }
+
+    #[test]
+    fn both_resolution_flags_set_is_unconstrained() {
+        let f = filter(None, true, true, false);
+        assert!(f.matches("KXNFLTD-A", "desc", true));
+        assert!(f.matches("KXNFLTD-A", "desc", false));
+    }
+
+    #[test]
+    fn names_flag_selects_ticker_print_mode() {
+        assert_eq!(filter(None, false, false, true).print_mode(), PrintMode::Ticker);
+        assert_eq!(filter(None, false, false, false).print_mode(), PrintMode::Description);
+    }
+}
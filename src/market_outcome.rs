@@ -0,0 +1,179 @@
+/// Structured counterpart to `platforms::kalshi::parse_ticker_details`: the
+/// bare-string parser discards the category it detected (scoring, ranking,
+/// moneyline, fallback), which forces downstream code to re-parse the
+/// English description if it wants to branch on category. `classify` yields
+/// a `MarketOutcome` instead, so callers can match on it directly or
+/// serialize it as JSON for piping into other tools.
+use serde::Serialize;
+
+use crate::ticker_rules::TickerRuleSet;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BetSide {
+    Yes,
+    No,
+}
+
+impl BetSide {
+    pub(crate) fn parse(side: &str) -> Self {
+        if side.eq_ignore_ascii_case("yes") {
+            BetSide::Yes
+        } else {
+            BetSide::No
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ScorerTiming {
+    First,
+    Last,
+    Anytime,
+}
+
+/// A ticker's parsed meaning, tagged by category so downstream code can
+/// branch on it instead of re-parsing `Display`'s English text.
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "category", rename_all = "snake_case")]
+pub enum MarketOutcome {
+    Scorer { player: String, timing: ScorerTiming, side: BetSide },
+    Placement { outcome: String, side: BetSide },
+    Moneyline { team: String, wins: bool },
+    /// A ticker shape this chunk doesn't have a dedicated category for yet
+    /// (totals, spreads, price thresholds, ...). `Display` falls back to
+    /// `parse_ticker_details` so existing output is unaffected.
+    Unknown { raw_ticker: String, side: BetSide },
+}
+
+impl std::fmt::Display for MarketOutcome {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MarketOutcome::Scorer { player, timing, side } => {
+                let timing = match timing {
+                    ScorerTiming::First => "first",
+                    ScorerTiming::Last => "last",
+                    ScorerTiming::Anytime => "anytime",
+                };
+                match side {
+                    BetSide::Yes => write!(f, "{} scores {} TD", player, timing),
+                    BetSide::No => write!(f, "{} doesn't score {} TD", player, timing),
+                }
+            }
+            MarketOutcome::Placement { outcome, side } => match side {
+                BetSide::Yes => write!(f, "{} finishes in position", outcome),
+                BetSide::No => write!(f, "{} doesn't finish in position", outcome),
+            },
+            MarketOutcome::Moneyline { team, wins } => {
+                if *wins {
+                    write!(f, "{} wins", team)
+                } else {
+                    write!(f, "{} doesn't win", team)
+                }
+            }
+            MarketOutcome::Unknown { raw_ticker, side } => {
+                let side = if *side == BetSide::Yes { "yes" } else { "no" };
+                write!(f, "{}", crate::platforms::kalshi::parse_ticker_details(raw_ticker, side))
+            }
+        }
+    }
+}
+
+/// Classify `ticker`/`side` into a `MarketOutcome`, consulting `rules` for
+/// the scorer/placement shapes (see `ticker_rules`) and falling back to
+/// `Unknown` for everything `parse_ticker_details` still handles as a bare
+/// string.
+pub fn classify(ticker: &str, side: &str, rules: &TickerRuleSet) -> MarketOutcome {
+    let bet_side = BetSide::parse(side);
+
+    if let Some((kind, captures)) = rules.match_captures(ticker) {
+        match kind {
+            Some("scorer:first") => {
+                return MarketOutcome::Scorer {
+                    player: captures.get("player").cloned().unwrap_or_default(),
+                    timing: ScorerTiming::First,
+                    side: bet_side,
+                };
+            }
+            Some("scorer:last") => {
+                return MarketOutcome::Scorer {
+                    player: captures.get("player").cloned().unwrap_or_default(),
+                    timing: ScorerTiming::Last,
+                    side: bet_side,
+                };
+            }
+            Some("scorer:anytime") => {
+                return MarketOutcome::Scorer {
+                    player: captures.get("player").cloned().unwrap_or_default(),
+                    timing: ScorerTiming::Anytime,
+                    side: bet_side,
+                };
+            }
+            Some("placement") => {
+                return MarketOutcome::Placement {
+                    outcome: captures.get("outcome").cloned().unwrap_or_default(),
+                    side: bet_side,
+                };
+            }
+            _ => {}
+        }
+    }
+
+    if ticker.contains("PRES") {
+        if let Some(outcome) = ticker.split('-').last() {
+            return MarketOutcome::Moneyline {
+                team: outcome.to_uppercase(),
+                wins: bet_side == BetSide::Yes,
+            };
+        }
+    }
+
+    MarketOutcome::Unknown { raw_ticker: ticker.to_string(), side: bet_side }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn classifies_scorer_ticker() {
+        let rules = TickerRuleSet::default_rules();
+        let outcome = classify("KXNFLFIRSTTD-26JAN08KC-PMAHOMES", "yes", &rules);
+        assert_eq!(outcome.to_string(), "PMAHOMES scores first TD");
+        assert!(matches!(outcome, MarketOutcome::Scorer { timing: ScorerTiming::First, .. }));
+    }
+
+    #[test]
+    fn classifies_placement_ticker() {
+        let rules = TickerRuleSet::default_rules();
+        let outcome = classify("KXF1TOP3-26JAN08-VERSTAPPEN", "no", &rules);
+        assert_eq!(outcome.to_string(), "VERSTAPPEN doesn't finish in position");
+        assert!(matches!(outcome, MarketOutcome::Placement { .. }));
+    }
+
+    #[test]
+    fn classifies_presidential_ticker_as_moneyline() {
+        let rules = TickerRuleSet::default_rules();
+        let outcome = classify("KXPRES-24-TRUMP", "yes", &rules);
+        assert_eq!(outcome.to_string(), "TRUMP wins");
+        assert!(matches!(outcome, MarketOutcome::Moneyline { wins: true, .. }));
+    }
+
+    #[test]
+    fn falls_back_to_unknown_for_unclassified_shapes() {
+        let rules = TickerRuleSet::default_rules();
+        let outcome = classify("KXNHLGAME-26JAN08ANACAR-CAR", "yes", &rules);
+        assert!(matches!(outcome, MarketOutcome::Unknown { .. }));
+        assert_eq!(outcome.to_string(), crate::platforms::kalshi::parse_ticker_details("KXNHLGAME-26JAN08ANACAR-CAR", "yes"));
+    }
+
+    #[test]
+    fn serializes_to_json_with_category_tag() {
+        let rules = TickerRuleSet::default_rules();
+        let outcome = classify("KXF1TOP3-26JAN08-VERSTAPPEN", "yes", &rules);
+        let json = serde_json::to_string(&outcome).unwrap();
+        assert!(json.contains("\"category\":\"placement\""));
+        assert!(json.contains("\"outcome\":\"VERSTAPPEN\""));
+    }
+}
@@ -0,0 +1,538 @@
+//! Minimal Prometheus metrics, modeled on mango-feeds-connector's
+//! `MetricU64`/`MetricType`: a handful of atomic counters/gauges registered
+//! once and rendered as Prometheus text format on demand. Kept deliberately
+//! small (no `prometheus` crate dependency) since all we need is a few
+//! monotonic counters and point-in-time gauges for the WS feed and alert
+//! pipeline — things that are otherwise invisible since parse failures are
+//! a silent `Err(_) => {}` skip and reconnects only go to `eprintln!`.
+use std::collections::HashMap;
+use std::net::SocketAddr;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, OnceLock};
+
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpListener;
+
+/// Whether a metric monotonically increases (`Counter`) or can move in
+/// either direction (`Gauge`). In Prometheus text format the two differ only
+/// in the `# TYPE` line they render.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MetricType {
+    Counter,
+    Gauge,
+}
+
+impl MetricType {
+    fn as_str(self) -> &'static str {
+        match self {
+            MetricType::Counter => "counter",
+            MetricType::Gauge => "gauge",
+        }
+    }
+}
+
+/// A single named `u64` metric backed by an atomic. Gauges store their
+/// value directly; counters are only ever advanced with `inc`/`add`.
+pub struct MetricU64 {
+    name: &'static str,
+    help: &'static str,
+    metric_type: MetricType,
+    value: AtomicU64,
+}
+
+impl MetricU64 {
+    pub fn inc(&self) {
+        self.value.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn add(&self, delta: u64) {
+        self.value.fetch_add(delta, Ordering::Relaxed);
+    }
+
+    pub fn set(&self, value: u64) {
+        self.value.store(value, Ordering::Relaxed);
+    }
+
+    pub fn get(&self) -> u64 {
+        self.value.load(Ordering::Relaxed)
+    }
+
+    fn render(&self) -> String {
+        format!(
+            "# HELP {name} {help}\n# TYPE {name} {ty}\n{name} {value}\n",
+            name = self.name,
+            help = self.help,
+            ty = self.metric_type.as_str(),
+            value = self.get(),
+        )
+    }
+}
+
+/// A counter split out by a single label (e.g. `scenario`, or a
+/// `platform_side` pair like `"kalshi_buy"`), for cases where the caller
+/// already has the label value on hand and doesn't need a full label-set
+/// type. Deliberately not a generic label-set type — just enough to avoid
+/// pulling in the `prometheus` crate for a handful of metrics. Label values
+/// are owned `String`s rather than `&'static str` since `alerts_by_platform_side`
+/// builds its label from runtime trade data, not a fixed set of literals.
+pub struct LabeledCounter {
+    name: &'static str,
+    help: &'static str,
+    label: &'static str,
+    values: Mutex<HashMap<String, u64>>,
+}
+
+impl LabeledCounter {
+    pub fn inc(&self, label_value: impl Into<String>) {
+        *self.values.lock().unwrap().entry(label_value.into()).or_insert(0) += 1;
+    }
+
+    fn render(&self) -> String {
+        let values = self.values.lock().unwrap();
+        let mut out = format!(
+            "# HELP {name} {help}\n# TYPE {name} counter\n",
+            name = self.name,
+            help = self.help,
+        );
+        for (label_value, count) in values.iter() {
+            out.push_str(&format!(
+                "{name}{{{label}=\"{label_value}\"}} {count}\n",
+                name = self.name,
+                label = self.label,
+                label_value = label_value,
+                count = count,
+            ));
+        }
+        out
+    }
+}
+
+/// Fixed-bucket latency histogram, Prometheus cumulative-bucket style
+/// (`_bucket{le="..."}`, `_sum`, `_count`). Buckets are milliseconds, chosen
+/// for webhook round-trip latency specifically rather than as a
+/// general-purpose default — a metric tracking something else should pick
+/// its own.
+pub struct Histogram {
+    name: &'static str,
+    help: &'static str,
+    buckets: &'static [f64],
+    bucket_counts: Mutex<Vec<u64>>,
+    sum: Mutex<f64>,
+    count: AtomicU64,
+}
+
+impl Histogram {
+    pub fn observe(&self, value: f64) {
+        let mut counts = self.bucket_counts.lock().unwrap();
+        for (i, bound) in self.buckets.iter().enumerate() {
+            if value <= *bound {
+                counts[i] += 1;
+            }
+        }
+        *self.sum.lock().unwrap() += value;
+        self.count.fetch_add(1, Ordering::Relaxed);
+    }
+
+    fn render(&self) -> String {
+        let counts = self.bucket_counts.lock().unwrap();
+        let mut out = format!(
+            "# HELP {name} {help}\n# TYPE {name} histogram\n",
+            name = self.name,
+            help = self.help,
+        );
+        for (bound, count) in self.buckets.iter().zip(counts.iter()) {
+            out.push_str(&format!(
+                "{name}_bucket{{le=\"{bound}\"}} {count}\n",
+                name = self.name,
+                bound = bound,
+                count = count,
+            ));
+        }
+        out.push_str(&format!(
+            "{name}_bucket{{le=\"+Inf\"}} {count}\n",
+            name = self.name,
+            count = self.count.load(Ordering::Relaxed),
+        ));
+        out.push_str(&format!("{name}_sum {sum}\n", name = self.name, sum = self.sum.lock().unwrap()));
+        out.push_str(&format!("{name}_count {count}\n", name = self.name, count = self.count.load(Ordering::Relaxed)));
+        out
+    }
+}
+
+/// Shared registry of every metric this process exposes. Metrics are
+/// registered once up front (see `registry()`) and handed out as `Arc`s so
+/// hot paths can hold a reference without touching the registry lock again.
+#[derive(Default)]
+pub struct Registry {
+    metrics: Mutex<Vec<Arc<MetricU64>>>,
+    labeled_metrics: Mutex<Vec<Arc<LabeledCounter>>>,
+    histograms: Mutex<Vec<Arc<Histogram>>>,
+}
+
+impl Registry {
+    fn register(&self, name: &'static str, help: &'static str, metric_type: MetricType) -> Arc<MetricU64> {
+        let metric = Arc::new(MetricU64 {
+            name,
+            help,
+            metric_type,
+            value: AtomicU64::new(0),
+        });
+        self.metrics.lock().unwrap().push(metric.clone());
+        metric
+    }
+
+    fn register_labeled(&self, name: &'static str, help: &'static str, label: &'static str) -> Arc<LabeledCounter> {
+        let metric = Arc::new(LabeledCounter {
+            name,
+            help,
+            label,
+            values: Mutex::new(HashMap::new()),
+        });
+        self.labeled_metrics.lock().unwrap().push(metric.clone());
+        metric
+    }
+
+    fn register_histogram(&self, name: &'static str, help: &'static str, buckets: &'static [f64]) -> Arc<Histogram> {
+        let metric = Arc::new(Histogram {
+            name,
+            help,
+            buckets,
+            bucket_counts: Mutex::new(vec![0; buckets.len()]),
+            sum: Mutex::new(0.0),
+            count: AtomicU64::new(0),
+        });
+        self.histograms.lock().unwrap().push(metric.clone());
+        metric
+    }
+
+    /// Render every registered metric in Prometheus text exposition format.
+    pub fn render(&self) -> String {
+        let plain = self
+            .metrics
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|m| m.render())
+            .collect::<Vec<_>>()
+            .join("");
+        let labeled = self
+            .labeled_metrics
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|m| m.render())
+            .collect::<Vec<_>>()
+            .join("");
+        let histograms = self
+            .histograms
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|m| m.render())
+            .collect::<Vec<_>>()
+            .join("");
+        plain + &labeled + &histograms
+    }
+}
+
+/// Process-wide metrics for the WS feed, alert pipeline, and whale tracker.
+/// Counters here are advanced from `ws::kalshi`'s read loops, `alerts::history`'s
+/// logging path, and `types::WalletTracker`/`commands::watch`'s poll loop;
+/// gauges are set from the same call sites whenever the underlying state changes.
+pub struct Metrics {
+    pub registry: Registry,
+    pub trades_received: Arc<MetricU64>,
+    pub messages_skipped: Arc<MetricU64>,
+    pub reconnects: Arc<MetricU64>,
+    pub alerts_logged: Arc<MetricU64>,
+    pub active_subscriptions: Arc<MetricU64>,
+    pub current_backoff_secs: Arc<MetricU64>,
+    pub seconds_since_last_message: Arc<MetricU64>,
+    pub stale_connections: Arc<MetricU64>,
+    pub known_wallets: Arc<MetricU64>,
+    pub trades_fetched: Arc<MetricU64>,
+    pub whale_returns: Arc<LabeledCounter>,
+    pub db_write_errors: Arc<MetricU64>,
+    pub cache_refresh_age_seconds: Arc<MetricU64>,
+    /// Alerts emitted, split by `"{platform}_{side}"` (e.g. `"kalshi_buy"`).
+    pub alerts_by_platform_side: Arc<LabeledCounter>,
+    pub webhook_failures: Arc<MetricU64>,
+    pub webhook_latency_ms: Arc<Histogram>,
+    pub markets_watched: Arc<MetricU64>,
+    /// Cumulative USD notional across every alert logged, rounded to the
+    /// nearest dollar — `MetricU64` has no float counter, and a whale
+    /// alert's `value` is already approximate (derived from trade
+    /// price * size).
+    pub alerted_notional_usd: Arc<MetricU64>,
+    /// Whale alerts rendered by `print_whale_alert`/`print_kalshi_alert`, by
+    /// `"{platform}_{actor}_{side}"` where `actor` is `heavy`/`repeat`/`normal`
+    /// (heavy and repeat can't both be labeled, so heavy wins — it's the
+    /// stronger signal) and `side` is `buy`/`sell`.
+    pub whale_alerts_by_actor: Arc<LabeledCounter>,
+    /// Copy-trade orders placed on Kalshi, by `side` (`yes`/`no`).
+    pub executed_trades_by_side: Arc<LabeledCounter>,
+    /// Expected value (in cents/contract) of each executed copy-trade, from
+    /// `ExecutionAlert::ev_cents`.
+    pub execution_ev_cents: Arc<Histogram>,
+    /// Quarter-Kelly position size (as a percent of bankroll) of each
+    /// executed copy-trade, from `ExecutionAlert::kelly_pct`.
+    pub execution_kelly_pct: Arc<Histogram>,
+    /// Kalshi balance (in cents) after the most recent executed copy-trade.
+    pub execution_balance_after_cents: Arc<MetricU64>,
+}
+
+/// Process-wide metrics singleton. A `OnceLock` keeps this consistent with
+/// the other shared-client patterns in the codebase (`shared_http_client`,
+/// Kalshi's `CLIENT`) instead of pulling in a dedicated crate for it.
+pub fn metrics() -> &'static Metrics {
+    static METRICS: OnceLock<Metrics> = OnceLock::new();
+    METRICS.get_or_init(|| {
+        let registry = Registry::default();
+        let trades_received = registry.register(
+            "wwatcher_trades_received_total",
+            "Trades received over the Kalshi WebSocket feed",
+            MetricType::Counter,
+        );
+        let messages_skipped = registry.register(
+            "wwatcher_messages_skipped_total",
+            "Malformed or unrecognized WS messages skipped",
+            MetricType::Counter,
+        );
+        let reconnects = registry.register(
+            "wwatcher_reconnects_total",
+            "WebSocket reconnect attempts across all feeds",
+            MetricType::Counter,
+        );
+        let alerts_logged = registry.register(
+            "wwatcher_alerts_logged_total",
+            "Whale alerts logged to history (DB + JSONL)",
+            MetricType::Counter,
+        );
+        let active_subscriptions = registry.register(
+            "wwatcher_active_subscriptions",
+            "Tickers currently subscribed to on the orderbook_delta feed",
+            MetricType::Gauge,
+        );
+        let current_backoff_secs = registry.register(
+            "wwatcher_current_backoff_seconds",
+            "Current reconnect backoff delay in seconds",
+            MetricType::Gauge,
+        );
+        let seconds_since_last_message = registry.register(
+            "wwatcher_seconds_since_last_message",
+            "Seconds since the last message was received on any WS feed",
+            MetricType::Gauge,
+        );
+        let stale_connections = registry.register(
+            "wwatcher_stale_connections_total",
+            "Connections force-closed by the heartbeat watchdog after missing too many pongs",
+            MetricType::Counter,
+        );
+        let known_wallets = registry.register(
+            "wwatcher_known_wallets",
+            "Wallets in WalletTracker's in-memory hot cache",
+            MetricType::Gauge,
+        );
+        let trades_fetched = registry.register(
+            "wwatcher_trades_fetched_total",
+            "Trades returned by Kalshi/Polymarket trade-poll fetches",
+            MetricType::Counter,
+        );
+        let whale_returns = registry.register_labeled(
+            "wwatcher_whale_returns_total",
+            "Returning-whale trades classified by WalletTracker, by scenario",
+            "scenario",
+        );
+        let db_write_errors = registry.register(
+            "wwatcher_db_write_errors_total",
+            "AlertStore writes that failed (previously only reached stderr)",
+            MetricType::Counter,
+        );
+        let cache_refresh_age_seconds = registry.register(
+            "wwatcher_cache_refresh_age_seconds",
+            "Seconds since WalletTracker's known-wallet hash cache was last refreshed",
+            MetricType::Gauge,
+        );
+        let alerts_by_platform_side = registry.register_labeled(
+            "wwatcher_alerts_total",
+            "Whale/arbitrage alerts emitted, by platform and side",
+            "platform_side",
+        );
+        let webhook_failures = registry.register(
+            "wwatcher_webhook_failures_total",
+            "Webhook deliveries that errored or returned a non-2xx status",
+            MetricType::Counter,
+        );
+        let webhook_latency_ms = registry.register_histogram(
+            "wwatcher_webhook_latency_ms",
+            "Webhook round-trip latency in milliseconds",
+            &[50.0, 100.0, 250.0, 500.0, 1000.0, 2500.0, 5000.0],
+        );
+        let markets_watched = registry.register(
+            "wwatcher_markets_watched",
+            "Markets currently being polled/streamed for whale alerts",
+            MetricType::Gauge,
+        );
+        let alerted_notional_usd = registry.register(
+            "wwatcher_alerted_notional_usd_total",
+            "Cumulative USD notional across every alert logged",
+            MetricType::Counter,
+        );
+        let whale_alerts_by_actor = registry.register_labeled(
+            "wwatcher_whale_alerts_total",
+            "Whale alerts rendered, by platform, actor type (heavy/repeat/normal), and side",
+            "platform_actor_side",
+        );
+        let executed_trades_by_side = registry.register_labeled(
+            "wwatcher_executed_trades_total",
+            "Copy-trade orders placed on Kalshi, by side",
+            "side",
+        );
+        let execution_ev_cents = registry.register_histogram(
+            "wwatcher_execution_ev_cents",
+            "Expected value in cents/contract of each executed copy-trade",
+            &[0.5, 1.0, 2.0, 5.0, 10.0, 25.0, 50.0],
+        );
+        let execution_kelly_pct = registry.register_histogram(
+            "wwatcher_execution_kelly_pct",
+            "Quarter-Kelly position size as a percent of bankroll for each executed copy-trade",
+            &[0.25, 0.5, 1.0, 2.0, 5.0, 10.0],
+        );
+        let execution_balance_after_cents = registry.register(
+            "wwatcher_execution_balance_after_cents",
+            "Kalshi balance in cents after the most recent executed copy-trade",
+            MetricType::Gauge,
+        );
+
+        Metrics {
+            registry,
+            trades_received,
+            messages_skipped,
+            reconnects,
+            alerts_logged,
+            active_subscriptions,
+            current_backoff_secs,
+            seconds_since_last_message,
+            stale_connections,
+            known_wallets,
+            trades_fetched,
+            whale_returns,
+            db_write_errors,
+            cache_refresh_age_seconds,
+            alerts_by_platform_side,
+            webhook_failures,
+            webhook_latency_ms,
+            markets_watched,
+            alerted_notional_usd,
+            whale_alerts_by_actor,
+            executed_trades_by_side,
+            execution_ev_cents,
+            execution_kelly_pct,
+            execution_balance_after_cents,
+        }
+    })
+}
+
+/// Serve the registry on `addr` as a bare-bones `GET /metrics` endpoint.
+/// Anything else gets a 404; this intentionally skips pulling in an HTTP
+/// framework for one read-only route, the same call the relay server in
+/// `ws::relay` made for its WS listener.
+pub async fn serve_metrics(addr: SocketAddr) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let listener = TcpListener::bind(addr).await?;
+    eprintln!("[metrics] serving Prometheus metrics on http://{}/metrics", addr);
+
+    loop {
+        let (mut stream, _) = listener.accept().await?;
+        tokio::spawn(async move {
+            let mut buf = [0u8; 1024];
+            let Ok(n) = stream.read(&mut buf).await else { return };
+            let request_line = String::from_utf8_lossy(&buf[..n]);
+            let is_metrics = request_line.starts_with("GET /metrics ");
+
+            let response = if is_metrics {
+                let body = metrics().registry.render();
+                format!(
+                    "HTTP/1.1 200 OK\r\nContent-Type: text/plain; version=0.0.4\r\nContent-Length: {}\r\n\r\n{}",
+                    body.len(),
+                    body,
+                )
+            } else {
+                let body = "not found";
+                format!(
+                    "HTTP/1.1 404 Not Found\r\nContent-Type: text/plain\r\nContent-Length: {}\r\n\r\n{}",
+                    body.len(),
+                    body,
+                )
+            };
+
+            let _ = stream.write_all(response.as_bytes()).await;
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn counter_renders_in_prometheus_text_format() {
+        let registry = Registry::default();
+        let counter = registry.register("test_counter_total", "A test counter", MetricType::Counter);
+        counter.inc();
+        counter.add(4);
+
+        let rendered = registry.render();
+        assert!(rendered.contains("# TYPE test_counter_total counter"));
+        assert!(rendered.contains("test_counter_total 5"));
+    }
+
+    #[test]
+    fn gauge_set_overwrites_rather_than_accumulates() {
+        let registry = Registry::default();
+        let gauge = registry.register("test_gauge", "A test gauge", MetricType::Gauge);
+        gauge.set(10);
+        gauge.set(3);
+
+        assert_eq!(gauge.get(), 3);
+        assert!(registry.render().contains("test_gauge 3"));
+    }
+
+    #[test]
+    fn labeled_counter_renders_one_line_per_label_value() {
+        let registry = Registry::default();
+        let counter = registry.register_labeled("test_returns_total", "A test labeled counter", "scenario");
+        counter.inc("flip");
+        counter.inc("flip");
+        counter.inc("known_whale");
+
+        let rendered = registry.render();
+        assert!(rendered.contains("# TYPE test_returns_total counter"));
+        assert!(rendered.contains("test_returns_total{scenario=\"flip\"} 2"));
+        assert!(rendered.contains("test_returns_total{scenario=\"known_whale\"} 1"));
+    }
+
+    #[test]
+    fn labeled_counter_accepts_a_runtime_built_label() {
+        let registry = Registry::default();
+        let counter = registry.register_labeled("test_alerts_total", "A test labeled counter", "platform_side");
+        let platform = "Kalshi".to_lowercase();
+        counter.inc(format!("{}_buy", platform));
+
+        assert!(registry.render().contains("test_alerts_total{platform_side=\"kalshi_buy\"} 1"));
+    }
+
+    #[test]
+    fn histogram_buckets_observations_cumulatively() {
+        let registry = Registry::default();
+        let histogram = registry.register_histogram("test_latency_ms", "A test histogram", &[100.0, 500.0]);
+        histogram.observe(50.0);
+        histogram.observe(200.0);
+        histogram.observe(9000.0);
+
+        let rendered = registry.render();
+        assert!(rendered.contains("test_latency_ms_bucket{le=\"100\"} 1"));
+        assert!(rendered.contains("test_latency_ms_bucket{le=\"500\"} 2"));
+        assert!(rendered.contains("test_latency_ms_bucket{le=\"+Inf\"} 3"));
+        assert!(rendered.contains("test_latency_ms_count 3"));
+    }
+}
@@ -0,0 +1,153 @@
+/// Interactive paper-trading loop built on the parsed `MarketOutcome`
+/// descriptions: place a fixed-stake mock bet on each queued market, settle
+/// it against a supplied or random resolution, and print the human-readable
+/// outcome. Turns the ticker parser into a backtesting/learning loop instead
+/// of a one-shot description printer.
+use crate::market_outcome::{BetSide, MarketOutcome};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SimState {
+    /// Waiting to stake the next queued market.
+    OpenForBets,
+    /// A bet is placed; the next `tick()` settles it.
+    Resolving,
+    /// Either the queue is empty or the wallet can't cover another stake.
+    Settled,
+}
+
+struct QueuedMarket {
+    ticker: String,
+    outcome: MarketOutcome,
+    /// Which side the simulator bets on this market.
+    side: BetSide,
+    /// `Some(true)` = market settles YES, `Some(false)` = settles NO,
+    /// `None` = resolve with a coin flip.
+    resolution: Option<bool>,
+}
+
+/// Fixed-stake paper-trading state machine: `wallet` is the mock balance,
+/// `bet` the flat stake risked per market.
+pub struct PaperTradingSimulator {
+    pub wallet: u64,
+    pub bet: u64,
+    state: SimState,
+    queue: std::collections::VecDeque<QueuedMarket>,
+    pending: Option<QueuedMarket>,
+}
+
+impl PaperTradingSimulator {
+    pub fn new(wallet: u64, bet: u64) -> Self {
+        Self {
+            wallet,
+            bet,
+            state: SimState::OpenForBets,
+            queue: std::collections::VecDeque::new(),
+            pending: None,
+        }
+    }
+
+    pub fn state(&self) -> SimState {
+        self.state
+    }
+
+    /// Queue a market to bet on. `resolution` fixes the outcome for
+    /// deterministic backtests; pass `None` to resolve it randomly instead.
+    pub fn queue_market(&mut self, ticker: impl Into<String>, outcome: MarketOutcome, side: BetSide, resolution: Option<bool>) {
+        self.queue.push_back(QueuedMarket { ticker: ticker.into(), outcome, side, resolution });
+    }
+
+    /// Advance one step: either stake the next queued market, or settle the
+    /// one currently pending. Returns `false` once funds are exhausted or
+    /// there's nothing left to bet on.
+    pub fn tick(&mut self) -> bool {
+        match self.state {
+            SimState::Settled => false,
+            SimState::OpenForBets => {
+                if self.wallet < self.bet {
+                    self.state = SimState::Settled;
+                    return false;
+                }
+
+                let Some(market) = self.queue.pop_front() else {
+                    self.state = SimState::Settled;
+                    return false;
+                };
+
+                self.wallet -= self.bet;
+                self.pending = Some(market);
+                self.state = SimState::Resolving;
+                true
+            }
+            SimState::Resolving => {
+                let Some(market) = self.pending.take() else {
+                    self.state = SimState::OpenForBets;
+                    return self.wallet >= self.bet;
+                };
+
+                let settled_yes = market.resolution.unwrap_or_else(rand::random);
+                let picked_yes = market.side == BetSide::Yes;
+                let won = picked_yes == settled_yes;
+
+                if won {
+                    // Even-money paper model: stake back plus matching winnings.
+                    self.wallet += self.bet * 2;
+                }
+
+                println!(
+                    "[{}] {} — {}",
+                    market.ticker,
+                    market.outcome,
+                    if won { "WIN" } else { "LOSS" }
+                );
+
+                self.state = SimState::OpenForBets;
+                self.wallet >= self.bet && !self.queue.is_empty()
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::market_outcome::ScorerTiming;
+
+    fn scorer_outcome(player: &str, side: BetSide) -> MarketOutcome {
+        MarketOutcome::Scorer { player: player.to_string(), timing: ScorerTiming::First, side }
+    }
+
+    #[test]
+    fn winning_bet_credits_stake_and_winnings() {
+        let mut sim = PaperTradingSimulator::new(100, 10);
+        sim.queue_market("KXNFLFIRSTTD-A", scorer_outcome("A", BetSide::Yes), BetSide::Yes, Some(true));
+
+        assert!(sim.tick()); // stake the bet
+        assert_eq!(sim.wallet, 90);
+        sim.tick(); // settle
+        assert_eq!(sim.wallet, 110);
+    }
+
+    #[test]
+    fn losing_bet_only_debits_stake() {
+        let mut sim = PaperTradingSimulator::new(100, 10);
+        sim.queue_market("KXNFLFIRSTTD-A", scorer_outcome("A", BetSide::Yes), BetSide::Yes, Some(false));
+
+        sim.tick();
+        sim.tick();
+        assert_eq!(sim.wallet, 90);
+    }
+
+    #[test]
+    fn tick_returns_false_once_funds_exhausted() {
+        let mut sim = PaperTradingSimulator::new(5, 10);
+        assert!(!sim.tick());
+        assert_eq!(sim.state(), SimState::Settled);
+    }
+
+    #[test]
+    fn tick_returns_false_when_queue_is_empty() {
+        let mut sim = PaperTradingSimulator::new(100, 10);
+        assert!(!sim.tick());
+        assert_eq!(sim.state(), SimState::Settled);
+    }
+}
@@ -0,0 +1,362 @@
+//! Realized-performance rollups over settled executions, the same OHLC-style
+//! bucketing `crate::candles` does for raw trade prices but applied to the
+//! outcome of each bet instead: wins/losses, realized P&L net of fees, and
+//! the average Gate 7 EV / quarter-Kelly figures the execution pipeline
+//! computed going in (see `db::migrate_v8_alert_ev_kelly`). Buckets live in
+//! the `performance_buckets` table (`db::migrate_v9_performance_buckets`)
+//! keyed by `(ticker, resolution, start_ts)`.
+//!
+//! `record_execution` upserts one bucket directly — used by `backfill` to
+//! replay `alerts` from scratch so re-running it after a gap never
+//! double-counts trades. `export_candle_feed` turns the table into a
+//! tickers→candles JSON feed external dashboards can chart.
+use rusqlite::{params, Connection};
+
+/// Bucket granularity for `performance_buckets`. Deliberately narrower than
+/// `candles::Resolution` — performance review cares about hourly/daily
+/// trends, not minute-level noise.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum BucketWidth {
+    Hour,
+    Day,
+}
+
+impl BucketWidth {
+    pub fn seconds(self) -> i64 {
+        match self {
+            BucketWidth::Hour => 3600,
+            BucketWidth::Day => 86400,
+        }
+    }
+
+    /// Stable text form stored in the `performance_buckets.resolution` column.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            BucketWidth::Hour => "1h",
+            BucketWidth::Day => "1d",
+        }
+    }
+
+    pub fn from_str(s: &str) -> Option<Self> {
+        match s {
+            "1h" => Some(BucketWidth::Hour),
+            "1d" => Some(BucketWidth::Day),
+            _ => None,
+        }
+    }
+}
+
+fn bucket_start(timestamp: i64, width: BucketWidth) -> i64 {
+    timestamp - timestamp.rem_euclid(width.seconds())
+}
+
+/// One aggregated bucket of realized performance for `ticker` at `width`,
+/// starting at `start_ts` (unix seconds). `avg_ev_cents`/`avg_kelly_pct` are
+/// recovered from the table's running sums, not stored directly.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PerformanceBucket {
+    pub ticker: String,
+    pub width: BucketWidth,
+    pub start_ts: i64,
+    pub trades: i64,
+    pub wins: i64,
+    pub losses: i64,
+    pub realized_pnl_cents: i64,
+    pub fees_cents: i64,
+    pub avg_ev_cents: f64,
+    pub avg_kelly_pct: f64,
+}
+
+impl PerformanceBucket {
+    /// Settled win rate for the bucket. `0.0` for an empty bucket rather than
+    /// `NaN`, matching `v_wallet_performance`'s `CAST(... ) / COUNT(*)` which
+    /// can never see a zero-trade row in the first place.
+    pub fn win_rate(&self) -> f64 {
+        if self.trades == 0 {
+            0.0
+        } else {
+            self.wins as f64 / self.trades as f64
+        }
+    }
+}
+
+/// Fold one settled execution directly into its `(ticker, resolution,
+/// bucket_start)` bucket via an UPSERT. `ev_cents`/`kelly_pct` are the
+/// pre-trade figures the pipeline computed at entry — summed here so the
+/// bucket's average calibration (`avg_ev_cents` vs `realized_pnl_cents /
+/// trades`) can be read back without re-joining `alerts`.
+pub fn record_execution(
+    conn: &Connection,
+    ticker: &str,
+    width: BucketWidth,
+    timestamp: i64,
+    realized_pnl_cents: i64,
+    won: bool,
+    fee_cents: i64,
+    ev_cents: f64,
+    kelly_pct: f64,
+) -> rusqlite::Result<()> {
+    let start_ts = bucket_start(timestamp, width);
+    let (wins, losses) = if won { (1, 0) } else { (0, 1) };
+
+    conn.execute(
+        "INSERT INTO performance_buckets
+            (ticker, resolution, start_ts, trades, wins, losses, realized_pnl_cents, fees_cents, ev_cents_sum, kelly_pct_sum)
+         VALUES (?1, ?2, ?3, 1, ?4, ?5, ?6, ?7, ?8, ?9)
+         ON CONFLICT(ticker, resolution, start_ts) DO UPDATE SET
+             trades = performance_buckets.trades + 1,
+             wins = performance_buckets.wins + excluded.wins,
+             losses = performance_buckets.losses + excluded.losses,
+             realized_pnl_cents = performance_buckets.realized_pnl_cents + excluded.realized_pnl_cents,
+             fees_cents = performance_buckets.fees_cents + excluded.fees_cents,
+             ev_cents_sum = performance_buckets.ev_cents_sum + excluded.ev_cents_sum,
+             kelly_pct_sum = performance_buckets.kelly_pct_sum + excluded.kelly_pct_sum",
+        params![ticker, width.as_str(), start_ts, wins, losses, realized_pnl_cents, fee_cents, ev_cents, kelly_pct],
+    )?;
+
+    Ok(())
+}
+
+/// Regenerate every ticker's `width` buckets over `[from_ts, to_ts]` from
+/// scratch: existing buckets in that range are cleared first, then replayed
+/// from settled `alerts` rows (`status = 'EXECUTED'` with a recorded
+/// `settled_outcome`) in timestamp order. Safe to re-run over the same range,
+/// same as `candles::backfill`. Returns the number of alerts replayed.
+pub fn backfill(conn: &Connection, width: BucketWidth, from_ts: i64, to_ts: i64) -> rusqlite::Result<usize> {
+    conn.execute(
+        "DELETE FROM performance_buckets WHERE resolution = ?1 AND start_ts >= ?2 AND start_ts <= ?3",
+        params![width.as_str(), bucket_start(from_ts, width), to_ts],
+    )?;
+
+    let mut stmt = conn.prepare(
+        "SELECT market_id, created_at, outcome, settled_outcome, pnl_value, fee_value, ev_cents, kelly_pct
+         FROM alerts
+         WHERE status = 'EXECUTED' AND settled_outcome IS NOT NULL AND market_id IS NOT NULL
+           AND created_at >= ?1 AND created_at <= ?2
+         ORDER BY created_at ASC",
+    )?;
+    let rows = stmt.query_map(params![from_ts, to_ts], |row| {
+        Ok((
+            row.get::<_, String>(0)?,
+            row.get::<_, i64>(1)?,
+            row.get::<_, Option<String>>(2)?,
+            row.get::<_, Option<String>>(3)?,
+            row.get::<_, Option<f64>>(4)?,
+            row.get::<_, Option<f64>>(5)?,
+            row.get::<_, Option<f64>>(6)?,
+            row.get::<_, Option<f64>>(7)?,
+        ))
+    })?;
+
+    let mut replayed = 0usize;
+    for row in rows {
+        let (ticker, created_at, outcome, settled_outcome, pnl_value, fee_value, ev_cents, kelly_pct) = row?;
+        let won = matches!((&outcome, &settled_outcome), (Some(o), Some(s)) if o.eq_ignore_ascii_case(s));
+        let realized_pnl_cents = (pnl_value.unwrap_or(0.0) * 100.0).round() as i64;
+        let fee_cents = (fee_value.unwrap_or(0.0) * 100.0).round() as i64;
+        record_execution(
+            conn,
+            &ticker,
+            width,
+            created_at,
+            realized_pnl_cents,
+            won,
+            fee_cents,
+            ev_cents.unwrap_or(0.0),
+            kelly_pct.unwrap_or(0.0),
+        )?;
+        replayed += 1;
+    }
+
+    Ok(replayed)
+}
+
+/// Most recent `limit` buckets for `ticker` at `width`, newest first.
+pub fn get_buckets(conn: &Connection, ticker: &str, width: BucketWidth, limit: u32) -> rusqlite::Result<Vec<PerformanceBucket>> {
+    let mut stmt = conn.prepare(
+        "SELECT ticker, start_ts, trades, wins, losses, realized_pnl_cents, fees_cents, ev_cents_sum, kelly_pct_sum
+         FROM performance_buckets
+         WHERE ticker = ?1 AND resolution = ?2
+         ORDER BY start_ts DESC
+         LIMIT ?3",
+    )?;
+
+    let rows = stmt.query_map(params![ticker, width.as_str(), limit], |row| {
+        let trades: i64 = row.get(2)?;
+        let ev_cents_sum: f64 = row.get(7)?;
+        let kelly_pct_sum: f64 = row.get(8)?;
+        Ok(PerformanceBucket {
+            ticker: row.get(0)?,
+            width,
+            start_ts: row.get(1)?,
+            trades,
+            wins: row.get(3)?,
+            losses: row.get(4)?,
+            realized_pnl_cents: row.get(5)?,
+            fees_cents: row.get(6)?,
+            avg_ev_cents: if trades > 0 { ev_cents_sum / trades as f64 } else { 0.0 },
+            avg_kelly_pct: if trades > 0 { kelly_pct_sum / trades as f64 } else { 0.0 },
+        })
+    })?;
+
+    rows.collect()
+}
+
+/// Render every ticker with at least one `width` bucket as a CoinGecko-style
+/// `{ ticker: [candle, ...] }` feed, newest-first, for external dashboards to
+/// chart the bot's track record. `limit_per_ticker` bounds how far back each
+/// ticker's history goes, the same way `get_candles`' `limit` does.
+pub fn export_candle_feed(conn: &Connection, width: BucketWidth, limit_per_ticker: u32) -> rusqlite::Result<serde_json::Value> {
+    let mut stmt = conn.prepare("SELECT DISTINCT ticker FROM performance_buckets WHERE resolution = ?1")?;
+    let tickers: Vec<String> = stmt
+        .query_map(params![width.as_str()], |row| row.get(0))?
+        .collect::<rusqlite::Result<_>>()?;
+
+    let mut feed = serde_json::Map::new();
+    for ticker in tickers {
+        let buckets = get_buckets(conn, &ticker, width, limit_per_ticker)?;
+        let candles: Vec<serde_json::Value> = buckets
+            .iter()
+            .map(|b| {
+                serde_json::json!({
+                    "start_ts": b.start_ts,
+                    "trades": b.trades,
+                    "wins": b.wins,
+                    "losses": b.losses,
+                    "win_rate": b.win_rate(),
+                    "realized_pnl_cents": b.realized_pnl_cents,
+                    "fees_cents": b.fees_cents,
+                    "avg_ev_cents": b.avg_ev_cents,
+                    "avg_kelly_pct": b.avg_kelly_pct,
+                })
+            })
+            .collect();
+        feed.insert(ticker, serde_json::Value::Array(candles));
+    }
+
+    Ok(serde_json::Value::Object(feed))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn setup() -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        crate::db::run_migrations(&conn).unwrap();
+        conn
+    }
+
+    #[test]
+    fn first_execution_in_bucket_seeds_counts() {
+        let conn = setup();
+        record_execution(&conn, "KXTEST", BucketWidth::Hour, 1_000, 45, true, 2, 10.0, 5.0).unwrap();
+
+        let buckets = get_buckets(&conn, "KXTEST", BucketWidth::Hour, 10).unwrap();
+        assert_eq!(buckets.len(), 1);
+        let bucket = &buckets[0];
+        assert_eq!(bucket.start_ts, 0);
+        assert_eq!(bucket.trades, 1);
+        assert_eq!(bucket.wins, 1);
+        assert_eq!(bucket.losses, 0);
+        assert_eq!(bucket.realized_pnl_cents, 45);
+        assert_eq!(bucket.avg_ev_cents, 10.0);
+        assert_eq!(bucket.avg_kelly_pct, 5.0);
+    }
+
+    #[test]
+    fn subsequent_executions_in_the_same_bucket_accumulate() {
+        let conn = setup();
+        record_execution(&conn, "KXTEST", BucketWidth::Hour, 0, 45, true, 2, 10.0, 5.0).unwrap();
+        record_execution(&conn, "KXTEST", BucketWidth::Hour, 100, -20, false, 2, 4.0, 3.0).unwrap();
+
+        let buckets = get_buckets(&conn, "KXTEST", BucketWidth::Hour, 10).unwrap();
+        assert_eq!(buckets.len(), 1);
+        let bucket = &buckets[0];
+        assert_eq!(bucket.trades, 2);
+        assert_eq!(bucket.wins, 1);
+        assert_eq!(bucket.losses, 1);
+        assert_eq!(bucket.realized_pnl_cents, 25);
+        assert_eq!(bucket.fees_cents, 4);
+        assert_eq!(bucket.win_rate(), 0.5);
+        assert_eq!(bucket.avg_ev_cents, 7.0);
+    }
+
+    #[test]
+    fn executions_in_different_buckets_produce_separate_rows() {
+        let conn = setup();
+        record_execution(&conn, "KXTEST", BucketWidth::Hour, 0, 10, true, 0, 1.0, 1.0).unwrap();
+        record_execution(&conn, "KXTEST", BucketWidth::Hour, 4_000, 10, true, 0, 1.0, 1.0).unwrap();
+
+        let buckets = get_buckets(&conn, "KXTEST", BucketWidth::Hour, 10).unwrap();
+        assert_eq!(buckets.len(), 2);
+        assert_eq!(buckets[0].start_ts, 3600, "newest-first ordering");
+        assert_eq!(buckets[1].start_ts, 0);
+    }
+
+    #[test]
+    fn empty_bucket_win_rate_is_zero_not_nan() {
+        let bucket = PerformanceBucket {
+            ticker: "KXTEST".to_string(),
+            width: BucketWidth::Day,
+            start_ts: 0,
+            trades: 0,
+            wins: 0,
+            losses: 0,
+            realized_pnl_cents: 0,
+            fees_cents: 0,
+            avg_ev_cents: 0.0,
+            avg_kelly_pct: 0.0,
+        };
+        assert_eq!(bucket.win_rate(), 0.0);
+    }
+
+    #[test]
+    fn backfill_replays_settled_alerts_and_is_idempotent() {
+        let conn = setup();
+        conn.execute(
+            "INSERT INTO alerts (platform, alert_type, action, value, price, size, timestamp, created_at, status, outcome, settled_outcome, market_id, pnl_value, fee_value, ev_cents, kelly_pct)
+             VALUES ('Kalshi', 'whale', 'BUY', 50.0, 0.5, 100.0, '2024-01-01T00:00:00Z', 100, 'EXECUTED', 'yes', 'yes', 'KXTEST', 0.45, 0.02, 10.0, 5.0)",
+            [],
+        ).unwrap();
+
+        let first = backfill(&conn, BucketWidth::Hour, 0, 200).unwrap();
+        let first_buckets = get_buckets(&conn, "KXTEST", BucketWidth::Hour, 10).unwrap();
+        let second = backfill(&conn, BucketWidth::Hour, 0, 200).unwrap();
+        let second_buckets = get_buckets(&conn, "KXTEST", BucketWidth::Hour, 10).unwrap();
+
+        assert_eq!(first, 1);
+        assert_eq!(second, 1);
+        assert_eq!(first_buckets, second_buckets);
+        assert_eq!(second_buckets[0].realized_pnl_cents, 45);
+        assert_eq!(second_buckets[0].wins, 1);
+    }
+
+    #[test]
+    fn backfill_counts_a_loss_when_outcome_misses_settlement() {
+        let conn = setup();
+        conn.execute(
+            "INSERT INTO alerts (platform, alert_type, action, value, price, size, timestamp, created_at, status, outcome, settled_outcome, market_id, pnl_value, fee_value, ev_cents, kelly_pct)
+             VALUES ('Kalshi', 'whale', 'BUY', 50.0, 0.5, 100.0, '2024-01-01T00:00:00Z', 100, 'EXECUTED', 'yes', 'no', 'KXTEST', -0.50, 0.02, 10.0, 5.0)",
+            [],
+        ).unwrap();
+
+        backfill(&conn, BucketWidth::Hour, 0, 200).unwrap();
+        let buckets = get_buckets(&conn, "KXTEST", BucketWidth::Hour, 10).unwrap();
+        assert_eq!(buckets[0].wins, 0);
+        assert_eq!(buckets[0].losses, 1);
+    }
+
+    #[test]
+    fn export_feed_groups_candles_by_ticker() {
+        let conn = setup();
+        record_execution(&conn, "KXONE", BucketWidth::Day, 0, 10, true, 0, 1.0, 1.0).unwrap();
+        record_execution(&conn, "KXTWO", BucketWidth::Day, 0, -5, false, 0, 1.0, 1.0).unwrap();
+
+        let feed = export_candle_feed(&conn, BucketWidth::Day, 10).unwrap();
+        let obj = feed.as_object().unwrap();
+        assert_eq!(obj.len(), 2);
+        assert_eq!(obj["KXONE"][0]["wins"], 1);
+        assert_eq!(obj["KXTWO"][0]["losses"], 1);
+    }
+}
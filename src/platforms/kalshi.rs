@@ -1,5 +1,7 @@
 use crate::config::Config;
 use serde::{Deserialize, Serialize};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
 use thiserror::Error;
 
 #[derive(Error, Debug)]
@@ -10,6 +12,69 @@ pub enum KalshiError {
     ParseError(String),
 }
 
+/// Token bucket shared by every call in this module, so fan-out (per-series
+/// `search_markets` queries, per-ticker context/orderbook lookups) can't
+/// collectively blow through Kalshi's per-tier rate limit. Refills continuously
+/// at `rate` tokens/sec up to `capacity`, mirroring the interval/limit shape
+/// exchange-info APIs advertise for their rate-limit descriptors.
+pub struct RateLimiter {
+    capacity: f64,
+    rate: f64,
+    state: Mutex<(f64, Instant)>,
+}
+
+impl RateLimiter {
+    pub fn new(rate_per_sec: f64) -> Self {
+        let capacity = rate_per_sec.max(1.0);
+        Self {
+            capacity,
+            rate: rate_per_sec.max(0.1),
+            state: Mutex::new((capacity, Instant::now())),
+        }
+    }
+
+    /// Block until a token is available, then consume one.
+    pub async fn acquire(&self) {
+        loop {
+            let wait_for = {
+                let mut state = self.state.lock().expect("rate limiter mutex poisoned");
+                let elapsed = state.1.elapsed().as_secs_f64();
+                state.0 = (state.0 + elapsed * self.rate).min(self.capacity);
+                state.1 = Instant::now();
+                if state.0 >= 1.0 {
+                    state.0 -= 1.0;
+                    None
+                } else {
+                    Some(Duration::from_secs_f64((1.0 - state.0) / self.rate))
+                }
+            };
+            match wait_for {
+                None => return,
+                Some(d) => tokio::time::sleep(d).await,
+            }
+        }
+    }
+}
+
+/// The shared `reqwest::Client` and `RateLimiter` every fetcher below goes
+/// through, so the whole crate respects one global Kalshi read budget instead
+/// of each function opening its own client and firing unthrottled.
+struct KalshiClient {
+    http: reqwest::Client,
+    limiter: RateLimiter,
+}
+
+fn shared_client() -> &'static KalshiClient {
+    static CLIENT: std::sync::OnceLock<KalshiClient> = std::sync::OnceLock::new();
+    CLIENT.get_or_init(|| {
+        let config = crate::config::load_config().unwrap_or_default();
+        KalshiClient {
+            http: reqwest::Client::new(),
+            limiter: RateLimiter::new(config.kalshi_rate_limit_per_sec),
+        }
+    })
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct Trade {
     pub trade_id: String,
@@ -33,12 +98,13 @@ struct TradesResponse {
 }
 
 pub async fn fetch_recent_trades(config: Option<&Config>) -> Result<Vec<Trade>, KalshiError> {
-    let client = reqwest::Client::new();
+    let client = shared_client();
+    client.limiter.acquire().await;
 
     // Kalshi's public trades endpoint
     let url = "https://api.elections.kalshi.com/trade-api/v2/markets/trades";
 
-    let mut request = client
+    let mut request = client.http
         .get(url)
         .query(&[("limit", "100")])
         .header("Accept", "application/json");
@@ -75,6 +141,78 @@ pub async fn fetch_recent_trades(config: Option<&Config>) -> Result<Vec<Trade>,
     }
 }
 
+/// Narrows `fetch_recent_trades` to a single ticker and/or a time window,
+/// for `commands::backfill`'s windowed walk and `commands::watch`'s
+/// reconnect gap reconciliation — both need a bounded slice of history
+/// rather than just the latest page.
+#[derive(Debug, Clone, Default)]
+pub struct TradeQuery {
+    /// Restrict to this ticker; `None` queries the full trade firehose.
+    pub ticker: Option<String>,
+    /// Only trades at or after this unix-ms timestamp.
+    pub min_ts: Option<i64>,
+    /// Only trades at or before this unix-ms timestamp.
+    pub max_ts: Option<i64>,
+    /// Trades per page (Kalshi caps this at 1000); 0 falls back to 100.
+    pub page_limit: u32,
+}
+
+/// Same as `fetch_recent_trades` but accepts a `TradeQuery` to filter by
+/// ticker and/or time window.
+pub async fn fetch_recent_trades_query(
+    config: Option<&Config>,
+    query: TradeQuery,
+) -> Result<Vec<Trade>, KalshiError> {
+    let client = shared_client();
+    client.limiter.acquire().await;
+
+    let url = "https://api.elections.kalshi.com/trade-api/v2/markets/trades";
+    let limit = if query.page_limit == 0 { 100 } else { query.page_limit }.to_string();
+    let min_ts = query.min_ts.map(|t| t.to_string());
+    let max_ts = query.max_ts.map(|t| t.to_string());
+
+    let mut request = client.http
+        .get(url)
+        .query(&[("limit", limit.as_str())])
+        .header("Accept", "application/json");
+    if let Some(ref ticker) = query.ticker {
+        request = request.query(&[("ticker", ticker.as_str())]);
+    }
+    if let Some(ref t) = min_ts {
+        request = request.query(&[("min_ts", t.as_str())]);
+    }
+    if let Some(ref t) = max_ts {
+        request = request.query(&[("max_ts", t.as_str())]);
+    }
+
+    if let Some(cfg) = config {
+        if let (Some(key_id), Some(_private_key)) =
+            (&cfg.kalshi_api_key_id, &cfg.kalshi_private_key)
+        {
+            request = request.header("KALSHI-ACCESS-KEY", key_id);
+        }
+    }
+
+    let response = request.send().await?;
+
+    if !response.status().is_success() {
+        return Err(KalshiError::ParseError(format!(
+            "API returned status: {}",
+            response.status()
+        )));
+    }
+
+    let text = response.text().await?;
+
+    match serde_json::from_str::<TradesResponse>(&text) {
+        Ok(response) => Ok(response.trades),
+        Err(e) => {
+            eprintln!("Warning: Failed to parse Kalshi response: {}", e);
+            Ok(Vec::new())
+        }
+    }
+}
+
 #[derive(Debug, Deserialize)]
 struct MarketResponse {
     market: MarketData,
@@ -90,16 +228,114 @@ struct MarketData {
     status: Option<String>,
     #[serde(default)]
     tags: Vec<String>,
+    #[serde(default)]
+    close_time: Option<String>,
+    #[serde(default)]
+    yes_bid: Option<f64>,
 }
 
 /// Market info including title and native category
-#[derive(Clone)]
+#[derive(Clone, Default)]
 pub struct MarketInfo {
     pub ticker: String,
     pub title: String,
     pub category: Option<String>,
     #[allow(dead_code)]
     pub tags: Vec<String>,
+    /// RFC3339 close timestamp, when the API reports one.
+    pub close_time: Option<String>,
+    /// Market status as reported by Kalshi (e.g. "open", "settled"), for
+    /// `Predicate::StatusIn`.
+    pub status: Option<String>,
+    /// Best yes bid, as a 0-1 fraction, for `Predicate::PriceBetween`.
+    pub yes_price: Option<f64>,
+}
+
+/// Composable filter for selecting which `MarketInfo`s (or, via
+/// `filter_trades`, `Trade`s) pass, so callers can declare a selection like
+/// "open NBA markets tagged Playoffs under 30c" from config instead of the
+/// hard-coded keyword checks `detect_series_tickers` uses, or the ad-hoc
+/// settled/finalized/`KXMV*` drops in `collect_markets_from_events`. Lives
+/// alongside `MarketInfo` since every leaf reads straight off it;
+/// `execution::matcher` re-exports this type for its own candidate prefilter.
+/// String leaves match case-insensitively (`TagIncludes` and `TitleMatches`
+/// by substring, `TickerPrefix` by prefix, the rest exactly); the
+/// combinators recurse.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", content = "args")]
+pub enum Predicate {
+    CategoryEquals(String),
+    TickerPrefix(String),
+    StatusIn(Vec<String>),
+    TagIncludes(String),
+    PriceBetween { min: f64, max: f64 },
+    TitleMatches(String),
+    /// Closes within this many days from now.
+    ClosesWithinDays(u64),
+    Not(Box<Predicate>),
+    AnyOf(Vec<Predicate>),
+    AllOf(Vec<Predicate>),
+}
+
+impl Predicate {
+    pub fn eval(&self, market: &MarketInfo) -> bool {
+        match self {
+            Predicate::CategoryEquals(cat) => market
+                .category
+                .as_deref()
+                .is_some_and(|c| c.eq_ignore_ascii_case(cat)),
+            Predicate::TickerPrefix(prefix) => {
+                market.ticker.to_lowercase().starts_with(&prefix.to_lowercase())
+            }
+            Predicate::StatusIn(statuses) => market
+                .status
+                .as_deref()
+                .is_some_and(|s| statuses.iter().any(|want| want.eq_ignore_ascii_case(s))),
+            Predicate::TagIncludes(tag) => {
+                let tag = tag.to_lowercase();
+                market.tags.iter().any(|t| t.to_lowercase().contains(&tag))
+            }
+            Predicate::PriceBetween { min, max } => {
+                market.yes_price.is_some_and(|p| p >= *min && p <= *max)
+            }
+            Predicate::TitleMatches(needle) => {
+                market.title.to_lowercase().contains(&needle.to_lowercase())
+            }
+            Predicate::ClosesWithinDays(days) => market
+                .close_time
+                .as_deref()
+                .and_then(|s| chrono::DateTime::parse_from_rfc3339(s).ok())
+                .is_some_and(|close| {
+                    let secs_left =
+                        (close.with_timezone(&chrono::Utc) - chrono::Utc::now()).num_seconds();
+                    secs_left >= 0 && secs_left <= *days as i64 * 86_400
+                }),
+            Predicate::Not(inner) => !inner.eval(market),
+            Predicate::AnyOf(preds) => preds.iter().any(|p| p.eval(market)),
+            Predicate::AllOf(preds) => preds.iter().all(|p| p.eval(market)),
+        }
+    }
+}
+
+/// Apply `predicate` (if any) to a list of trades by viewing each one as a
+/// minimal `MarketInfo` (ticker + market title + yes price); trades carry no
+/// category/tag/status, so leaves that need those simply don't match.
+pub fn filter_trades(trades: Vec<Trade>, predicate: Option<&Predicate>) -> Vec<Trade> {
+    match predicate {
+        None => trades,
+        Some(pred) => trades
+            .into_iter()
+            .filter(|t| {
+                let as_market = MarketInfo {
+                    ticker: t.ticker.clone(),
+                    title: t.market_title.clone().unwrap_or_default(),
+                    yes_price: Some(t.yes_price / 100.0),
+                    ..Default::default()
+                };
+                pred.eval(&as_market)
+            })
+            .collect(),
+    }
 }
 
 #[derive(Debug, Deserialize)]
@@ -205,6 +441,9 @@ fn collect_markets_from_events(
                 title: m.title.or(m.subtitle).unwrap_or_else(|| "Unknown".into()),
                 category: m.category.or(event.category.clone()),
                 tags: m.tags,
+                close_time: m.close_time,
+                status: m.status,
+                yes_price: m.yes_bid.map(|c| c / 100.0),
                 ticker: m.ticker,
             });
         }
@@ -213,8 +452,18 @@ fn collect_markets_from_events(
 
 /// On-demand search: query Kalshi for markets relevant to a Polymarket title.
 /// Uses targeted series queries for sports and a general events fetch otherwise.
+/// Same as `search_markets_filtered` but with no filter applied afterward.
 pub async fn search_markets(poly_title: &str) -> Result<Vec<MarketInfo>, KalshiError> {
-    let client = reqwest::Client::new();
+    search_markets_filtered(poly_title, None).await
+}
+
+/// On-demand search, then keep only markets matching `predicate` (if given) —
+/// e.g. a config-driven filter for "open NBA markets tagged Playoffs under 30c".
+pub async fn search_markets_filtered(
+    poly_title: &str,
+    predicate: Option<&Predicate>,
+) -> Result<Vec<MarketInfo>, KalshiError> {
+    let client = shared_client();
     let events_url = "https://api.elections.kalshi.com/trade-api/v2/events";
 
     let series = detect_series_tickers(poly_title);
@@ -222,7 +471,8 @@ pub async fn search_markets(poly_title: &str) -> Result<Vec<MarketInfo>, KalshiE
     let mut seen_tickers = std::collections::HashSet::new();
 
     for s in &series {
-        let resp = client
+        client.limiter.acquire().await;
+        let resp = client.http
             .get(events_url)
             .query(&[
                 ("series_ticker", *s),
@@ -245,7 +495,8 @@ pub async fn search_markets(poly_title: &str) -> Result<Vec<MarketInfo>, KalshiE
     }
 
     if series.is_empty() || all_markets.is_empty() {
-        let resp = client
+        client.limiter.acquire().await;
+        let resp = client.http
             .get(events_url)
             .query(&[
                 ("status", "open"),
@@ -264,6 +515,15 @@ pub async fn search_markets(poly_title: &str) -> Result<Vec<MarketInfo>, KalshiE
         }
     }
 
+    if let Some(pred) = predicate {
+        all_markets.retain(|m| pred.eval(m));
+    }
+
+    let all_markets: Vec<MarketInfo> = match_markets(poly_title, all_markets, DEFAULT_MATCH_THRESHOLD)
+        .into_iter()
+        .map(|(m, _score)| m)
+        .collect();
+
     println!(
         "🔍 On-demand search for \"{}\" → {} markets (series: {:?})",
         poly_title,
@@ -274,14 +534,134 @@ pub async fn search_markets(poly_title: &str) -> Result<Vec<MarketInfo>, KalshiE
     Ok(all_markets)
 }
 
+/// Default similarity cutoff for `match_markets`; below this, a candidate's
+/// title is considered unrelated noise rather than a near-miss phrasing.
+const DEFAULT_MATCH_THRESHOLD: f64 = 0.2;
+
+/// Rank `candidates` by how closely their title matches `poly_title`, keeping
+/// only pairs scoring at or above `threshold`. `detect_series_tickers`' team
+/// dictionaries only choose which series to query; this scores each
+/// candidate with a similarity measure that still links near-miss
+/// phrasings: a trigram-set Jaccard score, plus a token-overlap bonus.
+pub fn match_markets(
+    poly_title: &str,
+    candidates: Vec<MarketInfo>,
+    threshold: f64,
+) -> Vec<(MarketInfo, f64)> {
+    let mut scored: Vec<(MarketInfo, f64)> = candidates
+        .into_iter()
+        .map(|c| {
+            let score = title_similarity(poly_title, &c.title);
+            (c, score)
+        })
+        .filter(|(_, score)| *score >= threshold)
+        .collect();
+
+    scored.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
+    scored
+}
+
+/// Lowercase and drop everything but alphanumerics/whitespace, so punctuation
+/// and casing differences don't register as dissimilarity.
+fn normalize_for_match(s: &str) -> String {
+    s.to_lowercase()
+        .chars()
+        .filter(|c| c.is_alphanumeric() || c.is_whitespace())
+        .collect()
+}
+
+/// Overlapping 3-character shingles of `s` (already normalized).
+fn trigrams(s: &str) -> std::collections::HashSet<String> {
+    let chars: Vec<char> = s.chars().collect();
+    if chars.len() < 3 {
+        return std::iter::once(s.to_string()).collect();
+    }
+    chars.windows(3).map(|w| w.iter().collect()).collect()
+}
+
+fn jaccard(a: &std::collections::HashSet<String>, b: &std::collections::HashSet<String>) -> f64 {
+    if a.is_empty() && b.is_empty() {
+        return 0.0;
+    }
+    let union = a.union(b).count() as f64;
+    if union == 0.0 {
+        return 0.0;
+    }
+    a.intersection(b).count() as f64 / union
+}
+
+/// Fraction of whitespace tokens the two (already normalized) strings share,
+/// relative to the shorter token set.
+fn token_overlap(a: &str, b: &str) -> f64 {
+    let ta: std::collections::HashSet<&str> = a.split_whitespace().collect();
+    let tb: std::collections::HashSet<&str> = b.split_whitespace().collect();
+    let smaller = ta.len().min(tb.len());
+    if smaller == 0 {
+        return 0.0;
+    }
+    ta.intersection(&tb).count() as f64 / smaller as f64
+}
+
+/// Trigram-set Jaccard similarity between `a` and `b`, with a token-overlap
+/// bonus so markets sharing whole words (team names, tickers) outrank ones
+/// that merely share character sequences.
+fn title_similarity(a: &str, b: &str) -> f64 {
+    let na = normalize_for_match(a);
+    let nb = normalize_for_match(b);
+    let trigram_score = jaccard(&trigrams(&na), &trigrams(&nb));
+    let token_bonus = token_overlap(&na, &nb);
+    (trigram_score + 0.2 * token_bonus).min(1.0)
+}
+
+/// Fetch this market's event-mates — sibling markets in the same Kalshi
+/// multi-outcome event (e.g. state-by-state election winners) — as
+/// `OutcomeQuote`s for `detect_overround`'s cross-outcome check. Returns
+/// `None` when the event has fewer than two listed markets, since a
+/// single-outcome event can't overround against itself.
+async fn fetch_event_outcomes(event_ticker: &str) -> Option<Vec<crate::alerts::OutcomeQuote>> {
+    let client = shared_client();
+    let url = format!(
+        "https://api.elections.kalshi.com/trade-api/v2/events/{}?with_nested_markets=true",
+        event_ticker
+    );
+
+    client.limiter.acquire().await;
+    let response = client.http.get(&url).send().await.ok()?;
+    if !response.status().is_success() {
+        return None;
+    }
+
+    let text = response.text().await.ok()?;
+    let parsed: serde_json::Value = serde_json::from_str(&text).ok()?;
+    let markets = parsed.get("markets")?.as_array()?;
+
+    let outcomes: Vec<crate::alerts::OutcomeQuote> = markets.iter()
+        .filter_map(|m| {
+            let ticker = m.get("ticker")?.as_str()?;
+            let label = m.get("yes_sub_title")
+                .or_else(|| m.get("subtitle"))
+                .or_else(|| m.get("title"))
+                .and_then(|v| v.as_str())
+                .unwrap_or(ticker)
+                .to_string();
+            let price = m.get("yes_bid").and_then(|v| v.as_f64()).unwrap_or(0.0) / 100.0;
+            let volume = m.get("volume_24h").and_then(|v| v.as_f64()).unwrap_or(0.0);
+            Some(crate::alerts::OutcomeQuote { label, price, volume })
+        })
+        .collect();
+
+    if outcomes.len() < 2 { None } else { Some(outcomes) }
+}
+
 pub async fn fetch_market_context(ticker: &str) -> Option<crate::alerts::MarketContext> {
-    let client = reqwest::Client::new();
+    let client = shared_client();
     let url = format!(
         "https://api.elections.kalshi.com/trade-api/v2/markets/{}",
         ticker
     );
 
-    let response = client.get(&url).send().await.ok()?;
+    client.limiter.acquire().await;
+    let response = client.http.get(&url).send().await.ok()?;
     if !response.status().is_success() {
         return None;
     }
@@ -332,6 +712,11 @@ pub async fn fetch_market_context(ticker: &str) -> Option<crate::alerts::MarketC
         .map(|c| vec![c.to_string()])
         .unwrap_or_default();
 
+    let outcomes = match market.get("event_ticker").and_then(|v| v.as_str()) {
+        Some(event_ticker) => fetch_event_outcomes(event_ticker).await,
+        None => None,
+    };
+
     Some(crate::alerts::MarketContext {
         yes_price: yes_bid,
         no_price: no_bid,
@@ -341,6 +726,12 @@ pub async fn fetch_market_context(ticker: &str) -> Option<crate::alerts::MarketC
         price_change_24h,
         liquidity,
         tags,
+        fees: market_fees(yes_bid),
+        precision: crate::alerts::Precision {
+            tick_size: 0.01,
+            lot_size: 1.0,
+        },
+        outcomes,
         expiration_date: market.get("expiration_time")
         .or_else(|| market.get("result_v_time"))
         .or_else(|| market.get("close_time"))
@@ -349,21 +740,49 @@ pub async fn fetch_market_context(ticker: &str) -> Option<crate::alerts::MarketC
     })
 }
 
+/// Kalshi taker fee per contract in cents: ceil(7 × P × (100-P) / 10000),
+/// capped at 2c. Duplicated from `commands::watch`'s private
+/// `kalshi_taker_fee_cents` of the same formula — that one drives the
+/// quarter-Kelly risk math, this one just describes the fee a fetched
+/// `MarketContext` should report.
+fn taker_fee_cents(price_cents: i64) -> i64 {
+    let p = price_cents;
+    let q = 100 - price_cents;
+    let raw = 7 * p * q; // scaled by 10000
+    let fee = (raw + 9999) / 10000; // ceiling division
+    fee.min(2).max(0)
+}
+
+/// Kalshi has no maker fee and a taker fee that depends on price (see
+/// `taker_fee_cents`), expressed here as a fraction of the YES price rather
+/// than a flat percentage. `pub(crate)` so `ws::market_context` can derive
+/// fees for a context built from a live ticker frame instead of a REST
+/// response, without duplicating the formula a third time.
+pub(crate) fn market_fees(yes_price: f64) -> crate::alerts::Fees {
+    let price_cents = (yes_price * 100.0).round().clamp(0.0, 100.0) as i64;
+    let fee_cents = taker_fee_cents(price_cents);
+    let taker = if price_cents > 0 {
+        fee_cents as f64 / price_cents as f64
+    } else {
+        0.0
+    };
+    crate::alerts::Fees { maker: 0.0, taker }
+}
+
 /// Fetch order book from Kalshi public API
 pub async fn fetch_order_book(ticker: &str) -> Option<crate::alerts::OrderBookSummary> {
-    let client = reqwest::Client::builder()
-        .timeout(std::time::Duration::from_secs(5))
-        .build()
-        .ok()?;
+    let client = shared_client();
 
     let url = format!(
         "https://api.elections.kalshi.com/trade-api/v2/markets/{}/orderbook",
         ticker
     );
 
-    let response = client
+    client.limiter.acquire().await;
+    let response = client.http
         .get(&url)
         .header("Accept", "application/json")
+        .timeout(Duration::from_secs(5))
         .send()
         .await
         .ok()?;
@@ -431,13 +850,14 @@ pub async fn fetch_order_book(ticker: &str) -> Option<crate::alerts::OrderBookSu
 
 /// Fetch full market info including native category and tags
 pub async fn fetch_market_info_full(ticker: &str) -> Option<MarketInfo> {
-    let client = reqwest::Client::new();
+    let client = shared_client();
     let url = format!(
         "https://api.elections.kalshi.com/trade-api/v2/markets/{}",
         ticker
     );
 
-    match client.get(&url).send().await {
+    client.limiter.acquire().await;
+    match client.http.get(&url).send().await {
         Ok(response) if response.status().is_success() => {
             if let Ok(text) = response.text().await {
                 if let Ok(market_response) = serde_json::from_str::<MarketResponse>(&text) {
@@ -447,6 +867,9 @@ pub async fn fetch_market_info_full(ticker: &str) -> Option<MarketInfo> {
                         title,
                         category: market_response.market.category,
                         tags: market_response.market.tags,
+                        close_time: market_response.market.close_time,
+                        status: market_response.market.status,
+                        yes_price: market_response.market.yes_bid.map(|c| c / 100.0),
                         ticker: market_response.market.ticker,
                     });
                 }
@@ -458,6 +881,23 @@ pub async fn fetch_market_info_full(ticker: &str) -> Option<MarketInfo> {
     None
 }
 
+/// Built-in ticker rule set, compiled once and reused across calls.
+fn default_ticker_rules() -> &'static crate::ticker_rules::TickerRuleSet {
+    static RULES: std::sync::OnceLock<crate::ticker_rules::TickerRuleSet> = std::sync::OnceLock::new();
+    RULES.get_or_init(crate::ticker_rules::TickerRuleSet::default_rules)
+}
+
+/// Like `parse_ticker_details`, but matches scorer-timing/placement tickers
+/// against `rules` instead of the built-in set — for callers that loaded an
+/// operator-supplied rules file to cover market categories the built-ins
+/// don't.
+pub fn parse_ticker_details_with_rules(ticker: &str, side: &str, rules: &crate::ticker_rules::TickerRuleSet) -> String {
+    if let Some(description) = rules.describe(ticker, &side.to_uppercase()) {
+        return description;
+    }
+    parse_ticker_details(ticker, side)
+}
+
 pub fn parse_ticker_details(ticker: &str, side: &str) -> String {
     let betting_side = side.to_uppercase();
     // Parse Kalshi ticker to extract bet details
@@ -701,35 +1141,12 @@ pub fn parse_ticker_details(ticker: &str, side: &str) -> String {
         }
     }
 
-    // Check for first/last to score
-    if ticker.contains("FIRST") || ticker.contains("LAST") || ticker.contains("ANYTIME") {
-        let timing = if ticker.contains("FIRST") {
-            "first"
-        } else if ticker.contains("LAST") {
-            "last"
-        } else {
-            "anytime"
-        };
-        let parts: Vec<&str> = ticker.split('-').collect();
-        if let Some(player) = parts.last() {
-            if betting_side == "YES" {
-                return format!("{} scores {} TD", player.to_uppercase(), timing);
-            } else {
-                return format!("{} doesn't score {} TD", player.to_uppercase(), timing);
-            }
-        }
-    }
-
-    // Check for ranking/placement markets (TOP, FINISH, PLACE)
-    if ticker.contains("TOP") || ticker.contains("FINISH") || ticker.contains("PLACE") {
-        let parts: Vec<&str> = ticker.split('-').collect();
-        if let Some(outcome) = parts.last() {
-            return format!(
-                "{} {}",
-                outcome.to_uppercase(),
-                if betting_side == "YES" { "finishes in position" } else { "doesn't finish in position" }
-            );
-        }
+    // Scorer-timing (FIRST/LAST/ANYTIME) and ranking/placement (TOP/FINISH/
+    // PLACE) tickers are matched via the data-driven grammar in
+    // `ticker_rules`, so new shapes can be added through a rules file
+    // instead of another hardcoded substring check.
+    if let Some(description) = default_ticker_rules().describe(ticker, &betting_side) {
+        return description;
     }
 
     // Default: try to extract outcome from last part
@@ -751,3 +1168,48 @@ pub fn parse_ticker_details(ticker: &str, side: &str) -> String {
         String::from("NO - check market details")
     }
 }
+
+#[cfg(test)]
+mod match_tests {
+    use super::*;
+
+    fn market(ticker: &str, title: &str) -> MarketInfo {
+        MarketInfo {
+            ticker: ticker.to_string(),
+            title: title.to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn exact_title_scores_one() {
+        assert_eq!(title_similarity("Lakers win NBA title", "Lakers win NBA title"), 1.0);
+    }
+
+    #[test]
+    fn unrelated_titles_score_near_zero() {
+        assert!(title_similarity("Lakers win the NBA title", "Fed raises interest rates") < 0.1);
+    }
+
+    #[test]
+    fn near_miss_phrasing_scores_above_default_threshold() {
+        let score = title_similarity(
+            "Will the Lakers win the 2026 NBA championship?",
+            "Lakers NBA Championship 2026",
+        );
+        assert!(score >= DEFAULT_MATCH_THRESHOLD, "score {} below threshold", score);
+    }
+
+    #[test]
+    fn match_markets_filters_by_threshold_and_sorts_descending() {
+        let candidates = vec![
+            market("A", "Lakers win the 2026 NBA championship"),
+            market("B", "Fed raises interest rates in March"),
+            market("C", "Lakers NBA championship 2026"),
+        ];
+        let matched = match_markets("Lakers win 2026 NBA championship", candidates, 0.2);
+        assert_eq!(matched.len(), 2);
+        assert!(matched[0].1 >= matched[1].1);
+        assert!(matched.iter().all(|(m, _)| m.ticker != "B"));
+    }
+}
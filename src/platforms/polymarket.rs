@@ -135,6 +135,14 @@ pub async fn fetch_market_context(condition_id: &str) -> Option<crate::alerts::M
         })
         .unwrap_or_default();
 
+    // Polymarket's CLOB is currently fee-less for takers on most markets;
+    // fall back to that when the Gamma API doesn't report a base fee (in
+    // basis points) for this market.
+    let taker_fee = market.get("takerBaseFee")
+        .and_then(|v| v.as_f64().or_else(|| v.as_str().and_then(|s| s.parse().ok())))
+        .map(|bps: f64| bps / 10000.0)
+        .unwrap_or(0.0);
+
     Some(crate::alerts::MarketContext {
         yes_price,
         no_price,
@@ -144,6 +152,12 @@ pub async fn fetch_market_context(condition_id: &str) -> Option<crate::alerts::M
         price_change_24h,
         liquidity,
         tags,
+        fees: crate::alerts::Fees { maker: 0.0, taker: taker_fee },
+        precision: crate::alerts::Precision {
+            tick_size: 0.01,
+            lot_size: 1.0,
+        },
+        outcomes: None,
     })
 }
 
@@ -286,6 +300,61 @@ pub async fn fetch_top_holders(condition_id: &str) -> Option<crate::alerts::TopH
     })
 }
 
+/// Shared by both response shapes the data-api `/trades` endpoint has been
+/// observed to return (bare array vs `{"data": [...]}`), so neither
+/// `fetch_recent_trades` nor `fetch_trades_page` has to duplicate the
+/// item-to-`Trade` mapping.
+fn activity_items_to_trades(items: Vec<ActivityItem>) -> Vec<Trade> {
+    items
+        .into_iter()
+        .filter_map(|item| {
+            // Skip trades missing critical data
+            let market = item.market?;
+            let asset_id = item.asset?;
+            let side = item.side?;
+            let size = item.size?;
+            let price = item.price?;
+
+            Some(Trade {
+                id: item.id.clone(),
+                market,
+                asset_id,
+                side,
+                size,
+                price,
+                timestamp: item
+                    .timestamp
+                    .and_then(|ts| {
+                        chrono::DateTime::from_timestamp(ts, 0)
+                            .map(|dt| dt.to_rfc3339())
+                    })
+                    .unwrap_or_else(|| format!("timestamp_error_{}", item.id)),
+                // New API includes title and outcome directly
+                market_title: item.title,
+                outcome: item.outcome,
+                wallet_id: item.proxy_wallet.or(item.user).or(item.maker),
+            })
+        })
+        .collect()
+}
+
+/// Parses whichever of the two response shapes `text` turns out to hold. If
+/// parsing fails, this returns an empty list rather than an error, so the
+/// tool keeps working even if the data-api response format changes.
+fn parse_trades_response(text: &str) -> Vec<Trade> {
+    // Try to parse as array first (some endpoints return arrays directly)
+    if let Ok(items) = serde_json::from_str::<Vec<ActivityItem>>(text) {
+        return activity_items_to_trades(items);
+    }
+
+    // Try wrapped response format
+    if let Ok(wrapped) = serde_json::from_str::<TradesResponse>(text) {
+        return activity_items_to_trades(wrapped.data);
+    }
+
+    Vec::new()
+}
+
 pub async fn fetch_recent_trades(min_value: Option<u64>) -> Result<Vec<Trade>, PolymarketError> {
     let client = reqwest::Client::new();
 
@@ -316,81 +385,103 @@ pub async fn fetch_recent_trades(min_value: Option<u64>) -> Result<Vec<Trade>, P
     }
 
     let text = response.text().await?;
+    Ok(parse_trades_response(&text))
+}
 
-    // Try to parse as array first (some endpoints return arrays directly)
-    if let Ok(items) = serde_json::from_str::<Vec<ActivityItem>>(&text) {
-        let trades = items
-            .into_iter()
-            .filter_map(|item| {
-                // Skip trades missing critical data
-                let market = item.market?;
-                let asset_id = item.asset?;
-                let side = item.side?;
-                let size = item.size?;
-                let price = item.price?;
-
-                Some(Trade {
-                    id: item.id.clone(),
-                    market,
-                    asset_id,
-                    side,
-                    size,
-                    price,
-                    timestamp: item
-                        .timestamp
-                        .and_then(|ts| {
-                            chrono::DateTime::from_timestamp(ts, 0)
-                                .map(|dt| dt.to_rfc3339())
-                        })
-                        .unwrap_or_else(|| format!("timestamp_error_{}", item.id)),
-                    // New API includes title and outcome directly
-                    market_title: item.title,
-                    outcome: item.outcome,
-                    wallet_id: item.proxy_wallet.or(item.user).or(item.maker),
-                })
-            })
-            .collect();
-        return Ok(trades);
+/// Offset-paginated variant of `fetch_recent_trades`, for walking backward
+/// through history rather than just skimming the latest page. The data-api
+/// has no time-range filter, so `commands::backfill` pages through with this
+/// and stops once it sees a trade older than its `--since` cutoff.
+///
+/// Takes `client` rather than building its own (unlike the rest of this
+/// file) so a backfill walking thousands of pages reuses one connection
+/// pool instead of opening a fresh one per page.
+pub async fn fetch_trades_page(
+    client: &reqwest::Client,
+    offset: u32,
+    limit: u32,
+) -> Result<Vec<Trade>, PolymarketError> {
+    let url = "https://data-api.polymarket.com/trades";
+
+    let limit = limit.to_string();
+    let offset = offset.to_string();
+    let response = client
+        .get(url)
+        .header("Accept", "application/json")
+        .query(&[("limit", limit.as_str()), ("offset", offset.as_str()), ("takerOnly", "true")])
+        .send()
+        .await?;
+
+    if !response.status().is_success() {
+        return Err(PolymarketError::ParseError(format!(
+            "API returned status: {}",
+            response.status()
+        )));
     }
 
-    // Try wrapped response format
-    if let Ok(wrapped) = serde_json::from_str::<TradesResponse>(&text) {
-        let trades = wrapped
-            .data
-            .into_iter()
-            .filter_map(|item| {
-                // Skip trades missing critical data
-                let market = item.market?;
-                let asset_id = item.asset?;
-                let side = item.side?;
-                let size = item.size?;
-                let price = item.price?;
-
-                Some(Trade {
-                    id: item.id.clone(),
-                    market,
-                    asset_id,
-                    side,
-                    size,
-                    price,
-                    timestamp: item
-                        .timestamp
-                        .and_then(|ts| {
-                            chrono::DateTime::from_timestamp(ts, 0)
-                                .map(|dt| dt.to_rfc3339())
-                        })
-                        .unwrap_or_else(|| format!("timestamp_error_{}", item.id)),
-                    // New API includes title and outcome directly
-                    market_title: item.title,
-                    outcome: item.outcome,
-                    wallet_id: item.proxy_wallet.or(item.user).or(item.maker),
-                })
-            })
-            .collect();
-        return Ok(trades);
+    let text = response.text().await?;
+    Ok(parse_trades_response(&text))
+}
+
+#[derive(Debug, Deserialize)]
+struct GammaMarket {
+    #[serde(rename = "conditionId")]
+    condition_id: Option<String>,
+    question: Option<String>,
+    category: Option<String>,
+    #[serde(default)]
+    tags: Vec<String>,
+    #[serde(rename = "endDate")]
+    end_date: Option<String>,
+    #[serde(default)]
+    closed: bool,
+    #[serde(rename = "outcomePrices")]
+    outcome_prices: Option<String>,
+}
+
+/// Same threshold `platforms::kalshi::search_markets` uses to drop unrelated
+/// title matches.
+const MATCH_THRESHOLD: f64 = 0.2;
+
+/// Search open Gamma markets by title, ranking hits with the same
+/// trigram/token similarity `platforms::kalshi::match_markets` uses, so
+/// callers get comparably-scoped results from either venue.
+pub async fn search_markets(title: &str) -> Result<Vec<crate::platforms::kalshi::MarketInfo>, PolymarketError> {
+    let client = reqwest::Client::new();
+    let response = client
+        .get("https://gamma-api.polymarket.com/markets")
+        .query(&[("active", "true"), ("closed", "false"), ("limit", "100")])
+        .header("Accept", "application/json")
+        .send()
+        .await?;
+
+    if !response.status().is_success() {
+        return Ok(Vec::new());
     }
 
-    // If parsing fails, return empty list rather than error
-    // This allows the tool to continue working even if Polymarket API format changes
-    Ok(Vec::new())
+    let text = response.text().await?;
+    let markets: Vec<GammaMarket> = serde_json::from_str(&text).unwrap_or_default();
+
+    let candidates: Vec<crate::platforms::kalshi::MarketInfo> = markets
+        .into_iter()
+        .filter_map(|m| {
+            Some(crate::platforms::kalshi::MarketInfo {
+                ticker: m.condition_id?,
+                title: m.question?,
+                category: m.category,
+                tags: m.tags,
+                close_time: m.end_date,
+                status: Some(if m.closed { "closed".to_string() } else { "open".to_string() }),
+                yes_price: m.outcome_prices.as_deref().and_then(|s| {
+                    let prices: Vec<String> = serde_json::from_str(s).ok()?;
+                    prices.first()?.parse::<f64>().ok()
+                }),
+            })
+        })
+        .collect();
+
+    Ok(crate::platforms::kalshi::match_markets(title, candidates, MATCH_THRESHOLD)
+        .into_iter()
+        .map(|(m, _score)| m)
+        .collect())
 }
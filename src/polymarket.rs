@@ -0,0 +1,405 @@
+//! Authenticated CLOB access, alongside `platforms::polymarket`'s read-only
+//! `fetch_*` functions. Placing or cancelling an order requires the caller to
+//! hold a Polygon wallet key, so this lives in its own module rather than
+//! bolting signing onto the public fetchers — mirrors root `kalshi.rs` sitting
+//! next to `platforms::kalshi`'s public endpoints.
+use base64::{engine::general_purpose, Engine as _};
+use num_bigint::BigUint;
+use secp256k1::ecdsa::RecoverableSignature;
+use secp256k1::{Message, Secp256k1, SecretKey};
+use serde::{Deserialize, Serialize};
+use sha3::{Digest, Keccak256};
+use std::time::{SystemTime, UNIX_EPOCH};
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum PolymarketError {
+    #[error("HTTP request failed: {0}")]
+    RequestFailed(#[from] reqwest::Error),
+    #[error("Failed to parse response: {0}")]
+    ParseError(String),
+    #[error("Authentication failed: {0}")]
+    AuthError(String),
+    #[error("Order rejected: {0}")]
+    OrderRejected(String),
+}
+
+/// Polygon mainnet chain ID the CLOB's orders are signed against.
+const CHAIN_ID: u64 = 137;
+/// Polymarket's CTF Exchange contract — the EIP-712 `verifyingContract` every
+/// order's domain separator is bound to.
+const EXCHANGE_ADDRESS: &str = "0x4bFb41d5B3570DeFd03C39a9A4D8dE6Bd8B8982E";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum OrderSide {
+    Buy,
+    Sell,
+}
+
+impl OrderSide {
+    fn as_u8(self) -> u8 {
+        match self {
+            OrderSide::Buy => 0,
+            OrderSide::Sell => 1,
+        }
+    }
+}
+
+/// A Polymarket CLOB limit order, matching the CTF Exchange's on-chain
+/// `Order` struct field-for-field — this is exactly what gets EIP-712-hashed
+/// and signed, both for the order itself and for the `L2` auth headers.
+#[derive(Debug, Clone, Serialize)]
+pub struct Order {
+    pub salt: u64,
+    pub maker: String,
+    pub signer: String,
+    pub taker: String,
+    pub token_id: String,
+    pub maker_amount: String,
+    pub taker_amount: String,
+    pub expiration: u64,
+    pub nonce: u64,
+    pub fee_rate_bps: u64,
+    pub side: OrderSide,
+    pub signature_type: u8,
+}
+
+impl Order {
+    /// Build a GTC (expiration 0) limit order for `token_id`, sized in the
+    /// CLOB's raw integer units (6-decimal USDC / outcome-token amounts), signed
+    /// and funded by `signer`'s own wallet (`maker` == `signer`, no
+    /// proxy/relayer); `taker` is the zero address, so any counterparty can
+    /// fill it.
+    pub fn new(signer: &str, token_id: &str, side: OrderSide, maker_amount: &str, taker_amount: &str) -> Self {
+        let salt = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_nanos() as u64)
+            .unwrap_or(0);
+        Self {
+            salt,
+            maker: signer.to_string(),
+            signer: signer.to_string(),
+            taker: "0x0000000000000000000000000000000000000000".to_string(),
+            token_id: token_id.to_string(),
+            maker_amount: maker_amount.to_string(),
+            taker_amount: taker_amount.to_string(),
+            expiration: 0,
+            nonce: 0,
+            fee_rate_bps: 0,
+            side,
+            signature_type: 0,
+        }
+    }
+}
+
+/// Left-pads `addr` (a `0x`-prefixed 20-byte address) into the 32-byte word
+/// EIP-712 encodes `address` fields as.
+fn pad_address(addr: &str) -> Result<[u8; 32], PolymarketError> {
+    let bytes = hex::decode(addr.trim_start_matches("0x"))
+        .map_err(|e| PolymarketError::AuthError(format!("invalid address {}: {}", addr, e)))?;
+    if bytes.len() != 20 {
+        return Err(PolymarketError::AuthError(format!("address {} is not 20 bytes", addr)));
+    }
+    let mut word = [0u8; 32];
+    word[12..].copy_from_slice(&bytes);
+    Ok(word)
+}
+
+/// Left-pads a base-10 `uint256` into its 32-byte big-endian word.
+fn pad_uint(decimal: &str) -> Result<[u8; 32], PolymarketError> {
+    let n = BigUint::parse_bytes(decimal.as_bytes(), 10)
+        .ok_or_else(|| PolymarketError::AuthError(format!("invalid uint256 {}", decimal)))?;
+    let be = n.to_bytes_be();
+    if be.len() > 32 {
+        return Err(PolymarketError::AuthError(format!("{} overflows uint256", decimal)));
+    }
+    let mut word = [0u8; 32];
+    word[32 - be.len()..].copy_from_slice(&be);
+    Ok(word)
+}
+
+fn pad_u64(n: u64) -> [u8; 32] {
+    let mut word = [0u8; 32];
+    word[24..].copy_from_slice(&n.to_be_bytes());
+    word
+}
+
+fn pad_u8(n: u8) -> [u8; 32] {
+    let mut word = [0u8; 32];
+    word[31] = n;
+    word
+}
+
+const ORDER_TYPE_HASH_PREIMAGE: &str = "Order(uint256 salt,address maker,address signer,address taker,uint256 tokenId,uint256 makerAmount,uint256 takerAmount,uint256 expiration,uint256 nonce,uint256 feeRateBps,uint8 side,uint8 signatureType)";
+const DOMAIN_TYPE_HASH_PREIMAGE: &str = "EIP712Domain(string name,string version,uint256 chainId,address verifyingContract)";
+
+fn keccak256(data: &[u8]) -> [u8; 32] {
+    let mut hasher = Keccak256::new();
+    hasher.update(data);
+    hasher.finalize().into()
+}
+
+/// Signs Polymarket CLOB orders with EIP-712 typed-data + secp256k1, per the
+/// exchange's auth scheme: hash the order against the CTF Exchange's domain
+/// separator, sign with the wallet key, and attach `POLY_ADDRESS`/
+/// `POLY_SIGNATURE`/`POLY_TIMESTAMP` headers. Shared by `place_order` and
+/// `cancel_order`.
+#[derive(Clone)]
+pub struct PolymarketSigner {
+    address: String,
+    secret_key: SecretKey,
+}
+
+impl PolymarketSigner {
+    pub fn new(address: String, private_key_hex: &str) -> Result<Self, PolymarketError> {
+        let bytes = hex::decode(private_key_hex.trim_start_matches("0x"))
+            .map_err(|e| PolymarketError::AuthError(format!("invalid private key hex: {}", e)))?;
+        let secret_key = SecretKey::from_slice(&bytes)
+            .map_err(|e| PolymarketError::AuthError(format!("invalid private key: {}", e)))?;
+        Ok(Self { address, secret_key })
+    }
+
+    fn domain_separator(&self) -> [u8; 32] {
+        let type_hash = keccak256(DOMAIN_TYPE_HASH_PREIMAGE.as_bytes());
+        let name_hash = keccak256(b"Polymarket CTF Exchange");
+        let version_hash = keccak256(b"1");
+        let chain_id = pad_uint(&CHAIN_ID.to_string()).expect("chain id always fits in a uint256");
+        let verifying_contract = pad_address(EXCHANGE_ADDRESS).expect("exchange address is a valid address");
+
+        let mut preimage = Vec::with_capacity(32 * 4);
+        preimage.extend_from_slice(&type_hash);
+        preimage.extend_from_slice(&name_hash);
+        preimage.extend_from_slice(&version_hash);
+        preimage.extend_from_slice(&chain_id);
+        preimage.extend_from_slice(&verifying_contract);
+        keccak256(&preimage)
+    }
+
+    fn struct_hash(order: &Order) -> Result<[u8; 32], PolymarketError> {
+        let type_hash = keccak256(ORDER_TYPE_HASH_PREIMAGE.as_bytes());
+
+        let mut preimage = Vec::with_capacity(32 * 12);
+        preimage.extend_from_slice(&type_hash);
+        preimage.extend_from_slice(&pad_u64(order.salt));
+        preimage.extend_from_slice(&pad_address(&order.maker)?);
+        preimage.extend_from_slice(&pad_address(&order.signer)?);
+        preimage.extend_from_slice(&pad_address(&order.taker)?);
+        preimage.extend_from_slice(&pad_uint(&order.token_id)?);
+        preimage.extend_from_slice(&pad_uint(&order.maker_amount)?);
+        preimage.extend_from_slice(&pad_uint(&order.taker_amount)?);
+        preimage.extend_from_slice(&pad_u64(order.expiration));
+        preimage.extend_from_slice(&pad_u64(order.nonce));
+        preimage.extend_from_slice(&pad_u64(order.fee_rate_bps));
+        preimage.extend_from_slice(&pad_u8(order.side.as_u8()));
+        preimage.extend_from_slice(&pad_u8(order.signature_type));
+        Ok(keccak256(&preimage))
+    }
+
+    /// `keccak256("\x19\x01" || domainSeparator || structHash(order))`, the
+    /// standard EIP-712 typed-data digest.
+    fn hash_order(&self, order: &Order) -> Result<[u8; 32], PolymarketError> {
+        let mut preimage = Vec::with_capacity(2 + 32 + 32);
+        preimage.extend_from_slice(&[0x19, 0x01]);
+        preimage.extend_from_slice(&self.domain_separator());
+        preimage.extend_from_slice(&Self::struct_hash(order)?);
+        Ok(keccak256(&preimage))
+    }
+
+    /// Sign `order`'s EIP-712 digest and return the 65-byte `r || s || v`
+    /// Ethereum signature, hex-encoded with a `0x` prefix.
+    fn sign_order(&self, order: &Order) -> Result<String, PolymarketError> {
+        let digest = self.hash_order(order)?;
+        let secp = Secp256k1::signing_only();
+        let message = Message::from_digest(digest);
+        let sig: RecoverableSignature = secp.sign_ecdsa_recoverable(&message, &self.secret_key);
+        let (recovery_id, compact) = sig.serialize_compact();
+
+        let mut bytes = Vec::with_capacity(65);
+        bytes.extend_from_slice(&compact);
+        bytes.push(recovery_id.to_i32() as u8 + 27);
+        Ok(format!("0x{}", hex::encode(bytes)))
+    }
+
+    /// Build the `POLY_ADDRESS`/`POLY_SIGNATURE`/`POLY_TIMESTAMP` headers the
+    /// CLOB requires on private endpoints: sign `order`'s EIP-712 digest and
+    /// attach the signer's address plus the current Unix timestamp. The
+    /// timestamp is not part of the signed digest.
+    pub fn l2_headers(&self, order: &Order) -> Result<[(&'static str, String); 3], PolymarketError> {
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_err(|e| PolymarketError::AuthError(e.to_string()))?
+            .as_secs()
+            .to_string();
+        let signature = self.sign_order(order)?;
+
+        Ok([
+            ("POLY_ADDRESS", self.address.clone()),
+            ("POLY_SIGNATURE", signature),
+            ("POLY_TIMESTAMP", timestamp),
+        ])
+    }
+}
+
+/// Build a `PolymarketSigner` from `config`, failing with `AuthError` if
+/// credentials aren't configured. There is no unauthenticated fallback for
+/// the endpoints below.
+fn signer_from_config(config: &crate::config::Config) -> Result<PolymarketSigner, PolymarketError> {
+    let (address, private_key) = config
+        .polymarket_address
+        .as_ref()
+        .zip(config.polymarket_private_key.as_ref())
+        .ok_or_else(|| {
+            PolymarketError::AuthError("missing polymarket_address/polymarket_private_key in config".to_string())
+        })?;
+    PolymarketSigner::new(address.clone(), private_key)
+}
+
+#[derive(Debug, Deserialize)]
+struct PlaceOrderResponse {
+    #[serde(default)]
+    success: bool,
+    #[serde(rename = "orderID")]
+    order_id: Option<String>,
+    #[serde(rename = "errorMsg")]
+    error_msg: Option<String>,
+}
+
+/// Sign and submit a limit order to the CLOB. Returns the exchange-assigned
+/// order ID on success, or `OrderRejected` with the exchange's own error
+/// message (e.g. insufficient balance, price out of bounds) on failure.
+pub async fn place_order(config: &crate::config::Config, order: &Order) -> Result<String, PolymarketError> {
+    let signer = signer_from_config(config)?;
+    let signature = signer.sign_order(order)?;
+    let headers = signer.l2_headers(order)?;
+
+    let client = reqwest::Client::new();
+    let mut request = client
+        .post("https://clob.polymarket.com/order")
+        .header("Accept", "application/json")
+        .json(&serde_json::json!({
+            "order": {
+                "salt": order.salt.to_string(),
+                "maker": order.maker,
+                "signer": order.signer,
+                "taker": order.taker,
+                "tokenId": order.token_id,
+                "makerAmount": order.maker_amount,
+                "takerAmount": order.taker_amount,
+                "expiration": order.expiration.to_string(),
+                "nonce": order.nonce.to_string(),
+                "feeRateBps": order.fee_rate_bps.to_string(),
+                "side": order.side.as_u8(),
+                "signatureType": order.signature_type,
+                "signature": signature,
+            },
+        }));
+    for (name, value) in headers {
+        request = request.header(name, value);
+    }
+
+    let response = request.send().await?;
+    let status = response.status();
+    let text = response.text().await?;
+
+    if !status.is_success() {
+        return Err(PolymarketError::OrderRejected(format!("HTTP {}: {}", status, text)));
+    }
+
+    let parsed: PlaceOrderResponse =
+        serde_json::from_str(&text).map_err(|e| PolymarketError::ParseError(e.to_string()))?;
+
+    if !parsed.success {
+        return Err(PolymarketError::OrderRejected(
+            parsed.error_msg.unwrap_or_else(|| "unknown error".to_string()),
+        ));
+    }
+
+    parsed
+        .order_id
+        .ok_or_else(|| PolymarketError::ParseError("order accepted but no orderID returned".to_string()))
+}
+
+#[derive(Debug, Deserialize)]
+struct CancelOrderResponse {
+    #[serde(default)]
+    not_canceled: std::collections::HashMap<String, String>,
+}
+
+/// Cancel a resting order by ID. The CLOB's `DELETE /order` is keyed off the
+/// same L2 headers as `place_order`, signed over a zero-value cancel-intent
+/// order for `token_id` rather than the original order (the exchange matches
+/// on `order_id`, not the signature's payload).
+pub async fn cancel_order(
+    config: &crate::config::Config,
+    order_id: &str,
+    token_id: &str,
+) -> Result<(), PolymarketError> {
+    let signer = signer_from_config(config)?;
+    let cancel_intent = Order::new(&signer.address, token_id, OrderSide::Sell, "0", "0");
+    let headers = signer.l2_headers(&cancel_intent)?;
+
+    let client = reqwest::Client::new();
+    let mut request = client
+        .delete("https://clob.polymarket.com/order")
+        .header("Accept", "application/json")
+        .json(&serde_json::json!({ "orderID": order_id }));
+    for (name, value) in headers {
+        request = request.header(name, value);
+    }
+
+    let response = request.send().await?;
+    let status = response.status();
+    let text = response.text().await?;
+
+    if !status.is_success() {
+        return Err(PolymarketError::OrderRejected(format!("HTTP {}: {}", status, text)));
+    }
+
+    let parsed: CancelOrderResponse =
+        serde_json::from_str(&text).map_err(|e| PolymarketError::ParseError(e.to_string()))?;
+
+    if parsed.not_canceled.contains_key(order_id) {
+        let reason = parsed.not_canceled.get(order_id).cloned().unwrap_or_default();
+        return Err(PolymarketError::OrderRejected(reason));
+    }
+
+    Ok(())
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct OpenOrder {
+    pub id: String,
+    #[serde(rename = "asset_id")]
+    pub token_id: String,
+    pub side: String,
+    pub price: String,
+    #[serde(rename = "original_size")]
+    pub original_size: String,
+    #[serde(rename = "size_matched")]
+    pub size_matched: String,
+    pub status: String,
+}
+
+/// Fetch the authenticated wallet's own resting orders. There is no public
+/// equivalent of this endpoint, so credentials are mandatory.
+pub async fn get_open_orders(config: &crate::config::Config) -> Result<Vec<OpenOrder>, PolymarketError> {
+    let signer = signer_from_config(config)?;
+    // `GET /data/orders` is authenticated the same way as order placement: a
+    // signed zero-value order over no particular token just to derive headers.
+    let auth_intent = Order::new(&signer.address, "0", OrderSide::Buy, "0", "0");
+    let headers = signer.l2_headers(&auth_intent)?;
+
+    let client = reqwest::Client::new();
+    let mut request = client.get("https://clob.polymarket.com/data/orders").header("Accept", "application/json");
+    for (name, value) in headers {
+        request = request.header(name, value);
+    }
+
+    let response = request.send().await?;
+    if !response.status().is_success() {
+        return Err(PolymarketError::AuthError(format!("HTTP {}", response.status())));
+    }
+
+    let text = response.text().await?;
+    serde_json::from_str(&text).map_err(|e| PolymarketError::ParseError(e.to_string()))
+}
@@ -0,0 +1,173 @@
+//! Per-key snapshot cache that collapses concurrent fetches for the same
+//! key into a single in-flight request, the way a per-key price cache does:
+//! the *first* caller for a stale/missing key runs the fetch under the
+//! entry's own async mutex, and any concurrent caller for the same key
+//! blocks on that mutex and reads the value it just stored instead of
+//! firing its own duplicate upstream request. A burst of whale trades that
+//! land on the same market in one poll batch collapses into one
+//! `kalshi::fetch_market_context`/`fetch_order_book`-style fetch rather than
+//! one per trade, without weakening any downstream gate — callers always
+//! get data at least as fresh as `ttl`, not best-effort stale data.
+
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use tokio::sync::{Mutex, RwLock};
+
+/// What a cache lookup found. Kept distinct from a plain `Option<T>` so
+/// gate logic (`closes_within_24h`, `yes_price_cents`) can tell "never
+/// fetched" apart from "expired" when that distinction matters to a caller
+/// (e.g. logging), even though both currently mean "fetch now".
+#[derive(Debug, Clone)]
+pub enum CacheLookup<T> {
+    Fresh(T),
+    Stale,
+    Missing,
+}
+
+struct CachedSnapshot<T> {
+    value: Option<T>,
+    fetched_at: Option<Instant>,
+}
+
+impl<T> Default for CachedSnapshot<T> {
+    fn default() -> Self {
+        Self { value: None, fetched_at: None }
+    }
+}
+
+struct CacheEntry<T> {
+    inner: Arc<Mutex<CachedSnapshot<T>>>,
+}
+
+impl<T> Clone for CacheEntry<T> {
+    fn clone(&self) -> Self {
+        Self { inner: self.inner.clone() }
+    }
+}
+
+impl<T> Default for CacheEntry<T> {
+    fn default() -> Self {
+        Self { inner: Arc::new(Mutex::new(CachedSnapshot::default())) }
+    }
+}
+
+/// A per-key snapshot cache with a first-fetch mutex. `T` is whatever's
+/// being cached (a Kalshi market snapshot, an order book, a top-holders
+/// summary); separate `QuoteCache`s with different TTLs are expected for
+/// each — e.g. 3s for snapshots, 30s for top-holders, matching how often
+/// each one actually changes.
+pub struct QuoteCache<T> {
+    entries: RwLock<HashMap<String, CacheEntry<T>>>,
+    ttl: Duration,
+}
+
+impl<T: Clone> QuoteCache<T> {
+    pub fn new(ttl: Duration) -> Self {
+        Self { entries: RwLock::new(HashMap::new()), ttl }
+    }
+
+    /// Non-blocking read of whatever's cached for `key`, without
+    /// triggering a fetch.
+    pub async fn peek(&self, key: &str) -> CacheLookup<T> {
+        let entry = {
+            let entries = self.entries.read().await;
+            match entries.get(key) {
+                Some(e) => e.clone(),
+                None => return CacheLookup::Missing,
+            }
+        };
+        let snapshot = entry.inner.lock().await;
+        Self::lookup(&snapshot, self.ttl)
+    }
+
+    /// Return the cached value for `key` if still within `ttl`; otherwise
+    /// call `fetch` under the entry's mutex and cache whatever it returns.
+    /// Concurrent callers for the same key all await the same mutex — only
+    /// the one that acquires it first actually runs `fetch`; the rest then
+    /// see its result already cached and return that instead of fetching
+    /// again themselves.
+    pub async fn get_or_fetch<F, Fut>(&self, key: &str, fetch: F) -> Option<T>
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = Option<T>>,
+    {
+        let entry = {
+            let mut entries = self.entries.write().await;
+            entries.entry(key.to_string()).or_default().clone()
+        };
+
+        let mut snapshot = entry.inner.lock().await;
+        if let CacheLookup::Fresh(v) = Self::lookup(&snapshot, self.ttl) {
+            return Some(v);
+        }
+
+        let fresh = fetch().await;
+        snapshot.fetched_at = Some(Instant::now());
+        snapshot.value = fresh.clone();
+        fresh
+    }
+
+    fn lookup(snapshot: &CachedSnapshot<T>, ttl: Duration) -> CacheLookup<T> {
+        match (&snapshot.value, snapshot.fetched_at) {
+            (Some(v), Some(at)) if at.elapsed() < ttl => CacheLookup::Fresh(v.clone()),
+            (Some(_), Some(_)) => CacheLookup::Stale,
+            _ => CacheLookup::Missing,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    #[tokio::test]
+    async fn concurrent_callers_for_the_same_key_only_fetch_once() {
+        let cache = Arc::new(QuoteCache::<i64>::new(Duration::from_secs(60)));
+        let fetch_count = Arc::new(AtomicU32::new(0));
+
+        let mut handles = Vec::new();
+        for _ in 0..8 {
+            let cache = cache.clone();
+            let fetch_count = fetch_count.clone();
+            handles.push(tokio::spawn(async move {
+                cache
+                    .get_or_fetch("KXFB-WIN", || async {
+                        fetch_count.fetch_add(1, Ordering::SeqCst);
+                        tokio::time::sleep(Duration::from_millis(20)).await;
+                        Some(42)
+                    })
+                    .await
+            }));
+        }
+
+        for h in handles {
+            assert_eq!(h.await.unwrap(), Some(42));
+        }
+        assert_eq!(fetch_count.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn expired_entries_are_refetched() {
+        let cache = QuoteCache::<i64>::new(Duration::from_millis(10));
+        assert_eq!(cache.get_or_fetch("k", || async { Some(1) }).await, Some(1));
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        assert_eq!(cache.get_or_fetch("k", || async { Some(2) }).await, Some(2));
+    }
+
+    #[tokio::test]
+    async fn missing_key_peeks_as_missing() {
+        let cache = QuoteCache::<i64>::new(Duration::from_secs(60));
+        assert!(matches!(cache.peek("nope").await, CacheLookup::Missing));
+    }
+
+    #[tokio::test]
+    async fn fresh_entry_peeks_as_fresh() {
+        let cache = QuoteCache::<i64>::new(Duration::from_secs(60));
+        cache.get_or_fetch("k", || async { Some(7) }).await;
+        assert!(matches!(cache.peek("k").await, CacheLookup::Fresh(7)));
+    }
+}
@@ -0,0 +1,580 @@
+//! Async, connection-pooled persistence for wallet memory — the one slice
+//! of `AlertStore` that sits on the trade-processing hot path. Pulling it
+//! out behind its own trait lets `WalletTracker` run against a pooled
+//! Postgres backend for multi-instance deployments instead of serializing
+//! every write on SQLite's single `Mutex<Connection>`, without touching the
+//! rest of `AlertStore` (alerts, uncategorized titles, analytics views).
+//!
+//! Mirrors `alerts::sinks::PostgresSink`'s direct `tokio_postgres` use, just
+//! with a small round-robin pool instead of one client — no `deadpool`
+//! dependency for what's still a handful of connections.
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use async_trait::async_trait;
+use tokio_postgres::NoTls;
+
+use crate::db::{self, AlertStore, WalletMemoryRow};
+use crate::types::WalletActivity;
+
+/// Wallet memory is only consulted for the last 12h of activity.
+const WALLET_MEMORY_WINDOW_SECS: i64 = 43_200;
+
+/// Async persistence boundary for wallet-memory reads/writes. `WalletTracker`
+/// goes through this instead of blocking on `AlertStore` directly, so the
+/// backing store (local SQLite vs a pooled Postgres) can be swapped by
+/// config without `record_to_db`/`get_wallet_history`/`maybe_refresh_cache`
+/// changing.
+#[async_trait]
+pub trait WalletMemoryStore: Send + Sync {
+    /// Insert or update a wallet's sighting in a market. Returns `false` if
+    /// the write failed, so callers can surface it (e.g. as a metric).
+    #[allow(clippy::too_many_arguments)]
+    async fn record(
+        &self,
+        wallet_id: &str,
+        market_title: Option<&str>,
+        market_id: Option<&str>,
+        outcome: Option<&str>,
+        action: &str,
+        value: f64,
+        price: f64,
+        platform: &str,
+    ) -> bool;
+
+    /// A wallet's activity in the last 12h, most recent first.
+    async fn history(&self, wallet_hash: &str) -> Vec<WalletMemoryRow>;
+
+    /// Distinct wallet hashes seen in the last 12h.
+    async fn distinct_recent_hashes(&self) -> Vec<String>;
+
+    /// Rolling 1h/24h transaction count and value for `wallet_hash`,
+    /// reconstructed from persisted history rather than an in-process cache
+    /// — what survives a restart. See `db::AlertStore::wallet_activity`.
+    async fn activity(&self, wallet_hash: &str) -> WalletActivity;
+}
+
+/// Wraps the existing synchronous `AlertStore` (SQLite or in-memory) so its
+/// wallet-memory methods can be called from async code via `spawn_blocking`
+/// — the default backend, and the only one available without a Postgres DSN.
+pub struct SqliteWalletMemoryStore {
+    store: Arc<dyn AlertStore>,
+}
+
+impl SqliteWalletMemoryStore {
+    pub fn new(store: Arc<dyn AlertStore>) -> Self {
+        Self { store }
+    }
+}
+
+#[async_trait]
+impl WalletMemoryStore for SqliteWalletMemoryStore {
+    async fn record(
+        &self,
+        wallet_id: &str,
+        market_title: Option<&str>,
+        market_id: Option<&str>,
+        outcome: Option<&str>,
+        action: &str,
+        value: f64,
+        price: f64,
+        platform: &str,
+    ) -> bool {
+        let store = self.store.clone();
+        let wallet_id = wallet_id.to_string();
+        let market_title = market_title.map(String::from);
+        let market_id = market_id.map(String::from);
+        let outcome = outcome.map(String::from);
+        let action = action.to_string();
+        let platform = platform.to_string();
+
+        tokio::task::spawn_blocking(move || {
+            store.record_wallet_memory(
+                &wallet_id,
+                market_title.as_deref(),
+                market_id.as_deref(),
+                outcome.as_deref(),
+                &action,
+                value,
+                price,
+                &platform,
+            )
+        })
+        .await
+        .unwrap_or(false)
+    }
+
+    async fn history(&self, wallet_hash: &str) -> Vec<WalletMemoryRow> {
+        let store = self.store.clone();
+        let wallet_hash = wallet_hash.to_string();
+        tokio::task::spawn_blocking(move || store.wallet_history(&wallet_hash))
+            .await
+            .unwrap_or_default()
+    }
+
+    async fn distinct_recent_hashes(&self) -> Vec<String> {
+        let store = self.store.clone();
+        tokio::task::spawn_blocking(move || store.known_wallet_hashes())
+            .await
+            .unwrap_or_default()
+    }
+
+    async fn activity(&self, wallet_hash: &str) -> WalletActivity {
+        let store = self.store.clone();
+        let wallet_hash = wallet_hash.to_string();
+        tokio::task::spawn_blocking(move || store.wallet_activity(&wallet_hash))
+            .await
+            .unwrap_or_default()
+    }
+}
+
+/// Pooled Postgres-backed `WalletMemoryStore`, for running several watcher
+/// processes against one shared wallet-memory table instead of each one
+/// serializing writes on its own local SQLite file.
+pub struct PostgresWalletMemoryStore {
+    clients: Vec<tokio_postgres::Client>,
+    next: AtomicUsize,
+}
+
+impl PostgresWalletMemoryStore {
+    /// Opens `pool_size` connections to `connection_string` (a libpq-style
+    /// DSN, e.g. `host=localhost user=wwatcher dbname=analytics`) and
+    /// creates the `wallet_memory` table if it doesn't exist yet.
+    pub async fn connect(connection_string: &str, pool_size: usize) -> Result<Self, tokio_postgres::Error> {
+        let mut clients = Vec::with_capacity(pool_size.max(1));
+        for _ in 0..pool_size.max(1) {
+            let (client, connection) = tokio_postgres::connect(connection_string, NoTls).await?;
+            tokio::spawn(async move {
+                if let Err(e) = connection.await {
+                    eprintln!("[store] Postgres connection closed: {}", e);
+                }
+            });
+            clients.push(client);
+        }
+
+        clients[0]
+            .batch_execute(
+                "CREATE TABLE IF NOT EXISTS wallet_memory (
+                    id BIGSERIAL PRIMARY KEY,
+                    wallet_hash TEXT NOT NULL,
+                    wallet_id TEXT NOT NULL,
+                    market_title TEXT,
+                    market_id TEXT,
+                    outcome TEXT,
+                    action TEXT NOT NULL,
+                    value DOUBLE PRECISION NOT NULL,
+                    price DOUBLE PRECISION NOT NULL,
+                    platform TEXT NOT NULL,
+                    seen_at BIGINT NOT NULL,
+                    UNIQUE (wallet_hash, market_id, seen_at)
+                )",
+            )
+            .await?;
+
+        Ok(Self { clients, next: AtomicUsize::new(0) })
+    }
+
+    /// Round-robins across the pool so concurrent callers don't pile up on
+    /// one connection.
+    fn client(&self) -> &tokio_postgres::Client {
+        let idx = self.next.fetch_add(1, Ordering::Relaxed) % self.clients.len();
+        &self.clients[idx]
+    }
+}
+
+/// Async boundary for the last `AlertStore` read still worth decoupling from
+/// the local SQLite mutex — the alert count `commands::watch`'s startup
+/// banner and `show_status` display. Mirrors `WalletMemoryStore`'s shape so
+/// the backend is chosen the same way, from config: local SQLite by
+/// default, or the same Postgres database `alerts::sinks::PostgresSink`
+/// writes alerts into (`wwatcher_alerts`) when `postgres_alert_url` is set.
+#[async_trait]
+pub trait AlertCountStore: Send + Sync {
+    async fn alert_count(&self) -> i64;
+}
+
+/// Wraps the existing synchronous `AlertStore` so `alert_count` can be
+/// called from async code via `spawn_blocking` — the default backend.
+pub struct SqliteAlertCountStore {
+    store: Arc<dyn AlertStore>,
+}
+
+impl SqliteAlertCountStore {
+    pub fn new(store: Arc<dyn AlertStore>) -> Self {
+        Self { store }
+    }
+}
+
+#[async_trait]
+impl AlertCountStore for SqliteAlertCountStore {
+    async fn alert_count(&self) -> i64 {
+        let store = self.store.clone();
+        tokio::task::spawn_blocking(move || store.alert_count())
+            .await
+            .unwrap_or(0)
+    }
+}
+
+/// Counting is a low-frequency read (startup banner, `status`), not a
+/// per-trade hot path like wallet memory, so this pool stays small and
+/// fixed rather than taking a config knob like `wallet_memory_pool_size`.
+const ALERT_COUNT_POOL_SIZE: usize = 2;
+
+/// Pooled Postgres-backed `AlertCountStore`, reading `wwatcher_alerts` —
+/// the same table `PostgresSink` writes to — so a Postgres-backed
+/// deployment's banner reflects the shared analytics database rather than
+/// whichever instance's local SQLite file happens to answer.
+pub struct PostgresAlertCountStore {
+    clients: Vec<tokio_postgres::Client>,
+    next: AtomicUsize,
+}
+
+impl PostgresAlertCountStore {
+    /// Opens a small fixed-size pool to `connection_string` (a libpq-style
+    /// DSN). Assumes `wwatcher_alerts` already exists — same assumption
+    /// `PostgresSink` makes, since that table lives in an externally
+    /// provisioned analytics database rather than one this tool manages.
+    pub async fn connect(connection_string: &str) -> Result<Self, tokio_postgres::Error> {
+        let mut clients = Vec::with_capacity(ALERT_COUNT_POOL_SIZE);
+        for _ in 0..ALERT_COUNT_POOL_SIZE {
+            let (client, connection) = tokio_postgres::connect(connection_string, NoTls).await?;
+            tokio::spawn(async move {
+                if let Err(e) = connection.await {
+                    eprintln!("[store] Postgres connection closed: {}", e);
+                }
+            });
+            clients.push(client);
+        }
+
+        Ok(Self { clients, next: AtomicUsize::new(0) })
+    }
+
+    fn client(&self) -> &tokio_postgres::Client {
+        let idx = self.next.fetch_add(1, Ordering::Relaxed) % self.clients.len();
+        &self.clients[idx]
+    }
+}
+
+#[async_trait]
+impl AlertCountStore for PostgresAlertCountStore {
+    async fn alert_count(&self) -> i64 {
+        match self.client().query_one("SELECT COUNT(*) FROM wwatcher_alerts", &[]).await {
+            Ok(row) => row.get(0),
+            Err(e) => {
+                eprintln!("[store] Failed to query alert count: {}", e);
+                0
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl WalletMemoryStore for PostgresWalletMemoryStore {
+    async fn record(
+        &self,
+        wallet_id: &str,
+        market_title: Option<&str>,
+        market_id: Option<&str>,
+        outcome: Option<&str>,
+        action: &str,
+        value: f64,
+        price: f64,
+        platform: &str,
+    ) -> bool {
+        let hash = db::wallet_hash(wallet_id);
+        let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs() as i64;
+
+        let result = self
+            .client()
+            .execute(
+                "INSERT INTO wallet_memory
+                 (wallet_hash, wallet_id, market_title, market_id, outcome, action, value, price, platform, seen_at)
+                 VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
+                 ON CONFLICT (wallet_hash, market_id, seen_at) DO UPDATE SET
+                     value = excluded.value,
+                     price = excluded.price,
+                     action = excluded.action",
+                &[&hash, &wallet_id, &market_title, &market_id, &outcome, &action, &value, &price, &platform, &now],
+            )
+            .await;
+
+        if let Err(e) = &result {
+            eprintln!("[store] Failed to record wallet memory: {}", e);
+        }
+        result.is_ok()
+    }
+
+    async fn history(&self, wallet_hash: &str) -> Vec<WalletMemoryRow> {
+        let cutoff = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs() as i64 - WALLET_MEMORY_WINDOW_SECS;
+
+        let rows = self
+            .client()
+            .query(
+                "SELECT wallet_id, market_title, market_id, outcome, action, value, price, platform, seen_at
+                 FROM wallet_memory
+                 WHERE wallet_hash = $1 AND seen_at > $2
+                 ORDER BY seen_at DESC",
+                &[&wallet_hash, &cutoff],
+            )
+            .await;
+
+        match rows {
+            Ok(rows) => rows
+                .iter()
+                .map(|row| WalletMemoryRow {
+                    wallet_id: row.get(0),
+                    market_title: row.get(1),
+                    market_id: row.get(2),
+                    outcome: row.get(3),
+                    action: row.get(4),
+                    value: row.get(5),
+                    price: row.get(6),
+                    platform: row.get(7),
+                    seen_at: row.get(8),
+                })
+                .collect(),
+            Err(e) => {
+                eprintln!("[store] Failed to query wallet history: {}", e);
+                Vec::new()
+            }
+        }
+    }
+
+    async fn distinct_recent_hashes(&self) -> Vec<String> {
+        let cutoff = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs() as i64 - WALLET_MEMORY_WINDOW_SECS;
+
+        let rows = self
+            .client()
+            .query(
+                "SELECT DISTINCT wallet_hash FROM wallet_memory WHERE seen_at > $1",
+                &[&cutoff],
+            )
+            .await;
+
+        match rows {
+            Ok(rows) => rows.iter().map(|row| row.get(0)).collect(),
+            Err(e) => {
+                eprintln!("[store] Failed to query known wallet hashes: {}", e);
+                Vec::new()
+            }
+        }
+    }
+
+    async fn activity(&self, wallet_hash: &str) -> WalletActivity {
+        let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs() as i64;
+        let hour_cutoff = now - 3600;
+        let day_cutoff = now - 86_400;
+
+        let row = self
+            .client()
+            .query_one(
+                "SELECT
+                    COALESCE(SUM(CASE WHEN seen_at > $2 THEN 1 ELSE 0 END), 0),
+                    COUNT(*),
+                    COALESCE(SUM(CASE WHEN seen_at > $2 THEN value ELSE 0 END), 0),
+                    COALESCE(SUM(value), 0)
+                 FROM wallet_memory
+                 WHERE wallet_hash = $1 AND seen_at > $3",
+                &[&wallet_hash, &hour_cutoff, &day_cutoff],
+            )
+            .await;
+
+        match row {
+            Ok(row) => {
+                let txns_hour: i64 = row.get(0);
+                let txns_day: i64 = row.get(1);
+                let value_hour: f64 = row.get(2);
+                let value_day: f64 = row.get(3);
+                WalletActivity {
+                    transactions_last_hour: txns_hour as usize,
+                    transactions_last_day: txns_day as usize,
+                    total_value_hour: value_hour,
+                    total_value_day: value_day,
+                    is_repeat_actor: txns_hour > 1,
+                    is_heavy_actor: txns_day >= 5,
+                }
+            }
+            Err(e) => {
+                eprintln!("[store] Failed to query wallet activity: {}", e);
+                WalletActivity::default()
+            }
+        }
+    }
+}
+
+/// Every resolution a trade is rolled into on ingest, same list
+/// `candles::ALL_RESOLUTIONS` uses for the local SQLite candle cache.
+const TRADE_STORE_RESOLUTIONS: [crate::candles::Resolution; 6] = crate::candles::ALL_RESOLUTIONS;
+
+/// Pooled Postgres persistence for raw trades and the OHLCV candles rolled
+/// up from them, for deployments that want a queryable flow database
+/// shared across instances instead of (or alongside) the local SQLite
+/// `candles` table `candles::CandleCache` maintains. Mirrors
+/// `PostgresWalletMemoryStore`'s round-robin pool — a handful of
+/// connections is plenty for this volume, so no `deadpool` here either.
+pub struct PostgresTradeStore {
+    clients: Vec<tokio_postgres::Client>,
+    next: AtomicUsize,
+}
+
+impl PostgresTradeStore {
+    /// Opens `pool_size` connections to `connection_string` and creates the
+    /// `trades` and `candles` tables if they don't exist yet. Named the same
+    /// as the SQLite side's `candles` table, but this is a separate
+    /// Postgres database — there's no cross-database foreign key to keep
+    /// the two in sync, by design (see `config::Config::trade_store_url`).
+    pub async fn connect(connection_string: &str, pool_size: usize) -> Result<Self, tokio_postgres::Error> {
+        let mut clients = Vec::with_capacity(pool_size.max(1));
+        for _ in 0..pool_size.max(1) {
+            let (client, connection) = tokio_postgres::connect(connection_string, NoTls).await?;
+            tokio::spawn(async move {
+                if let Err(e) = connection.await {
+                    eprintln!("[store] Postgres connection closed: {}", e);
+                }
+            });
+            clients.push(client);
+        }
+
+        clients[0]
+            .batch_execute(
+                "CREATE TABLE IF NOT EXISTS trades (
+                    id BIGSERIAL PRIMARY KEY,
+                    platform TEXT NOT NULL,
+                    market TEXT NOT NULL,
+                    side TEXT NOT NULL,
+                    price DOUBLE PRECISION NOT NULL,
+                    size DOUBLE PRECISION NOT NULL,
+                    value DOUBLE PRECISION NOT NULL,
+                    occurred_at BIGINT NOT NULL
+                );
+                CREATE TABLE IF NOT EXISTS candles (
+                    platform TEXT NOT NULL,
+                    market TEXT NOT NULL,
+                    resolution TEXT NOT NULL,
+                    start_ts BIGINT NOT NULL,
+                    open DOUBLE PRECISION NOT NULL,
+                    high DOUBLE PRECISION NOT NULL,
+                    low DOUBLE PRECISION NOT NULL,
+                    close DOUBLE PRECISION NOT NULL,
+                    volume DOUBLE PRECISION NOT NULL,
+                    trade_count BIGINT NOT NULL,
+                    last_ts BIGINT NOT NULL,
+                    PRIMARY KEY (platform, market, resolution, start_ts)
+                )",
+            )
+            .await?;
+
+        Ok(Self { clients, next: AtomicUsize::new(0) })
+    }
+
+    fn client(&self) -> &tokio_postgres::Client {
+        let idx = self.next.fetch_add(1, Ordering::Relaxed) % self.clients.len();
+        &self.clients[idx]
+    }
+
+    /// Persist one normalized trade and roll it into every configured
+    /// resolution's candle bucket, keyed by `occurred_at - occurred_at %
+    /// resolution_secs`. Each candle upsert is `ON CONFLICT DO UPDATE` adding
+    /// to the existing bucket, and `trades` has no unique key, so replaying
+    /// the same trade twice (e.g. `backfill` re-running a window) inserts it
+    /// twice and double-counts `volume`/`trade_count` — callers that need
+    /// replay-safety should dedup
+    /// before calling this, the same way `candles::record_trade` expects.
+    pub async fn record_trade(
+        &self,
+        platform: &str,
+        market: &str,
+        side: &str,
+        price: f64,
+        size: f64,
+        occurred_at: i64,
+    ) -> bool {
+        let value = price * size;
+        let client = self.client();
+
+        let inserted = client
+            .execute(
+                "INSERT INTO trades (platform, market, side, price, size, value, occurred_at)
+                 VALUES ($1, $2, $3, $4, $5, $6, $7)",
+                &[&platform, &market, &side, &price, &size, &value, &occurred_at],
+            )
+            .await;
+        if let Err(e) = &inserted {
+            eprintln!("[store] Failed to persist trade: {}", e);
+            return false;
+        }
+
+        for resolution in TRADE_STORE_RESOLUTIONS {
+            let start_ts = occurred_at - occurred_at.rem_euclid(resolution.seconds());
+            let result = client
+                .execute(
+                    "INSERT INTO candles (platform, market, resolution, start_ts, open, high, low, close, volume, trade_count, last_ts)
+                     VALUES ($1, $2, $3, $4, $5, $5, $5, $5, $6, 1, $7)
+                     ON CONFLICT (platform, market, resolution, start_ts) DO UPDATE SET
+                         high = GREATEST(candles.high, excluded.high),
+                         low = LEAST(candles.low, excluded.low),
+                         close = CASE WHEN excluded.last_ts >= candles.last_ts THEN excluded.close ELSE candles.close END,
+                         last_ts = GREATEST(candles.last_ts, excluded.last_ts),
+                         volume = candles.volume + excluded.volume,
+                         trade_count = candles.trade_count + 1",
+                    &[&platform, &market, &resolution.as_str(), &start_ts, &price, &value, &occurred_at],
+                )
+                .await;
+            if let Err(e) = result {
+                eprintln!("[store] Failed to upsert candle for {} {} ({}): {}", platform, market, resolution.as_str(), e);
+                return false;
+            }
+        }
+
+        true
+    }
+
+    /// Candles for `(platform, market, resolution)` within `[from_ts,
+    /// to_ts]`, oldest first — the Postgres-backed counterpart to
+    /// `AlertStore::query_candles_range`, read by `commands::history`.
+    pub async fn query_candles(
+        &self,
+        platform: &str,
+        market: &str,
+        resolution: &str,
+        from_ts: i64,
+        to_ts: i64,
+    ) -> Vec<crate::candles::Candle> {
+        let rows = self
+            .client()
+            .query(
+                "SELECT platform, market, resolution, start_ts, open, high, low, close, volume, trade_count, last_ts
+                 FROM candles
+                 WHERE platform = $1 AND market = $2 AND resolution = $3 AND start_ts BETWEEN $4 AND $5
+                 ORDER BY start_ts ASC",
+                &[&platform, &market, &resolution, &from_ts, &to_ts],
+            )
+            .await;
+
+        match rows {
+            Ok(rows) => rows
+                .iter()
+                .filter_map(|row| {
+                    Some(crate::candles::Candle {
+                        platform: row.get(0),
+                        market: row.get(1),
+                        resolution: crate::candles::Resolution::from_str(row.get(2))?,
+                        start_ts: row.get(3),
+                        open: row.get(4),
+                        high: row.get(5),
+                        low: row.get(6),
+                        close: row.get(7),
+                        volume: row.get(8),
+                        trade_count: row.get(9),
+                        last_ts: row.get(10),
+                    })
+                })
+                .collect(),
+            Err(e) => {
+                eprintln!("[store] Failed to query candles: {}", e);
+                Vec::new()
+            }
+        }
+    }
+}
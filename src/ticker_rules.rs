@@ -0,0 +1,215 @@
+/// Data-driven replacement for the hardcoded substring checks
+/// `platforms::kalshi::parse_ticker_details` used for scorer-timing
+/// (FIRST/LAST/ANYTIME) and placement (TOP/FINISH/PLACE) tickers: an ordered
+/// list of regexes, each with a YES/NO description template, so new ticker
+/// shapes can be taught to the parser via a rules file instead of a
+/// recompile.
+use regex::Regex;
+use serde::Deserialize;
+
+/// One rule as loaded from JSON: `pattern` is matched against the raw
+/// ticker, and whichever of `yes_template`/`no_template` applies has its
+/// `{name}` placeholders substituted with `pattern`'s named capture groups.
+#[derive(Debug, Clone, Deserialize)]
+pub struct TickerRule {
+    pub pattern: String,
+    pub yes_template: String,
+    pub no_template: String,
+    /// Free-form category tag (e.g. "scorer:first", "placement"), read by
+    /// `market_outcome::classify` to build a structured `MarketOutcome`
+    /// instead of just a description string. Optional — rules loaded from a
+    /// user-supplied file that omit it simply can't be classified, only
+    /// described.
+    #[serde(default)]
+    pub kind: Option<String>,
+}
+
+struct CompiledRule {
+    regex: Regex,
+    yes_template: String,
+    no_template: String,
+    kind: Option<String>,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum TickerRuleError {
+    #[error("failed to read ticker rules file: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("failed to parse ticker rules file: {0}")]
+    Parse(#[from] serde_json::Error),
+    #[error("invalid regex in rule {index} (\"{pattern}\"): {source}")]
+    InvalidPattern {
+        index: usize,
+        pattern: String,
+        #[source]
+        source: regex::Error,
+    },
+}
+
+/// An ordered, compiled set of `TickerRule`s. Rules are tried in order; the
+/// first whose pattern matches the ticker wins.
+pub struct TickerRuleSet {
+    rules: Vec<CompiledRule>,
+}
+
+impl TickerRuleSet {
+    pub fn compile(rules: Vec<TickerRule>) -> Result<Self, TickerRuleError> {
+        let rules = rules
+            .into_iter()
+            .enumerate()
+            .map(|(index, rule)| {
+                let regex = Regex::new(&rule.pattern).map_err(|source| TickerRuleError::InvalidPattern {
+                    index,
+                    pattern: rule.pattern.clone(),
+                    source,
+                })?;
+                Ok(CompiledRule {
+                    regex,
+                    yes_template: rule.yes_template,
+                    no_template: rule.no_template,
+                    kind: rule.kind,
+                })
+            })
+            .collect::<Result<Vec<_>, TickerRuleError>>()?;
+
+        Ok(Self { rules })
+    }
+
+    /// Load an operator-supplied rules file (a JSON array of `TickerRule`),
+    /// tried ahead of nothing else — callers decide how to combine this
+    /// with `default_rules()` (e.g. user rules first, falling back to the
+    /// built-ins).
+    pub fn from_file(path: &std::path::Path) -> Result<Self, TickerRuleError> {
+        let text = std::fs::read_to_string(path)?;
+        let rules: Vec<TickerRule> = serde_json::from_str(&text)?;
+        Self::compile(rules)
+    }
+
+    /// Built-in ruleset covering the scorer-timing and placement ticker
+    /// shapes `parse_ticker_details` used to hardcode.
+    pub fn default_rules() -> Self {
+        Self::compile(default_rule_definitions()).expect("built-in ticker rules must compile")
+    }
+
+    /// Try each rule in order against `ticker`; returns the first match's
+    /// description for `side` ("yes"/"no", case-insensitive), or `None` if
+    /// no rule matched.
+    pub fn describe(&self, ticker: &str, side: &str) -> Option<String> {
+        let is_yes = side.eq_ignore_ascii_case("yes");
+
+        for rule in &self.rules {
+            if let Some(caps) = rule.regex.captures(ticker) {
+                let template = if is_yes { &rule.yes_template } else { &rule.no_template };
+                return Some(substitute_captures(template, &rule.regex, &caps));
+            }
+        }
+
+        None
+    }
+
+    /// Like `describe`, but returns the matching rule's `kind` tag plus its
+    /// named captures instead of a rendered string, so a caller (e.g.
+    /// `market_outcome::classify`) can build a structured result.
+    pub fn match_captures(&self, ticker: &str) -> Option<(Option<&str>, std::collections::HashMap<String, String>)> {
+        for rule in &self.rules {
+            if let Some(caps) = rule.regex.captures(ticker) {
+                let named: std::collections::HashMap<String, String> = rule
+                    .regex
+                    .capture_names()
+                    .flatten()
+                    .filter_map(|name| caps.name(name).map(|m| (name.to_string(), m.as_str().to_string())))
+                    .collect();
+                return Some((rule.kind.as_deref(), named));
+            }
+        }
+
+        None
+    }
+}
+
+fn substitute_captures(template: &str, regex: &Regex, caps: &regex::Captures) -> String {
+    let mut out = template.to_string();
+    for name in regex.capture_names().flatten() {
+        if let Some(value) = caps.name(name) {
+            out = out.replace(&format!("{{{}}}", name), value.as_str());
+        }
+    }
+    out
+}
+
+fn default_rule_definitions() -> Vec<TickerRule> {
+    vec![
+        TickerRule {
+            pattern: r"(?i)FIRST.*-(?P<player>[^-]+)$".to_string(),
+            yes_template: "{player} scores first TD".to_string(),
+            no_template: "{player} doesn't score first TD".to_string(),
+            kind: Some("scorer:first".to_string()),
+        },
+        TickerRule {
+            pattern: r"(?i)LAST.*-(?P<player>[^-]+)$".to_string(),
+            yes_template: "{player} scores last TD".to_string(),
+            no_template: "{player} doesn't score last TD".to_string(),
+            kind: Some("scorer:last".to_string()),
+        },
+        TickerRule {
+            pattern: r"(?i)ANYTIME.*-(?P<player>[^-]+)$".to_string(),
+            yes_template: "{player} scores anytime TD".to_string(),
+            no_template: "{player} doesn't score anytime TD".to_string(),
+            kind: Some("scorer:anytime".to_string()),
+        },
+        TickerRule {
+            pattern: r"(?i)(?:TOP|FINISH|PLACE).*-(?P<outcome>[^-]+)$".to_string(),
+            yes_template: "{outcome} finishes in position".to_string(),
+            no_template: "{outcome} doesn't finish in position".to_string(),
+            kind: Some("placement".to_string()),
+        },
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn first_scorer_rule_matches_and_substitutes() {
+        let rules = TickerRuleSet::default_rules();
+        assert_eq!(
+            rules.describe("KXNFLFIRSTTD-26JAN08KC-PMAHOMES", "yes").as_deref(),
+            Some("PMAHOMES scores first TD")
+        );
+        assert_eq!(
+            rules.describe("KXNFLFIRSTTD-26JAN08KC-PMAHOMES", "no").as_deref(),
+            Some("PMAHOMES doesn't score first TD")
+        );
+    }
+
+    #[test]
+    fn first_rule_takes_precedence_over_anytime() {
+        let rules = TickerRuleSet::default_rules();
+        let desc = rules.describe("KXNFLFIRSTANYTIMETD-PMAHOMES", "yes");
+        assert_eq!(desc.as_deref(), Some("PMAHOMES scores first TD"));
+    }
+
+    #[test]
+    fn placement_rule_matches() {
+        let rules = TickerRuleSet::default_rules();
+        assert_eq!(
+            rules.describe("KXF1TOP3-26JAN08-VERSTAPPEN", "yes").as_deref(),
+            Some("VERSTAPPEN finishes in position")
+        );
+    }
+
+    #[test]
+    fn unrelated_ticker_matches_nothing() {
+        let rules = TickerRuleSet::default_rules();
+        assert_eq!(rules.describe("KXNHLGAME-26JAN08ANACAR-CAR", "yes"), None);
+    }
+
+    #[test]
+    fn custom_rule_from_json_compiles_and_matches() {
+        let json = r#"[{"pattern": "(?P<x>ABC)", "yes_template": "got {x}", "no_template": "no {x}"}]"#;
+        let rules: Vec<TickerRule> = serde_json::from_str(json).unwrap();
+        let set = TickerRuleSet::compile(rules).unwrap();
+        assert_eq!(set.describe("XYZ-ABC-123", "yes").as_deref(), Some("got ABC"));
+    }
+}
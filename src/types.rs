@@ -1,13 +1,12 @@
 // Shared types and utilities across modules
 
-use std::collections::{HashMap, HashSet};
+use std::collections::{HashMap, HashSet, VecDeque};
 use std::time::{Instant, SystemTime, UNIX_EPOCH};
 
-use rusqlite::{Connection, params};
-
 use crate::db;
+use crate::store::WalletMemoryStore;
 
-// ─── Wallet Memory (SQLite-backed with in-memory hot cache) ─────────
+// ─── Wallet Memory (pooled async store with in-memory hot cache) ────
 
 pub struct WalletTracker {
     // In-memory cache of known wallet hashes (refreshed periodically)
@@ -48,10 +47,15 @@ impl WalletTracker {
         self.cleanup_old_transactions();
     }
 
-    /// Record a transaction into the SQLite wallet_memory table
-    pub fn record_to_db(
+    /// Record a transaction into the wallet_memory store, via the pooled
+    /// async `WalletMemoryStore` rather than blocking on a `rusqlite`
+    /// connection. Returns `false` (and leaves the hot cache untouched) if
+    /// the underlying store failed to persist it; the failure is already
+    /// counted in the `db_write_errors` metric here.
+    #[allow(clippy::too_many_arguments)]
+    pub async fn record_to_db(
         &mut self,
-        conn: &Connection,
+        store: &dyn WalletMemoryStore,
         wallet_id: &str,
         market_title: Option<&str>,
         market_id: Option<&str>,
@@ -60,78 +64,59 @@ impl WalletTracker {
         value: f64,
         price: f64,
         platform: &str,
-    ) {
-        let hash = db::wallet_hash(wallet_id);
-        let now = SystemTime::now()
-            .duration_since(UNIX_EPOCH)
-            .unwrap()
-            .as_secs() as i64;
-
-        let result = conn.execute(
-            "INSERT OR REPLACE INTO wallet_memory
-             (wallet_hash, wallet_id, market_title, market_id, outcome, action, value, price, platform, seen_at)
-             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
-            params![hash, wallet_id, market_title, market_id, outcome, action, value, price, platform, now],
-        );
-
-        if let Err(e) = result {
-            eprintln!("Warning: Failed to record wallet memory: {}", e);
+    ) -> bool {
+        if !store.record(wallet_id, market_title, market_id, outcome, action, value, price, platform).await {
+            crate::metrics::metrics().db_write_errors.inc();
+            return false;
         }
 
         // Add to hot cache
-        self.known_hashes.insert(hash);
+        self.known_hashes.insert(db::wallet_hash(wallet_id));
+        crate::metrics::metrics().known_wallets.set(self.known_hashes.len() as u64);
+        true
     }
 
-    /// Query wallet history from SQLite (last 12h)
-    pub fn get_wallet_history(&self, conn: &Connection, wallet_id: &str) -> Vec<WalletMemoryEntry> {
+    /// Query wallet history from the store (last 12h)
+    pub async fn get_wallet_history(&self, store: &dyn WalletMemoryStore, wallet_id: &str) -> Vec<WalletMemoryEntry> {
         let hash = db::wallet_hash(wallet_id);
-        let mut entries = Vec::new();
-
-        let result = conn.prepare(
-            "SELECT wallet_id, market_title, market_id, outcome, action, value, price, platform, seen_at
-             FROM wallet_memory
-             WHERE wallet_hash = ?1 AND seen_at > (strftime('%s', 'now') - 43200)
-             ORDER BY seen_at DESC"
-        );
-
-        if let Ok(mut stmt) = result {
-            let rows = stmt.query_map(params![hash], |row| {
-                Ok(WalletMemoryEntry {
-                    wallet_id: row.get(0)?,
-                    market_title: row.get(1)?,
-                    market_id: row.get(2)?,
-                    outcome: row.get(3)?,
-                    action: row.get(4)?,
-                    value: row.get(5)?,
-                    price: row.get(6)?,
-                    platform: row.get(7)?,
-                    seen_at: row.get(8)?,
-                })
-            });
-
-            if let Ok(rows) = rows {
-                for row in rows.flatten() {
-                    entries.push(row);
-                }
-            }
-        }
-
-        entries
+        store
+            .history(&hash)
+            .await
+            .into_iter()
+            .map(|row| WalletMemoryEntry {
+                wallet_id: row.wallet_id,
+                market_title: row.market_title,
+                market_id: row.market_id,
+                outcome: row.outcome,
+                action: row.action,
+                value: row.value,
+                price: row.price,
+                platform: row.platform,
+                seen_at: row.seen_at,
+            })
+            .collect()
     }
 
-    /// Classify the returning whale scenario
-    pub fn classify_whale_return(
+    /// Classify the returning whale scenario. `current_action` (`"BUY"` /
+    /// `"SELL"`) plus `current_value`/`current_price` let this tell a sell
+    /// against an accumulated position (`ProfitTaking`) apart from a plain
+    /// same-outcome re-entry (`DoublingDown`).
+    #[allow(clippy::too_many_arguments)]
+    pub async fn classify_whale_return(
         &self,
-        conn: &Connection,
+        store: &dyn WalletMemoryStore,
         wallet_id: &str,
         current_market_id: Option<&str>,
         current_outcome: Option<&str>,
+        current_action: &str,
+        current_value: f64,
+        current_price: f64,
     ) -> Option<WhaleReturnScenario> {
         if !self.is_known(wallet_id) {
             return None;
         }
 
-        let history = self.get_wallet_history(conn, wallet_id);
+        let history = self.get_wallet_history(store, wallet_id).await;
         if history.is_empty() {
             return None;
         }
@@ -164,6 +149,7 @@ impl WalletTracker {
                     if opposite_side {
                         let prev = &same_market[0];
                         let hours_ago = (now - prev.seen_at) as f64 / 3600.0;
+                        crate::metrics::metrics().whale_returns.inc("flip");
                         return Some(WhaleReturnScenario::Flip {
                             previous_outcome: prev.outcome.clone().unwrap_or_default(),
                             previous_value: prev.value,
@@ -172,7 +158,25 @@ impl WalletTracker {
                             total_12h_txns: total_txns,
                         });
                     } else if same_side {
+                        if current_action.eq_ignore_ascii_case("sell") && current_price > 0.0 {
+                            let mut same_market_oldest_first = same_market.clone();
+                            same_market_oldest_first.reverse(); // history is seen_at DESC
+                            let sell_shares = current_value / current_price;
+                            let (realized_pnl, avg_cost_basis, remaining_shares) =
+                                fifo_sell(&same_market_oldest_first, sell_shares, current_price);
+
+                            crate::metrics::metrics().whale_returns.inc("profit_taking");
+                            return Some(WhaleReturnScenario::ProfitTaking {
+                                realized_pnl,
+                                avg_cost_basis,
+                                remaining_shares,
+                                total_12h_volume: total_volume,
+                                total_12h_txns: total_txns,
+                            });
+                        }
+
                         let prev_total: f64 = same_market.iter().map(|e| e.value).sum();
+                        crate::metrics::metrics().whale_returns.inc("doubling_down");
                         return Some(WhaleReturnScenario::DoublingDown {
                             previous_value: prev_total,
                             previous_txns: same_market.len(),
@@ -185,6 +189,7 @@ impl WalletTracker {
         }
 
         // General known whale
+        crate::metrics::metrics().whale_returns.inc("known_whale");
         Some(WhaleReturnScenario::KnownWhale {
             total_12h_volume: total_volume,
             total_12h_txns: total_txns,
@@ -192,32 +197,21 @@ impl WalletTracker {
         })
     }
 
-    /// Refresh the in-memory hash cache from DB (every 5 minutes)
-    pub fn maybe_refresh_cache(&mut self, conn: &Connection) {
-        if self.last_cache_refresh.elapsed().as_secs() < 300 {
+    /// Refresh the in-memory hash cache from the store (every 5 minutes).
+    /// Updates `cache_refresh_age_seconds` on every call (even when skipped)
+    /// so the gauge reflects staleness between refreshes, and `known_wallets`
+    /// whenever the cache is actually reloaded.
+    pub async fn maybe_refresh_cache(&mut self, store: &dyn WalletMemoryStore) {
+        let age = self.last_cache_refresh.elapsed().as_secs();
+        crate::metrics::metrics().cache_refresh_age_seconds.set(age);
+        if age < 300 {
             return;
         }
 
-        let result = conn.prepare(
-            "SELECT DISTINCT wallet_hash FROM wallet_memory
-             WHERE seen_at > (strftime('%s', 'now') - 43200)"
-        );
-
-        if let Ok(mut stmt) = result {
-            let rows = stmt.query_map([], |row| {
-                let hash: String = row.get(0)?;
-                Ok(hash)
-            });
-
-            if let Ok(rows) = rows {
-                self.known_hashes.clear();
-                for row in rows.flatten() {
-                    self.known_hashes.insert(row);
-                }
-            }
-        }
-
+        self.known_hashes = store.distinct_recent_hashes().await.into_iter().collect();
         self.last_cache_refresh = Instant::now();
+        crate::metrics::metrics().known_wallets.set(self.known_hashes.len() as u64);
+        crate::metrics::metrics().cache_refresh_age_seconds.set(0);
     }
 
     /// Get real-time activity stats (from in-memory tracker)
@@ -254,6 +248,40 @@ impl WalletTracker {
         }
     }
 
+    /// Record `wallet_id`'s trade and return its rolling 1h/24h activity.
+    /// On this wallet's first sighting in the current process (e.g. right
+    /// after a restart), reconstructs activity from the persisted
+    /// `wallet_memory` store instead of the fresh-and-empty in-memory
+    /// tracker, so a known heavy/repeat actor doesn't have to be
+    /// rediscovered from scratch — merged with whatever the in-memory
+    /// tracker already has (element-wise max) in case this trade landed
+    /// before the store's own read-after-write settles. Every sighting after
+    /// the first is served purely from the in-memory tracker, so the hot
+    /// path isn't round-tripping to the DB on every trade.
+    pub async fn record_and_get_activity(
+        &mut self,
+        store: &dyn WalletMemoryStore,
+        wallet_id: &str,
+        value: f64,
+    ) -> WalletActivity {
+        let seen_this_run = self.transactions.contains_key(wallet_id);
+        self.record_transaction(wallet_id, value);
+        let current = self.get_activity(wallet_id);
+        if seen_this_run {
+            return current;
+        }
+
+        let hash = db::wallet_hash(wallet_id);
+        let mut persisted = store.activity(&hash).await;
+        persisted.transactions_last_hour = persisted.transactions_last_hour.max(current.transactions_last_hour);
+        persisted.transactions_last_day = persisted.transactions_last_day.max(current.transactions_last_day);
+        persisted.total_value_hour = persisted.total_value_hour.max(current.total_value_hour);
+        persisted.total_value_day = persisted.total_value_day.max(current.total_value_day);
+        persisted.is_repeat_actor = persisted.transactions_last_hour > 1;
+        persisted.is_heavy_actor = persisted.transactions_last_day >= 5;
+        persisted
+    }
+
     fn cleanup_old_transactions(&mut self) {
         let current_time = SystemTime::now()
             .duration_since(UNIX_EPOCH)
@@ -331,4 +359,123 @@ pub enum WhaleReturnScenario {
         total_12h_txns: usize,
         previous_entries: Vec<WalletMemoryEntry>,
     },
+    /// Same market, same outcome, but this trade is a sell against an
+    /// accumulated position — the whale is cashing out rather than adding
+    /// to (or reversing) their directional view.
+    ProfitTaking {
+        realized_pnl: f64,
+        avg_cost_basis: f64,
+        remaining_shares: f64,
+        total_12h_volume: f64,
+        total_12h_txns: usize,
+    },
+}
+
+/// Apply FIFO lot matching over a wallet's same-market-same-outcome history
+/// (oldest first) plus the sell being classified right now, the way a
+/// fill indexer derives running balances and realized P&L from individual
+/// buy/sell fills. `entries` share sizes are derived from each entry's
+/// `value`/`price` since `wallet_memory` doesn't store share counts directly.
+/// Returns the P&L realized by this sell, the average cost basis of any
+/// lots still open afterward, and the remaining open share count.
+fn fifo_sell(entries_oldest_first: &[&WalletMemoryEntry], sell_shares: f64, sell_price: f64) -> (f64, f64, f64) {
+    // Drop the oldest `shares` worth of lots without pricing them — used to
+    // replay historical sells, where we only care about the resulting open
+    // position, not a P&L figure we'd discard anyway.
+    fn drain(lots: &mut VecDeque<(f64, f64)>, mut shares: f64) {
+        while shares > 0.0 {
+            match lots.front_mut() {
+                Some((lot_shares, _)) if *lot_shares > shares => {
+                    *lot_shares -= shares;
+                    shares = 0.0;
+                }
+                Some((lot_shares, _)) => {
+                    shares -= *lot_shares;
+                    lots.pop_front();
+                }
+                // Selling more than the 12h window shows lots for — stop
+                // rather than manufacture a negative position.
+                None => break,
+            }
+        }
+    }
+
+    // Same as `drain`, but against `price` — used for the sell being
+    // classified right now, whose realized P&L we actually want.
+    fn drain_priced(lots: &mut VecDeque<(f64, f64)>, mut shares: f64, price: f64) -> f64 {
+        let mut realized = 0.0;
+        while shares > 0.0 {
+            match lots.front_mut() {
+                Some((lot_shares, lot_price)) if *lot_shares > shares => {
+                    realized += shares * (price - *lot_price);
+                    *lot_shares -= shares;
+                    shares = 0.0;
+                }
+                Some((lot_shares, lot_price)) => {
+                    realized += *lot_shares * (price - *lot_price);
+                    shares -= *lot_shares;
+                    lots.pop_front();
+                }
+                None => break,
+            }
+        }
+        realized
+    }
+
+    let mut lots: VecDeque<(f64, f64)> = VecDeque::new();
+    for entry in entries_oldest_first {
+        let shares = if entry.price > 0.0 { entry.value / entry.price } else { 0.0 };
+        match entry.action.as_deref() {
+            Some(a) if a.eq_ignore_ascii_case("buy") => lots.push_back((shares, entry.price)),
+            Some(a) if a.eq_ignore_ascii_case("sell") => drain(&mut lots, shares),
+            _ => {}
+        }
+    }
+
+    let realized_pnl = drain_priced(&mut lots, sell_shares, sell_price);
+
+    let remaining_shares: f64 = lots.iter().map(|(shares, _)| shares).sum();
+    let total_cost: f64 = lots.iter().map(|(shares, price)| shares * price).sum();
+    let avg_cost_basis = if remaining_shares > 0.0 { total_cost / remaining_shares } else { 0.0 };
+
+    (realized_pnl, avg_cost_basis, remaining_shares)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::InMemoryStore;
+    use crate::store::SqliteWalletMemoryStore;
+    use std::sync::Arc;
+
+    #[tokio::test]
+    async fn record_and_get_activity_serves_fresh_sightings_from_memory() {
+        let store = SqliteWalletMemoryStore::new(std::sync::Arc::new(InMemoryStore::new()));
+        let mut tracker = WalletTracker::new();
+
+        let first = tracker.record_and_get_activity(&store, "0xabc", 100.0).await;
+        assert_eq!(first.transactions_last_hour, 1);
+
+        let second = tracker.record_and_get_activity(&store, "0xabc", 50.0).await;
+        assert_eq!(second.transactions_last_hour, 2);
+        assert_eq!(second.total_value_day, 150.0);
+    }
+
+    #[tokio::test]
+    async fn record_and_get_activity_hydrates_from_persisted_history_on_first_sighting() {
+        let inner = Arc::new(InMemoryStore::new());
+        inner.record_wallet_memory("0xabc", Some("Market"), Some("mkt1"), Some("Yes"), "BUY", 100.0, 0.5, "Polymarket");
+        inner.record_wallet_memory("0xabc", Some("Market"), Some("mkt2"), Some("No"), "BUY", 50.0, 0.3, "Polymarket");
+        let store = SqliteWalletMemoryStore::new(inner);
+
+        // A fresh `WalletTracker` (as after a restart) has never seen "0xabc"
+        // this process, so its first sighting should pull in the persisted
+        // history above rather than reporting just the one new trade.
+        let mut tracker = WalletTracker::new();
+        let activity = tracker.record_and_get_activity(&store, "0xabc", 25.0).await;
+
+        assert_eq!(activity.transactions_last_hour, 2);
+        assert_eq!(activity.total_value_day, 150.0);
+        assert!(activity.is_repeat_actor);
+    }
 }
@@ -4,14 +4,21 @@ use std::time::{Duration, Instant};
 
 use serde::{Deserialize, Serialize};
 
-fn shared_client() -> &'static reqwest::Client {
-    static CLIENT: OnceLock<reqwest::Client> = OnceLock::new();
+use crate::http_fetch::{HttpFetch, ReqwestFetch};
+
+/// The real, network-backed fetcher used outside of tests. Timeout and retry
+/// budget follow `Config::http_timeout_secs`/`http_max_retries`, so a single 429 or
+/// timeout on a leaderboard/position/win-rate call doesn't drop the whole profile.
+fn shared_fetch() -> &'static ReqwestFetch {
+    static CLIENT: OnceLock<ReqwestFetch> = OnceLock::new();
     CLIENT.get_or_init(|| {
-        reqwest::Client::builder()
-            .timeout(Duration::from_secs(10))
+        let config = crate::config::load_config().unwrap_or_default();
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(config.http_timeout_secs))
             .pool_max_idle_per_host(4)
             .build()
-            .expect("failed to build shared HTTP client")
+            .expect("failed to build shared HTTP client");
+        ReqwestFetch::with_retry_budget(client, config.http_max_retries, Duration::from_millis(200))
     })
 }
 
@@ -125,13 +132,18 @@ impl WhaleProfileCache {
 
     /// Refresh leaderboard cache if stale
     pub async fn refresh_leaderboard_if_needed(&mut self) {
+        self.refresh_leaderboard_with(shared_fetch()).await
+    }
+
+    /// Same as `refresh_leaderboard_if_needed` but with an injectable fetcher, for tests.
+    pub async fn refresh_leaderboard_with(&mut self, fetch: &dyn HttpFetch) {
         let needs_refresh = match &self.leaderboard {
             None => true,
             Some((_, fetched_at)) => fetched_at.elapsed() >= LEADERBOARD_TTL,
         };
 
         if needs_refresh {
-            if let Some(entries) = fetch_leaderboard().await {
+            if let Some(entries) = fetch_leaderboard(fetch).await {
                 self.leaderboard = Some((entries, Instant::now()));
             }
         }
@@ -144,47 +156,46 @@ impl WhaleProfileCache {
 }
 
 /// Fetch trader leaderboard (top 500)
-async fn fetch_leaderboard() -> Option<Vec<LeaderboardEntry>> {
-    let response = shared_client()
-        .get("https://data-api.polymarket.com/v1/leaderboard")
-        .query(&[("limit", "500")])
-        .header("Accept", "application/json")
-        .send()
+async fn fetch_leaderboard(fetch: &dyn HttpFetch) -> Option<Vec<LeaderboardEntry>> {
+    let text = fetch
+        .get_json(
+            "https://data-api.polymarket.com/v1/leaderboard",
+            &[("limit", "500")],
+            &[("Accept", "application/json")],
+        )
         .await
         .ok()?;
 
-    if !response.status().is_success() {
-        return None;
-    }
-
-    let text = response.text().await.ok()?;
     serde_json::from_str(&text).ok()
 }
 
-/// Fetch portfolio total value for a wallet
-async fn fetch_portfolio_value(wallet_id: &str) -> Option<f64> {
-    let response = shared_client()
-        .get("https://data-api.polymarket.com/value")
-        .query(&[("user", wallet_id)])
-        .header("Accept", "application/json")
-        .send()
-        .await
-        .ok()?;
-
-    if !response.status().is_success() {
-        return None;
-    }
-
-    let text = response.text().await.ok()?;
+/// Fetch portfolio total value for a wallet. `Err` means the retry budget was
+/// exhausted (or the error wasn't retryable) — distinct from `Ok(None)`, which
+/// means the API responded but had no value to report.
+async fn fetch_portfolio_value(fetch: &dyn HttpFetch, wallet_id: &str) -> Result<Option<f64>, String> {
+    let text = fetch
+        .get_json(
+            "https://data-api.polymarket.com/value",
+            &[("user", wallet_id)],
+            &[("Accept", "application/json")],
+        )
+        .await?;
+
+    Ok(parse_portfolio_value(&text))
+}
 
-    // The response might be a direct number, an object, or an array of objects
+/// Parse the `/value` response, which the API returns as a direct number, an
+/// object, or an array of objects depending on endpoint version.
+fn parse_portfolio_value(text: &str) -> Option<f64> {
     if let Ok(val) = text.trim().parse::<f64>() {
         return Some(val);
     }
-    if let Ok(resp) = serde_json::from_str::<ValueResponse>(&text) {
-        return resp.value;
+    if let Ok(resp) = serde_json::from_str::<ValueResponse>(text) {
+        if let Some(v) = resp.value {
+            return Some(v);
+        }
     }
-    if let Ok(v) = serde_json::from_str::<serde_json::Value>(&text) {
+    if let Ok(v) = serde_json::from_str::<serde_json::Value>(text) {
         if let Some(arr) = v.as_array() {
             if let Some(first) = arr.first() {
                 return first.get("value")
@@ -199,50 +210,56 @@ async fn fetch_portfolio_value(wallet_id: &str) -> Option<f64> {
     None
 }
 
-/// Fetch current open positions count
-async fn fetch_positions_count(wallet_id: &str) -> Option<u32> {
-    let response = shared_client()
-        .get("https://data-api.polymarket.com/positions")
-        .query(&[("user", wallet_id), ("limit", "100")])
-        .header("Accept", "application/json")
-        .send()
-        .await
-        .ok()?;
-
-    if !response.status().is_success() {
-        return None;
-    }
-
-    let text = response.text().await.ok()?;
-    let positions: Vec<PositionEntry> = serde_json::from_str(&text).ok()?;
-    Some(positions.len() as u32)
+/// Fetch current open positions count. See `fetch_portfolio_value` for the
+/// `Err`-vs-`Ok(None)` distinction.
+async fn fetch_positions_count(fetch: &dyn HttpFetch, wallet_id: &str) -> Result<Option<u32>, String> {
+    let text = fetch
+        .get_json(
+            "https://data-api.polymarket.com/positions",
+            &[("user", wallet_id), ("limit", "100")],
+            &[("Accept", "application/json")],
+        )
+        .await?;
+
+    let positions: Vec<PositionEntry> = match serde_json::from_str(&text) {
+        Ok(positions) => positions,
+        Err(_) => return Ok(None),
+    };
+    Ok(Some(positions.len() as u32))
 }
 
 /// Compute win rate from ALL closed positions (paginated â€” API returns 50 per page sorted by PnL desc).
-async fn fetch_win_rate(wallet_id: &str) -> Option<(f64, u32)> {
+///
+/// Each page already retries transient failures internally (see `ReqwestFetch`), so
+/// only a failure on the very first page is treated as "exhausted" (`Err`); a later
+/// page failing after retries still yields the partial result gathered so far.
+async fn fetch_win_rate(fetch: &dyn HttpFetch, wallet_id: &str) -> Result<Option<(f64, u32)>, String> {
     let mut all_positions: Vec<ClosedPositionEntry> = Vec::new();
     let page_size = 50;
 
     for page in 0..20 {
         let offset = page * page_size;
-        let resp = shared_client()
-            .get("https://data-api.polymarket.com/closed-positions")
-            .query(&[
-                ("user", wallet_id),
-                ("limit", &page_size.to_string()),
-                ("offset", &offset.to_string()),
-            ])
-            .header("Accept", "application/json")
-            .send()
+        let text = match fetch
+            .get_json(
+                "https://data-api.polymarket.com/closed-positions",
+                &[
+                    ("user", wallet_id),
+                    ("limit", &page_size.to_string()),
+                    ("offset", &offset.to_string()),
+                ],
+                &[("Accept", "application/json")],
+            )
             .await
-            .ok()?;
-
-        if !resp.status().is_success() {
-            break;
-        }
+        {
+            Ok(text) => text,
+            Err(e) if page == 0 => return Err(e),
+            Err(_) => break,
+        };
 
-        let text = resp.text().await.ok()?;
-        let page_positions: Vec<ClosedPositionEntry> = serde_json::from_str(&text).ok()?;
+        let page_positions: Vec<ClosedPositionEntry> = match serde_json::from_str(&text) {
+            Ok(positions) => positions,
+            Err(_) => break,
+        };
         let count = page_positions.len();
         all_positions.extend(page_positions);
 
@@ -252,7 +269,7 @@ async fn fetch_win_rate(wallet_id: &str) -> Option<(f64, u32)> {
     }
 
     if all_positions.is_empty() {
-        return None;
+        return Ok(None);
     }
 
     let total = all_positions.len() as u32;
@@ -266,26 +283,43 @@ async fn fetch_win_rate(wallet_id: &str) -> Option<(f64, u32)> {
     } else {
         0.0
     };
-    Some((rate, total))
+    Ok(Some((rate, total)))
 }
 
 /// Fetch full whale profile for a Polymarket wallet (3 parallel API calls + leaderboard lookup)
 pub async fn fetch_whale_profile(wallet_id: &str, cache: &mut WhaleProfileCache) -> Option<WhaleProfile> {
+    fetch_whale_profile_with(shared_fetch(), wallet_id, cache).await
+}
+
+/// Same as `fetch_whale_profile` but with an injectable fetcher, so tests can feed
+/// recorded Polymarket payloads instead of hitting the live API.
+pub async fn fetch_whale_profile_with(
+    fetch: &dyn HttpFetch,
+    wallet_id: &str,
+    cache: &mut WhaleProfileCache,
+) -> Option<WhaleProfile> {
     // Check cache first
     if let Some(cached) = cache.get(wallet_id) {
         return Some(cached.clone());
     }
 
     // Refresh leaderboard if needed
-    cache.refresh_leaderboard_if_needed().await;
+    cache.refresh_leaderboard_with(fetch).await;
 
     // Fetch portfolio data in parallel
     let (value, positions, win_data) = tokio::join!(
-        fetch_portfolio_value(wallet_id),
-        fetch_positions_count(wallet_id),
-        fetch_win_rate(wallet_id),
+        fetch_portfolio_value(fetch, wallet_id),
+        fetch_positions_count(fetch, wallet_id),
+        fetch_win_rate(fetch, wallet_id),
     );
 
+    // Exhausted retries on any leg means this profile is incomplete, not confirmed
+    // empty — don't let it get cached as if it were the real, final answer.
+    let had_hard_failure = value.is_err() || positions.is_err() || win_data.is_err();
+    let value = value.unwrap_or(None);
+    let positions = positions.unwrap_or(None);
+    let win_data = win_data.unwrap_or(None);
+
     // Look up in leaderboard
     let lb = cache.leaderboard_lookup(wallet_id);
 
@@ -304,10 +338,104 @@ pub async fn fetch_whale_profile(wallet_id: &str, cache: &mut WhaleProfileCache)
         markets_traded,
     };
 
-    // Only cache if we got at least some data
-    if profile.portfolio_value.is_some() || profile.leaderboard_rank.is_some() || profile.win_rate.is_some() {
+    // Only cache if we got at least some data and nothing hit a hard failure
+    let has_data = profile.portfolio_value.is_some() || profile.leaderboard_rank.is_some() || profile.win_rate.is_some();
+    if has_data && !had_hard_failure {
         cache.insert(profile.clone());
     }
 
     Some(profile)
 }
+
+/// Fill `profile.win_rate`/`markets_traded` from this wallet's own settled
+/// alert history when the live API call above didn't have (or couldn't
+/// reach) the same data — `fetch_win_rate`'s paginated `closed-positions`
+/// call is the one leg of `fetch_whale_profile_with` most likely to come up
+/// empty for a wallet this tool has actually traded against before, since by
+/// then its outcome is already sitting in `v_wallet_performance`. Never
+/// overwrites a value the live call already filled in.
+pub fn backfill_from_history(profile: &mut WhaleProfile, store: &dyn crate::db::AlertStore) {
+    if profile.win_rate.is_some() && profile.markets_traded.is_some() {
+        return;
+    }
+
+    let hash = crate::db::wallet_hash(&profile.wallet_id);
+    let Some((win_rate, settled_trades)) = store.wallet_performance_for(&hash) else {
+        return;
+    };
+
+    if profile.win_rate.is_none() {
+        profile.win_rate = Some(win_rate);
+    }
+    if profile.markets_traded.is_none() {
+        profile.markets_traded = Some(settled_trades as u32);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::http_fetch::MockFetch;
+
+    #[test]
+    fn parse_portfolio_value_handles_bare_number() {
+        assert_eq!(parse_portfolio_value("1234.5"), Some(1234.5));
+    }
+
+    #[test]
+    fn parse_portfolio_value_handles_object() {
+        assert_eq!(parse_portfolio_value(r#"{"value": 500.0}"#), Some(500.0));
+    }
+
+    #[test]
+    fn parse_portfolio_value_handles_array() {
+        assert_eq!(
+            parse_portfolio_value(r#"[{"value": 42.0}]"#),
+            Some(42.0)
+        );
+        assert_eq!(
+            parse_portfolio_value(r#"[{"totalValue": "99.5"}]"#),
+            Some(99.5)
+        );
+    }
+
+    #[test]
+    fn parse_portfolio_value_rejects_garbage() {
+        assert_eq!(parse_portfolio_value("not json"), None);
+    }
+
+    #[tokio::test]
+    async fn fetch_portfolio_value_uses_mocked_fetch() {
+        let mock = MockFetch::new().with_response(
+            "https://data-api.polymarket.com/value",
+            r#"{"value": 777.0}"#,
+        );
+        assert_eq!(fetch_portfolio_value(&mock, "0xwhale").await, Ok(Some(777.0)));
+    }
+
+    #[tokio::test]
+    async fn fetch_win_rate_paginates_until_short_page() {
+        let page1: Vec<serde_json::Value> = (0..50)
+            .map(|i| serde_json::json!({ "realizedPnl": if i % 2 == 0 { 10.0 } else { -5.0 } }))
+            .collect();
+        let page2 = vec![serde_json::json!({ "realizedPnl": 1.0 })];
+
+        let mock = MockFetch::new().with_sequence(
+            "https://data-api.polymarket.com/closed-positions",
+            vec![
+                serde_json::to_string(&page1).unwrap(),
+                serde_json::to_string(&page2).unwrap(),
+            ],
+        );
+
+        let (rate, total) = fetch_win_rate(&mock, "0xwhale").await.unwrap().unwrap();
+        assert_eq!(total, 51);
+        assert_eq!(rate, 26.0 / 51.0);
+    }
+
+    #[tokio::test]
+    async fn fetch_win_rate_errs_when_first_page_fails() {
+        let mock = MockFetch::new(); // no canned response -> first page fails
+        assert!(fetch_win_rate(&mock, "0xwhale").await.is_err());
+    }
+}
@@ -1,21 +1,28 @@
+use std::sync::Arc;
 use std::time::Duration;
 
 use futures_util::{SinkExt, StreamExt};
-use serde::Deserialize;
-use tokio::sync::mpsc;
+use serde::{Deserialize, Serialize};
+use tokio::sync::{mpsc, Mutex};
 use tokio_tungstenite::connect_async;
 use tokio_tungstenite::tungstenite::Message;
 use http::Request;
 
+use crate::metrics::metrics;
 use crate::ws::auth::generate_auth_headers;
 
 const KALSHI_WS_URL: &str = "wss://api.elections.kalshi.com/trade-api/ws/v2";
 const PING_INTERVAL: Duration = Duration::from_secs(10);
 const RECONNECT_BASE: Duration = Duration::from_secs(2);
 const RECONNECT_MAX: Duration = Duration::from_secs(60);
+/// A half-open TCP connection can leave `read.next().await` hanging forever
+/// without ever producing a frame to reconnect on, so the ping task doubles
+/// as a watchdog: after this many missed ping intervals with no frame of any
+/// kind received, it forces the connection closed.
+const MISSED_PINGS_BEFORE_STALE: u32 = 3;
 
 /// A trade received from the Kalshi WebSocket
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, serde::Serialize)]
 pub struct WsTrade {
     pub trade_id: String,
     pub ticker: String,
@@ -54,51 +61,133 @@ struct WsTradeEntry {
     timestamp: Option<i64>,
 }
 
-/// Subscribe command for Kalshi WebSocket
-fn subscribe_cmd() -> String {
+/// Ticks `seconds_since_last_message` up once a second so the gauge reflects
+/// elapsed time even when no messages arrive; read-loop call sites reset it
+/// to 0 on every message received, from either feed.
+fn spawn_staleness_ticker() -> tokio::task::JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(Duration::from_secs(1));
+        loop {
+            interval.tick().await;
+            metrics().seconds_since_last_message.add(1);
+        }
+    })
+}
+
+/// Subscribe command for Kalshi WebSocket, scoped to `tickers` if non-empty
+/// or the full trade firehose if empty.
+fn subscribe_cmd(tickers: &[String]) -> String {
+    let mut params = serde_json::json!({ "channels": ["trade"] });
+    if !tickers.is_empty() {
+        params["market_tickers"] = serde_json::json!(tickers);
+    }
     serde_json::json!({
         "id": 1,
         "cmd": "subscribe",
+        "params": params,
+    })
+    .to_string()
+}
+
+/// Command to add or drop a single ticker from the live trade-channel
+/// subscription, sent over `write_tx` without tearing down the socket.
+fn watchlist_delta_cmd(cmd: &str, ticker: &str) -> String {
+    serde_json::json!({
+        "id": 1,
+        "cmd": cmd,
         "params": {
-            "channels": ["trade"]
+            "channels": ["trade"],
+            "market_tickers": [ticker],
         }
     })
     .to_string()
 }
 
+/// Runtime mutation of the trade-channel watchlist, sent on the control
+/// channel returned by `spawn_kalshi_ws`.
+#[derive(Debug, Clone)]
+pub enum WatchlistCommand {
+    Add(String),
+    Remove(String),
+}
+
+/// Emitted on the channel returned by `spawn_kalshi_ws` whenever the socket
+/// transitions from silent back to active, so a caller can trigger a bounded
+/// HTTP catch-up fetch for whatever trades happened during the outage
+/// instead of silently resuming from "now".
+#[derive(Debug, Clone, Copy)]
+pub struct Reconnected;
+
 /// Spawn a Kalshi WebSocket listener that sends trades to the returned channel.
-/// The connection auto-reconnects with exponential backoff on failure.
-pub fn spawn_kalshi_ws(api_key_id: Option<String>, private_key: Option<String>) -> mpsc::UnboundedReceiver<WsTrade> {
+/// The connection auto-reconnects with exponential backoff on failure, and
+/// the `watchlist` (full trade firehose if empty) is re-subscribed after
+/// every reconnect. The second returned channel lets a caller add/remove
+/// tickers at runtime without tearing down the socket; the third fires a
+/// `Reconnected` event every time the socket comes back up after the first
+/// connection, so gap reconciliation only runs on genuine reconnects, not
+/// on startup.
+pub fn spawn_kalshi_ws(
+    api_key_id: Option<String>,
+    private_key: Option<String>,
+    watchlist: Vec<String>,
+) -> (
+    mpsc::UnboundedReceiver<WsTrade>,
+    mpsc::UnboundedSender<WatchlistCommand>,
+    mpsc::UnboundedReceiver<Reconnected>,
+) {
     let (tx, rx) = mpsc::unbounded_channel();
+    let (control_tx, mut control_rx) = mpsc::unbounded_channel();
+    let (reconnect_tx, reconnect_rx) = mpsc::unbounded_channel();
+    let watchlist: Arc<Mutex<Vec<String>>> = Arc::new(Mutex::new(watchlist));
 
     tokio::spawn(async move {
         let mut backoff = RECONNECT_BASE;
+        let mut first_connect = true;
 
         loop {
-            match connect_and_listen(&tx, api_key_id.as_deref(), private_key.as_deref()).await {
+            metrics().current_backoff_secs.set(0);
+            match connect_and_listen(
+                &tx,
+                api_key_id.as_deref(),
+                private_key.as_deref(),
+                &watchlist,
+                &mut control_rx,
+                &reconnect_tx,
+                first_connect,
+            )
+            .await
+            {
                 Ok(()) => {
                     // Clean disconnect â€” reconnect immediately
                     eprintln!("[WS] Kalshi WebSocket disconnected, reconnecting...");
+                    metrics().reconnects.inc();
                     backoff = RECONNECT_BASE;
                 }
                 Err(e) => {
                     eprintln!("[WS] Kalshi WebSocket error: {}, reconnecting in {:?}...", e, backoff);
+                    metrics().reconnects.inc();
+                    metrics().current_backoff_secs.set(backoff.as_secs());
                     tokio::time::sleep(backoff).await;
                     backoff = (backoff * 2).min(RECONNECT_MAX);
                 }
             }
+            first_connect = false;
         }
     });
 
-    rx
+    (rx, control_tx, reconnect_rx)
 }
 
 async fn connect_and_listen(
     tx: &mpsc::UnboundedSender<WsTrade>,
     api_key_id: Option<&str>,
     private_key: Option<&str>,
+    watchlist: &Arc<Mutex<Vec<String>>>,
+    control_rx: &mut mpsc::UnboundedReceiver<WatchlistCommand>,
+    reconnect_tx: &mpsc::UnboundedSender<Reconnected>,
+    first_connect: bool,
 ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
-    
+
     // Build the request with auth headers if credentials provided
     let mut builder = Request::builder()
         .method("GET")
@@ -121,8 +210,17 @@ async fn connect_and_listen(
     let (ws_stream, _) = connect_async(request).await?;
     let (mut write, mut read) = ws_stream.split();
 
-    // Subscribe to trade channel
-    write.send(Message::Text(subscribe_cmd())).await?;
+    // Subscribe to trade channel, re-sending the current watchlist so a
+    // reconnect doesn't silently fall back to the full firehose.
+    let subscribe_msg = subscribe_cmd(&watchlist.lock().await);
+    write.send(Message::Text(subscribe_msg)).await?;
+
+    // Notify the caller the socket is back up, unless this is the very
+    // first connection of the process (nothing to reconcile a gap against
+    // yet).
+    if !first_connect {
+        let _ = reconnect_tx.send(Reconnected);
+    }
 
     // Writer channel
     let (write_tx, mut write_rx) = mpsc::unbounded_channel::<Message>();
@@ -136,8 +234,17 @@ async fn connect_and_listen(
         }
     });
 
-    // Spawn ping task
+    // Last time any `Ok(_)` frame was received, checked by the ping task so
+    // a half-open connection (pings go out, nothing ever comes back) is
+    // detected even though `read.next().await` never itself returns.
+    let last_message_at = Arc::new(std::sync::Mutex::new(std::time::Instant::now()));
+    let stale_timeout = PING_INTERVAL * MISSED_PINGS_BEFORE_STALE;
+    let (stale_tx, mut stale_rx) = tokio::sync::oneshot::channel::<()>();
+
+    // Spawn ping task; doubles as the heartbeat watchdog
     let ping_write_tx = write_tx.clone();
+    let ping_last_message_at = last_message_at.clone();
+    let mut stale_tx = Some(stale_tx);
     let ping_task = tokio::spawn(async move {
         let mut interval = tokio::time::interval(PING_INTERVAL);
         loop {
@@ -145,11 +252,61 @@ async fn connect_and_listen(
             if ping_write_tx.send(Message::Ping(vec![])).is_err() {
                 break;
             }
+            let elapsed = ping_last_message_at.lock().unwrap().elapsed();
+            if elapsed >= stale_timeout {
+                eprintln!("[WS] Kalshi connection stale ({:?} since last frame), forcing reconnect...", elapsed);
+                metrics().stale_connections.inc();
+                if let Some(tx) = stale_tx.take() {
+                    let _ = tx.send(());
+                }
+                break;
+            }
         }
     });
+    let staleness_task = spawn_staleness_ticker();
+    // Once every `WatchlistCommand` sender is dropped, `recv()` resolves to
+    // `None` immediately on every poll; this keeps the select from busy-
+    // looping on a permanently closed control channel.
+    let mut control_closed = false;
 
     // Read loop
-    while let Some(msg) = read.next().await {
+    loop {
+        let msg = tokio::select! {
+            msg = read.next() => match msg {
+                Some(msg) => msg,
+                None => break,
+            },
+            _ = &mut stale_rx => {
+                ping_task.abort();
+                writer_handle.abort();
+                staleness_task.abort();
+                return Err("Kalshi WebSocket heartbeat timed out, no frames received".into());
+            }
+            cmd = control_rx.recv(), if !control_closed => {
+                let Some(cmd) = cmd else {
+                    control_closed = true;
+                    continue;
+                };
+                let (delta_cmd, ticker) = match cmd {
+                    WatchlistCommand::Add(ticker) => {
+                        let mut wl = watchlist.lock().await;
+                        if !wl.contains(&ticker) {
+                            wl.push(ticker.clone());
+                        }
+                        ("subscribe", ticker)
+                    }
+                    WatchlistCommand::Remove(ticker) => {
+                        watchlist.lock().await.retain(|t| t != &ticker);
+                        ("unsubscribe", ticker)
+                    }
+                };
+                let _ = write_tx.send(Message::Text(watchlist_delta_cmd(delta_cmd, &ticker)));
+                continue;
+            }
+        };
+
+        metrics().seconds_since_last_message.set(0);
+        *last_message_at.lock().unwrap() = std::time::Instant::now();
         match msg {
             Ok(Message::Text(text)) => {
                 match serde_json::from_str::<WsMessage>(&text) {
@@ -160,14 +317,18 @@ async fn connect_and_listen(
                                     WsTradePayload::Batch { trades } => trades,
                                     WsTradePayload::Single(entry) => vec![entry],
                                 };
-                                
+
                                 for entry in entries {
                                     if let Some(trade) = parse_ws_trade(entry) {
+                                        metrics().trades_received.inc();
                                         if tx.send(trade).is_err() {
                                             ping_task.abort();
                                             writer_handle.abort();
+                                            staleness_task.abort();
                                             return Ok(());
                                         }
+                                    } else {
+                                        metrics().messages_skipped.inc();
                                     }
                                 }
                             }
@@ -175,7 +336,328 @@ async fn connect_and_listen(
                     },
                     Err(_) => {
                         // Silent skip malformed messages (e.g. system status)
+                        metrics().messages_skipped.inc();
+                    }
+                }
+            }
+            Ok(Message::Ping(data)) => {
+                let _ = write_tx.send(Message::Pong(data));
+            }
+            Ok(Message::Close(_)) => {
+                break;
+            }
+            Err(e) => {
+                ping_task.abort();
+                writer_handle.abort();
+                staleness_task.abort();
+                return Err(Box::new(e));
+            }
+            _ => {}
+        }
+    }
+
+    ping_task.abort();
+    writer_handle.abort();
+    staleness_task.abort();
+    Ok(())
+}
+
+/// Top-of-book plus the full ladder and last applied sequence number for
+/// one ticker's `LocalBook`, read by new re-broadcast-server subscribers
+/// (see `ws::relay`) so they can render current state immediately instead
+/// of waiting for the next delta.
+#[derive(Debug, Clone, Serialize)]
+pub struct LevelCheckpoint {
+    pub ticker: String,
+    pub best_bid: f64,
+    pub best_ask: f64,
+    /// Full yes-side ladder: price (in cents) -> quantity.
+    pub yes_levels: Vec<(i64, i64)>,
+    /// Full no-side ladder: price (in cents) -> quantity.
+    pub no_levels: Vec<(i64, i64)>,
+    pub last_seq: Option<i64>,
+}
+
+/// Locally maintained order book for one ticker, seeded by an
+/// `orderbook_snapshot` message and kept current by applying
+/// `orderbook_delta` messages in sequence order. Prices are tracked in
+/// cents to avoid floating-point drift across many small deltas.
+#[derive(Default)]
+pub(crate) struct LocalBook {
+    yes: std::collections::BTreeMap<i64, i64>,
+    no: std::collections::BTreeMap<i64, i64>,
+    seq: Option<i64>,
+}
+
+impl LocalBook {
+    fn apply_snapshot(&mut self, yes: Vec<(i64, i64)>, no: Vec<(i64, i64)>, seq: Option<i64>) {
+        self.yes = yes.into_iter().collect();
+        self.no = no.into_iter().collect();
+        self.seq = seq;
+    }
+
+    /// Applies a single delta, returning `false` if `seq` is not the
+    /// expected next sequence number for this ticker (the caller should
+    /// then force a resubscribe to get a fresh snapshot).
+    fn apply_delta(&mut self, side: &str, price: i64, delta: i64, seq: i64) -> bool {
+        if let Some(last) = self.seq {
+            if seq != last + 1 {
+                return false;
+            }
+        }
+
+        let levels = if side.eq_ignore_ascii_case("no") { &mut self.no } else { &mut self.yes };
+        let qty = levels.entry(price).or_insert(0);
+        *qty += delta;
+        if *qty <= 0 {
+            levels.remove(&price);
+        }
+        self.seq = Some(seq);
+        true
+    }
+
+    /// Same best-bid/best-ask/depth computation `fetch_order_book` does
+    /// from a REST snapshot, applied to the locally maintained book.
+    fn summary(&self) -> crate::alerts::OrderBookSummary {
+        let (best_bid, bid_depth, bid_levels) = side_best_and_depth(&self.yes, true);
+        let (best_no, ask_depth, ask_levels) = side_best_and_depth(&self.no, false);
+        let best_ask = if ask_levels > 0 { 1.0 - best_no } else { 1.0 };
+
+        crate::alerts::OrderBookSummary {
+            best_bid,
+            best_ask,
+            bid_depth_10pct: bid_depth,
+            ask_depth_10pct: ask_depth,
+            bid_levels,
+            ask_levels,
+        }
+    }
+
+    /// Top-of-book plus the full ladder, for handing a fresh subscriber
+    /// current state instead of making it wait for the next delta.
+    pub(crate) fn checkpoint(&self, ticker: &str) -> LevelCheckpoint {
+        let summary = self.summary();
+        LevelCheckpoint {
+            ticker: ticker.to_string(),
+            best_bid: summary.best_bid,
+            best_ask: summary.best_ask,
+            yes_levels: self.yes.iter().map(|(&p, &q)| (p, q)).collect(),
+            no_levels: self.no.iter().map(|(&p, &q)| (p, q)).collect(),
+            last_seq: self.seq,
+        }
+    }
+}
+
+fn side_best_and_depth(levels: &std::collections::BTreeMap<i64, i64>, want_max: bool) -> (f64, f64, u32) {
+    if levels.is_empty() {
+        return (0.0, 0.0, 0);
+    }
+
+    let mut best = if want_max { 0.0f64 } else { 1.0f64 };
+    let mut depth = 0.0f64;
+    for (&price_cents, &qty) in levels {
+        let price = price_cents as f64 / 100.0;
+        depth += price * qty as f64;
+        if want_max {
+            if price > best { best = price; }
+        } else if price < best {
+            best = price;
+        }
+    }
+    (best, depth, levels.len() as u32)
+}
+
+#[derive(Debug, Deserialize)]
+struct ObMessage {
+    #[serde(rename = "type")]
+    msg_type: Option<String>,
+    #[serde(default)]
+    msg: Option<serde_json::Value>,
+}
+
+/// Subscribe command for the `orderbook_delta` channel, scoped to `tickers`.
+fn subscribe_orderbook_cmd(tickers: &[String]) -> String {
+    serde_json::json!({
+        "id": 1,
+        "cmd": "subscribe",
+        "params": {
+            "channels": ["orderbook_delta"],
+            "market_tickers": tickers
+        }
+    })
+    .to_string()
+}
+
+fn price_levels(value: Option<&serde_json::Value>) -> Vec<(i64, i64)> {
+    value
+        .and_then(|v| v.as_array())
+        .map(|levels| {
+            levels
+                .iter()
+                .filter_map(|entry| {
+                    let arr = entry.as_array()?;
+                    let price = arr.first()?.as_i64()?;
+                    let qty = arr.get(1)?.as_i64()?;
+                    Some((price, qty))
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Shared per-ticker book state, kept alongside the summary stream so other
+/// consumers (`ws::relay`'s re-broadcast server) can read current state —
+/// e.g. to checkpoint a freshly subscribed client — without re-deriving it
+/// from the raw WS messages themselves.
+pub type BookRegistry = Arc<Mutex<std::collections::HashMap<String, LocalBook>>>;
+
+/// Read the current `LevelCheckpoint` for `ticker`, if the registry has
+/// seen a snapshot/delta for it yet.
+pub async fn checkpoint_for(registry: &BookRegistry, ticker: &str) -> Option<LevelCheckpoint> {
+    registry.lock().await.get(ticker).map(|book| book.checkpoint(ticker))
+}
+
+/// Stream live `OrderBookSummary` updates for `tickers` over Kalshi's
+/// `orderbook_delta` channel instead of polling `fetch_order_book` per
+/// ticker. Each snapshot/delta is applied to a locally maintained book and
+/// the resulting summary is pushed as `(ticker, summary)`. A sequence-number
+/// gap forces a reconnect, which resubscribes and reseeds every book from a
+/// fresh snapshot. The returned `BookRegistry` holds the same books this
+/// stream is updating, so callers can also pull point-in-time checkpoints.
+pub async fn stream_order_books(
+    tickers: Vec<String>,
+) -> (BookRegistry, mpsc::UnboundedReceiver<(String, crate::alerts::OrderBookSummary)>) {
+    let (tx, rx) = mpsc::unbounded_channel();
+    let registry: BookRegistry = Arc::new(Mutex::new(std::collections::HashMap::new()));
+    let task_registry = registry.clone();
+
+    metrics().active_subscriptions.set(tickers.len() as u64);
+
+    tokio::spawn(async move {
+        let mut backoff = RECONNECT_BASE;
+
+        loop {
+            metrics().current_backoff_secs.set(0);
+            match connect_and_stream_books(&tickers, &tx, &task_registry).await {
+                Ok(()) => {
+                    eprintln!("[WS] Kalshi order book stream disconnected, reconnecting...");
+                    metrics().reconnects.inc();
+                    backoff = RECONNECT_BASE;
+                }
+                Err(e) => {
+                    eprintln!("[WS] Kalshi order book stream error: {}, reconnecting in {:?}...", e, backoff);
+                    metrics().reconnects.inc();
+                    metrics().current_backoff_secs.set(backoff.as_secs());
+                    tokio::time::sleep(backoff).await;
+                    backoff = (backoff * 2).min(RECONNECT_MAX);
+                }
+            }
+        }
+    });
+
+    (registry, rx)
+}
+
+async fn connect_and_stream_books(
+    tickers: &[String],
+    tx: &mpsc::UnboundedSender<(String, crate::alerts::OrderBookSummary)>,
+    registry: &BookRegistry,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let builder = Request::builder()
+        .method("GET")
+        .uri(KALSHI_WS_URL)
+        .header("Host", "api.elections.kalshi.com")
+        .header("Connection", "Upgrade")
+        .header("Upgrade", "websocket")
+        .header("Sec-WebSocket-Version", "13")
+        .header("Sec-WebSocket-Key", tokio_tungstenite::tungstenite::handshake::client::generate_key());
+
+    let request = builder.body(())?;
+
+    let (ws_stream, _) = connect_async(request).await?;
+    let (mut write, mut read) = ws_stream.split();
+
+    write.send(Message::Text(subscribe_orderbook_cmd(tickers))).await?;
+
+    let (write_tx, mut write_rx) = mpsc::unbounded_channel::<Message>();
+
+    let writer_handle = tokio::spawn(async move {
+        while let Some(msg) = write_rx.recv().await {
+            if write.send(msg).await.is_err() {
+                break;
+            }
+        }
+    });
+
+    let ping_write_tx = write_tx.clone();
+    let ping_task = tokio::spawn(async move {
+        let mut interval = tokio::time::interval(PING_INTERVAL);
+        loop {
+            interval.tick().await;
+            if ping_write_tx.send(Message::Ping(vec![])).is_err() {
+                break;
+            }
+        }
+    });
+    let staleness_task = spawn_staleness_ticker();
+
+    while let Some(msg) = read.next().await {
+        metrics().seconds_since_last_message.set(0);
+        match msg {
+            Ok(Message::Text(text)) => {
+                if let Ok(ws_msg) = serde_json::from_str::<ObMessage>(&text) {
+                    match (ws_msg.msg_type.as_deref(), ws_msg.msg) {
+                        (Some("orderbook_snapshot"), Some(payload)) => {
+                            let Some(ticker) = payload.get("market_ticker").and_then(|v| v.as_str()) else { continue };
+                            let seq = payload.get("seq").and_then(|v| v.as_i64());
+                            let yes = price_levels(payload.get("yes"));
+                            let no = price_levels(payload.get("no"));
+
+                            let mut books = registry.lock().await;
+                            let book = books.entry(ticker.to_string()).or_default();
+                            book.apply_snapshot(yes, no, seq);
+                            let summary = book.summary();
+                            drop(books);
+                            if tx.send((ticker.to_string(), summary)).is_err() {
+                                ping_task.abort();
+                                writer_handle.abort();
+                                staleness_task.abort();
+                                return Ok(());
+                            }
+                        }
+                        (Some("orderbook_delta"), Some(payload)) => {
+                            let Some(ticker) = payload.get("market_ticker").and_then(|v| v.as_str()) else { continue };
+                            let Some(side) = payload.get("side").and_then(|v| v.as_str()) else { continue };
+                            let Some(price) = payload.get("price").and_then(|v| v.as_i64()) else { continue };
+                            let Some(delta) = payload.get("delta").and_then(|v| v.as_i64()) else { continue };
+                            let Some(seq) = payload.get("seq").and_then(|v| v.as_i64()) else { continue };
+
+                            let mut books = registry.lock().await;
+                            let book = books.entry(ticker.to_string()).or_default();
+                            if !book.apply_delta(side, price, delta, seq) {
+                                // Sequence gap: drop the connection so the
+                                // outer loop reconnects and resnapshots.
+                                eprintln!("[WS] Kalshi order book sequence gap on {}, resnapshotting...", ticker);
+                                ping_task.abort();
+                                writer_handle.abort();
+                                staleness_task.abort();
+                                return Ok(());
+                            }
+                            let summary = book.summary();
+                            drop(books);
+                            if tx.send((ticker.to_string(), summary)).is_err() {
+                                ping_task.abort();
+                                writer_handle.abort();
+                                staleness_task.abort();
+                                return Ok(());
+                            }
+                        }
+                        _ => {
+                            metrics().messages_skipped.inc();
+                        }
                     }
+                } else {
+                    metrics().messages_skipped.inc();
                 }
             }
             Ok(Message::Ping(data)) => {
@@ -187,6 +669,7 @@ async fn connect_and_listen(
             Err(e) => {
                 ping_task.abort();
                 writer_handle.abort();
+                staleness_task.abort();
                 return Err(Box::new(e));
             }
             _ => {}
@@ -195,6 +678,7 @@ async fn connect_and_listen(
 
     ping_task.abort();
     writer_handle.abort();
+    staleness_task.abort();
     Ok(())
 }
 
@@ -0,0 +1,196 @@
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use futures_util::{SinkExt, StreamExt};
+use http::Request;
+use serde::Deserialize;
+use tokio::sync::{oneshot, Mutex};
+use tokio_tungstenite::connect_async;
+use tokio_tungstenite::tungstenite::Message;
+
+use crate::ws::auth::generate_auth_headers;
+
+const KALSHI_WS_URL: &str = "wss://api.elections.kalshi.com/trade-api/ws/v2";
+const RECONNECT_BASE: Duration = Duration::from_secs(2);
+const RECONNECT_MAX: Duration = Duration::from_secs(60);
+
+/// One push update on an order's fill progress from the `fill` channel.
+#[derive(Debug, Clone)]
+pub struct FillUpdate {
+    pub status: String,
+    pub fill_count: i32,
+}
+
+#[derive(Debug, Deserialize)]
+struct WsMessage {
+    #[serde(rename = "type")]
+    msg_type: Option<String>,
+    #[serde(default)]
+    msg: Option<FillPayload>,
+}
+
+#[derive(Debug, Deserialize)]
+struct FillPayload {
+    order_id: Option<String>,
+    #[serde(default)]
+    status: Option<String>,
+    #[serde(default)]
+    fill_count: Option<i32>,
+    #[serde(default)]
+    count: Option<i32>,
+}
+
+fn subscribe_cmd() -> String {
+    serde_json::json!({
+        "id": 1,
+        "cmd": "subscribe",
+        "params": { "channels": ["fill"] },
+    })
+    .to_string()
+}
+
+/// A still-open `await_fill` call: the contract size it's waiting to see
+/// filled, and the sender that resolves it.
+struct Waiter {
+    target_count: i32,
+    tx: oneshot::Sender<FillUpdate>,
+}
+
+type Waiters = Arc<Mutex<HashMap<String, Waiter>>>;
+
+/// Subscribes once to Kalshi's authenticated per-account `fill` channel and
+/// resolves a per-`order_id` `oneshot` the moment a push update reports
+/// `status == "executed"`, `status == "canceled"`, or cumulative
+/// `fill_count` reaches the order's full size — the same trigger
+/// `commands::watch`'s old 5-attempt/2s HTTP poll loop checked on a fixed
+/// cadence. Auto-reconnects with the same backoff `ws::kalshi::spawn_kalshi_ws`
+/// uses. `is_active()` mirrors `kalshi_ws_active`'s gating: a caller should
+/// fall back to `executor.get_order_status` polling whenever this is false.
+pub struct FillWatcher {
+    waiters: Waiters,
+    connected: Arc<AtomicBool>,
+}
+
+impl FillWatcher {
+    /// Spawn the listener task. Returns immediately; the socket connects in
+    /// the background and `is_active()` stays false until it does.
+    pub fn spawn(api_key_id: Option<String>, private_key: Option<String>) -> Self {
+        let waiters: Waiters = Arc::new(Mutex::new(HashMap::new()));
+        let connected = Arc::new(AtomicBool::new(false));
+
+        let task_waiters = waiters.clone();
+        let task_connected = connected.clone();
+        tokio::spawn(async move {
+            let mut backoff = RECONNECT_BASE;
+            loop {
+                match connect_and_listen(api_key_id.as_deref(), private_key.as_deref(), &task_waiters, &task_connected).await {
+                    Ok(()) => {
+                        eprintln!("[WS] Kalshi fill WebSocket disconnected, reconnecting...");
+                        backoff = RECONNECT_BASE;
+                    }
+                    Err(e) => {
+                        eprintln!("[WS] Kalshi fill WebSocket error: {}, reconnecting in {:?}...", e, backoff);
+                        tokio::time::sleep(backoff).await;
+                        backoff = (backoff * 2).min(RECONNECT_MAX);
+                    }
+                }
+                task_connected.store(false, Ordering::Relaxed);
+            }
+        });
+
+        Self { waiters, connected }
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.connected.load(Ordering::Relaxed)
+    }
+
+    /// Wait up to `timeout` for `order_id` to reach `target_count` fills (or
+    /// report `executed`/`canceled`). Returns `None` on timeout, in which
+    /// case the caller should fall back to polling rather than assume a fill.
+    pub async fn await_fill(&self, order_id: &str, target_count: i32, timeout: Duration) -> Option<FillUpdate> {
+        let (tx, rx) = oneshot::channel();
+        self.waiters.lock().await.insert(order_id.to_string(), Waiter { target_count, tx });
+        match tokio::time::timeout(timeout, rx).await {
+            Ok(Ok(update)) => Some(update),
+            _ => {
+                self.waiters.lock().await.remove(order_id);
+                None
+            }
+        }
+    }
+}
+
+async fn connect_and_listen(
+    api_key_id: Option<&str>,
+    private_key: Option<&str>,
+    waiters: &Waiters,
+    connected: &Arc<AtomicBool>,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let (key_id, priv_key) = match (api_key_id, private_key) {
+        (Some(k), Some(p)) => (k, p),
+        // No credentials — nothing to authenticate the fill channel with.
+        // Idle rather than busy-loop reconnecting forever.
+        _ => {
+            std::future::pending::<()>().await;
+            unreachable!()
+        }
+    };
+
+    let mut builder = Request::builder()
+        .method("GET")
+        .uri(KALSHI_WS_URL)
+        .header("Host", "api.elections.kalshi.com")
+        .header("Connection", "Upgrade")
+        .header("Upgrade", "websocket")
+        .header("Sec-WebSocket-Version", "13")
+        .header("Sec-WebSocket-Key", tokio_tungstenite::tungstenite::handshake::client::generate_key());
+
+    let headers = generate_auth_headers(key_id, priv_key)?;
+    for (name, value) in headers {
+        builder = builder.header(name, value);
+    }
+    let request = builder.body(())?;
+
+    let (ws_stream, _) = connect_async(request).await?;
+    let (mut write, mut read) = ws_stream.split();
+    write.send(Message::Text(subscribe_cmd())).await?;
+    connected.store(true, Ordering::Relaxed);
+
+    while let Some(frame) = read.next().await {
+        let frame = frame?;
+        let text = match frame {
+            Message::Text(t) => t,
+            Message::Close(_) => break,
+            _ => continue,
+        };
+
+        let parsed: WsMessage = match serde_json::from_str(&text) {
+            Ok(m) => m,
+            Err(_) => continue,
+        };
+        if parsed.msg_type.as_deref() != Some("fill") {
+            continue;
+        }
+        let Some(payload) = parsed.msg else { continue };
+        let Some(order_id) = payload.order_id else { continue };
+        let status = payload.status.unwrap_or_default();
+        let fill_count = payload.fill_count.or(payload.count).unwrap_or(0);
+
+        let mut waiters = waiters.lock().await;
+        let resolved = match waiters.get(&order_id) {
+            Some(waiter) => status == "executed" || status == "canceled" || fill_count >= waiter.target_count,
+            None => false,
+        };
+        if resolved {
+            if let Some(waiter) = waiters.remove(&order_id) {
+                let _ = waiter.tx.send(FillUpdate { status, fill_count });
+            }
+        }
+    }
+
+    connected.store(false, Ordering::Relaxed);
+    Ok(())
+}
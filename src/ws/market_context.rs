@@ -0,0 +1,301 @@
+//! Live per-ticker `MarketContext`, derived from Kalshi's `ticker_v2`
+//! channel instead of a `platforms::kalshi::fetch_market_context` REST call
+//! per alert. Modeled the same way `ws::kalshi::stream_order_books` turns
+//! `orderbook_delta` into a `BookRegistry`: a background reconnect loop
+//! keeps a shared registry current, and `commands::watch` reads from it on
+//! the hot path instead of awaiting a fresh fetch. Wire frames split into
+//! two layers: a `#[serde(tag = "event")]` enum for the connection-level
+//! system/subscription/heartbeat frames, and a separate untagged enum for
+//! the two shapes of market data proper — a full `Snapshot` right after
+//! subscribing, then incremental `Ticker` updates as the quote moves.
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+use futures_util::{SinkExt, StreamExt};
+use serde::Deserialize;
+use tokio::sync::{mpsc, Mutex};
+use tokio_tungstenite::connect_async;
+use tokio_tungstenite::tungstenite::Message;
+use http::Request;
+
+use crate::alerts::{MarketContext, Precision};
+use crate::metrics::metrics;
+
+const KALSHI_WS_URL: &str = "wss://api.elections.kalshi.com/trade-api/ws/v2";
+const PING_INTERVAL: Duration = Duration::from_secs(10);
+const RECONNECT_BASE: Duration = Duration::from_secs(2);
+const RECONNECT_MAX: Duration = Duration::from_secs(60);
+
+/// Connection-level frames, distinguished by an explicit `event` tag.
+#[derive(Debug, Deserialize)]
+#[serde(tag = "event")]
+enum ControlFrame {
+    #[serde(rename = "system")]
+    System {
+        #[serde(default)]
+        message: Option<String>,
+    },
+    #[serde(rename = "subscribed")]
+    Subscribed {
+        #[serde(default)]
+        channel: Option<String>,
+    },
+    #[serde(rename = "heartbeat")]
+    Heartbeat {},
+}
+
+/// A `ticker_v2` market-data frame. Prices arrive in cents, matching every
+/// other Kalshi payload in this codebase (`fetch_market_context`,
+/// `ws::kalshi::WsTrade`).
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum MarketDataFrame {
+    Snapshot {
+        market_ticker: String,
+        yes_bid: f64,
+        yes_ask: f64,
+        // Required, so a quote-only frame falls through to `Ticker`.
+        volume: f64,
+        open_interest: f64,
+    },
+    Ticker {
+        market_ticker: String,
+        yes_bid: f64,
+        yes_ask: f64,
+    },
+}
+
+impl MarketDataFrame {
+    fn ticker(&self) -> &str {
+        match self {
+            MarketDataFrame::Snapshot { market_ticker, .. } => market_ticker,
+            MarketDataFrame::Ticker { market_ticker, .. } => market_ticker,
+        }
+    }
+
+    /// Build (or update) a `MarketContext` from this frame. A bare `Ticker`
+    /// update only carries the new quote, so `volume_24h`/`open_interest`/
+    /// `liquidity`/`tags` carry over from `previous` rather than resetting
+    /// to zero on every tick.
+    fn to_context(&self, previous: Option<&MarketContext>) -> MarketContext {
+        let (yes_bid, yes_ask, volume_24h, open_interest) = match *self {
+            MarketDataFrame::Snapshot { yes_bid, yes_ask, volume, open_interest, .. } => {
+                (yes_bid, yes_ask, volume, open_interest)
+            }
+            MarketDataFrame::Ticker { yes_bid, yes_ask, .. } => (
+                yes_bid,
+                yes_ask,
+                previous.map(|p| p.volume_24h).unwrap_or(0.0),
+                previous.map(|p| p.open_interest).unwrap_or(0.0),
+            ),
+        };
+
+        let yes_price = yes_bid / 100.0;
+        let no_price = 1.0 - yes_ask / 100.0;
+
+        MarketContext {
+            yes_price,
+            no_price,
+            spread: (yes_ask - yes_bid).abs() / 100.0,
+            volume_24h,
+            open_interest,
+            price_change_24h: previous.map(|p| yes_price - p.yes_price).unwrap_or(0.0),
+            liquidity: previous.map(|p| p.liquidity).unwrap_or(0.0),
+            tags: previous.map(|p| p.tags.clone()).unwrap_or_default(),
+            fees: crate::platforms::kalshi::market_fees(yes_price),
+            precision: Precision { tick_size: 0.01, lot_size: 1.0 },
+            outcomes: previous.and_then(|p| p.outcomes.clone()),
+        }
+    }
+}
+
+/// Shared per-ticker context state, read by `commands::watch` instead of
+/// awaiting a REST fetch.
+pub type ContextRegistry = Arc<Mutex<HashMap<String, MarketContext>>>;
+
+/// Read the current `MarketContext` for `ticker`, if the registry has seen
+/// a snapshot/ticker frame for it yet.
+pub async fn context_for(registry: &ContextRegistry, ticker: &str) -> Option<MarketContext> {
+    registry.lock().await.get(ticker).cloned()
+}
+
+fn subscribe_cmd(tickers: &[String]) -> String {
+    serde_json::json!({
+        "id": 1,
+        "cmd": "subscribe",
+        "params": {
+            "channels": ["ticker_v2"],
+            "market_tickers": tickers,
+        }
+    })
+    .to_string()
+}
+
+/// Stream live `MarketContext` updates for `tickers` over Kalshi's
+/// `ticker_v2` channel. Each frame updates the shared `ContextRegistry` and
+/// is also pushed as `(ticker, context)`, the same dual registry-plus-channel
+/// shape `ws::kalshi::stream_order_books` uses. Reconnects with the same
+/// exponential backoff on disconnect or error.
+pub async fn stream_market_contexts(
+    tickers: Vec<String>,
+) -> (ContextRegistry, mpsc::UnboundedReceiver<(String, MarketContext)>) {
+    let (tx, rx) = mpsc::unbounded_channel();
+    let registry: ContextRegistry = Arc::new(Mutex::new(HashMap::new()));
+    let task_registry = registry.clone();
+
+    tokio::spawn(async move {
+        let mut backoff = RECONNECT_BASE;
+
+        loop {
+            match connect_and_stream_contexts(&tickers, &tx, &task_registry).await {
+                Ok(()) => {
+                    eprintln!("[WS] Kalshi market context stream disconnected, reconnecting...");
+                    metrics().reconnects.inc();
+                    backoff = RECONNECT_BASE;
+                }
+                Err(e) => {
+                    eprintln!("[WS] Kalshi market context stream error: {}, reconnecting in {:?}...", e, backoff);
+                    metrics().reconnects.inc();
+                    metrics().current_backoff_secs.set(backoff.as_secs());
+                    tokio::time::sleep(backoff).await;
+                    backoff = (backoff * 2).min(RECONNECT_MAX);
+                }
+            }
+        }
+    });
+
+    (registry, rx)
+}
+
+async fn connect_and_stream_contexts(
+    tickers: &[String],
+    tx: &mpsc::UnboundedSender<(String, MarketContext)>,
+    registry: &ContextRegistry,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let request = Request::builder()
+        .method("GET")
+        .uri(KALSHI_WS_URL)
+        .header("Host", "api.elections.kalshi.com")
+        .header("Connection", "Upgrade")
+        .header("Upgrade", "websocket")
+        .header("Sec-WebSocket-Version", "13")
+        .header("Sec-WebSocket-Key", tokio_tungstenite::tungstenite::handshake::client::generate_key())
+        .body(())?;
+
+    let (ws_stream, _) = connect_async(request).await?;
+    let (mut write, mut read) = ws_stream.split();
+    write.send(Message::Text(subscribe_cmd(tickers))).await?;
+
+    let mut ping_due = tokio::time::interval(PING_INTERVAL);
+    ping_due.tick().await; // first tick fires immediately
+
+    loop {
+        tokio::select! {
+            _ = ping_due.tick() => {
+                write.send(Message::Ping(vec![])).await?;
+            }
+            msg = read.next() => {
+                let Some(msg) = msg else { break };
+                match msg? {
+                    Message::Text(text) => {
+                        if parse_control_frame(&text) {
+                            continue;
+                        }
+                        let Ok(frame) = serde_json::from_str::<MarketDataFrame>(&text) else {
+                            metrics().messages_skipped.inc();
+                            continue;
+                        };
+
+                        let mut contexts = registry.lock().await;
+                        let previous = contexts.get(frame.ticker()).cloned();
+                        let context = frame.to_context(previous.as_ref());
+                        let ticker = frame.ticker().to_string();
+                        contexts.insert(ticker.clone(), context.clone());
+                        drop(contexts);
+
+                        if tx.send((ticker, context)).is_err() {
+                            break;
+                        }
+                    }
+                    Message::Ping(data) => {
+                        write.send(Message::Pong(data)).await?;
+                    }
+                    Message::Close(_) => break,
+                    _ => {}
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Returns `true` (and handles logging) if `text` parses as a control frame
+/// — distinguishing these from market-data frames is why `event` is a tag
+/// rather than a plain field: a market-data frame simply won't have a
+/// recognized `event` value and falls through to `MarketDataFrame` instead.
+fn parse_control_frame(text: &str) -> bool {
+    let Ok(frame) = serde_json::from_str::<ControlFrame>(text) else { return false };
+    match frame {
+        ControlFrame::System { message } => {
+            if let Some(message) = message {
+                eprintln!("[WS] Kalshi market context stream system message: {}", message);
+            }
+        }
+        ControlFrame::Subscribed { channel } => {
+            eprintln!("[WS] Kalshi market context stream subscribed to {:?}", channel);
+        }
+        ControlFrame::Heartbeat {} => {}
+    }
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn snapshot_frame_builds_a_fresh_context() {
+        let frame = MarketDataFrame::Snapshot {
+            market_ticker: "KXTEST".to_string(),
+            yes_bid: 60.0,
+            yes_ask: 65.0,
+            volume: 1200.0,
+            open_interest: 500.0,
+        };
+        let ctx = frame.to_context(None);
+        assert!((ctx.yes_price - 0.60).abs() < 1e-9);
+        assert!((ctx.no_price - 0.35).abs() < 1e-9);
+        assert_eq!(ctx.volume_24h, 1200.0);
+    }
+
+    #[test]
+    fn ticker_frame_carries_over_volume_and_open_interest() {
+        let previous = MarketDataFrame::Snapshot {
+            market_ticker: "KXTEST".to_string(),
+            yes_bid: 60.0,
+            yes_ask: 65.0,
+            volume: 1200.0,
+            open_interest: 500.0,
+        }
+        .to_context(None);
+
+        let update = MarketDataFrame::Ticker {
+            market_ticker: "KXTEST".to_string(),
+            yes_bid: 62.0,
+            yes_ask: 66.0,
+        };
+        let ctx = update.to_context(Some(&previous));
+        assert_eq!(ctx.volume_24h, 1200.0);
+        assert_eq!(ctx.open_interest, 500.0);
+        assert!((ctx.price_change_24h - 0.02).abs() < 1e-9);
+    }
+
+    #[test]
+    fn recognizes_control_frames_and_ignores_market_data() {
+        assert!(parse_control_frame(r#"{"event":"heartbeat"}"#));
+        assert!(parse_control_frame(r#"{"event":"subscribed","channel":"ticker_v2"}"#));
+        assert!(!parse_control_frame(r#"{"market_ticker":"KXTEST","yes_bid":60,"yes_ask":65}"#));
+    }
+}
@@ -0,0 +1,161 @@
+/// Event-driven counterpart to `ws::kalshi`'s channel-based trade/order-book
+/// streams: instead of handing back an `mpsc::Receiver`, this exposes a
+/// `futures_core::Stream` of classified `MarketEvent`s, modeled on the
+/// streaming board-state loops game-playing clients use to watch a live
+/// match tick by tick. Each raw `ticker_v2`/`market_lifecycle_v2` message is
+/// run through `market_outcome::classify` before it reaches the caller, so
+/// `run_event_loop` callers see "VERSTAPPEN finishes in position" rather
+/// than a bare ticker string.
+use std::time::Duration;
+
+use async_stream::try_stream;
+use futures_util::{Stream, TryStreamExt, SinkExt, StreamExt};
+use serde::Deserialize;
+use tokio_tungstenite::connect_async;
+use tokio_tungstenite::tungstenite::Message;
+use http::Request;
+
+use crate::market_outcome::{self, BetSide, MarketOutcome};
+use crate::ticker_rules::TickerRuleSet;
+
+const KALSHI_WS_URL: &str = "wss://api.elections.kalshi.com/trade-api/ws/v2";
+const PING_INTERVAL: Duration = Duration::from_secs(10);
+
+/// A single classified update for `ticker`, surfaced alongside the
+/// human-readable `MarketOutcome` description so callers don't have to
+/// re-parse the ticker themselves.
+#[derive(Debug, Clone)]
+pub enum MarketEvent {
+    MarketOpened { ticker: String, outcome: MarketOutcome },
+    PriceUpdate { ticker: String, outcome: MarketOutcome, side: BetSide, price: f64 },
+    MarketResolved { ticker: String, outcome: MarketOutcome, result_yes: bool },
+}
+
+#[derive(Debug, Deserialize)]
+struct WsEnvelope {
+    #[serde(rename = "type")]
+    msg_type: Option<String>,
+    #[serde(default)]
+    msg: Option<serde_json::Value>,
+}
+
+fn subscribe_cmd() -> String {
+    serde_json::json!({
+        "id": 1,
+        "cmd": "subscribe",
+        "params": {
+            "channels": ["ticker_v2", "market_lifecycle_v2"]
+        }
+    })
+    .to_string()
+}
+
+/// Open one Kalshi WebSocket connection and yield a `MarketEvent` per
+/// `ticker_v2`/`market_lifecycle_v2` message, classified against `rules`.
+/// Unlike `ws::kalshi::spawn_kalshi_ws`, this does not auto-reconnect — a
+/// transport error ends the stream with an `Err`, and a close frame or the
+/// end of the socket ends it without one, so callers driving it
+/// via `run_event_loop` can decide whether to retry.
+pub fn market_event_stream(
+    rules: TickerRuleSet,
+) -> impl Stream<Item = Result<MarketEvent, Box<dyn std::error::Error + Send + Sync>>> {
+    try_stream! {
+        let request = Request::builder()
+            .method("GET")
+            .uri(KALSHI_WS_URL)
+            .header("Host", "api.elections.kalshi.com")
+            .header("Connection", "Upgrade")
+            .header("Upgrade", "websocket")
+            .header("Sec-WebSocket-Version", "13")
+            .header("Sec-WebSocket-Key", tokio_tungstenite::tungstenite::handshake::client::generate_key())
+            .body(())?;
+
+        let (ws_stream, _) = connect_async(request).await?;
+        let (mut write, mut read) = ws_stream.split();
+        write.send(Message::Text(subscribe_cmd())).await?;
+
+        let mut ping_due = tokio::time::interval(PING_INTERVAL);
+        ping_due.tick().await; // first tick fires immediately
+
+        loop {
+            tokio::select! {
+                _ = ping_due.tick() => {
+                    write.send(Message::Ping(vec![])).await?;
+                }
+                msg = read.next() => {
+                    let Some(msg) = msg else { break };
+                    match msg? {
+                        Message::Text(text) => {
+                            let Ok(envelope) = serde_json::from_str::<WsEnvelope>(&text) else { continue };
+                            let Some(event) = classify_envelope(&envelope, &rules) else { continue };
+                            yield event;
+                        }
+                        Message::Ping(data) => {
+                            write.send(Message::Pong(data)).await?;
+                        }
+                        Message::Close(_) => break,
+                        _ => {}
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn classify_envelope(envelope: &WsEnvelope, rules: &TickerRuleSet) -> Option<MarketEvent> {
+    let payload = envelope.msg.as_ref()?;
+    let ticker = payload.get("market_ticker").and_then(|v| v.as_str())?.to_string();
+
+    match envelope.msg_type.as_deref() {
+        Some("ticker_v2") => {
+            let side = if payload.get("yes_bid").is_some() { "yes" } else { "no" };
+            let price = payload
+                .get("yes_bid")
+                .or_else(|| payload.get("price"))
+                .and_then(|v| v.as_f64())
+                .unwrap_or(0.0)
+                / 100.0;
+            let outcome = market_outcome::classify(&ticker, side, rules);
+            Some(MarketEvent::PriceUpdate {
+                ticker,
+                outcome,
+                side: BetSide::parse(side),
+                price,
+            })
+        }
+        Some("market_lifecycle_v2") => {
+            let lifecycle = payload.get("lifecycle_state").and_then(|v| v.as_str())?;
+            let outcome = market_outcome::classify(&ticker, "yes", rules);
+            match lifecycle {
+                "activated" | "initialized" => Some(MarketEvent::MarketOpened { ticker, outcome }),
+                "determined" | "settled" => {
+                    let result_yes = payload
+                        .get("result")
+                        .and_then(|v| v.as_str())
+                        .map(|r| r.eq_ignore_ascii_case("yes"))
+                        .unwrap_or(false);
+                    Some(MarketEvent::MarketResolved { ticker, outcome, result_yes })
+                }
+                _ => None,
+            }
+        }
+        _ => None,
+    }
+}
+
+/// Drive `market_event_stream` with a user-supplied handler, so the same
+/// loop can feed alerts, logging, or `paper_trading::PaperTradingSimulator`
+/// without each caller re-implementing the `try_next` loop.
+pub async fn run_event_loop<F>(
+    rules: TickerRuleSet,
+    mut on_event: F,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>>
+where
+    F: FnMut(MarketEvent),
+{
+    let mut stream = Box::pin(market_event_stream(rules));
+    while let Some(event) = stream.try_next().await? {
+        on_event(event);
+    }
+    Ok(())
+}
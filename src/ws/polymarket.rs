@@ -0,0 +1,209 @@
+//! Push-driven Polymarket trades, so `commands::watch` isn't solely reliant
+//! on polling `polymarket::fetch_recent_trades` every `tick_interval`. Unlike
+//! Kalshi's `trade` channel (which supports an unscoped firehose
+//! subscription — see `ws::kalshi::subscribe_cmd`), Polymarket's public
+//! real-time feed is a flat trade-activity stream with no subscription
+//! filter to narrow at all, so there's no equivalent "empty watchlist"
+//! concept here: connecting at all means every trade above $0 crosses the
+//! wire, and `commands::watch` is the one that applies the dollar
+//! threshold. No credentials are required — trade prints are public data,
+//! unlike Kalshi's `trade` channel which accepts (but doesn't require)
+//! `generate_auth_headers`.
+use std::time::Duration;
+
+use futures_util::{SinkExt, StreamExt};
+use serde::Deserialize;
+use tokio::sync::mpsc;
+use tokio_tungstenite::connect_async;
+use tokio_tungstenite::tungstenite::Message;
+use http::Request;
+
+use crate::metrics::metrics;
+use crate::platforms::polymarket::Trade;
+
+const POLYMARKET_WS_URL: &str = "wss://ws-live-data.polymarket.com";
+const PING_INTERVAL: Duration = Duration::from_secs(10);
+const RECONNECT_BASE: Duration = Duration::from_secs(2);
+const RECONNECT_MAX: Duration = Duration::from_secs(60);
+
+#[derive(Debug, Deserialize)]
+struct WsEnvelope {
+    #[serde(default)]
+    topic: Option<String>,
+    #[serde(default)]
+    payload: Option<WsTradeEntry>,
+}
+
+#[derive(Debug, Deserialize)]
+struct WsTradeEntry {
+    #[serde(rename = "transactionHash")]
+    transaction_hash: Option<String>,
+    #[serde(rename = "conditionId")]
+    condition_id: Option<String>,
+    asset: Option<String>,
+    side: Option<String>,
+    size: Option<f64>,
+    price: Option<f64>,
+    timestamp: Option<i64>,
+}
+
+impl WsTradeEntry {
+    /// Only the fields this feed actually carries — `market_title`/
+    /// `outcome`/`wallet_id` are `None` here and filled in afterward the
+    /// same way `ws::kalshi::WsTrade` leaves title lookup to
+    /// `commands::watch`'s own market-info cache rather than the socket.
+    fn into_trade(self) -> Option<Trade> {
+        let market = self.condition_id?;
+        let asset_id = self.asset.unwrap_or_default();
+        Some(Trade {
+            id: self.transaction_hash.unwrap_or_else(|| format!("{}-{}", asset_id, self.timestamp.unwrap_or(0))),
+            market,
+            asset_id,
+            side: self.side.unwrap_or_default(),
+            size: self.size.unwrap_or(0.0),
+            price: self.price.unwrap_or(0.0),
+            timestamp: self
+                .timestamp
+                .and_then(|ts| chrono::DateTime::from_timestamp(ts, 0))
+                .map(|dt| dt.to_rfc3339())
+                .unwrap_or_else(|| chrono::Utc::now().to_rfc3339()),
+            market_title: None,
+            outcome: None,
+            wallet_id: None,
+        })
+    }
+}
+
+fn subscribe_cmd() -> String {
+    serde_json::json!({
+        "type": "subscribe",
+        "channel": "activity",
+        "filters": [{ "event_type": "trade" }],
+    })
+    .to_string()
+}
+
+/// Spawn a Polymarket trade listener with the same reconnect/backoff shape
+/// `ws::kalshi::spawn_kalshi_ws`/`ws::market_context::stream_market_contexts`
+/// use. `commands::watch` drains the returned channel non-blockingly each
+/// tick and falls back to `polymarket::fetch_recent_trades` polling
+/// whenever it's been quiet for a while, the same way it already does for
+/// Kalshi's `kalshi_ws_active` flag.
+pub fn spawn_polymarket_ws() -> mpsc::UnboundedReceiver<Trade> {
+    let (tx, rx) = mpsc::unbounded_channel();
+
+    tokio::spawn(async move {
+        let mut backoff = RECONNECT_BASE;
+
+        loop {
+            match connect_and_stream(&tx).await {
+                Ok(()) => {
+                    eprintln!("[WS] Polymarket trade stream disconnected, reconnecting...");
+                    metrics().reconnects.inc();
+                    backoff = RECONNECT_BASE;
+                }
+                Err(e) => {
+                    eprintln!("[WS] Polymarket trade stream error: {}, reconnecting in {:?}...", e, backoff);
+                    metrics().reconnects.inc();
+                    metrics().current_backoff_secs.set(backoff.as_secs());
+                    tokio::time::sleep(backoff).await;
+                    backoff = (backoff * 2).min(RECONNECT_MAX);
+                }
+            }
+        }
+    });
+
+    rx
+}
+
+async fn connect_and_stream(
+    tx: &mpsc::UnboundedSender<Trade>,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let request = Request::builder()
+        .method("GET")
+        .uri(POLYMARKET_WS_URL)
+        .header("Host", "ws-live-data.polymarket.com")
+        .header("Connection", "Upgrade")
+        .header("Upgrade", "websocket")
+        .header("Sec-WebSocket-Version", "13")
+        .header("Sec-WebSocket-Key", tokio_tungstenite::tungstenite::handshake::client::generate_key())
+        .body(())?;
+
+    let (ws_stream, _) = connect_async(request).await?;
+    let (mut write, mut read) = ws_stream.split();
+    write.send(Message::Text(subscribe_cmd())).await?;
+
+    let mut ping_due = tokio::time::interval(PING_INTERVAL);
+    ping_due.tick().await;
+
+    loop {
+        tokio::select! {
+            _ = ping_due.tick() => {
+                write.send(Message::Ping(vec![])).await?;
+            }
+            msg = read.next() => {
+                let Some(msg) = msg else { break };
+                match msg? {
+                    Message::Text(text) => {
+                        let Ok(envelope) = serde_json::from_str::<WsEnvelope>(&text) else {
+                            metrics().messages_skipped.inc();
+                            continue;
+                        };
+                        if envelope.topic.as_deref() != Some("activity") {
+                            continue;
+                        }
+                        let Some(trade) = envelope.payload.and_then(WsTradeEntry::into_trade) else {
+                            continue;
+                        };
+                        if tx.send(trade).is_err() {
+                            break;
+                        }
+                    }
+                    Message::Ping(data) => {
+                        write.send(Message::Pong(data)).await?;
+                    }
+                    Message::Close(_) => break,
+                    _ => {}
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_a_trade_entry_missing_a_transaction_hash_with_a_synthesized_id() {
+        let entry = WsTradeEntry {
+            transaction_hash: None,
+            condition_id: Some("0xabc".to_string()),
+            asset: Some("123".to_string()),
+            side: Some("BUY".to_string()),
+            size: Some(10.0),
+            price: Some(0.62),
+            timestamp: Some(1_700_000_000),
+        };
+        let trade = entry.into_trade().expect("expected a trade");
+        assert_eq!(trade.market, "0xabc");
+        assert_eq!(trade.id, "123-1700000000");
+        assert!(trade.market_title.is_none());
+    }
+
+    #[test]
+    fn drops_an_entry_with_no_condition_id() {
+        let entry = WsTradeEntry {
+            transaction_hash: Some("0xhash".to_string()),
+            condition_id: None,
+            asset: Some("123".to_string()),
+            side: Some("BUY".to_string()),
+            size: Some(10.0),
+            price: Some(0.62),
+            timestamp: Some(1_700_000_000),
+        };
+        assert!(entry.into_trade().is_none());
+    }
+}
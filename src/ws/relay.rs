@@ -0,0 +1,209 @@
+/// Local fan-out gateway over the single upstream `spawn_kalshi_ws` feed:
+/// `mpsc::UnboundedReceiver<WsTrade>` only has one consumer, so anything
+/// downstream that also wants the trade stream (a second process, a
+/// browser client) has to open its own Kalshi connection. This runs a
+/// `tokio::net::TcpListener` WebSocket server — modeled on the
+/// subscribe/fan-out shape of a fills/orderbook relay service — that takes
+/// that single upstream feed and re-broadcasts it to however many local
+/// clients connect, each scoped to the tickers it subscribed to.
+use std::collections::{HashMap, HashSet};
+use std::net::SocketAddr;
+use std::sync::Arc;
+
+use futures_util::{SinkExt, StreamExt};
+use serde::Serialize;
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::{mpsc, Mutex};
+use tokio_tungstenite::tungstenite::Message;
+
+use crate::ws::kalshi::{checkpoint_for, BookRegistry, WsTrade};
+
+/// A command a connected client can send as JSON, tagged by either a
+/// `"cmd"` or `"command"` field (clients disagree on which, so both are
+/// accepted) — e.g. `{"cmd": "subscribe", "market_ticker": "KXNFLTD-A"}`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Command {
+    Subscribe { market_ticker: String },
+    Unsubscribe { market_ticker: String },
+    GetMarkets,
+}
+
+/// Sentinel ticker a client can subscribe to in order to receive every
+/// trade the relay sees, instead of enumerating tickers one at a time.
+const ALL_MARKETS: &str = "*";
+
+fn parse_command(text: &str) -> Option<Command> {
+    let value: serde_json::Value = serde_json::from_str(text).ok()?;
+    let tag = value
+        .get("cmd")
+        .or_else(|| value.get("command"))
+        .and_then(|v| v.as_str())?;
+
+    match tag {
+        "subscribe" => Some(Command::Subscribe {
+            market_ticker: value.get("market_ticker")?.as_str()?.to_string(),
+        }),
+        "unsubscribe" => Some(Command::Unsubscribe {
+            market_ticker: value.get("market_ticker")?.as_str()?.to_string(),
+        }),
+        "get_markets" => Some(Command::GetMarkets),
+        _ => None,
+    }
+}
+
+/// One connected client: an outbound channel to its write half, plus which
+/// tickers it's subscribed to.
+pub struct Peer {
+    sender: mpsc::UnboundedSender<Message>,
+    subscriptions: HashSet<String>,
+}
+
+impl Peer {
+    fn wants(&self, ticker: &str) -> bool {
+        self.subscriptions.contains(ALL_MARKETS) || self.subscriptions.contains(ticker)
+    }
+}
+
+pub type PeerMap = Arc<Mutex<HashMap<SocketAddr, Peer>>>;
+
+fn to_json_message<T: Serialize>(value: &T) -> Option<Message> {
+    serde_json::to_string(value).ok().map(Message::Text)
+}
+
+/// Run the relay: accept client connections on `addr` and forward every
+/// `WsTrade` pulled from `trades` to whichever peers are subscribed to it.
+/// Returns once `trades` closes (the upstream Kalshi feed is gone).
+pub async fn run_relay_server(
+    addr: SocketAddr,
+    mut trades: mpsc::UnboundedReceiver<WsTrade>,
+    books: Option<BookRegistry>,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let listener = TcpListener::bind(addr).await?;
+    let peers: PeerMap = Arc::new(Mutex::new(HashMap::new()));
+
+    let accept_peers = peers.clone();
+    let accept_books = books.clone();
+    tokio::spawn(async move {
+        loop {
+            let Ok((stream, peer_addr)) = listener.accept().await else { break };
+            tokio::spawn(handle_connection(accept_peers.clone(), stream, peer_addr, accept_books.clone()));
+        }
+    });
+
+    while let Some(trade) = trades.recv().await {
+        let Some(message) = to_json_message(&trade) else { continue };
+        let mut peers = peers.lock().await;
+        peers.retain(|_, peer| {
+            if peer.wants(&trade.ticker) {
+                peer.sender.send(message.clone()).is_ok()
+            } else {
+                // Drop peers whose write half has already gone away, same
+                // as a subscribed peer that failed to send.
+                !peer.sender.is_closed()
+            }
+        });
+    }
+
+    Ok(())
+}
+
+async fn handle_connection(peers: PeerMap, stream: TcpStream, addr: SocketAddr, books: Option<BookRegistry>) {
+    let Ok(ws_stream) = tokio_tungstenite::accept_async(stream).await else { return };
+    let (mut write, mut read) = ws_stream.split();
+
+    let (tx, mut rx) = mpsc::unbounded_channel();
+    peers.lock().await.insert(addr, Peer { sender: tx, subscriptions: HashSet::new() });
+
+    let writer_handle = tokio::spawn(async move {
+        while let Some(message) = rx.recv().await {
+            if write.send(message).await.is_err() {
+                break;
+            }
+        }
+    });
+
+    while let Some(Ok(message)) = read.next().await {
+        let Message::Text(text) = message else { continue };
+        let Some(command) = parse_command(&text) else { continue };
+
+        let mut peers = peers.lock().await;
+        let Some(peer) = peers.get_mut(&addr) else { break };
+        match command {
+            Command::Subscribe { market_ticker } => {
+                peer.subscriptions.insert(market_ticker.clone());
+                let sender = peer.sender.clone();
+                // Drop the peer-map lock before awaiting the registry lock,
+                // so a slow book lookup can't hold up other peers'
+                // subscribe/unsubscribe commands.
+                drop(peers);
+                if let Some(books) = &books {
+                    if let Some(checkpoint) = checkpoint_for(books, &market_ticker).await {
+                        if let Some(message) = to_json_message(&checkpoint) {
+                            let _ = sender.send(message);
+                        }
+                    }
+                }
+                continue;
+            }
+            Command::Unsubscribe { market_ticker } => {
+                peer.subscriptions.remove(&market_ticker);
+            }
+            Command::GetMarkets => {
+                let markets: Vec<String> = peer.subscriptions.iter().cloned().collect();
+                if let Some(message) = to_json_message(&markets) {
+                    let _ = peer.sender.send(message);
+                }
+            }
+        }
+    }
+
+    peers.lock().await.remove(&addr);
+    writer_handle.abort();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_subscribe_with_either_tag_field() {
+        assert_eq!(
+            parse_command(r#"{"cmd": "subscribe", "market_ticker": "KXNFLTD-A"}"#),
+            Some(Command::Subscribe { market_ticker: "KXNFLTD-A".to_string() })
+        );
+        assert_eq!(
+            parse_command(r#"{"command": "subscribe", "market_ticker": "KXNFLTD-A"}"#),
+            Some(Command::Subscribe { market_ticker: "KXNFLTD-A".to_string() })
+        );
+    }
+
+    #[test]
+    fn parses_unsubscribe_and_get_markets() {
+        assert_eq!(
+            parse_command(r#"{"cmd": "unsubscribe", "market_ticker": "KXNFLTD-A"}"#),
+            Some(Command::Unsubscribe { market_ticker: "KXNFLTD-A".to_string() })
+        );
+        assert_eq!(parse_command(r#"{"cmd": "get_markets"}"#), Some(Command::GetMarkets));
+    }
+
+    #[test]
+    fn rejects_unknown_or_malformed_commands() {
+        assert_eq!(parse_command(r#"{"cmd": "nonsense"}"#), None);
+        assert_eq!(parse_command("not json"), None);
+        assert_eq!(parse_command(r#"{"cmd": "subscribe"}"#), None);
+    }
+
+    #[test]
+    fn peer_wants_ticker_it_subscribed_to_or_wildcard() {
+        let (tx, _rx) = mpsc::unbounded_channel();
+        let mut peer = Peer { sender: tx, subscriptions: HashSet::new() };
+        assert!(!peer.wants("KXNFLTD-A"));
+
+        peer.subscriptions.insert("KXNFLTD-A".to_string());
+        assert!(peer.wants("KXNFLTD-A"));
+        assert!(!peer.wants("KXNFLTD-B"));
+
+        peer.subscriptions.insert(ALL_MARKETS.to_string());
+        assert!(peer.wants("KXNFLTD-B"));
+    }
+}